@@ -0,0 +1,440 @@
+use crate::edge::{AnyEdgeInfo, Edge, EdgeInfo};
+use crate::errors::CodecError;
+use crate::hyperedge::Hyperedge;
+use crate::node::{AnyNodeInfo, NodeId, NodeInfo};
+use crate::property::PropertyOwner;
+use crate::relation::{AnyRelationInfo, RelationId, RelationInfo};
+use crate::value::Value;
+use std::collections::BTreeMap;
+
+/// Converts type erased node, relation and edge infos to and from bytes.
+///
+/// Edge infos are optional; the default methods reject all of them.
+pub trait InfoCodec {
+    /// Append the encoded form of a node info to `buf`.
+    fn encode_node_info(&self, info: &dyn NodeInfo, buf: &mut Vec<u8>) -> Result<(), CodecError>;
+
+    /// Decode a node info previously encoded with `encode_node_info`.
+    fn decode_node_info(&self, bytes: &[u8]) -> Result<AnyNodeInfo, CodecError>;
+
+    /// Append the encoded form of a relation info to `buf`.
+    fn encode_relation_info(
+        &self,
+        info: &dyn RelationInfo,
+        buf: &mut Vec<u8>,
+    ) -> Result<(), CodecError>;
+
+    /// Decode a relation info previously encoded with `encode_relation_info`.
+    fn decode_relation_info(&self, bytes: &[u8]) -> Result<AnyRelationInfo, CodecError>;
+
+    /// Append the encoded form of an edge info to `buf`.
+    fn encode_edge_info(&self, _info: &dyn EdgeInfo, _buf: &mut Vec<u8>) -> Result<(), CodecError> {
+        Err(CodecError::UnsupportedType)
+    }
+
+    /// Decode an edge info previously encoded with `encode_edge_info`.
+    fn decode_edge_info(&self, _bytes: &[u8]) -> Result<AnyEdgeInfo, CodecError> {
+        Err(CodecError::UnsupportedType)
+    }
+}
+
+const TAG_UNIT: u8 = 0;
+const TAG_I32: u8 = 1;
+const TAG_U32: u8 = 2;
+const TAG_STRING: u8 = 3;
+
+/// Codec for the info types that have built-in `NodeInfo`, `RelationInfo`
+/// and `EdgeInfo` impls.
+#[derive(Copy, Clone, Default, Debug)]
+pub struct DefaultCodec;
+
+impl DefaultCodec {
+    fn decode_tagged(bytes: &[u8]) -> Result<Tagged, CodecError> {
+        let mut reader = Reader::new(bytes);
+        let value = match reader.read_u8()? {
+            TAG_UNIT => Tagged::Unit,
+            TAG_I32 => Tagged::I32(reader.read_u32()? as i32),
+            TAG_U32 => Tagged::U32(reader.read_u32()?),
+            TAG_STRING => Tagged::String(reader.read_string()?),
+            _ => return Err(CodecError::Malformed),
+        };
+        reader.finish()?;
+        Ok(value)
+    }
+}
+
+enum Tagged {
+    Unit,
+    I32(i32),
+    U32(u32),
+    String(String),
+}
+
+impl InfoCodec for DefaultCodec {
+    fn encode_node_info(&self, info: &dyn NodeInfo, buf: &mut Vec<u8>) -> Result<(), CodecError> {
+        if info.is::<()>() {
+            buf.push(TAG_UNIT);
+        } else if let Some(value) = info.downcast_ref::<i32>() {
+            buf.push(TAG_I32);
+            write_u32(buf, *value as u32);
+        } else if let Some(value) = info.downcast_ref::<u32>() {
+            buf.push(TAG_U32);
+            write_u32(buf, *value);
+        } else if let Some(value) = info.downcast_ref::<String>() {
+            buf.push(TAG_STRING);
+            write_bytes(buf, value.as_bytes());
+        } else {
+            return Err(CodecError::UnsupportedType);
+        }
+        Ok(())
+    }
+
+    fn decode_node_info(&self, bytes: &[u8]) -> Result<AnyNodeInfo, CodecError> {
+        Ok(match Self::decode_tagged(bytes)? {
+            Tagged::Unit => ().into(),
+            Tagged::I32(value) => value.into(),
+            Tagged::U32(value) => value.into(),
+            Tagged::String(value) => value.into(),
+        })
+    }
+
+    fn encode_relation_info(
+        &self,
+        info: &dyn RelationInfo,
+        buf: &mut Vec<u8>,
+    ) -> Result<(), CodecError> {
+        if let Some(value) = info.downcast_ref::<i32>() {
+            buf.push(TAG_I32);
+            write_u32(buf, *value as u32);
+        } else if let Some(value) = info.downcast_ref::<u32>() {
+            buf.push(TAG_U32);
+            write_u32(buf, *value);
+        } else if let Some(value) = info.downcast_ref::<String>() {
+            buf.push(TAG_STRING);
+            write_bytes(buf, value.as_bytes());
+        } else {
+            return Err(CodecError::UnsupportedType);
+        }
+        Ok(())
+    }
+
+    fn decode_relation_info(&self, bytes: &[u8]) -> Result<AnyRelationInfo, CodecError> {
+        Ok(match Self::decode_tagged(bytes)? {
+            Tagged::Unit => return Err(CodecError::Malformed),
+            Tagged::I32(value) => value.into(),
+            Tagged::U32(value) => value.into(),
+            Tagged::String(value) => value.into(),
+        })
+    }
+
+    fn encode_edge_info(&self, info: &dyn EdgeInfo, buf: &mut Vec<u8>) -> Result<(), CodecError> {
+        if info.is::<()>() {
+            buf.push(TAG_UNIT);
+        } else if let Some(value) = info.downcast_ref::<i32>() {
+            buf.push(TAG_I32);
+            write_u32(buf, *value as u32);
+        } else if let Some(value) = info.downcast_ref::<u32>() {
+            buf.push(TAG_U32);
+            write_u32(buf, *value);
+        } else if let Some(value) = info.downcast_ref::<String>() {
+            buf.push(TAG_STRING);
+            write_bytes(buf, value.as_bytes());
+        } else {
+            return Err(CodecError::UnsupportedType);
+        }
+        Ok(())
+    }
+
+    fn decode_edge_info(&self, bytes: &[u8]) -> Result<AnyEdgeInfo, CodecError> {
+        Ok(match Self::decode_tagged(bytes)? {
+            Tagged::Unit => ().into(),
+            Tagged::I32(value) => value.into(),
+            Tagged::U32(value) => value.into(),
+            Tagged::String(value) => value.into(),
+        })
+    }
+}
+
+pub(crate) fn write_u32(buf: &mut Vec<u8>, value: u32) {
+    buf.extend_from_slice(&value.to_le_bytes());
+}
+
+pub(crate) fn write_u64(buf: &mut Vec<u8>, value: u64) {
+    buf.extend_from_slice(&value.to_le_bytes());
+}
+
+/// Write `bytes` prefixed by their length.
+pub(crate) fn write_bytes(buf: &mut Vec<u8>, bytes: &[u8]) {
+    write_u32(buf, bytes.len() as u32);
+    buf.extend_from_slice(bytes);
+}
+
+/// Cursor over an encoded byte slice.
+pub(crate) struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    pub(crate) fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes }
+    }
+
+    /// Get the number of bytes left.
+    pub(crate) fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    pub(crate) fn read_slice(&mut self, len: usize) -> Result<&'a [u8], CodecError> {
+        if self.bytes.len() < len {
+            return Err(CodecError::Malformed);
+        }
+        let (head, tail) = self.bytes.split_at(len);
+        self.bytes = tail;
+        Ok(head)
+    }
+
+    pub(crate) fn read_u8(&mut self) -> Result<u8, CodecError> {
+        Ok(self.read_slice(1)?[0])
+    }
+
+    pub(crate) fn read_u32(&mut self) -> Result<u32, CodecError> {
+        let mut raw = [0; 4];
+        raw.copy_from_slice(self.read_slice(4)?);
+        Ok(u32::from_le_bytes(raw))
+    }
+
+    pub(crate) fn read_u64(&mut self) -> Result<u64, CodecError> {
+        let mut raw = [0; 8];
+        raw.copy_from_slice(self.read_slice(8)?);
+        Ok(u64::from_le_bytes(raw))
+    }
+
+    /// Read bytes written by `write_bytes`.
+    pub(crate) fn read_bytes(&mut self) -> Result<&'a [u8], CodecError> {
+        let len = self.read_u32()? as usize;
+        self.read_slice(len)
+    }
+
+    pub(crate) fn read_string(&mut self) -> Result<String, CodecError> {
+        String::from_utf8(self.read_bytes()?.to_vec()).map_err(|_| CodecError::Malformed)
+    }
+
+    /// Make sure all bytes were consumed.
+    pub(crate) fn finish(self) -> Result<(), CodecError> {
+        if self.bytes.is_empty() {
+            Ok(())
+        } else {
+            Err(CodecError::Malformed)
+        }
+    }
+}
+
+/// Write an optional edge info as a flag byte followed by the encoded info.
+pub(crate) fn write_edge_info<C>(
+    bytes: &mut Vec<u8>,
+    info: &Option<AnyEdgeInfo>,
+    codec: &C,
+) -> Result<(), CodecError>
+where
+    C: InfoCodec,
+{
+    match info {
+        Some(info) => {
+            let mut encoded = Vec::new();
+            codec.encode_edge_info(&**info, &mut encoded)?;
+            bytes.push(1);
+            write_bytes(bytes, &encoded);
+        }
+        None => bytes.push(0),
+    }
+    Ok(())
+}
+
+/// Read an optional edge info written with `write_edge_info`.
+pub(crate) fn read_edge_info<C>(
+    reader: &mut Reader,
+    codec: &C,
+) -> Result<Option<AnyEdgeInfo>, CodecError>
+where
+    C: InfoCodec,
+{
+    match reader.read_u8()? {
+        0 => Ok(None),
+        1 => Ok(Some(codec.decode_edge_info(reader.read_bytes()?)?)),
+        _ => Err(CodecError::Malformed),
+    }
+}
+
+/// Write an optional name as a flag byte followed by the name.
+pub(crate) fn write_name(bytes: &mut Vec<u8>, name: Option<&str>) {
+    match name {
+        Some(name) => {
+            bytes.push(1);
+            write_bytes(bytes, name.as_bytes());
+        }
+        None => bytes.push(0),
+    }
+}
+
+/// Read a name written with `write_name`.
+pub(crate) fn read_name(reader: &mut Reader) -> Result<Option<String>, CodecError> {
+    match reader.read_u8()? {
+        0 => Ok(None),
+        1 => Ok(Some(reader.read_string()?)),
+        _ => Err(CodecError::Malformed),
+    }
+}
+
+/// Write the sources and targets of a hyperedge as two lists of `NodeIds`.
+pub(crate) fn write_hyperedge(bytes: &mut Vec<u8>, hyperedge: &Hyperedge) {
+    for nodes in [hyperedge.sources(), hyperedge.targets()].iter() {
+        write_u64(bytes, nodes.len() as u64);
+        for node_id in nodes.iter() {
+            write_u64(bytes, **node_id);
+        }
+    }
+}
+
+/// Read a hyperedge written with `write_hyperedge`.
+pub(crate) fn read_hyperedge(reader: &mut Reader) -> Result<Hyperedge, CodecError> {
+    let mut read_nodes = || -> Result<Vec<NodeId>, CodecError> {
+        (0..reader.read_u64()?)
+            .map(|_| Ok(NodeId::new(reader.read_u64()?)))
+            .collect()
+    };
+    let sources = read_nodes()?;
+    let targets = read_nodes()?;
+    Ok(Hyperedge::new(sources, targets))
+}
+
+const VALUE_NULL: u8 = 0;
+const VALUE_BOOL: u8 = 1;
+const VALUE_INT: u8 = 2;
+const VALUE_FLOAT: u8 = 3;
+const VALUE_STRING: u8 = 4;
+const VALUE_BYTES: u8 = 5;
+const VALUE_LIST: u8 = 6;
+const VALUE_MAP: u8 = 7;
+
+/// Write a property value as a tag byte followed by its contents.
+pub(crate) fn write_value(bytes: &mut Vec<u8>, value: &Value) {
+    match value {
+        Value::Null => bytes.push(VALUE_NULL),
+        Value::Bool(value) => {
+            bytes.push(VALUE_BOOL);
+            bytes.push(u8::from(*value));
+        }
+        Value::Int(value) => {
+            bytes.push(VALUE_INT);
+            write_u64(bytes, *value as u64);
+        }
+        Value::Float(value) => {
+            bytes.push(VALUE_FLOAT);
+            write_u64(bytes, value.to_bits());
+        }
+        Value::String(value) => {
+            bytes.push(VALUE_STRING);
+            write_bytes(bytes, value.as_bytes());
+        }
+        Value::Bytes(value) => {
+            bytes.push(VALUE_BYTES);
+            write_bytes(bytes, value);
+        }
+        Value::List(values) => {
+            bytes.push(VALUE_LIST);
+            write_u64(bytes, values.len() as u64);
+            for value in values {
+                write_value(bytes, value);
+            }
+        }
+        Value::Map(entries) => {
+            bytes.push(VALUE_MAP);
+            write_u64(bytes, entries.len() as u64);
+            for (key, value) in entries {
+                write_bytes(bytes, key.as_bytes());
+                write_value(bytes, value);
+            }
+        }
+    }
+}
+
+/// Read a property value written with `write_value`.
+pub(crate) fn read_value(reader: &mut Reader) -> Result<Value, CodecError> {
+    Ok(match reader.read_u8()? {
+        VALUE_NULL => Value::Null,
+        VALUE_BOOL => match reader.read_u8()? {
+            0 => Value::Bool(false),
+            1 => Value::Bool(true),
+            _ => return Err(CodecError::Malformed),
+        },
+        VALUE_INT => Value::Int(reader.read_u64()? as i64),
+        VALUE_FLOAT => Value::Float(f64::from_bits(reader.read_u64()?)),
+        VALUE_STRING => Value::String(reader.read_string()?),
+        VALUE_BYTES => Value::Bytes(reader.read_bytes()?.to_vec()),
+        VALUE_LIST => Value::List(
+            (0..reader.read_u64()?)
+                .map(|_| read_value(reader))
+                .collect::<Result<_, _>>()?,
+        ),
+        VALUE_MAP => {
+            let mut entries = BTreeMap::new();
+            for _ in 0..reader.read_u64()? {
+                let key = reader.read_string()?;
+                entries.insert(key, read_value(reader)?);
+            }
+            Value::Map(entries)
+        }
+        _ => return Err(CodecError::Malformed),
+    })
+}
+
+/// Write the owner of a property as a tag byte followed by its ids.
+pub(crate) fn write_property_owner(bytes: &mut Vec<u8>, owner: PropertyOwner) {
+    match owner {
+        PropertyOwner::Node(node_id) => {
+            bytes.push(0);
+            write_u64(bytes, *node_id);
+        }
+        PropertyOwner::Relation(relation_id) => {
+            bytes.push(1);
+            write_u64(bytes, relation_id.get());
+        }
+        PropertyOwner::Edge(edge, relation_id) => {
+            bytes.push(2);
+            write_u64(bytes, *edge.src());
+            write_u64(bytes, *edge.dst());
+            write_u64(bytes, relation_id.get());
+        }
+    }
+}
+
+/// Read the owner of a property written with `write_property_owner`.
+pub(crate) fn read_property_owner(reader: &mut Reader) -> Result<PropertyOwner, CodecError> {
+    let read_relation_id = |reader: &mut Reader| match reader.read_u64()? {
+        0 => Err(CodecError::Malformed),
+        id => Ok(RelationId::new(id)),
+    };
+    Ok(match reader.read_u8()? {
+        0 => PropertyOwner::Node(NodeId::new(reader.read_u64()?)),
+        1 => PropertyOwner::Relation(read_relation_id(reader)?),
+        2 => {
+            let src = NodeId::new(reader.read_u64()?);
+            let dst = NodeId::new(reader.read_u64()?);
+            PropertyOwner::Edge(Edge::new(src, dst), read_relation_id(reader)?)
+        }
+        _ => return Err(CodecError::Malformed),
+    })
+}
+
+/// Compute the CRC-32 (IEEE) checksum of `bytes`.
+pub(crate) fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in bytes {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+    !crc
+}
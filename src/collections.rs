@@ -0,0 +1,528 @@
+use std::borrow::Borrow;
+use std::collections::{btree_map, btree_set, BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fmt;
+use std::fmt::{Debug, Formatter};
+use std::hash::Hash;
+use std::ops::{Index, IndexMut, RangeBounds};
+
+/// A collection, or a value that holds collections, which can move its
+/// entries to structurally shared storage.
+///
+/// The collections of this module keep their entries in the std
+/// collections, which are the fastest to change but are copied entry by
+/// entry when cloned. Once `share` is called they keep them in the
+/// persistent collections of `im` instead, so clones share their entries
+/// and a change only copies the few tree nodes on its path. A `Graph` is
+/// shared by its first snapshot or transaction.
+pub(crate) trait Share {
+    /// Move the entries to structurally shared storage, if they are not
+    /// there already.
+    fn share(&mut self);
+}
+
+/// An iterator over the entries of either storage of a collection.
+pub(crate) enum Iter<A, B> {
+    Owned(A),
+    Shared(B),
+}
+
+impl<A, B> Iterator for Iter<A, B>
+where
+    A: Iterator,
+    B: Iterator<Item = A::Item>,
+{
+    type Item = A::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Self::Owned(iter) => iter.next(),
+            Self::Shared(iter) => iter.next(),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self {
+            Self::Owned(iter) => iter.size_hint(),
+            Self::Shared(iter) => iter.size_hint(),
+        }
+    }
+}
+
+impl<A, B> DoubleEndedIterator for Iter<A, B>
+where
+    A: DoubleEndedIterator,
+    B: DoubleEndedIterator<Item = A::Item>,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        match self {
+            Self::Owned(iter) => iter.next_back(),
+            Self::Shared(iter) => iter.next_back(),
+        }
+    }
+}
+
+/// A hash map backed by a std `HashMap` until it is shared, see `Share`.
+#[derive(Clone)]
+pub(crate) enum Map<K, V> {
+    Owned(HashMap<K, V>),
+    Shared(im::HashMap<K, V>),
+}
+
+impl<K, V> Default for Map<K, V> {
+    fn default() -> Self {
+        Self::Owned(HashMap::new())
+    }
+}
+
+impl<K, V> Map<K, V>
+where
+    K: Hash + Eq + Clone,
+    V: Clone,
+{
+    pub(crate) fn is_shared(&self) -> bool {
+        matches!(self, Self::Shared(_))
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        match self {
+            Self::Owned(map) => map.len(),
+            Self::Shared(map) => map.len(),
+        }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub(crate) fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        match self {
+            Self::Owned(map) => map.get(key),
+            Self::Shared(map) => map.get(key),
+        }
+    }
+
+    pub(crate) fn get_key_value<Q>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        match self {
+            Self::Owned(map) => map.get_key_value(key),
+            Self::Shared(map) => map.get_key_value(key),
+        }
+    }
+
+    pub(crate) fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        match self {
+            Self::Owned(map) => map.get_mut(key),
+            Self::Shared(map) => map.get_mut(key),
+        }
+    }
+
+    pub(crate) fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.get(key).is_some()
+    }
+
+    pub(crate) fn insert(&mut self, key: K, value: V) -> Option<V> {
+        match self {
+            Self::Owned(map) => map.insert(key, value),
+            Self::Shared(map) => map.insert(key, value),
+        }
+    }
+
+    pub(crate) fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        match self {
+            Self::Owned(map) => map.remove(key),
+            Self::Shared(map) => map.remove(key),
+        }
+    }
+
+    /// Get the value at `key`, inserting the result of `f` if there is none.
+    pub(crate) fn get_or_insert_with<F>(&mut self, key: K, f: F) -> &mut V
+    where
+        F: FnOnce() -> V,
+    {
+        match self {
+            Self::Owned(map) => map.entry(key).or_insert_with(f),
+            Self::Shared(map) => map.entry(key).or_insert_with(f),
+        }
+    }
+
+    /// Get the value at `key`, inserting an empty one if there is none. The
+    /// new value is shared if the map is.
+    pub(crate) fn get_or_default(&mut self, key: K) -> &mut V
+    where
+        V: Default + Share,
+    {
+        let shared = self.is_shared();
+        self.get_or_insert_with(key, || {
+            let mut value = V::default();
+            if shared {
+                value.share();
+            }
+            value
+        })
+    }
+
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        match self {
+            Self::Owned(map) => Iter::Owned(map.iter()),
+            Self::Shared(map) => Iter::Shared(map.iter()),
+        }
+    }
+
+    pub(crate) fn iter_mut(&mut self) -> impl Iterator<Item = (&K, &mut V)> {
+        match self {
+            Self::Owned(map) => Iter::Owned(map.iter_mut()),
+            Self::Shared(map) => Iter::Shared(map.iter_mut()),
+        }
+    }
+
+    pub(crate) fn keys(&self) -> impl Iterator<Item = &K> {
+        self.iter().map(|(key, _)| key)
+    }
+
+    pub(crate) fn values(&self) -> impl Iterator<Item = &V> {
+        self.iter().map(|(_, value)| value)
+    }
+
+    pub(crate) fn values_mut(&mut self) -> impl Iterator<Item = &mut V> {
+        self.iter_mut().map(|(_, value)| value)
+    }
+
+    /// Share the map and every value in it.
+    pub(crate) fn share_values(&mut self)
+    where
+        V: Share,
+    {
+        if !self.is_shared() {
+            self.values_mut().for_each(Share::share);
+            self.share();
+        }
+    }
+}
+
+impl<K, V> Share for Map<K, V>
+where
+    K: Hash + Eq + Clone,
+    V: Clone,
+{
+    fn share(&mut self) {
+        if let Self::Owned(map) = self {
+            *self = Self::Shared(std::mem::take(map).into_iter().collect());
+        }
+    }
+}
+
+impl<K, V, Q> Index<&Q> for Map<K, V>
+where
+    K: Hash + Eq + Clone + Borrow<Q>,
+    V: Clone,
+    Q: Hash + Eq + ?Sized,
+{
+    type Output = V;
+
+    fn index(&self, key: &Q) -> &V {
+        self.get(key).expect("No entry found for key")
+    }
+}
+
+impl<K, V, Q> IndexMut<&Q> for Map<K, V>
+where
+    K: Hash + Eq + Clone + Borrow<Q>,
+    V: Clone,
+    Q: Hash + Eq + ?Sized,
+{
+    fn index_mut(&mut self, key: &Q) -> &mut V {
+        self.get_mut(key).expect("No entry found for key")
+    }
+}
+
+impl<K, V> PartialEq for Map<K, V>
+where
+    K: Hash + Eq + Clone,
+    V: Clone + PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len()
+            && self
+                .iter()
+                .all(|(key, value)| other.get(key) == Some(value))
+    }
+}
+
+impl<K, V> Debug for Map<K, V>
+where
+    K: Hash + Eq + Clone + Debug,
+    V: Clone + Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+/// A hash set backed by a std `HashSet` until it is shared, see `Share`.
+#[derive(Clone)]
+pub(crate) enum Set<T> {
+    Owned(HashSet<T>),
+    Shared(im::HashSet<T>),
+}
+
+impl<T> Default for Set<T> {
+    fn default() -> Self {
+        Self::Owned(HashSet::new())
+    }
+}
+
+impl<T> Set<T>
+where
+    T: Hash + Eq + Clone,
+{
+    pub(crate) fn len(&self) -> usize {
+        match self {
+            Self::Owned(set) => set.len(),
+            Self::Shared(set) => set.len(),
+        }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub(crate) fn contains(&self, value: &T) -> bool {
+        match self {
+            Self::Owned(set) => set.contains(value),
+            Self::Shared(set) => set.contains(value),
+        }
+    }
+
+    /// Add `value` and return whether it was not in the set yet.
+    pub(crate) fn insert(&mut self, value: T) -> bool {
+        match self {
+            Self::Owned(set) => set.insert(value),
+            Self::Shared(set) => set.insert(value).is_none(),
+        }
+    }
+
+    /// Remove `value` and return whether it was in the set.
+    pub(crate) fn remove(&mut self, value: &T) -> bool {
+        match self {
+            Self::Owned(set) => set.remove(value),
+            Self::Shared(set) => set.remove(value).is_some(),
+        }
+    }
+
+    pub(crate) fn iter(&self) -> impl Iterator<Item = &T> {
+        match self {
+            Self::Owned(set) => Iter::Owned(set.iter()),
+            Self::Shared(set) => Iter::Shared(set.iter()),
+        }
+    }
+}
+
+impl<T> Share for Set<T>
+where
+    T: Hash + Eq + Clone,
+{
+    fn share(&mut self) {
+        if let Self::Owned(set) = self {
+            *self = Self::Shared(std::mem::take(set).into_iter().collect());
+        }
+    }
+}
+
+/// A sorted set backed by a std `BTreeSet` until it is shared, see `Share`.
+#[derive(Clone)]
+pub(crate) enum SortedSet<T> {
+    Owned(BTreeSet<T>),
+    Shared(im::OrdSet<T>),
+}
+
+impl<T> Default for SortedSet<T> {
+    fn default() -> Self {
+        Self::Owned(BTreeSet::new())
+    }
+}
+
+impl<T> SortedSet<T>
+where
+    T: Ord + Clone,
+{
+    /// Add `value` and return whether it was not in the set yet.
+    pub(crate) fn insert(&mut self, value: T) -> bool {
+        match self {
+            Self::Owned(set) => set.insert(value),
+            Self::Shared(set) => set.insert(value).is_none(),
+        }
+    }
+
+    /// Remove `value` and return whether it was in the set.
+    pub(crate) fn remove(&mut self, value: &T) -> bool {
+        match self {
+            Self::Owned(set) => set.remove(value),
+            Self::Shared(set) => set.remove(value).is_some(),
+        }
+    }
+
+    /// Get an iterator over the values in `range`, in ascending order.
+    pub(crate) fn range<R>(
+        &self,
+        range: R,
+    ) -> Iter<btree_set::Range<'_, T>, im::ordset::RangedIter<'_, T>>
+    where
+        R: RangeBounds<T>,
+    {
+        match self {
+            Self::Owned(set) => Iter::Owned(set.range(range)),
+            Self::Shared(set) => Iter::Shared(set.range(range)),
+        }
+    }
+}
+
+impl<T> Share for SortedSet<T>
+where
+    T: Ord + Clone,
+{
+    fn share(&mut self) {
+        if let Self::Owned(set) = self {
+            *self = Self::Shared(std::mem::take(set).into_iter().collect());
+        }
+    }
+}
+
+/// A sorted map backed by a std `BTreeMap` until it is shared, see `Share`.
+#[derive(Clone)]
+pub(crate) enum SortedMap<K, V> {
+    Owned(BTreeMap<K, V>),
+    Shared(im::OrdMap<K, V>),
+}
+
+impl<K, V> Default for SortedMap<K, V> {
+    fn default() -> Self {
+        Self::Owned(BTreeMap::new())
+    }
+}
+
+impl<K, V> SortedMap<K, V>
+where
+    K: Ord + Clone,
+    V: Clone,
+{
+    pub(crate) fn is_shared(&self) -> bool {
+        matches!(self, Self::Shared(_))
+    }
+
+    pub(crate) fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        match self {
+            Self::Owned(map) => map.get(key),
+            Self::Shared(map) => map.get(key),
+        }
+    }
+
+    pub(crate) fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        match self {
+            Self::Owned(map) => map.get_mut(key),
+            Self::Shared(map) => map.get_mut(key),
+        }
+    }
+
+    pub(crate) fn insert(&mut self, key: K, value: V) -> Option<V> {
+        match self {
+            Self::Owned(map) => map.insert(key, value),
+            Self::Shared(map) => map.insert(key, value),
+        }
+    }
+
+    pub(crate) fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        match self {
+            Self::Owned(map) => map.remove(key),
+            Self::Shared(map) => map.remove(key),
+        }
+    }
+
+    /// Get the value at `key`, inserting an empty one if there is none. The
+    /// new value is shared if the map is.
+    pub(crate) fn get_or_default(&mut self, key: K) -> &mut V
+    where
+        V: Default + Share,
+    {
+        let shared = self.is_shared();
+        let new = || {
+            let mut value = V::default();
+            if shared {
+                value.share();
+            }
+            value
+        };
+        match self {
+            Self::Owned(map) => map.entry(key).or_insert_with(new),
+            Self::Shared(map) => map.entry(key).or_insert_with(new),
+        }
+    }
+
+    /// Get an iterator over the entries with a key in `range`, in
+    /// ascending order of their keys.
+    pub(crate) fn range<R>(
+        &self,
+        range: R,
+    ) -> Iter<btree_map::Range<'_, K, V>, im::ordmap::Iter<'_, K, V>>
+    where
+        R: RangeBounds<K>,
+    {
+        match self {
+            Self::Owned(map) => Iter::Owned(map.range(range)),
+            Self::Shared(map) => Iter::Shared(map.range(range)),
+        }
+    }
+
+    /// Share the map and every value in it.
+    pub(crate) fn share_values(&mut self)
+    where
+        V: Share,
+    {
+        if let Self::Owned(map) = self {
+            map.values_mut().for_each(Share::share);
+            self.share();
+        }
+    }
+}
+
+impl<K, V> Share for SortedMap<K, V>
+where
+    K: Ord + Clone,
+    V: Clone,
+{
+    fn share(&mut self) {
+        if let Self::Owned(map) = self {
+            *self = Self::Shared(std::mem::take(map).into_iter().collect());
+        }
+    }
+}
@@ -0,0 +1,164 @@
+use crate::edge::Edge;
+use crate::errors::UniqueError;
+use crate::graph::Graph;
+use crate::id::IdMap;
+use crate::node::{AnyNodeInfo, NodeId, NodeInfo};
+use crate::relation::RelationId;
+use crate::subgraph::{sorted_relation_ids, Extractor};
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Map the keys `node_key` extracts from the `Nodes` of `graph` to their
+/// `NodeIds`. If several nodes have the same key, the oldest one is used.
+fn nodes_by_key<K, KF>(graph: &Graph, node_key: &KF) -> HashMap<K, NodeId>
+where
+    K: Hash + Eq,
+    KF: Fn(&dyn NodeInfo) -> Option<K>,
+{
+    let mut node_ids: Vec<_> = graph.iter_node_ids().map(|(node_id, _)| node_id).collect();
+    node_ids.sort_by_key(|node_id| **node_id);
+    let mut nodes = HashMap::new();
+    for node_id in node_ids {
+        if let Some(key) = node_key(graph.node_info(node_id).unwrap()) {
+            nodes.entry(key).or_insert(node_id);
+        }
+    }
+    nodes
+}
+
+/// Find the `Relation` of `target` with the same name as a relation of
+/// `source`. Unnamed relations match no relation, since the ids of two
+/// graphs say nothing about each other.
+fn matching_relation(
+    source: &Graph,
+    target: &Graph,
+    relation_id: RelationId,
+) -> Option<RelationId> {
+    target.relation_by_name(source.relation(relation_id)?.name()?)
+}
+
+/// Merge `other` into `graph` and return the map from the ids of `other`
+/// to the ids in `graph`. If an info violates a unique index of `graph`,
+/// the merge is rolled back and the error returned.
+pub(crate) fn merge<K, KF, MF>(
+    graph: &mut Graph,
+    other: &Graph,
+    node_key: KF,
+    mut merge_info: MF,
+) -> Result<IdMap, UniqueError>
+where
+    K: Hash + Eq,
+    KF: Fn(&dyn NodeInfo) -> Option<K>,
+    MF: FnMut(&AnyNodeInfo, &AnyNodeInfo) -> AnyNodeInfo,
+{
+    graph.transaction(|graph| {
+        let mut nodes = nodes_by_key(graph, &node_key);
+        let mut ids = IdMap::default();
+        let relation_ids = sorted_relation_ids(other);
+        for relation_id in &relation_ids {
+            if let Some(existing) = matching_relation(other, graph, *relation_id) {
+                ids.insert_relation(*relation_id, existing);
+            }
+        }
+
+        let mut other_ids: Vec<_> = other.iter_node_ids().map(|(node_id, _)| node_id).collect();
+        other_ids.sort_by_key(|node_id| **node_id);
+        let target: &mut Graph = graph;
+        let mut extractor = Extractor::with_target(other, target, ids, |_, info| info.clone());
+        for node_id in other_ids {
+            let info = other.any_node_info(node_id).unwrap();
+            let key = node_key(&**info);
+            // Nodes of `other` with the same key are merged into one as well
+            match key.as_ref().and_then(|key| nodes.get(key).copied()) {
+                Some(existing) => {
+                    let target = extractor.target_mut();
+                    let old = target.any_node_info(existing).unwrap().clone();
+                    let merged = merge_info(&old, info);
+                    if !merged.ptr_eq(&old) {
+                        target.replace_node_info(existing, merged)?;
+                    }
+                    extractor.map_node(node_id, existing);
+                }
+                None => {
+                    let new_id = extractor.add_node(node_id)?.unwrap();
+                    if let Some(key) = key {
+                        nodes.insert(key, new_id);
+                    }
+                }
+            }
+        }
+        extractor.add_induced_edges(relation_ids);
+        Ok(extractor.finish().1)
+    })
+}
+
+/// Copy `graph` with only the edges for which `keep` returns `true`, given
+/// whether `other` has a matching edge. Relations without a match in
+/// `other` are left out if `matched_only` is set.
+fn filter_edges<K, KF, P>(
+    graph: &Graph,
+    other: &Graph,
+    node_key: KF,
+    matched_only: bool,
+    keep: P,
+) -> (Graph, IdMap)
+where
+    K: Hash + Eq,
+    KF: Fn(&dyn NodeInfo) -> Option<K>,
+    P: Fn(bool) -> bool,
+{
+    let other_nodes = nodes_by_key(other, &node_key);
+    let other_node = |node_id: NodeId| {
+        let key = node_key(graph.node_info(node_id)?)?;
+        other_nodes.get(&key).copied()
+    };
+
+    let mut extractor = Extractor::new(graph, |_, info| info.clone());
+    let mut node_ids: Vec<_> = graph.iter_node_ids().map(|(node_id, _)| node_id).collect();
+    node_ids.sort_by_key(|node_id| **node_id);
+    for node_id in node_ids {
+        let _ = extractor.add_node(node_id);
+    }
+    for relation_id in sorted_relation_ids(graph) {
+        let other_relation = matching_relation(graph, other, relation_id)
+            .and_then(|other_relation_id| other.relation(other_relation_id));
+        if matched_only && other_relation.is_none() {
+            continue;
+        }
+        extractor.add_relation(relation_id);
+        for edge in graph.iter_relation_edges(relation_id).unwrap() {
+            let in_other = match (
+                other_node(edge.src()),
+                other_node(edge.dst()),
+                other_relation,
+            ) {
+                (Some(src), Some(dst), Some(relation)) => {
+                    relation.contains_edge(&Edge::new(src, dst))
+                }
+                _ => false,
+            };
+            if keep(in_other) {
+                extractor.add_edge(*edge, relation_id);
+            }
+        }
+    }
+    extractor.finish()
+}
+
+/// Copy `graph` with only the edges that `other` has as well.
+pub(crate) fn intersection<K, KF>(graph: &Graph, other: &Graph, node_key: KF) -> (Graph, IdMap)
+where
+    K: Hash + Eq,
+    KF: Fn(&dyn NodeInfo) -> Option<K>,
+{
+    filter_edges(graph, other, node_key, true, |in_other| in_other)
+}
+
+/// Copy `graph` without the edges that `other` has as well.
+pub(crate) fn difference<K, KF>(graph: &Graph, other: &Graph, node_key: KF) -> (Graph, IdMap)
+where
+    K: Hash + Eq,
+    KF: Fn(&dyn NodeInfo) -> Option<K>,
+{
+    filter_edges(graph, other, node_key, false, |in_other| !in_other)
+}
@@ -0,0 +1,423 @@
+use crate::edge::Edge;
+use crate::errors::ConnectError;
+use crate::graph::Graph;
+use crate::id::IdGenerator;
+use crate::node::{AnyNodeInfo, NodeId, NodeInfo};
+use crate::relation::{AnyRelationInfo, Relation, RelationId};
+use std::collections::{HashMap, HashSet};
+use std::sync::{PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+/// Number of shards used by `ConcurrentGraph::new`.
+pub const DEFAULT_SHARDS: usize = 64;
+
+type Shard<K, V> = RwLock<HashMap<K, V>>;
+
+/// A node together with its adjacency.
+struct NodeEntry {
+    info: AnyNodeInfo,
+    next_nodes: HashMap<NodeId, HashSet<RelationId>>,
+    prev_nodes: HashMap<NodeId, HashSet<RelationId>>,
+}
+
+/// A graph that can be read and mutated from many threads at once.
+///
+/// Nodes and relations are spread over shards that are locked independently,
+/// so operations on unrelated parts of the graph don't contend. Locks are
+/// always taken relation shards first and then node shards, each in
+/// ascending order, which rules out deadlocks. `remove_node` and
+/// `remove_relation` lock every shard, since the edges they touch aren't
+/// known up front.
+pub struct ConcurrentGraph {
+    nodes: Box<[Shard<NodeId, NodeEntry>]>,
+    relations: Box<[Shard<RelationId, Relation>]>,
+    node_id_generator: IdGenerator,
+    relation_id_generator: IdGenerator,
+}
+
+impl Default for ConcurrentGraph {
+    fn default() -> Self {
+        Self::with_shards(DEFAULT_SHARDS)
+    }
+}
+
+impl ConcurrentGraph {
+    /// Create an empty `ConcurrentGraph`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create an empty `ConcurrentGraph` with `shards` node and relation shards.
+    pub fn with_shards(shards: usize) -> Self {
+        let shards = shards.max(1);
+        Self {
+            nodes: (0..shards).map(|_| RwLock::default()).collect(),
+            relations: (0..shards).map(|_| RwLock::default()).collect(),
+            node_id_generator: IdGenerator::default(),
+            relation_id_generator: IdGenerator::default(),
+        }
+    }
+
+    fn node_shard(&self, node_id: NodeId) -> usize {
+        (*node_id % self.nodes.len() as u64) as usize
+    }
+
+    fn relation_shard(&self, relation_id: RelationId) -> usize {
+        (relation_id.get() % self.relations.len() as u64) as usize
+    }
+
+    /// Create a `Node` in the graph with `info` and return its `NodeId`.
+    pub fn add_node<I>(&self, info: I) -> NodeId
+    where
+        I: Into<AnyNodeInfo>,
+    {
+        let id = NodeId::new(self.node_id_generator.generate_id());
+        let entry = NodeEntry {
+            info: info.into(),
+            next_nodes: HashMap::new(),
+            prev_nodes: HashMap::new(),
+        };
+        write(&self.nodes[self.node_shard(id)]).insert(id, entry);
+        id
+    }
+
+    /// Remove the `Node` at `node_id` and return its info if it was removed.
+    pub fn remove_node(&self, node_id: NodeId) -> Option<AnyNodeInfo> {
+        let mut relations: Vec<_> = self.relations.iter().map(write).collect();
+        let mut nodes: Vec<_> = self.nodes.iter().map(write).collect();
+
+        let entry = nodes[self.node_shard(node_id)].remove(&node_id)?;
+        for (src_id, relation_ids) in &entry.prev_nodes {
+            if let Some(src) = nodes[self.node_shard(*src_id)].get_mut(src_id) {
+                src.next_nodes.remove(&node_id);
+            }
+            for relation_id in relation_ids {
+                if let Some(relation) =
+                    relations[self.relation_shard(*relation_id)].get_mut(relation_id)
+                {
+                    relation.remove_edge(&Edge::new(*src_id, node_id));
+                }
+            }
+        }
+        for (dst_id, relation_ids) in &entry.next_nodes {
+            if let Some(dst) = nodes[self.node_shard(*dst_id)].get_mut(dst_id) {
+                dst.prev_nodes.remove(&node_id);
+            }
+            for relation_id in relation_ids {
+                if let Some(relation) =
+                    relations[self.relation_shard(*relation_id)].get_mut(relation_id)
+                {
+                    relation.remove_edge(&Edge::new(node_id, *dst_id));
+                }
+            }
+        }
+        Some(entry.info)
+    }
+
+    /// Create a `Relation` in the graph with `info` and return its `RelationId`.
+    pub fn add_relation<I>(&self, info: I) -> RelationId
+    where
+        I: Into<AnyRelationInfo>,
+    {
+        let id = RelationId::new(self.relation_id_generator.generate_id());
+        write(&self.relations[self.relation_shard(id)]).insert(id, Relation::new(info));
+        id
+    }
+
+    /// Remove the `Relation` at `relation_id` and return its info if it was removed.
+    pub fn remove_relation(&self, relation_id: RelationId) -> Option<AnyRelationInfo> {
+        let mut relations: Vec<_> = self.relations.iter().map(write).collect();
+        let mut nodes: Vec<_> = self.nodes.iter().map(write).collect();
+
+        let relation = relations[self.relation_shard(relation_id)].remove(&relation_id)?;
+        for edge in relation.iter_edges() {
+            let (src, dst) = (edge.src(), edge.dst());
+            if let Some(entry) = nodes[self.node_shard(src)].get_mut(&src) {
+                remove_adjacency(&mut entry.next_nodes, dst, relation_id);
+            }
+            if let Some(entry) = nodes[self.node_shard(dst)].get_mut(&dst) {
+                remove_adjacency(&mut entry.prev_nodes, src, relation_id);
+            }
+        }
+        Some(relation.into_info())
+    }
+
+    /// Connect two `Nodes` in the graph with a `Relation`.
+    pub fn connect(
+        &self,
+        src: NodeId,
+        dst: NodeId,
+        relation_id: RelationId,
+    ) -> Result<bool, ConnectError> {
+        self.update_edge(src, dst, relation_id, true)
+    }
+
+    /// Disconnect the `Relation` between two `Nodes`.
+    pub fn disconnect(
+        &self,
+        src: NodeId,
+        dst: NodeId,
+        relation_id: RelationId,
+    ) -> Result<bool, ConnectError> {
+        self.update_edge(src, dst, relation_id, false)
+    }
+
+    fn update_edge(
+        &self,
+        src: NodeId,
+        dst: NodeId,
+        relation_id: RelationId,
+        insert: bool,
+    ) -> Result<bool, ConnectError> {
+        let mut relations = write(&self.relations[self.relation_shard(relation_id)]);
+        let src_shard = self.node_shard(src);
+        let dst_shard = self.node_shard(dst);
+        let mut first = write(&self.nodes[src_shard.min(dst_shard)]);
+        let mut second = if src_shard != dst_shard {
+            Some(write(&self.nodes[src_shard.max(dst_shard)]))
+        } else {
+            None
+        };
+        let (src_nodes, dst_nodes) = match &mut second {
+            None => (&mut *first, None),
+            Some(second) if src_shard < dst_shard => (&mut *first, Some(&mut **second)),
+            Some(second) => (&mut **second, Some(&mut *first)),
+        };
+
+        let dst_present = match &dst_nodes {
+            Some(dst_nodes) => dst_nodes.contains_key(&dst),
+            None => src_nodes.contains_key(&dst),
+        };
+        if !dst_present {
+            return Err(ConnectError::InvalidDstNodeId);
+        }
+        if !src_nodes.contains_key(&src) {
+            return Err(ConnectError::InvalidSrcNodeId);
+        }
+        let relation = relations
+            .get_mut(&relation_id)
+            .ok_or(ConnectError::InvalidRelationId)?;
+
+        let edge = Edge::new(src, dst);
+        let changed = if insert {
+            relation.insert_edge(edge)
+        } else {
+            relation.remove_edge(&edge)
+        };
+        if !changed {
+            return Ok(false);
+        }
+
+        let src_entry = src_nodes.get_mut(&src).unwrap();
+        if insert {
+            src_entry
+                .next_nodes
+                .entry(dst)
+                .or_default()
+                .insert(relation_id);
+        } else {
+            remove_adjacency(&mut src_entry.next_nodes, dst, relation_id);
+        }
+        let dst_entry = match dst_nodes {
+            Some(dst_nodes) => dst_nodes.get_mut(&dst).unwrap(),
+            None => src_nodes.get_mut(&dst).unwrap(),
+        };
+        if insert {
+            dst_entry
+                .prev_nodes
+                .entry(src)
+                .or_default()
+                .insert(relation_id);
+        } else {
+            remove_adjacency(&mut dst_entry.prev_nodes, src, relation_id);
+        }
+        Ok(true)
+    }
+
+    /// Get the number of `Nodes` in the graph.
+    pub fn nr_nodes(&self) -> usize {
+        self.nodes.iter().map(|shard| read(shard).len()).sum()
+    }
+
+    /// Get the number of `Relations` in the graph.
+    pub fn nr_relations(&self) -> usize {
+        self.relations.iter().map(|shard| read(shard).len()).sum()
+    }
+
+    /// Check if the graph contains a `Node` with `node_id`.
+    pub fn contains_node(&self, node_id: NodeId) -> bool {
+        read(&self.nodes[self.node_shard(node_id)]).contains_key(&node_id)
+    }
+
+    /// Check if the graph contains a `Relation` with `relation_id`.
+    pub fn contains_relation(&self, relation_id: RelationId) -> bool {
+        read(&self.relations[self.relation_shard(relation_id)]).contains_key(&relation_id)
+    }
+
+    /// Check if the `Relation` at `relation_id` contains an edge.
+    pub fn contains_edge(&self, edge: &Edge, relation_id: RelationId) -> bool {
+        read(&self.relations[self.relation_shard(relation_id)])
+            .get(&relation_id)
+            .is_some_and(|relation| relation.contains_edge(edge))
+    }
+
+    /// Call `f` with the info of a `Node` while its shard is read locked.
+    pub fn with_node_info<F, T>(&self, node_id: NodeId, f: F) -> Option<T>
+    where
+        F: FnOnce(&dyn NodeInfo) -> T,
+    {
+        read(&self.nodes[self.node_shard(node_id)])
+            .get(&node_id)
+            .map(|entry| f(&*entry.info))
+    }
+
+    /// Get the info of a `Node`, shared with the graph until one of them
+    /// mutates it.
+    pub(crate) fn any_node_info(&self, node_id: NodeId) -> Option<AnyNodeInfo> {
+        read(&self.nodes[self.node_shard(node_id)])
+            .get(&node_id)
+            .map(|entry| entry.info.clone())
+    }
+
+    /// Call `f` with the info of a `Node`, if it is a `T`, while its shard
+    /// is write locked. An info shared with a copy is copied first.
+    pub fn with_node_info_mut<T, F, R>(&self, node_id: NodeId, f: F) -> Option<R>
+    where
+        T: NodeInfo + Clone,
+        F: FnOnce(&mut T) -> R,
+    {
+        write(&self.nodes[self.node_shard(node_id)])
+            .get_mut(&node_id)?
+            .info
+            .make_mut()
+            .map(f)
+    }
+
+    /// Call `f` with a `Relation` while its shard is read locked.
+    pub fn with_relation<F, T>(&self, relation_id: RelationId, f: F) -> Option<T>
+    where
+        F: FnOnce(&Relation) -> T,
+    {
+        read(&self.relations[self.relation_shard(relation_id)])
+            .get(&relation_id)
+            .map(f)
+    }
+
+    /// Get the `NodeIds` of all `Nodes` in the graph.
+    pub fn node_ids(&self) -> Vec<NodeId> {
+        let mut node_ids = Vec::new();
+        for shard in self.nodes.iter() {
+            node_ids.extend(read(shard).keys().copied());
+        }
+        node_ids
+    }
+
+    /// Get the `RelationIds` of all `Relations` in the graph.
+    pub fn relation_ids(&self) -> Vec<RelationId> {
+        let mut relation_ids = Vec::new();
+        for shard in self.relations.iter() {
+            relation_ids.extend(read(shard).keys().copied());
+        }
+        relation_ids
+    }
+
+    /// Get the in degree of a `Node`.
+    pub fn in_degree_of(&self, node_id: NodeId) -> Option<usize> {
+        read(&self.nodes[self.node_shard(node_id)])
+            .get(&node_id)
+            .map(|entry| entry.prev_nodes.values().map(|r| r.len()).sum())
+    }
+
+    /// Get the out degree of a `Node`.
+    pub fn out_degree_of(&self, node_id: NodeId) -> Option<usize> {
+        read(&self.nodes[self.node_shard(node_id)])
+            .get(&node_id)
+            .map(|entry| entry.next_nodes.values().map(|r| r.len()).sum())
+    }
+
+    /// Get the destinations of all edges leaving a `Node`,
+    /// together with the `RelationId` of each edge.
+    pub fn next_nodes(&self, node_id: NodeId) -> Option<Vec<(NodeId, RelationId)>> {
+        read(&self.nodes[self.node_shard(node_id)])
+            .get(&node_id)
+            .map(|entry| flatten_adjacency(&entry.next_nodes))
+    }
+
+    /// Get the sources of all edges entering a `Node`,
+    /// together with the `RelationId` of each edge.
+    pub fn prev_nodes(&self, node_id: NodeId) -> Option<Vec<(NodeId, RelationId)>> {
+        read(&self.nodes[self.node_shard(node_id)])
+            .get(&node_id)
+            .map(|entry| flatten_adjacency(&entry.prev_nodes))
+    }
+
+    /// Get a copy of all edges with `relation_id`.
+    pub fn relation_edges(&self, relation_id: RelationId) -> Option<Vec<Edge>> {
+        self.with_relation(relation_id, |relation| {
+            relation.iter_edges().copied().collect()
+        })
+    }
+
+    /// Consume the graph and turn it into a single threaded `Graph`
+    /// with the same ids.
+    pub fn into_graph(self) -> Graph {
+        let mut graph = Graph::new();
+        let mut edges = Vec::new();
+        for shard in self.relations.into_vec() {
+            for (relation_id, relation) in into_inner(shard) {
+                edges.extend(relation.iter_edges().map(|edge| (*edge, relation_id)));
+                graph.insert_relation(relation_id, &relation);
+            }
+        }
+        for shard in self.nodes.into_vec() {
+            for (node_id, entry) in into_inner(shard) {
+                graph.insert_node(node_id, entry.info);
+            }
+        }
+        for (edge, relation_id) in edges {
+            graph.connect(edge.src(), edge.dst(), relation_id).unwrap();
+        }
+        graph.reserve_ids(
+            self.node_id_generator.peek_id() - 1,
+            self.relation_id_generator.peek_id() - 1,
+        );
+        graph
+    }
+}
+
+fn remove_adjacency(
+    adjacency: &mut HashMap<NodeId, HashSet<RelationId>>,
+    node_id: NodeId,
+    relation_id: RelationId,
+) {
+    if let Some(relation_ids) = adjacency.get_mut(&node_id) {
+        relation_ids.remove(&relation_id);
+        if relation_ids.is_empty() {
+            adjacency.remove(&node_id);
+        }
+    }
+}
+
+fn flatten_adjacency(
+    adjacency: &HashMap<NodeId, HashSet<RelationId>>,
+) -> Vec<(NodeId, RelationId)> {
+    adjacency
+        .iter()
+        .flat_map(|(node_id, relation_ids)| relation_ids.iter().map(move |r| (*node_id, *r)))
+        .collect()
+}
+
+// A panic while a lock is held can't leave a shard half updated, since
+// shards are only mutated after all checks have passed, so poisoning is
+// ignored.
+
+fn read<T>(lock: &RwLock<T>) -> RwLockReadGuard<'_, T> {
+    lock.read().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn write<T>(lock: &RwLock<T>) -> RwLockWriteGuard<'_, T> {
+    lock.write().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn into_inner<T>(lock: RwLock<T>) -> T {
+    lock.into_inner().unwrap_or_else(PoisonError::into_inner)
+}
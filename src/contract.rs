@@ -0,0 +1,218 @@
+use crate::edge::{AnyEdgeInfo, Edge};
+use crate::errors::MergeError;
+use crate::graph::Graph;
+use crate::hyperedge::Hyperedge;
+use crate::node::{AnyNodeInfo, NodeId};
+use crate::property::{Properties, PropertyOwner};
+use crate::relation::RelationId;
+use std::collections::HashSet;
+
+/// What `Graph::contract_edge` does with the other edges between the two
+/// `Nodes`, which become self-loops of the kept node.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum SelfLoops {
+    /// Keep the edges as self-loops.
+    Keep,
+    /// Disconnect the edges.
+    Drop,
+}
+
+/// An edge of the absorbed `Node`, with what is needed to connect it again.
+struct MovedEdge {
+    edge: Edge,
+    relation_id: RelationId,
+    /// The info of every parallel edge, or of the edge itself.
+    infos: Vec<Option<AnyEdgeInfo>>,
+    properties: Option<Properties>,
+}
+
+/// Collect every edge of `node_id` in the relations that are not
+/// hyperedge relations.
+fn incident_edges(graph: &Graph, node_id: NodeId) -> Vec<MovedEdge> {
+    let next_nodes = graph.iter_next_nodes(node_id).into_iter().flatten();
+    let next_edges = next_nodes.map(|(dst, r)| (Edge::new(node_id, dst), r));
+    let prev_nodes = graph.iter_prev_nodes(node_id).into_iter().flatten();
+    let prev_edges = prev_nodes.map(|(src, r)| (Edge::new(src, node_id), r));
+    let mut seen = HashSet::new();
+    let mut edges = Vec::new();
+    for (edge, relation_id) in next_edges.chain(prev_edges) {
+        let relation = graph.relation(relation_id).unwrap();
+        let edge = relation.kind().normalize(edge);
+        if !seen.insert((edge, relation_id)) {
+            continue;
+        }
+        let infos = match relation.is_multi() {
+            true => relation
+                .iter_edge_ids(&edge)
+                .map(|edge_id| relation.any_parallel_edge_info(edge_id).cloned())
+                .collect(),
+            false => vec![relation.any_edge_info(&edge).cloned()],
+        };
+        edges.push(MovedEdge {
+            edge,
+            relation_id,
+            infos,
+            properties: relation.edge_properties(&edge).cloned(),
+        });
+    }
+    // Reconnect in a fixed order, so the new parallel edge ids don't
+    // depend on the order of the adjacency maps
+    edges.sort_by_key(|moved| {
+        (
+            moved.relation_id.get(),
+            *moved.edge.src(),
+            *moved.edge.dst(),
+        )
+    });
+    edges
+}
+
+/// Connect a moved edge between its new endpoints. An edge that already
+/// exists keeps its info and the properties it has.
+fn connect_moved(graph: &mut Graph, moved: MovedEdge, edge: Edge) -> Result<(), MergeError> {
+    let (src, dst, relation_id) = (edge.src(), edge.dst(), moved.relation_id);
+    let relation = graph.relation(relation_id).unwrap();
+    if relation.is_multi() {
+        for info in moved.infos {
+            match info {
+                Some(info) => graph.connect_multi_with(src, dst, relation_id, info)?,
+                None => graph.connect_multi(src, dst, relation_id)?,
+            };
+        }
+    } else {
+        let has_info = relation.edge_info(&edge).is_some();
+        match moved.infos.into_iter().next().flatten() {
+            Some(info) if !has_info => graph.connect_with(src, dst, relation_id, info)?,
+            _ => graph.connect(src, dst, relation_id)?,
+        };
+    }
+    let owner = PropertyOwner::Edge(edge, relation_id);
+    for (key, value) in moved.properties.iter().flat_map(|p| p.iter()) {
+        if graph.property(owner, key).is_none() {
+            let _ = graph.set_property(owner, key, value.clone());
+        }
+    }
+    Ok(())
+}
+
+/// Move the edges, hyperedges, labels and properties of `absorb` to
+/// `keep`, give `keep` the info from `info_merge` and remove `absorb`.
+///
+/// Edges between the two nodes are passed to `keep_loop`, which decides
+/// whether each is kept as a self-loop. It is called for every parallel
+/// edge of a multigraph relation, with its position among the parallel
+/// edges in `EdgeId` order. Nothing is changed if a moved edge or the
+/// merged info is rejected.
+pub(crate) fn merge_nodes<MF, LF>(
+    graph: &mut Graph,
+    keep: NodeId,
+    absorb: NodeId,
+    info_merge: MF,
+    keep_loop: LF,
+) -> Result<(), MergeError>
+where
+    MF: FnOnce(&AnyNodeInfo, &AnyNodeInfo) -> AnyNodeInfo,
+    LF: Fn(Edge, RelationId, usize) -> bool,
+{
+    if keep == absorb {
+        return Err(MergeError::SameNode);
+    }
+    let (keep_info, absorb_info) = match (graph.any_node_info(keep), graph.any_node_info(absorb)) {
+        (Some(keep_info), Some(absorb_info)) => (keep_info, absorb_info),
+        _ => return Err(MergeError::InvalidNodeId),
+    };
+    let info = info_merge(keep_info, absorb_info);
+    let edges = incident_edges(graph, absorb);
+    // Hyperedges are connected again with new ids, in their old order
+    let mut hyperedges: Vec<_> = graph
+        .iter_hyperedges_of(absorb)
+        .into_iter()
+        .flatten()
+        .map(|(edge_id, relation_id)| {
+            let hyperedge = graph.hyperedge(edge_id).unwrap().0.clone();
+            (edge_id, relation_id, hyperedge)
+        })
+        .collect();
+    hyperedges.sort_by_key(|(edge_id, _, _)| **edge_id);
+    let labels: Vec<_> = graph
+        .labels_of(absorb)
+        .into_iter()
+        .flatten()
+        .cloned()
+        .collect();
+    let properties = graph.properties(PropertyOwner::Node(absorb)).cloned();
+
+    graph.transaction(|graph| {
+        graph.remove_node(absorb);
+        graph.replace_node_info(keep, info)?;
+        for label in &labels {
+            graph.add_label(keep, label);
+        }
+        let owner = PropertyOwner::Node(keep);
+        for (key, value) in properties.iter().flat_map(|p| p.iter()) {
+            if graph.property(owner, key).is_none() {
+                let _ = graph.set_property(owner, key, value.clone());
+            }
+        }
+        let moved_id = |node_id| match node_id == absorb {
+            true => keep,
+            false => node_id,
+        };
+        for mut moved in edges {
+            let (src, dst) = (moved.edge.src(), moved.edge.dst());
+            let is_between = (src == keep && dst == absorb) || (src == absorb && dst == keep);
+            if is_between {
+                let (edge, relation_id) = (moved.edge, moved.relation_id);
+                let mut i = 0;
+                moved.infos.retain(|_| {
+                    i += 1;
+                    keep_loop(edge, relation_id, i - 1)
+                });
+                if moved.infos.is_empty() {
+                    continue;
+                }
+            }
+            let edge = Edge::new(moved_id(src), moved_id(dst));
+            connect_moved(graph, moved, edge)?;
+        }
+        for (_, relation_id, hyperedge) in hyperedges {
+            let sources = hyperedge.sources().iter().map(|id| moved_id(*id));
+            let targets = hyperedge.targets().iter().map(|id| moved_id(*id));
+            graph.connect_hyperedge(relation_id, Hyperedge::new(sources, targets))?;
+        }
+        Ok(())
+    })
+}
+
+/// Merge `dst` into `src` and disconnect the contracted edge, or in a
+/// multigraph relation the oldest of its parallel edges.
+pub(crate) fn contract_edge(
+    graph: &mut Graph,
+    src: NodeId,
+    dst: NodeId,
+    relation_id: RelationId,
+    self_loops: SelfLoops,
+) -> Result<(), MergeError> {
+    let contracted = match graph.relation(relation_id) {
+        Some(relation) if relation.contains_edge(&Edge::new(src, dst)) => {
+            relation.kind().normalize(Edge::new(src, dst))
+        }
+        _ => return Err(MergeError::InvalidEdge),
+    };
+    // The reverse edge in an inverse relation is contracted along
+    let inverse = graph.inverse_of(relation_id).map(|inverse| {
+        let kind = graph.relation(inverse).unwrap().kind();
+        (kind.normalize(contracted.reversed()), inverse)
+    });
+    merge_nodes(
+        graph,
+        src,
+        dst,
+        |info, _| info.clone(),
+        |edge, r, i| {
+            let is_contracted =
+                (edge, r) == (contracted, relation_id) || Some((edge, r)) == inverse;
+            self_loops == SelfLoops::Keep && !(is_contracted && i == 0)
+        },
+    )
+}
@@ -0,0 +1,785 @@
+use crate::codec::{
+    read_edge_info, read_hyperedge, read_name, read_property_owner, read_value, write_bytes,
+    write_edge_info, write_hyperedge, write_name, write_property_owner, write_u64, write_value,
+    InfoCodec, Reader,
+};
+use crate::edge::{AnyEdgeInfo, Edge, EdgeId};
+use crate::errors::{CodecError, WriteError};
+use crate::graph::Graph;
+use crate::hyperedge::Hyperedge;
+use crate::label::Label;
+use crate::node::{AnyNodeInfo, NodeId};
+use crate::property::{PropertyKey, PropertyOwner};
+use crate::relation::{Relation, RelationId};
+use crate::value::Value;
+use std::sync::Arc;
+
+const MAGIC: &[u8; 8] = b"GFDIFF08";
+
+/// The changes that turn one `Graph` into another, made with `Graph::diff`.
+///
+/// Nodes and relations are matched by their ids. Nodes whose info is no
+/// longer equal are listed as changed, and so are edges whose info is no
+/// longer equal or was attached or detached. Edges of relations that are
+/// removed, and edges of nodes that are removed, are not listed, since they
+/// disappear together with the relation or node. Added edges carry their
+/// info. Relations that keep their id but change their name are listed as
+/// renamed, and pairs of inverse relations that were set or removed are
+/// listed as well.
+/// Parallel edges of multigraph relations and hyperedges are matched by
+/// their `EdgeId`. Labels of added nodes are listed as added labels.
+/// Properties that were added or changed are listed as set, including the
+/// properties of added nodes, relations and edges.
+#[derive(Clone, Default)]
+pub struct GraphDiff {
+    added_nodes: Vec<(NodeId, AnyNodeInfo)>,
+    removed_nodes: Vec<NodeId>,
+    changed_nodes: Vec<(NodeId, AnyNodeInfo)>,
+    added_labels: Vec<(NodeId, Label)>,
+    removed_labels: Vec<(NodeId, Label)>,
+    added_relations: Vec<(RelationId, Relation)>,
+    removed_relations: Vec<RelationId>,
+    renamed_relations: Vec<(RelationId, Option<String>)>,
+    set_inverses: Vec<(RelationId, RelationId)>,
+    removed_inverses: Vec<RelationId>,
+    added_edges: Vec<(Edge, RelationId, Option<AnyEdgeInfo>)>,
+    removed_edges: Vec<(Edge, RelationId)>,
+    changed_edges: Vec<(Edge, RelationId, Option<AnyEdgeInfo>)>,
+    added_parallel_edges: Vec<(EdgeId, Edge, RelationId, Option<AnyEdgeInfo>)>,
+    removed_parallel_edges: Vec<EdgeId>,
+    changed_parallel_edges: Vec<(EdgeId, Option<AnyEdgeInfo>)>,
+    added_hyperedges: Vec<(EdgeId, RelationId, Hyperedge)>,
+    removed_hyperedges: Vec<EdgeId>,
+    set_properties: Vec<(PropertyOwner, PropertyKey, Value)>,
+    removed_properties: Vec<(PropertyOwner, PropertyKey)>,
+}
+
+impl GraphDiff {
+    /// Compute the changes that turn `old` into `new`, comparing infos by
+    /// their encoding with `codec`.
+    pub(crate) fn between<C>(old: &Graph, new: &Graph, codec: &C) -> Self
+    where
+        C: InfoCodec,
+    {
+        let mut diff = Self::default();
+        for (node_id, info) in new.iter_node_ids() {
+            match old.any_node_info(node_id) {
+                None => diff.added_nodes.push((node_id, info.clone())),
+                Some(old_info) if !same_node_info(codec, old_info, info) => {
+                    diff.changed_nodes.push((node_id, info.clone()))
+                }
+                Some(_) => {}
+            }
+        }
+        for (node_id, _) in old.iter_node_ids() {
+            if !new.contains_node(node_id) {
+                diff.removed_nodes.push(node_id);
+            }
+        }
+        for (node_id, _) in new.iter_node_ids() {
+            for label in new.labels_of(node_id).into_iter().flatten() {
+                if !old.has_label(node_id, label) {
+                    diff.added_labels.push((node_id, label.clone()));
+                }
+            }
+            for label in old.labels_of(node_id).into_iter().flatten() {
+                if !new.has_label(node_id, label) {
+                    diff.removed_labels.push((node_id, label.clone()));
+                }
+            }
+        }
+
+        for (relation_id, relation) in new.iter_relations() {
+            let old_relation = old.relation(relation_id);
+            match old_relation {
+                None => diff
+                    .added_relations
+                    .push((relation_id, relation.without_edges())),
+                Some(old_relation) if old_relation.name() != relation.name() => diff
+                    .renamed_relations
+                    .push((relation_id, relation.name().map(String::from))),
+                Some(_) => {}
+            }
+            if relation.is_multi() {
+                diff.add_parallel_edges(old_relation, relation, relation_id, new, codec);
+                continue;
+            }
+            if relation.is_hyper() {
+                diff.add_hyperedges(old_relation, relation, relation_id, new);
+                continue;
+            }
+            match old_relation {
+                None => {
+                    diff.added_edges.extend(
+                        relation
+                            .iter_edges()
+                            .map(|edge| with_info(relation, *edge, relation_id)),
+                    );
+                }
+                Some(old_relation) => {
+                    diff.added_edges.extend(
+                        relation
+                            .iter_edges()
+                            .filter(|edge| !old_relation.contains_edge(edge))
+                            .map(|edge| with_info(relation, *edge, relation_id)),
+                    );
+                    diff.removed_edges.extend(
+                        old_relation
+                            .iter_edges()
+                            .filter(|edge| !relation.contains_edge(edge))
+                            .filter(|edge| {
+                                new.contains_node(edge.src()) && new.contains_node(edge.dst())
+                            })
+                            .map(|edge| (*edge, relation_id)),
+                    );
+                    diff.changed_edges.extend(
+                        relation
+                            .iter_edges()
+                            .filter(|edge| {
+                                old_relation.contains_edge(edge)
+                                    && !same_edge_info(
+                                        codec,
+                                        old_relation.any_edge_info(edge),
+                                        relation.any_edge_info(edge),
+                                    )
+                            })
+                            .map(|edge| with_info(relation, *edge, relation_id)),
+                    );
+                }
+            }
+        }
+        for (relation_id, _) in old.iter_relations() {
+            if !new.contains_relation(relation_id) {
+                diff.removed_relations.push(relation_id);
+            }
+        }
+        for (a, b) in new.iter_inverses() {
+            if a.get() < b.get() && old.inverse_of(a) != Some(b) {
+                diff.set_inverses.push((a, b));
+            }
+        }
+        for (a, b) in old.iter_inverses() {
+            let kept = new.contains_relation(a) && new.contains_relation(b);
+            if a.get() < b.get() && kept && new.inverse_of(a) != Some(b) {
+                diff.removed_inverses.push(a);
+            }
+        }
+
+        for (owner, properties) in new.iter_properties() {
+            for (key, value) in properties.iter() {
+                if old.property(owner, key) != Some(value) {
+                    diff.set_properties
+                        .push((owner, key.clone(), value.clone()));
+                }
+            }
+        }
+        for (owner, properties) in old.iter_properties() {
+            if new.check_property_owner(owner).is_err() {
+                continue;
+            }
+            for (key, _) in properties.iter() {
+                if new.property(owner, key).is_none() {
+                    diff.removed_properties.push((owner, key.clone()));
+                }
+            }
+        }
+        diff
+    }
+
+    /// List the parallel edges of a multigraph relation that were added
+    /// or removed, matching them by their `EdgeId`.
+    fn add_parallel_edges<C>(
+        &mut self,
+        old_relation: Option<&Relation>,
+        relation: &Relation,
+        relation_id: RelationId,
+        new: &Graph,
+        codec: &C,
+    ) where
+        C: InfoCodec,
+    {
+        let is_old = |edge_id| old_relation.is_some_and(|old| old.parallel_edge(edge_id).is_some());
+        self.added_parallel_edges.extend(
+            relation
+                .iter_parallel_edges()
+                .filter(|(edge_id, _)| !is_old(*edge_id))
+                .map(|(edge_id, edge)| {
+                    let info = relation.any_parallel_edge_info(edge_id).cloned();
+                    (edge_id, edge, relation_id, info)
+                }),
+        );
+        self.removed_parallel_edges.extend(
+            old_relation
+                .into_iter()
+                .flat_map(|old| old.iter_parallel_edges())
+                .filter(|(edge_id, _)| relation.parallel_edge(*edge_id).is_none())
+                .filter(|(_, edge)| new.contains_node(edge.src()) && new.contains_node(edge.dst()))
+                .map(|(edge_id, _)| edge_id),
+        );
+        self.changed_parallel_edges.extend(
+            relation
+                .iter_parallel_edges()
+                .filter(|(edge_id, _)| {
+                    old_relation.is_some_and(|old| {
+                        old.parallel_edge(*edge_id).is_some()
+                            && !same_edge_info(
+                                codec,
+                                old.any_parallel_edge_info(*edge_id),
+                                relation.any_parallel_edge_info(*edge_id),
+                            )
+                    })
+                })
+                .map(|(edge_id, _)| (edge_id, relation.any_parallel_edge_info(edge_id).cloned())),
+        );
+    }
+
+    /// List the hyperedges of a hyperedge relation that were added or
+    /// removed, matching them by their `EdgeId`.
+    fn add_hyperedges(
+        &mut self,
+        old_relation: Option<&Relation>,
+        relation: &Relation,
+        relation_id: RelationId,
+        new: &Graph,
+    ) {
+        let is_old = |edge_id| old_relation.is_some_and(|old| old.hyperedge(edge_id).is_some());
+        self.added_hyperedges.extend(
+            relation
+                .iter_hyperedges()
+                .filter(|(edge_id, _)| !is_old(*edge_id))
+                .map(|(edge_id, hyperedge)| (edge_id, relation_id, hyperedge.clone())),
+        );
+        self.removed_hyperedges.extend(
+            old_relation
+                .into_iter()
+                .flat_map(|old| old.iter_hyperedges())
+                .filter(|(edge_id, _)| relation.hyperedge(*edge_id).is_none())
+                .filter(|(_, hyperedge)| hyperedge.iter_nodes().all(|id| new.contains_node(id)))
+                .map(|(edge_id, _)| edge_id),
+        );
+    }
+
+    /// Apply the changes to `graph`, stopping at the first error.
+    pub(crate) fn apply_to(&self, graph: &mut Graph) -> Result<(), WriteError> {
+        for (owner, key) in &self.removed_properties {
+            graph.remove_property(*owner, key);
+        }
+        // Pairings are removed first, so removed edges are not mirrored
+        for relation_id in &self.removed_inverses {
+            graph.remove_inverse(*relation_id);
+        }
+        for (edge, relation_id) in &self.removed_edges {
+            graph.disconnect(edge.src(), edge.dst(), *relation_id)?;
+        }
+        for edge_id in &self.removed_parallel_edges {
+            graph.disconnect_edge(*edge_id);
+        }
+        for edge_id in &self.removed_hyperedges {
+            graph.disconnect_hyperedge(*edge_id);
+        }
+        for relation_id in &self.removed_relations {
+            graph.remove_relation(*relation_id);
+        }
+        for (node_id, label) in &self.removed_labels {
+            graph.remove_label(*node_id, label);
+        }
+        for node_id in &self.removed_nodes {
+            graph.remove_node(*node_id);
+        }
+        for (node_id, info) in &self.changed_nodes {
+            graph.replace_node_info(*node_id, info.clone())?;
+        }
+        // Names are cleared first, so relations can swap their names
+        for (relation_id, _) in &self.renamed_relations {
+            graph.set_relation_name(*relation_id, None)?;
+        }
+        for (relation_id, name) in &self.renamed_relations {
+            let name = name.as_deref().map(Arc::from);
+            graph.set_relation_name(*relation_id, name)?;
+        }
+        for (node_id, info) in &self.added_nodes {
+            graph.check_unique(Some(*node_id), info)?;
+            graph.insert_node(*node_id, info.clone());
+        }
+        for (node_id, label) in &self.added_labels {
+            graph.add_label(*node_id, label);
+        }
+        for (relation_id, relation) in &self.added_relations {
+            graph.insert_relation(*relation_id, relation);
+        }
+        for (edge, relation_id, info) in self.added_edges.iter().chain(&self.changed_edges) {
+            match info {
+                Some(info) => {
+                    graph.connect_with(edge.src(), edge.dst(), *relation_id, info.clone())?;
+                }
+                None => {
+                    graph.connect(edge.src(), edge.dst(), *relation_id)?;
+                    graph.remove_edge_info(edge.src(), edge.dst(), *relation_id);
+                }
+            }
+        }
+        for (edge_id, edge, relation_id, info) in &self.added_parallel_edges {
+            graph.insert_parallel_edge(*edge_id, *edge, *relation_id, info.clone())?;
+        }
+        for (edge_id, info) in &self.changed_parallel_edges {
+            graph.set_parallel_edge_info(*edge_id, info.clone());
+        }
+        for (edge_id, relation_id, hyperedge) in &self.added_hyperedges {
+            graph.insert_hyperedge(*edge_id, *relation_id, hyperedge.clone())?;
+        }
+        for (a, b) in &self.set_inverses {
+            graph.set_inverse(*a, *b)?;
+        }
+        for (owner, key, value) in &self.set_properties {
+            graph.set_property(*owner, key, value.clone())?;
+        }
+        Ok(())
+    }
+
+    /// Check if the diff contains no changes.
+    pub fn is_empty(&self) -> bool {
+        self.added_nodes.is_empty()
+            && self.removed_nodes.is_empty()
+            && self.changed_nodes.is_empty()
+            && self.added_labels.is_empty()
+            && self.removed_labels.is_empty()
+            && self.added_relations.is_empty()
+            && self.removed_relations.is_empty()
+            && self.renamed_relations.is_empty()
+            && self.set_inverses.is_empty()
+            && self.removed_inverses.is_empty()
+            && self.added_edges.is_empty()
+            && self.removed_edges.is_empty()
+            && self.changed_edges.is_empty()
+            && self.added_parallel_edges.is_empty()
+            && self.removed_parallel_edges.is_empty()
+            && self.changed_parallel_edges.is_empty()
+            && self.added_hyperedges.is_empty()
+            && self.removed_hyperedges.is_empty()
+            && self.set_properties.is_empty()
+            && self.removed_properties.is_empty()
+    }
+
+    /// Get an iterator over the ids and infos of the added `Nodes`.
+    pub fn iter_added_nodes(&self) -> impl Iterator<Item = (NodeId, &AnyNodeInfo)> {
+        self.added_nodes.iter().map(|(id, info)| (*id, info))
+    }
+
+    /// Get an iterator over the ids of the removed `Nodes`.
+    pub fn iter_removed_nodes(&self) -> impl Iterator<Item = NodeId> + '_ {
+        self.removed_nodes.iter().copied()
+    }
+
+    /// Get an iterator over the ids and new infos of the changed `Nodes`.
+    pub fn iter_changed_nodes(&self) -> impl Iterator<Item = (NodeId, &AnyNodeInfo)> {
+        self.changed_nodes.iter().map(|(id, info)| (*id, info))
+    }
+
+    /// Get an iterator over the labels added to `Nodes`.
+    pub fn iter_added_labels(&self) -> impl Iterator<Item = (NodeId, &Label)> {
+        self.added_labels.iter().map(|(id, label)| (*id, label))
+    }
+
+    /// Get an iterator over the labels removed from `Nodes` that are kept.
+    pub fn iter_removed_labels(&self) -> impl Iterator<Item = (NodeId, &Label)> {
+        self.removed_labels.iter().map(|(id, label)| (*id, label))
+    }
+
+    /// Get an iterator over the ids of the added `Relations`, together with
+    /// a copy of each relation without its edges.
+    pub fn iter_added_relations(&self) -> impl Iterator<Item = (RelationId, &Relation)> {
+        self.added_relations
+            .iter()
+            .map(|(id, relation)| (*id, relation))
+    }
+
+    /// Get an iterator over the ids of the removed `Relations`.
+    pub fn iter_removed_relations(&self) -> impl Iterator<Item = RelationId> + '_ {
+        self.removed_relations.iter().copied()
+    }
+
+    /// Get an iterator over the ids and new names of the renamed `Relations`.
+    pub fn iter_renamed_relations(&self) -> impl Iterator<Item = (RelationId, Option<&str>)> {
+        self.renamed_relations
+            .iter()
+            .map(|(id, name)| (*id, name.as_deref()))
+    }
+
+    /// Get an iterator over the pairs of relations that were made each
+    /// other's inverse.
+    pub fn iter_set_inverses(&self) -> impl Iterator<Item = (RelationId, RelationId)> + '_ {
+        self.set_inverses.iter().copied()
+    }
+
+    /// Get an iterator over the relations whose pairing with their inverse
+    /// was removed, one of each pair.
+    pub fn iter_removed_inverses(&self) -> impl Iterator<Item = RelationId> + '_ {
+        self.removed_inverses.iter().copied()
+    }
+
+    /// Get an iterator over the added edges and their `RelationIds`.
+    pub fn iter_added_edges(&self) -> impl Iterator<Item = (Edge, RelationId)> + '_ {
+        self.added_edges
+            .iter()
+            .map(|(edge, relation_id, _)| (*edge, *relation_id))
+    }
+
+    /// Get an iterator over the removed edges and their `RelationIds`.
+    pub fn iter_removed_edges(&self) -> impl Iterator<Item = (Edge, RelationId)> + '_ {
+        self.removed_edges.iter().copied()
+    }
+
+    /// Get an iterator over the edges whose info changed and their
+    /// `RelationIds`.
+    pub fn iter_changed_edges(&self) -> impl Iterator<Item = (Edge, RelationId)> + '_ {
+        self.changed_edges
+            .iter()
+            .map(|(edge, relation_id, _)| (*edge, *relation_id))
+    }
+
+    /// Get an iterator over the added parallel edges, together with the
+    /// nodes they connect and their `RelationIds`.
+    pub fn iter_added_parallel_edges(
+        &self,
+    ) -> impl Iterator<Item = (EdgeId, Edge, RelationId)> + '_ {
+        self.added_parallel_edges
+            .iter()
+            .map(|(edge_id, edge, relation_id, _)| (*edge_id, *edge, *relation_id))
+    }
+
+    /// Get an iterator over the `EdgeIds` of the removed parallel edges.
+    pub fn iter_removed_parallel_edges(&self) -> impl Iterator<Item = EdgeId> + '_ {
+        self.removed_parallel_edges.iter().copied()
+    }
+
+    /// Get an iterator over the `EdgeIds` of the parallel edges whose info
+    /// changed.
+    pub fn iter_changed_parallel_edges(&self) -> impl Iterator<Item = EdgeId> + '_ {
+        self.changed_parallel_edges
+            .iter()
+            .map(|(edge_id, _)| *edge_id)
+    }
+
+    /// Get an iterator over the added hyperedges and their `RelationIds`.
+    pub fn iter_added_hyperedges(
+        &self,
+    ) -> impl Iterator<Item = (EdgeId, RelationId, &Hyperedge)> + '_ {
+        self.added_hyperedges
+            .iter()
+            .map(|(edge_id, relation_id, hyperedge)| (*edge_id, *relation_id, hyperedge))
+    }
+
+    /// Get an iterator over the `EdgeIds` of the removed hyperedges.
+    pub fn iter_removed_hyperedges(&self) -> impl Iterator<Item = EdgeId> + '_ {
+        self.removed_hyperedges.iter().copied()
+    }
+
+    /// Get an iterator over the properties that were added or changed,
+    /// together with their new values.
+    pub fn iter_set_properties(
+        &self,
+    ) -> impl Iterator<Item = (PropertyOwner, &PropertyKey, &Value)> {
+        self.set_properties
+            .iter()
+            .map(|(owner, key, value)| (*owner, key, value))
+    }
+
+    /// Get an iterator over the properties that were removed from owners
+    /// that are kept.
+    pub fn iter_removed_properties(&self) -> impl Iterator<Item = (PropertyOwner, &PropertyKey)> {
+        self.removed_properties
+            .iter()
+            .map(|(owner, key)| (*owner, key))
+    }
+
+    /// Encode the diff to bytes, using `codec` for the infos.
+    /// Schemas of added relations are not encoded.
+    pub fn encode<C>(&self, codec: &C) -> Result<Vec<u8>, CodecError>
+    where
+        C: InfoCodec,
+    {
+        let mut bytes = MAGIC.to_vec();
+        let mut info = Vec::new();
+
+        write_u64(&mut bytes, self.added_nodes.len() as u64);
+        for (node_id, node_info) in &self.added_nodes {
+            info.clear();
+            codec.encode_node_info(&**node_info, &mut info)?;
+            write_u64(&mut bytes, **node_id);
+            write_bytes(&mut bytes, &info);
+        }
+        write_u64(&mut bytes, self.removed_nodes.len() as u64);
+        for node_id in &self.removed_nodes {
+            write_u64(&mut bytes, **node_id);
+        }
+        write_u64(&mut bytes, self.changed_nodes.len() as u64);
+        for (node_id, node_info) in &self.changed_nodes {
+            info.clear();
+            codec.encode_node_info(&**node_info, &mut info)?;
+            write_u64(&mut bytes, **node_id);
+            write_bytes(&mut bytes, &info);
+        }
+        for labels in [&self.added_labels, &self.removed_labels] {
+            write_u64(&mut bytes, labels.len() as u64);
+            for (node_id, label) in labels {
+                write_u64(&mut bytes, **node_id);
+                write_bytes(&mut bytes, label.as_bytes());
+            }
+        }
+
+        write_u64(&mut bytes, self.added_relations.len() as u64);
+        for (relation_id, relation) in &self.added_relations {
+            info.clear();
+            codec.encode_relation_info(relation.info(), &mut info)?;
+            write_u64(&mut bytes, relation_id.get());
+            bytes.push(relation.kind().code());
+            bytes.push(relation.shape_code());
+            write_name(&mut bytes, relation.name());
+            write_bytes(&mut bytes, &info);
+        }
+        write_u64(&mut bytes, self.removed_relations.len() as u64);
+        for relation_id in &self.removed_relations {
+            write_u64(&mut bytes, relation_id.get());
+        }
+        write_u64(&mut bytes, self.renamed_relations.len() as u64);
+        for (relation_id, name) in &self.renamed_relations {
+            write_u64(&mut bytes, relation_id.get());
+            write_name(&mut bytes, name.as_deref());
+        }
+        write_u64(&mut bytes, self.set_inverses.len() as u64);
+        for (a, b) in &self.set_inverses {
+            write_u64(&mut bytes, a.get());
+            write_u64(&mut bytes, b.get());
+        }
+        write_u64(&mut bytes, self.removed_inverses.len() as u64);
+        for relation_id in &self.removed_inverses {
+            write_u64(&mut bytes, relation_id.get());
+        }
+
+        write_u64(&mut bytes, self.added_edges.len() as u64);
+        for (edge, relation_id, edge_info) in &self.added_edges {
+            write_edge(&mut bytes, edge, *relation_id);
+            write_edge_info(&mut bytes, edge_info, codec)?;
+        }
+        write_u64(&mut bytes, self.removed_edges.len() as u64);
+        for (edge, relation_id) in &self.removed_edges {
+            write_edge(&mut bytes, edge, *relation_id);
+        }
+        write_u64(&mut bytes, self.changed_edges.len() as u64);
+        for (edge, relation_id, edge_info) in &self.changed_edges {
+            write_edge(&mut bytes, edge, *relation_id);
+            write_edge_info(&mut bytes, edge_info, codec)?;
+        }
+
+        write_u64(&mut bytes, self.added_parallel_edges.len() as u64);
+        for (edge_id, edge, relation_id, edge_info) in &self.added_parallel_edges {
+            write_u64(&mut bytes, **edge_id);
+            write_edge(&mut bytes, edge, *relation_id);
+            write_edge_info(&mut bytes, edge_info, codec)?;
+        }
+        write_u64(&mut bytes, self.removed_parallel_edges.len() as u64);
+        for edge_id in &self.removed_parallel_edges {
+            write_u64(&mut bytes, **edge_id);
+        }
+        write_u64(&mut bytes, self.changed_parallel_edges.len() as u64);
+        for (edge_id, edge_info) in &self.changed_parallel_edges {
+            write_u64(&mut bytes, **edge_id);
+            write_edge_info(&mut bytes, edge_info, codec)?;
+        }
+
+        write_u64(&mut bytes, self.added_hyperedges.len() as u64);
+        for (edge_id, relation_id, hyperedge) in &self.added_hyperedges {
+            write_u64(&mut bytes, **edge_id);
+            write_u64(&mut bytes, relation_id.get());
+            write_hyperedge(&mut bytes, hyperedge);
+        }
+        write_u64(&mut bytes, self.removed_hyperedges.len() as u64);
+        for edge_id in &self.removed_hyperedges {
+            write_u64(&mut bytes, **edge_id);
+        }
+
+        write_u64(&mut bytes, self.set_properties.len() as u64);
+        for (owner, key, value) in &self.set_properties {
+            write_property_owner(&mut bytes, *owner);
+            write_bytes(&mut bytes, key.as_bytes());
+            write_value(&mut bytes, value);
+        }
+        write_u64(&mut bytes, self.removed_properties.len() as u64);
+        for (owner, key) in &self.removed_properties {
+            write_property_owner(&mut bytes, *owner);
+            write_bytes(&mut bytes, key.as_bytes());
+        }
+        Ok(bytes)
+    }
+
+    /// Decode a diff encoded with `encode`, using `codec` for the infos.
+    pub fn decode<C>(bytes: &[u8], codec: &C) -> Result<Self, CodecError>
+    where
+        C: InfoCodec,
+    {
+        let mut reader = Reader::new(bytes);
+        if reader.read_slice(MAGIC.len())? != MAGIC {
+            return Err(CodecError::Malformed);
+        }
+        let mut diff = Self::default();
+
+        for _ in 0..reader.read_u64()? {
+            let node_id = NodeId::new(reader.read_u64()?);
+            let info = codec.decode_node_info(reader.read_bytes()?)?;
+            diff.added_nodes.push((node_id, info));
+        }
+        for _ in 0..reader.read_u64()? {
+            diff.removed_nodes.push(NodeId::new(reader.read_u64()?));
+        }
+        for _ in 0..reader.read_u64()? {
+            let node_id = NodeId::new(reader.read_u64()?);
+            let info = codec.decode_node_info(reader.read_bytes()?)?;
+            diff.changed_nodes.push((node_id, info));
+        }
+        for labels in [&mut diff.added_labels, &mut diff.removed_labels] {
+            for _ in 0..reader.read_u64()? {
+                let node_id = NodeId::new(reader.read_u64()?);
+                labels.push((node_id, Label::new(reader.read_string()?)));
+            }
+        }
+
+        for _ in 0..reader.read_u64()? {
+            let relation_id = read_relation_id(&mut reader)?;
+            let (kind, shape) = (reader.read_u8()?, reader.read_u8()?);
+            let name = read_name(&mut reader)?;
+            let info = codec.decode_relation_info(reader.read_bytes()?)?;
+            let relation = Relation::from_codes(info, kind, shape, name);
+            diff.added_relations
+                .push((relation_id, relation.ok_or(CodecError::Malformed)?));
+        }
+        for _ in 0..reader.read_u64()? {
+            diff.removed_relations.push(read_relation_id(&mut reader)?);
+        }
+        for _ in 0..reader.read_u64()? {
+            let relation_id = read_relation_id(&mut reader)?;
+            diff.renamed_relations
+                .push((relation_id, read_name(&mut reader)?));
+        }
+        for _ in 0..reader.read_u64()? {
+            let a = read_relation_id(&mut reader)?;
+            diff.set_inverses.push((a, read_relation_id(&mut reader)?));
+        }
+        for _ in 0..reader.read_u64()? {
+            diff.removed_inverses.push(read_relation_id(&mut reader)?);
+        }
+
+        for _ in 0..reader.read_u64()? {
+            let (edge, relation_id) = read_edge(&mut reader)?;
+            let info = read_edge_info(&mut reader, codec)?;
+            diff.added_edges.push((edge, relation_id, info));
+        }
+        for _ in 0..reader.read_u64()? {
+            diff.removed_edges.push(read_edge(&mut reader)?);
+        }
+        for _ in 0..reader.read_u64()? {
+            let (edge, relation_id) = read_edge(&mut reader)?;
+            let info = read_edge_info(&mut reader, codec)?;
+            diff.changed_edges.push((edge, relation_id, info));
+        }
+
+        for _ in 0..reader.read_u64()? {
+            let edge_id = EdgeId::new(reader.read_u64()?);
+            let (edge, relation_id) = read_edge(&mut reader)?;
+            let info = read_edge_info(&mut reader, codec)?;
+            diff.added_parallel_edges
+                .push((edge_id, edge, relation_id, info));
+        }
+        for _ in 0..reader.read_u64()? {
+            diff.removed_parallel_edges
+                .push(EdgeId::new(reader.read_u64()?));
+        }
+        for _ in 0..reader.read_u64()? {
+            let edge_id = EdgeId::new(reader.read_u64()?);
+            let info = read_edge_info(&mut reader, codec)?;
+            diff.changed_parallel_edges.push((edge_id, info));
+        }
+
+        for _ in 0..reader.read_u64()? {
+            let edge_id = EdgeId::new(reader.read_u64()?);
+            let relation_id = read_relation_id(&mut reader)?;
+            let hyperedge = read_hyperedge(&mut reader)?;
+            diff.added_hyperedges
+                .push((edge_id, relation_id, hyperedge));
+        }
+        for _ in 0..reader.read_u64()? {
+            diff.removed_hyperedges
+                .push(EdgeId::new(reader.read_u64()?));
+        }
+
+        for _ in 0..reader.read_u64()? {
+            let owner = read_property_owner(&mut reader)?;
+            let key = PropertyKey::new(reader.read_string()?);
+            diff.set_properties
+                .push((owner, key, read_value(&mut reader)?));
+        }
+        for _ in 0..reader.read_u64()? {
+            let owner = read_property_owner(&mut reader)?;
+            let key = PropertyKey::new(reader.read_string()?);
+            diff.removed_properties.push((owner, key));
+        }
+        reader.finish()?;
+        Ok(diff)
+    }
+}
+
+/// Check if two node infos are the same info or have the same encoding.
+fn same_node_info<C>(codec: &C, old: &AnyNodeInfo, new: &AnyNodeInfo) -> bool
+where
+    C: InfoCodec,
+{
+    let (mut old_bytes, mut new_bytes) = (Vec::new(), Vec::new());
+    old.ptr_eq(new)
+        || (codec.encode_node_info(&**old, &mut old_bytes).is_ok()
+            && codec.encode_node_info(&**new, &mut new_bytes).is_ok()
+            && old_bytes == new_bytes)
+}
+
+/// Check if two optional edge infos are the same info or have the same
+/// encoding.
+fn same_edge_info<C>(codec: &C, old: Option<&AnyEdgeInfo>, new: Option<&AnyEdgeInfo>) -> bool
+where
+    C: InfoCodec,
+{
+    let (old, new) = match (old, new) {
+        (None, None) => return true,
+        (Some(old), Some(new)) => (old, new),
+        _ => return false,
+    };
+    let (mut old_bytes, mut new_bytes) = (Vec::new(), Vec::new());
+    old.ptr_eq(new)
+        || (codec.encode_edge_info(&**old, &mut old_bytes).is_ok()
+            && codec.encode_edge_info(&**new, &mut new_bytes).is_ok()
+            && old_bytes == new_bytes)
+}
+
+fn with_info(
+    relation: &Relation,
+    edge: Edge,
+    relation_id: RelationId,
+) -> (Edge, RelationId, Option<AnyEdgeInfo>) {
+    (edge, relation_id, relation.any_edge_info(&edge).cloned())
+}
+
+fn write_edge(bytes: &mut Vec<u8>, edge: &Edge, relation_id: RelationId) {
+    write_u64(bytes, *edge.src());
+    write_u64(bytes, *edge.dst());
+    write_u64(bytes, relation_id.get());
+}
+
+fn read_edge(reader: &mut Reader) -> Result<(Edge, RelationId), CodecError> {
+    let src = NodeId::new(reader.read_u64()?);
+    let dst = NodeId::new(reader.read_u64()?);
+    Ok((Edge::new(src, dst), read_relation_id(reader)?))
+}
+
+fn read_relation_id(reader: &mut Reader) -> Result<RelationId, CodecError> {
+    match reader.read_u64()? {
+        0 => Err(CodecError::Malformed),
+        id => Ok(RelationId::new(id)),
+    }
+}
@@ -1,7 +1,12 @@
 use crate::node::NodeId;
+use downcast_rs::{impl_downcast, DowncastSync};
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::ops::Deref;
+use std::sync::Arc;
 
 /// Represents a connection between two `Nodes`.
-#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
 pub struct Edge {
     src: NodeId,
     dst: NodeId,
@@ -22,4 +27,113 @@ impl Edge {
     pub fn dst(&self) -> NodeId {
         self.dst
     }
+
+    /// Get the edge with its endpoints ordered by `NodeId`, which is how
+    /// edges of undirected relations are stored.
+    pub fn normalized(&self) -> Self {
+        if *self.dst < *self.src {
+            Self::new(self.dst, self.src)
+        } else {
+            *self
+        }
+    }
+
+    /// Get the edge with its source and destination swapped.
+    pub fn reversed(&self) -> Self {
+        Self::new(self.dst, self.src)
+    }
+}
+
+/// Uniquely identifies one of the parallel edges of a multigraph `Relation`
+/// within a graph.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct EdgeId(u64);
+
+impl EdgeId {
+    /// Create a new `EdgeId`.
+    pub fn new(id: u64) -> Self {
+        Self(id)
+    }
+}
+
+impl Deref for EdgeId {
+    type Target = u64;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl Display for EdgeId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Type erased container for the info of a single edge.
+///
+/// The info is reference counted so graph snapshots can share it,
+/// which makes cloning an `AnyEdgeInfo` cheap. A shared info can only be
+/// mutated through `make_mut`, which copies it first so the other holders
+/// keep seeing the old info.
+#[derive(Clone)]
+pub struct AnyEdgeInfo(Arc<dyn EdgeInfo>);
+
+impl<I> From<I> for AnyEdgeInfo
+where
+    I: EdgeInfo,
+{
+    fn from(info: I) -> Self {
+        Self(Arc::new(info))
+    }
 }
+
+impl AnyEdgeInfo {
+    /// Get an exclusive reference to the info, unless it is shared
+    /// with another `AnyEdgeInfo`, e.g. one held by a snapshot.
+    pub fn get_mut(&mut self) -> Option<&mut dyn EdgeInfo> {
+        Arc::get_mut(&mut self.0)
+    }
+
+    /// Get an exclusive reference to the info if it is a `T`. An info
+    /// shared with another `AnyEdgeInfo` is copied first.
+    pub fn make_mut<T>(&mut self) -> Option<&mut T>
+    where
+        T: EdgeInfo + Clone,
+    {
+        if Arc::get_mut(&mut self.0).is_none() {
+            self.0 = Arc::new(self.0.downcast_ref::<T>()?.clone());
+        }
+        Arc::get_mut(&mut self.0)?.downcast_mut::<T>()
+    }
+
+    /// Check if both containers hold the same info, rather than equal ones.
+    pub(crate) fn ptr_eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Deref for AnyEdgeInfo {
+    type Target = dyn EdgeInfo;
+
+    fn deref(&self) -> &Self::Target {
+        Arc::as_ref(&self.0)
+    }
+}
+
+/// Trait implemented by types which can be stored on single edges.
+pub trait EdgeInfo
+where
+    Self: DowncastSync,
+{
+}
+
+impl_downcast!(sync EdgeInfo);
+
+impl EdgeInfo for () {}
+
+impl EdgeInfo for i32 {}
+
+impl EdgeInfo for u32 {}
+
+impl EdgeInfo for String {}
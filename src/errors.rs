@@ -1,8 +1,11 @@
+use crate::node::NodeId;
 use std::error::Error;
 use std::fmt;
 use std::fmt::{Display, Formatter};
+use std::io;
 
-/// Error returned by the `connect` and `disconnect` methods of `Graph`.
+/// Error returned by the `connect`, `connect_multi`, `connect_hyperedge`,
+/// `disconnect` and `set_inverse` methods of `Graph`.
 #[derive(Debug)]
 pub enum ConnectError {
     /// An invalid source node ID was provided.
@@ -11,6 +14,23 @@ pub enum ConnectError {
     InvalidDstNodeId,
     /// An invalid relation ID was provided.
     InvalidRelationId,
+    /// The edge is not allowed by the `RelationSchema` of the relation.
+    SchemaViolation(SchemaViolation),
+    /// The relation is antisymmetric and already has the reverse edge.
+    ReverseEdgeExists,
+    /// One of the relations already has a different inverse, or is a
+    /// multigraph or hyperedge relation.
+    InverseConflict,
+    /// The relation is a multigraph relation, whose edges are connected
+    /// with `connect_multi`.
+    MultiRelation,
+    /// The relation is not a multigraph relation.
+    NotMultiRelation,
+    /// The relation is a hyperedge relation, whose edges are connected
+    /// with `connect_hyperedge`.
+    HyperRelation,
+    /// The relation is not a hyperedge relation.
+    NotHyperRelation,
 }
 
 impl Error for ConnectError {}
@@ -21,6 +41,327 @@ impl Display for ConnectError {
             Self::InvalidSrcNodeId => write!(f, "Invalid source node ID"),
             Self::InvalidDstNodeId => write!(f, "Invalid destination node ID"),
             Self::InvalidRelationId => write!(f, "Invalid relation ID"),
+            Self::SchemaViolation(violation) => write!(f, "Schema violation: {}", violation),
+            Self::ReverseEdgeExists => write!(f, "Reverse edge exists in antisymmetric relation"),
+            Self::InverseConflict => write!(f, "Relation already has a different inverse"),
+            Self::MultiRelation => write!(f, "Relation is a multigraph relation"),
+            Self::NotMultiRelation => write!(f, "Relation is not a multigraph relation"),
+            Self::HyperRelation => write!(f, "Relation is a hyperedge relation"),
+            Self::NotHyperRelation => write!(f, "Relation is not a hyperedge relation"),
         }
     }
 }
+
+/// The part of a `RelationSchema` that an edge does not satisfy.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum SchemaViolation {
+    /// The info of the source node has a type that is not allowed.
+    SrcType,
+    /// The info of the destination node has a type that is not allowed.
+    DstType,
+    /// The source node already has the maximum number of outgoing edges.
+    MaxOutDegree,
+    /// The destination node already has the maximum number of incoming edges.
+    MaxInDegree,
+}
+
+impl Display for SchemaViolation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::SrcType => write!(f, "source node type not allowed"),
+            Self::DstType => write!(f, "destination node type not allowed"),
+            Self::MaxOutDegree => write!(f, "too many outgoing edges"),
+            Self::MaxInDegree => write!(f, "too many incoming edges"),
+        }
+    }
+}
+
+/// Error returned by the methods of `Graph` that name relations.
+#[derive(Debug)]
+pub enum NameError {
+    /// An invalid relation ID was provided.
+    InvalidRelationId,
+    /// Another relation already has the name.
+    NameTaken,
+}
+
+impl Error for NameError {}
+
+impl Display for NameError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidRelationId => write!(f, "Invalid relation ID"),
+            Self::NameTaken => write!(f, "Relation name already taken"),
+        }
+    }
+}
+
+/// Error returned by `Graph::set_property` when the owner does not exist.
+#[derive(Debug)]
+pub enum PropertyError {
+    /// An invalid node ID was provided.
+    InvalidNodeId,
+    /// An invalid relation ID was provided.
+    InvalidRelationId,
+    /// The nodes are not connected in the relation, or it is a hyperedge
+    /// relation.
+    InvalidEdge,
+}
+
+impl Error for PropertyError {}
+
+impl Display for PropertyError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidNodeId => write!(f, "Invalid node ID"),
+            Self::InvalidRelationId => write!(f, "Invalid relation ID"),
+            Self::InvalidEdge => write!(f, "Edge does not exist"),
+        }
+    }
+}
+
+/// Error returned when a node info would give a `Node` the same key in a
+/// unique index as another node.
+#[derive(Debug)]
+pub enum UniqueError {
+    /// The `Node` with the given id already has the key.
+    DuplicateKey(NodeId),
+    /// The index is not in the graph, because it was removed or the graph
+    /// was loaded from a store.
+    UnknownIndex,
+}
+
+impl Error for UniqueError {}
+
+impl Display for UniqueError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DuplicateKey(node_id) => {
+                write!(f, "Node {} already has the key in a unique index", node_id)
+            }
+            Self::UnknownIndex => write!(f, "Unique index is not in the graph"),
+        }
+    }
+}
+
+/// Error returned by `Graph::merge_nodes` and `Graph::contract_edge`.
+#[derive(Debug)]
+pub enum MergeError {
+    /// An invalid node ID was provided.
+    InvalidNodeId,
+    /// The node to keep and the node to absorb are the same.
+    SameNode,
+    /// The edge to contract does not exist.
+    InvalidEdge,
+    /// A moved edge was rejected by its relation.
+    Connect(ConnectError),
+    /// The merged node info was rejected by a unique index.
+    Unique(UniqueError),
+}
+
+impl Error for MergeError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Connect(err) => Some(err),
+            Self::Unique(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl Display for MergeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidNodeId => write!(f, "Invalid node ID"),
+            Self::SameNode => write!(f, "Cannot merge a node into itself"),
+            Self::InvalidEdge => write!(f, "Edge does not exist"),
+            Self::Connect(err) => write!(f, "{}", err),
+            Self::Unique(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl From<ConnectError> for MergeError {
+    fn from(err: ConnectError) -> Self {
+        Self::Connect(err)
+    }
+}
+
+impl From<UniqueError> for MergeError {
+    fn from(err: UniqueError) -> Self {
+        Self::Unique(err)
+    }
+}
+
+/// Error returned by the `GraphWrite` methods of `Graph` and `History`,
+/// and when a `GraphDiff` can't be applied.
+#[derive(Debug)]
+pub enum WriteError {
+    /// The mutation was rejected by the graph.
+    Connect(ConnectError),
+    /// The node info was rejected by a unique index.
+    Unique(UniqueError),
+    /// The relation name was rejected by the graph.
+    Name(NameError),
+    /// The property owner does not exist.
+    Property(PropertyError),
+}
+
+impl Error for WriteError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Connect(err) => Some(err),
+            Self::Unique(err) => Some(err),
+            Self::Name(err) => Some(err),
+            Self::Property(err) => Some(err),
+        }
+    }
+}
+
+impl Display for WriteError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Connect(err) => write!(f, "{}", err),
+            Self::Unique(err) => write!(f, "{}", err),
+            Self::Name(err) => write!(f, "{}", err),
+            Self::Property(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl From<ConnectError> for WriteError {
+    fn from(err: ConnectError) -> Self {
+        Self::Connect(err)
+    }
+}
+
+impl From<UniqueError> for WriteError {
+    fn from(err: UniqueError) -> Self {
+        Self::Unique(err)
+    }
+}
+
+impl From<NameError> for WriteError {
+    fn from(err: NameError) -> Self {
+        Self::Name(err)
+    }
+}
+
+impl From<PropertyError> for WriteError {
+    fn from(err: PropertyError) -> Self {
+        Self::Property(err)
+    }
+}
+
+/// Error returned when a node or relation info can't be encoded or decoded.
+#[derive(Debug)]
+pub enum CodecError {
+    /// The concrete type of the info is not supported by the codec.
+    UnsupportedType,
+    /// The encoded bytes are truncated or otherwise malformed.
+    Malformed,
+}
+
+impl Error for CodecError {}
+
+impl Display for CodecError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnsupportedType => write!(f, "Unsupported info type"),
+            Self::Malformed => write!(f, "Malformed encoded info"),
+        }
+    }
+}
+
+/// Error returned by the methods of `PersistentGraph`.
+#[derive(Debug)]
+pub enum StoreError {
+    /// Reading or writing the store files failed.
+    Io(io::Error),
+    /// An info could not be encoded or decoded.
+    Codec(CodecError),
+    /// The mutation was rejected by the graph.
+    Connect(ConnectError),
+    /// The relation name was rejected by the graph.
+    Name(NameError),
+    /// The property owner does not exist.
+    Property(PropertyError),
+    /// The node info was rejected by a unique index.
+    Unique(UniqueError),
+    /// The snapshot file is damaged.
+    CorruptSnapshot,
+    /// A record in the middle of the log is damaged.
+    CorruptLog,
+    /// The log holds records that are not in the snapshot yet.
+    PendingLog,
+    /// A record could not be appended to the log nor removed from it, so
+    /// the store rejects all further mutations.
+    Poisoned,
+}
+
+impl Error for StoreError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Io(err) => Some(err),
+            Self::Codec(err) => Some(err),
+            Self::Connect(err) => Some(err),
+            Self::Name(err) => Some(err),
+            Self::Property(err) => Some(err),
+            Self::Unique(err) => Some(err),
+            Self::CorruptSnapshot | Self::CorruptLog | Self::PendingLog | Self::Poisoned => None,
+        }
+    }
+}
+
+impl Display for StoreError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "Store I/O error: {}", err),
+            Self::Codec(err) => write!(f, "Store codec error: {}", err),
+            Self::Connect(err) => write!(f, "{}", err),
+            Self::Name(err) => write!(f, "{}", err),
+            Self::Property(err) => write!(f, "{}", err),
+            Self::Unique(err) => write!(f, "{}", err),
+            Self::CorruptSnapshot => write!(f, "Corrupt snapshot file"),
+            Self::CorruptLog => write!(f, "Corrupt record in the middle of the log"),
+            Self::PendingLog => write!(f, "Log holds records that are not in the snapshot"),
+            Self::Poisoned => write!(f, "Store is poisoned by a failed log write"),
+        }
+    }
+}
+
+impl From<io::Error> for StoreError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl From<CodecError> for StoreError {
+    fn from(err: CodecError) -> Self {
+        Self::Codec(err)
+    }
+}
+
+impl From<ConnectError> for StoreError {
+    fn from(err: ConnectError) -> Self {
+        Self::Connect(err)
+    }
+}
+
+impl From<NameError> for StoreError {
+    fn from(err: NameError) -> Self {
+        Self::Name(err)
+    }
+}
+
+impl From<PropertyError> for StoreError {
+    fn from(err: PropertyError) -> Self {
+        Self::Property(err)
+    }
+}
+
+impl From<UniqueError> for StoreError {
+    fn from(err: UniqueError) -> Self {
+        Self::Unique(err)
+    }
+}
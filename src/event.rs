@@ -0,0 +1,216 @@
+use crate::edge::{Edge, EdgeId};
+use crate::node::NodeId;
+use crate::relation::RelationId;
+use std::collections::HashSet;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::sync::mpsc::{self, Receiver, Sender};
+
+/// A change made to a `Graph`.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum GraphEvent {
+    /// A `Node` was created.
+    NodeAdded(NodeId),
+    /// A `Node` was removed, after all its edges were disconnected.
+    NodeRemoved(NodeId),
+    /// The info of a `Node` was replaced or changed.
+    NodeInfoChanged(NodeId),
+    /// A `Relation` was created.
+    RelationAdded(RelationId),
+    /// A `Relation` was removed, after all its edges were disconnected.
+    RelationRemoved(RelationId),
+    /// An edge was added to a `Relation`.
+    EdgeConnected { edge: Edge, relation_id: RelationId },
+    /// An edge was removed from a `Relation`.
+    EdgeDisconnected { edge: Edge, relation_id: RelationId },
+    /// A hyperedge was added to a `Relation`.
+    HyperedgeConnected {
+        edge_id: EdgeId,
+        relation_id: RelationId,
+    },
+    /// A hyperedge was removed from a `Relation`.
+    HyperedgeDisconnected {
+        edge_id: EdgeId,
+        relation_id: RelationId,
+    },
+}
+
+impl GraphEvent {
+    /// Get the `RelationId` the event is about, if any.
+    pub fn relation_id(&self) -> Option<RelationId> {
+        match self {
+            Self::NodeAdded(_) | Self::NodeRemoved(_) | Self::NodeInfoChanged(_) => None,
+            Self::RelationAdded(relation_id) | Self::RelationRemoved(relation_id) => {
+                Some(*relation_id)
+            }
+            Self::EdgeConnected { relation_id, .. }
+            | Self::EdgeDisconnected { relation_id, .. }
+            | Self::HyperedgeConnected { relation_id, .. }
+            | Self::HyperedgeDisconnected { relation_id, .. } => Some(*relation_id),
+        }
+    }
+}
+
+/// Selects which events a subscriber receives.
+#[derive(Clone, Default, Debug)]
+pub struct EventFilter {
+    relation_ids: Option<HashSet<RelationId>>,
+}
+
+impl EventFilter {
+    /// Receive every event.
+    pub fn all() -> Self {
+        Self::default()
+    }
+
+    /// Only receive events about the given relations. Node events are not
+    /// about any relation, so they are not received.
+    pub fn relations<I>(relation_ids: I) -> Self
+    where
+        I: IntoIterator<Item = RelationId>,
+    {
+        Self {
+            relation_ids: Some(relation_ids.into_iter().collect()),
+        }
+    }
+
+    /// Check if the filter lets `event` through.
+    pub fn matches(&self, event: &GraphEvent) -> bool {
+        match &self.relation_ids {
+            None => true,
+            Some(relation_ids) => event
+                .relation_id()
+                .is_some_and(|relation_id| relation_ids.contains(&relation_id)),
+        }
+    }
+}
+
+/// Identifies a subscription made with `Graph::subscribe`.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct SubscriptionId(u64);
+
+impl Display for SubscriptionId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+type Callback = dyn Fn(&GraphEvent) + Send + Sync;
+
+enum Sink {
+    Callback(Box<Callback>),
+    Channel(Sender<GraphEvent>),
+}
+
+struct Subscriber {
+    id: SubscriptionId,
+    filter: EventFilter,
+    sink: Sink,
+}
+
+/// The subscribers of a `Graph`.
+///
+/// While a `Transaction` is open, events are held back and only delivered
+/// once the outermost transaction commits. The events of a transaction that
+/// is rolled back are discarded.
+#[derive(Default)]
+pub(crate) struct Observers {
+    subscribers: Vec<Subscriber>,
+    next_id: u64,
+    pending: Vec<GraphEvent>,
+    /// The length of `pending` when each open transaction started.
+    transactions: Vec<usize>,
+}
+
+/// Subscriptions belong to one graph, so clones of it start without any.
+impl Clone for Observers {
+    fn clone(&self) -> Self {
+        Self {
+            subscribers: Vec::new(),
+            next_id: self.next_id,
+            pending: Vec::new(),
+            transactions: Vec::new(),
+        }
+    }
+}
+
+impl Observers {
+    pub(crate) fn subscribe<F>(&mut self, filter: EventFilter, callback: F) -> SubscriptionId
+    where
+        F: Fn(&GraphEvent) + Send + Sync + 'static,
+    {
+        self.add(filter, Sink::Callback(Box::new(callback)))
+    }
+
+    pub(crate) fn subscribe_channel(
+        &mut self,
+        filter: EventFilter,
+    ) -> (SubscriptionId, Receiver<GraphEvent>) {
+        let (sender, receiver) = mpsc::channel();
+        (self.add(filter, Sink::Channel(sender)), receiver)
+    }
+
+    fn add(&mut self, filter: EventFilter, sink: Sink) -> SubscriptionId {
+        self.next_id += 1;
+        let id = SubscriptionId(self.next_id);
+        self.subscribers.push(Subscriber { id, filter, sink });
+        id
+    }
+
+    pub(crate) fn unsubscribe(&mut self, id: SubscriptionId) -> bool {
+        let len = self.subscribers.len();
+        self.subscribers.retain(|subscriber| subscriber.id != id);
+        self.subscribers.len() != len
+    }
+
+    /// Deliver `event` to all matching subscribers, or hold it back until
+    /// the open transactions commit.
+    pub(crate) fn emit(&mut self, event: GraphEvent) {
+        if self.subscribers.is_empty() {
+            return;
+        }
+        match self.transactions.is_empty() {
+            true => self.deliver(event),
+            false => self.pending.push(event),
+        }
+    }
+
+    pub(crate) fn begin_transaction(&mut self) {
+        self.transactions.push(self.pending.len());
+    }
+
+    /// Keep the events of the innermost transaction, and deliver all held
+    /// back events if it was the outermost one.
+    pub(crate) fn commit_transaction(&mut self) {
+        self.transactions.pop();
+        if self.transactions.is_empty() {
+            for event in std::mem::take(&mut self.pending) {
+                self.deliver(event);
+            }
+        }
+    }
+
+    /// Discard the events of the innermost transaction.
+    pub(crate) fn rollback_transaction(&mut self) {
+        if let Some(len) = self.transactions.pop() {
+            self.pending.truncate(len);
+        }
+    }
+
+    /// Deliver `event` to all matching subscribers. Channel subscribers
+    /// whose receiver was dropped are unsubscribed.
+    fn deliver(&mut self, event: GraphEvent) {
+        self.subscribers.retain(|subscriber| {
+            if !subscriber.filter.matches(&event) {
+                return true;
+            }
+            match &subscriber.sink {
+                Sink::Callback(callback) => {
+                    callback(&event);
+                    true
+                }
+                Sink::Channel(sender) => sender.send(event).is_ok(),
+            }
+        });
+    }
+}
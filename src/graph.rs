@@ -1,18 +1,76 @@
-use crate::edge::Edge;
-use crate::errors::ConnectError;
-use crate::id::IdGenerator;
-use crate::node::{AnyNodeInfo, NodeId};
-use crate::relation::{AnyRelationInfo, Relation, RelationId};
+use crate::codec::{DefaultCodec, InfoCodec};
+use crate::collections::{Map, Set, Share, SortedMap, SortedSet};
+use crate::combine;
+use crate::contract::{self, SelfLoops};
+use crate::diff::GraphDiff;
+use crate::edge::{AnyEdgeInfo, Edge, EdgeId, EdgeInfo};
+use crate::errors::{
+    ConnectError, MergeError, NameError, PropertyError, SchemaViolation, UniqueError, WriteError,
+};
+use crate::event::{EventFilter, GraphEvent, Observers, SubscriptionId};
+use crate::hyperedge::{Hyperedge, HyperedgeKind};
+use crate::id::{IdGenerator, IdMap};
+use crate::index::{
+    AnyIndex, HashEntries, HashIndex, IndexData, IndexId, NodeIndex, OrderedEntries, OrderedIndex,
+    UniqueIndex,
+};
+use crate::label::Label;
+use crate::node::{AnyNodeInfo, NodeId, NodeInfo};
+use crate::property::{Properties, PropertyKey, PropertyOwner};
+use crate::relation::{AnyRelationInfo, Relation, RelationId, RelationKind};
+use crate::schema::RelationSchema;
+use crate::snapshot::GraphSnapshot;
+use crate::subgraph;
+use crate::text::{TextEntries, TextIndex};
+use crate::transaction::Transaction;
+use crate::value::Value;
 use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+use std::ops::{Bound, RangeBounds};
+use std::sync::mpsc::Receiver;
+use std::sync::Arc;
 
-#[derive(Default)]
+/// A directed graph of typed nodes connected by any number of relations.
+///
+/// Besides binary edges, nodes can be connected by the `Hyperedges` of
+/// hyperedge relations, which are indexed by node so the hyperedges a node
+/// takes part in can be found quickly. The relation of a parallel edge or
+/// a hyperedge is indexed by its `EdgeId`.
+///
+/// Nodes, relations and edges can also have dynamically typed properties,
+/// see `set_property`. Nodes can be found by a key derived from their info
+/// through secondary indexes, see `add_hash_index` and `add_ordered_index`,
+/// and a key can be kept unique with `add_unique_index`. Nodes can also be
+/// searched by text with `add_text_index`.
+///
+/// Nodes, relations, edges and their adjacency are kept in std maps and
+/// sets, until the first `snapshot` or `Transaction` moves them to
+/// persistent ones. From then on cloning the graph, taking a snapshot or
+/// starting a transaction takes constant time. Clones share their
+/// structure, and a change only copies the few map nodes on its path.
+/// Subscriptions made with `subscribe` are not carried over to clones.
+#[derive(Clone, Default)]
 pub struct Graph {
-    next_nodes: HashMap<NodeId, HashMap<NodeId, HashSet<RelationId>>>,
-    prev_nodes: HashMap<NodeId, HashMap<NodeId, HashSet<RelationId>>>,
-    node_info: HashMap<NodeId, AnyNodeInfo>,
-    relations: HashMap<RelationId, Relation>,
+    next_nodes: Adjacency,
+    prev_nodes: Adjacency,
+    node_info: Map<NodeId, AnyNodeInfo>,
+    relations: Map<RelationId, Relation>,
+    inverses: Map<RelationId, RelationId>,
+    edge_relations: Map<EdgeId, RelationId>,
+    hyper_incidence: SortedMap<(NodeId, EdgeId), RelationId>,
+    relation_names: Map<Arc<str>, RelationId>,
+    node_labels: SortedSet<(NodeId, Label)>,
+    label_index: Map<Label, Set<NodeId>>,
+    node_properties: Map<NodeId, Properties>,
+    property_keys: Map<PropertyKey, ()>,
+    indexes: Map<IndexId, AnyIndex>,
+    text_indexes: Map<IndexId, TextEntries>,
+    multi_relations: usize,
     node_id_generator: IdGenerator,
     relation_id_generator: IdGenerator,
+    edge_id_generator: IdGenerator,
+    index_id_generator: IdGenerator,
+    observers: Observers,
 }
 
 impl Graph {
@@ -29,138 +87,1521 @@ impl Graph {
         RelationId::new(self.relation_id_generator.generate_id_sync())
     }
 
+    fn generate_edge_id(&mut self) -> EdgeId {
+        EdgeId::new(self.edge_id_generator.generate_id_sync())
+    }
+
     /// Create a `Node` in the graph with `info` and return its `NodeId`.
+    ///
+    /// Unique indexes are not checked, so a graph that has them should add
+    /// nodes with `try_add_node` or `upsert_node` instead.
     pub fn add_node<I>(&mut self, info: I) -> NodeId
     where
-        I: Into<AnyNodeInfo>,
+        I: Into<AnyNodeInfo>,
+    {
+        let info = info.into();
+        let id = self.generate_node_id();
+        self.index_node(id, &info);
+        self.node_info.insert(id, info);
+        self.observers.emit(GraphEvent::NodeAdded(id));
+        id
+    }
+
+    /// Create a `Node` in the graph with `info` and return its `NodeId`, or
+    /// an error if another node has the same key in a unique index.
+    pub fn try_add_node<I>(&mut self, info: I) -> Result<NodeId, UniqueError>
+    where
+        I: Into<AnyNodeInfo>,
+    {
+        let info = info.into();
+        self.check_unique(None, &info)?;
+        Ok(self.add_node(info))
+    }
+
+    /// Create a `Node` with a known `node_id`, used when restoring a graph.
+    /// If the node exists, its info is replaced instead.
+    pub(crate) fn insert_node(&mut self, node_id: NodeId, info: AnyNodeInfo) {
+        if self.contains_node(node_id) {
+            self.set_node_info(node_id, info);
+            return;
+        }
+        self.node_id_generator.reserve_sync(*node_id);
+        self.index_node(node_id, &info);
+        self.node_info.insert(node_id, info);
+        self.observers.emit(GraphEvent::NodeAdded(node_id));
+    }
+
+    /// Remove the `Node` at `node_id` and return its info if it was removed.
+    pub fn remove_node(&mut self, node_id: NodeId) -> Option<AnyNodeInfo> {
+        let hyperedges: Vec<_> = self.iter_hyperedges_of(node_id)?.collect();
+        for (edge_id, relation_id) in hyperedges {
+            self.remove_hyperedge(edge_id, relation_id);
+        }
+        let info = self.node_info.remove(&node_id)?;
+        self.unindex_node(node_id, &info);
+        let labels: Vec<_> = self
+            .node_labels
+            .range(label_range(node_id))
+            .cloned()
+            .collect();
+        for (_, label) in labels {
+            self.node_labels.remove(&(node_id, label.clone()));
+            self.unindex_label(node_id, &label);
+        }
+        self.node_properties.remove(&node_id);
+
+        let prev_nodes: Vec<_> = iter_adjacency(&self.prev_nodes, node_id).collect();
+        for (src_id, relation_id) in prev_nodes {
+            self.unlink(Edge::new(src_id, node_id), relation_id);
+            self.remove_incident_edge(Edge::new(src_id, node_id), relation_id);
+        }
+
+        // Self loops were already removed above
+        let next_nodes: Vec<_> = iter_adjacency(&self.next_nodes, node_id).collect();
+        for (dst_id, relation_id) in next_nodes {
+            self.unlink(Edge::new(node_id, dst_id), relation_id);
+            self.remove_incident_edge(Edge::new(node_id, dst_id), relation_id);
+        }
+        self.prev_nodes.remove(&node_id);
+        self.next_nodes.remove(&node_id);
+
+        self.observers.emit(GraphEvent::NodeRemoved(node_id));
+        Some(info)
+    }
+
+    /// Replace the info of a `Node` and return the old info, or an error if
+    /// another node has the same key in a unique index.
+    pub fn replace_node_info<I>(
+        &mut self,
+        node_id: NodeId,
+        info: I,
+    ) -> Result<Option<AnyNodeInfo>, UniqueError>
+    where
+        I: Into<AnyNodeInfo>,
+    {
+        let info = info.into();
+        if self.contains_node(node_id) {
+            self.check_unique(Some(node_id), &info)?;
+        }
+        Ok(self.set_node_info(node_id, info))
+    }
+
+    /// Replace the info of a `Node` without checking the unique indexes,
+    /// used when restoring a graph.
+    pub(crate) fn set_node_info(
+        &mut self,
+        node_id: NodeId,
+        info: AnyNodeInfo,
+    ) -> Option<AnyNodeInfo> {
+        let old = self.node_info.get(&node_id)?.clone();
+        self.unindex_node(node_id, &old);
+        self.index_node(node_id, &info);
+        self.node_info.insert(node_id, info);
+        self.observers.emit(GraphEvent::NodeInfoChanged(node_id));
+        Some(old)
+    }
+
+    /// Add a `Node` with `info`, or replace the info of the node that has the
+    /// same key in the unique `index`, and return the `NodeId` of the node.
+    ///
+    /// Fails if `index` is not in the graph, or if the new info has the same
+    /// key as another node in a different unique index.
+    pub fn upsert_node<K, I>(
+        &mut self,
+        index: UniqueIndex<K>,
+        info: I,
+    ) -> Result<NodeId, UniqueError>
+    where
+        K: Hash + Eq + Clone + Send + Sync + 'static,
+        I: Into<AnyNodeInfo>,
+    {
+        let info = info.into();
+        match self.unique_node(index, &info)? {
+            Some(node_id) => {
+                self.replace_node_info(node_id, info)?;
+                Ok(node_id)
+            }
+            None => self.try_add_node(info),
+        }
+    }
+
+    /// Get the `NodeId` of the node that has the key of `info` in `index`,
+    /// failing if the graph has no such index.
+    pub(crate) fn unique_node<K>(
+        &self,
+        index: UniqueIndex<K>,
+        info: &AnyNodeInfo,
+    ) -> Result<Option<NodeId>, UniqueError>
+    where
+        K: Hash + Eq + Clone + Send + Sync + 'static,
+    {
+        let entries = index.entries(self).ok_or(UniqueError::UnknownIndex)?;
+        Ok(entries
+            .key(&**info)
+            .and_then(|key| entries.entries.get(&key)?.iter().next().copied()))
+    }
+
+    /// Call `f` with an exclusive reference to the info of a `Node`, if it
+    /// is a `T`. An info shared with a snapshot or clone of the graph is
+    /// copied first.
+    ///
+    /// Returns `Ok(None)` without calling `f` if there is no such node or
+    /// its info is not a `T`. `f` changes a copy of the info, which
+    /// replaces the info once `f` returns, so a panic in `f` leaves the
+    /// node as it was. The copy is dropped with an error if another node
+    /// has its new key in a unique index.
+    pub fn with_node_info_mut<T, F, R>(
+        &mut self,
+        node_id: NodeId,
+        f: F,
+    ) -> Result<Option<R>, UniqueError>
+    where
+        T: NodeInfo + Clone,
+        F: FnOnce(&mut T) -> R,
+    {
+        let mut info = match self.node_info.get(&node_id) {
+            Some(info) => info.clone(),
+            None => return Ok(None),
+        };
+        let result = match info.make_mut() {
+            Some(info) => f(info),
+            None => return Ok(None),
+        };
+        self.check_unique(Some(node_id), &info)?;
+        self.set_node_info(node_id, info);
+        Ok(Some(result))
+    }
+
+    /// Create a hash index of the `Nodes` by the key `key_fn` extracts from
+    /// their info. Nodes for which it returns `None` are not indexed.
+    pub fn add_hash_index<K, F>(&mut self, key_fn: F) -> HashIndex<K>
+    where
+        K: Hash + Eq + Clone + Send + Sync + 'static,
+        F: Fn(&dyn NodeInfo) -> Option<K> + Send + Sync + 'static,
+    {
+        HashIndex::new(self.insert_index(HashEntries::new(Arc::new(key_fn), false)))
+    }
+
+    /// Create a unique hash index of the `Nodes` by the key `key_fn` extracts
+    /// from their info. Nodes for which it returns `None` are not indexed.
+    ///
+    /// Once it exists, `try_add_node`, `upsert_node` and info replacement
+    /// refuse to give a node the same key as another node. Fails if two
+    /// existing nodes have the same key.
+    pub fn add_unique_index<K, F>(&mut self, key_fn: F) -> Result<UniqueIndex<K>, UniqueError>
+    where
+        K: Hash + Eq + Clone + Send + Sync + 'static,
+        F: Fn(&dyn NodeInfo) -> Option<K> + Send + Sync + 'static,
+    {
+        let mut index = HashEntries::new(Arc::new(key_fn), true);
+        for (node_id, info) in self.node_info.iter() {
+            if let Some(other) = index.conflict(Some(*node_id), &**info) {
+                return Err(UniqueError::DuplicateKey(other));
+            }
+            index.insert(*node_id, &**info);
+        }
+        Ok(UniqueIndex::new(self.register_index(index)))
+    }
+
+    /// Create an ordered index of the `Nodes` by the key `key_fn` extracts
+    /// from their info. Nodes for which it returns `None` are not indexed.
+    pub fn add_ordered_index<K, F>(&mut self, key_fn: F) -> OrderedIndex<K>
+    where
+        K: Ord + Clone + Send + Sync + 'static,
+        F: Fn(&dyn NodeInfo) -> Option<K> + Send + Sync + 'static,
+    {
+        OrderedIndex::new(self.insert_index(OrderedEntries::new(Arc::new(key_fn))))
+    }
+
+    fn insert_index<I>(&mut self, mut index: I) -> IndexId
+    where
+        I: IndexData,
+    {
+        for (node_id, info) in self.node_info.iter() {
+            index.insert(*node_id, &**info);
+        }
+        self.register_index(index)
+    }
+
+    fn register_index<I>(&mut self, index: I) -> IndexId
+    where
+        I: IndexData,
+    {
+        let id = IndexId::new(self.index_id_generator.generate_id_sync());
+        let mut index = AnyIndex::new(index);
+        if self.is_shared() {
+            index.share();
+        }
+        self.indexes.insert(id, index);
+        id
+    }
+
+    /// Remove a secondary index and return whether it existed.
+    pub fn remove_index<I>(&mut self, index: I) -> bool
+    where
+        I: NodeIndex,
+    {
+        self.indexes.remove(&index.id()).is_some()
+    }
+
+    /// Create a full-text index of the `Nodes` by the text `text_fn` extracts
+    /// from their info. Nodes for which it returns `None` are not indexed.
+    pub fn add_text_index<F>(&mut self, text_fn: F) -> TextIndex
+    where
+        F: Fn(&dyn NodeInfo) -> Option<String> + Send + Sync + 'static,
+    {
+        let mut entries = TextEntries::new(Arc::new(text_fn));
+        for (node_id, info) in self.node_info.iter() {
+            entries.insert(*node_id, &**info);
+        }
+        if self.is_shared() {
+            entries.share();
+        }
+        let id = IndexId::new(self.index_id_generator.generate_id_sync());
+        self.text_indexes.insert(id, entries);
+        TextIndex::new(id)
+    }
+
+    /// Create a full-text index of the `Nodes` whose info is a `String`.
+    pub fn add_string_text_index(&mut self) -> TextIndex {
+        self.add_text_index(|info| info.downcast_ref::<String>().cloned())
+    }
+
+    /// Remove a full-text index and return whether it existed.
+    pub fn remove_text_index(&mut self, index: TextIndex) -> bool {
+        self.text_indexes.remove(&index.id()).is_some()
+    }
+
+    /// Search a full-text index for the `Nodes` that contain any term of
+    /// `query`. Returns the nodes with their BM25 scores, best match first.
+    pub fn search(&self, index: TextIndex, query: &str) -> Vec<(NodeId, f64)> {
+        index
+            .entries(self)
+            .map(|entries| entries.search(query))
+            .unwrap_or_default()
+    }
+
+    /// Search a full-text index for the `Nodes` that contain a term starting
+    /// with `prefix`. Returns the nodes with their BM25 scores, best match
+    /// first.
+    pub fn search_prefix(&self, index: TextIndex, prefix: &str) -> Vec<(NodeId, f64)> {
+        index
+            .entries(self)
+            .map(|entries| entries.search_prefix(prefix))
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn index(&self, index_id: IndexId) -> Option<&dyn IndexData> {
+        self.indexes.get(&index_id).map(|index| index.get())
+    }
+
+    pub(crate) fn text_index(&self, index_id: IndexId) -> Option<&TextEntries> {
+        self.text_indexes.get(&index_id)
+    }
+
+    /// Get an iterator over the `NodeIds` of the nodes indexed under `key`.
+    pub fn lookup<I>(&self, index: I, key: &I::Key) -> impl Iterator<Item = NodeId> + '_
+    where
+        I: NodeIndex,
+    {
+        index.nodes(self, key)
+    }
+
+    /// Get an iterator over the `NodeIds` of the nodes indexed under a key
+    /// in `range`, in the order of their keys.
+    pub fn range<K, R>(&self, index: OrderedIndex<K>, range: R) -> impl Iterator<Item = NodeId> + '_
+    where
+        K: Ord + Clone + Send + Sync + 'static,
+        R: RangeBounds<K>,
+    {
+        index
+            .entries(self)
+            .map(|index| index.entries.range(range))
+            .into_iter()
+            .flatten()
+            .flat_map(|(_, nodes)| nodes.iter().copied())
+    }
+
+    /// Check that no other `Node` than `node_id` has the key of `info` in a
+    /// unique index.
+    pub(crate) fn check_unique(
+        &self,
+        node_id: Option<NodeId>,
+        info: &AnyNodeInfo,
+    ) -> Result<(), UniqueError> {
+        for index in self.indexes.values() {
+            if let Some(other) = index.get().conflict(node_id, &**info) {
+                return Err(UniqueError::DuplicateKey(other));
+            }
+        }
+        Ok(())
+    }
+
+    fn index_node(&mut self, node_id: NodeId, info: &AnyNodeInfo) {
+        for (_, index) in self.indexes.iter_mut() {
+            index.get_mut().insert(node_id, &**info);
+        }
+        for (_, entries) in self.text_indexes.iter_mut() {
+            entries.insert(node_id, &**info);
+        }
+    }
+
+    fn unindex_node(&mut self, node_id: NodeId, info: &AnyNodeInfo) {
+        for (_, index) in self.indexes.iter_mut() {
+            index.get_mut().remove(node_id, &**info);
+        }
+        for (_, entries) in self.text_indexes.iter_mut() {
+            entries.remove(node_id, &**info);
+        }
+    }
+
+    /// Add `label` to a `Node` and return whether it was added. Returns
+    /// `false` if the node does not exist or already has the label.
+    pub fn add_label(&mut self, node_id: NodeId, label: &str) -> bool {
+        if !self.contains_node(node_id) {
+            return false;
+        }
+        let label = match self.label_index.get_key_value(label) {
+            Some((interned, _)) => interned.clone(),
+            None => Label::new(label),
+        };
+        if !self.node_labels.insert((node_id, label.clone())) {
+            return false;
+        }
+        self.label_index.get_or_default(label).insert(node_id);
+        true
+    }
+
+    /// Remove `label` from a `Node` and return whether it had the label.
+    pub fn remove_label(&mut self, node_id: NodeId, label: &str) -> bool {
+        let label = match self.label_index.get_key_value(label) {
+            Some((interned, _)) => interned.clone(),
+            None => return false,
+        };
+        if !self.node_labels.remove(&(node_id, label.clone())) {
+            return false;
+        }
+        self.unindex_label(node_id, &label);
+        true
+    }
+
+    fn unindex_label(&mut self, node_id: NodeId, label: &Label) {
+        if let Some(node_ids) = self.label_index.get_mut(label) {
+            node_ids.remove(&node_id);
+            if node_ids.is_empty() {
+                self.label_index.remove(label);
+            }
+        }
+    }
+
+    /// Check if a `Node` has `label`.
+    pub fn has_label(&self, node_id: NodeId, label: &str) -> bool {
+        self.label_index
+            .get(label)
+            .is_some_and(|node_ids| node_ids.contains(&node_id))
+    }
+
+    /// Get an iterator over the labels of a `Node`.
+    pub fn labels_of(&self, node_id: NodeId) -> Option<impl Iterator<Item = &Label>> {
+        if !self.contains_node(node_id) {
+            return None;
+        }
+        Some(
+            self.node_labels
+                .range(label_range(node_id))
+                .map(|(_, label)| label),
+        )
+    }
+
+    /// Get an iterator over the `NodeIds` of all nodes with `label`.
+    pub fn nodes_with_label(&self, label: &str) -> impl Iterator<Item = NodeId> + '_ {
+        self.label_index
+            .get(label)
+            .into_iter()
+            .flat_map(Set::iter)
+            .copied()
+    }
+
+    /// Get an iterator over every label that at least one `Node` has.
+    pub fn iter_labels(&self) -> impl Iterator<Item = &Label> {
+        self.label_index.keys()
+    }
+
+    /// Get the properties of a node, relation or edge, if it has any.
+    pub fn properties(&self, owner: PropertyOwner) -> Option<&Properties> {
+        match owner {
+            PropertyOwner::Node(node_id) => self.node_properties.get(&node_id),
+            PropertyOwner::Relation(relation_id) => self
+                .relations
+                .get(&relation_id)
+                .map(|relation| relation.properties())
+                .filter(|properties| !properties.is_empty()),
+            PropertyOwner::Edge(edge, relation_id) => {
+                self.relations.get(&relation_id)?.edge_properties(&edge)
+            }
+        }
+    }
+
+    /// Get the value of the property with `key` of a node, relation or edge.
+    pub fn property(&self, owner: PropertyOwner, key: &str) -> Option<&Value> {
+        self.properties(owner)?.get(key)
+    }
+
+    /// Set the property with `key` of a node, relation or edge to `value`
+    /// and return its previous value.
+    pub fn set_property<V>(
+        &mut self,
+        owner: PropertyOwner,
+        key: &str,
+        value: V,
+    ) -> Result<Option<Value>, PropertyError>
+    where
+        V: Into<Value>,
+    {
+        self.check_property_owner(owner)?;
+        let key = match self.property_keys.get_key_value(key) {
+            Some((interned, _)) => interned.clone(),
+            None => {
+                let key = PropertyKey::new(key);
+                self.property_keys.insert(key.clone(), ());
+                key
+            }
+        };
+        let value = value.into();
+        Ok(match owner {
+            PropertyOwner::Node(node_id) => self
+                .node_properties
+                .get_or_default(node_id)
+                .insert(key, value),
+            PropertyOwner::Relation(relation_id) => self.relations[&relation_id]
+                .properties_mut()
+                .insert(key, value),
+            PropertyOwner::Edge(edge, relation_id) => {
+                self.relations[&relation_id].set_edge_property(&edge, key, value)
+            }
+        })
+    }
+
+    /// Remove the property with `key` of a node, relation or edge and
+    /// return its value.
+    pub fn remove_property(&mut self, owner: PropertyOwner, key: &str) -> Option<Value> {
+        match owner {
+            PropertyOwner::Node(node_id) => {
+                let properties = self.node_properties.get_mut(&node_id)?;
+                let value = properties.remove(key);
+                if properties.is_empty() {
+                    self.node_properties.remove(&node_id);
+                }
+                value
+            }
+            PropertyOwner::Relation(relation_id) => self
+                .relations
+                .get_mut(&relation_id)?
+                .properties_mut()
+                .remove(key),
+            PropertyOwner::Edge(edge, relation_id) => self
+                .relations
+                .get_mut(&relation_id)?
+                .remove_edge_property(&edge, key),
+        }
+    }
+
+    /// Get an iterator over every node, relation and edge that has
+    /// properties, together with its properties.
+    pub fn iter_properties(&self) -> impl Iterator<Item = (PropertyOwner, &Properties)> {
+        let nodes = self
+            .node_properties
+            .iter()
+            .map(|(node_id, properties)| (PropertyOwner::Node(*node_id), properties));
+        let relations = self.relations.iter().flat_map(|(relation_id, relation)| {
+            let relation_id = *relation_id;
+            let own = Some(relation.properties())
+                .filter(|properties| !properties.is_empty())
+                .map(|properties| (PropertyOwner::Relation(relation_id), properties));
+            let edges = relation
+                .iter_edge_properties()
+                .map(move |(edge, properties)| {
+                    (PropertyOwner::Edge(*edge, relation_id), properties)
+                });
+            own.into_iter().chain(edges)
+        });
+        nodes.chain(relations)
+    }
+
+    /// Check that the owner of a property exists.
+    pub(crate) fn check_property_owner(&self, owner: PropertyOwner) -> Result<(), PropertyError> {
+        match owner {
+            PropertyOwner::Node(node_id) => match self.contains_node(node_id) {
+                true => Ok(()),
+                false => Err(PropertyError::InvalidNodeId),
+            },
+            PropertyOwner::Relation(relation_id) => match self.contains_relation(relation_id) {
+                true => Ok(()),
+                false => Err(PropertyError::InvalidRelationId),
+            },
+            PropertyOwner::Edge(edge, relation_id) => {
+                let relation = self
+                    .relations
+                    .get(&relation_id)
+                    .ok_or(PropertyError::InvalidRelationId)?;
+                match !relation.is_hyper() && relation.contains_edge(&edge) {
+                    true => Ok(()),
+                    false => Err(PropertyError::InvalidEdge),
+                }
+            }
+        }
+    }
+
+    /// Remove an edge of a node that is being removed from its relation.
+    fn remove_incident_edge(&mut self, edge: Edge, relation_id: RelationId) {
+        if let Some(relation) = self.relations.get_mut(&relation_id) {
+            let edge = relation.kind().normalize(edge);
+            let multiplicity = relation.multiplicity(&edge);
+            for edge_id in relation.iter_edge_ids(&edge) {
+                self.edge_relations.remove(&edge_id);
+            }
+            if relation.remove_edge(&edge) {
+                for _ in 0..multiplicity {
+                    self.observers
+                        .emit(GraphEvent::EdgeDisconnected { edge, relation_id });
+                }
+            }
+        }
+    }
+
+    /// Create a `Relation` in the graph with `info` and return its `RelationId`.
+    pub fn add_relation<I>(&mut self, info: I) -> RelationId
+    where
+        I: Into<AnyRelationInfo>,
+    {
+        self.add_relation_of_kind(info, RelationKind::Directed)
+    }
+
+    /// Create a `Relation` in the graph with a unique `name` and `info`
+    /// and return its `RelationId`.
+    pub fn add_named_relation<S, I>(&mut self, name: S, info: I) -> Result<RelationId, NameError>
+    where
+        S: Into<String>,
+        I: Into<AnyRelationInfo>,
+    {
+        let name: Arc<str> = name.into().into();
+        if self.relation_names.contains_key(&name) {
+            return Err(NameError::NameTaken);
+        }
+        let relation_id = self.add_relation(info);
+        self.set_relation_name(relation_id, Some(name))?;
+        Ok(relation_id)
+    }
+
+    /// Get the `RelationId` of the `Relation` called `name`.
+    pub fn relation_by_name(&self, name: &str) -> Option<RelationId> {
+        self.relation_names.get(name).copied()
+    }
+
+    /// Give a `Relation` a new unique `name` and return its old name.
+    /// Renaming a relation to the name it already has does nothing.
+    pub fn rename_relation<S>(
+        &mut self,
+        relation_id: RelationId,
+        name: S,
+    ) -> Result<Option<String>, NameError>
+    where
+        S: Into<String>,
+    {
+        let old = self.set_relation_name(relation_id, Some(name.into().into()))?;
+        Ok(old.map(|old| old.to_string()))
+    }
+
+    /// Remove the name of a `Relation` and return it.
+    pub fn clear_relation_name(&mut self, relation_id: RelationId) -> Option<String> {
+        let old = self.set_relation_name(relation_id, None).ok()??;
+        Some(old.to_string())
+    }
+
+    /// Replace the name of a `Relation` and return the name it replaced.
+    pub(crate) fn set_relation_name(
+        &mut self,
+        relation_id: RelationId,
+        name: Option<Arc<str>>,
+    ) -> Result<Option<Arc<str>>, NameError> {
+        if !self.relations.contains_key(&relation_id) {
+            return Err(NameError::InvalidRelationId);
+        }
+        if let Some(name) = &name {
+            match self.relation_names.get(name) {
+                Some(owner) if *owner != relation_id => return Err(NameError::NameTaken),
+                _ => {}
+            }
+        }
+        let relation = self.relations.get_mut(&relation_id).unwrap();
+        let old = relation.set_name(name.clone());
+        if let Some(old) = &old {
+            self.relation_names.remove(old);
+        }
+        if let Some(name) = name {
+            self.relation_names.insert(name, relation_id);
+        }
+        Ok(old)
+    }
+
+    /// Create a `Relation` of the given kind in the graph with `info`
+    /// and return its `RelationId`.
+    pub fn add_relation_of_kind<I>(&mut self, info: I, kind: RelationKind) -> RelationId
+    where
+        I: Into<AnyRelationInfo>,
+    {
+        let id = self.generate_relation_id();
+        self.put_relation(id, Relation::with_kind(info, kind));
+        id
+    }
+
+    /// Create a multigraph `Relation` of the given kind in the graph with
+    /// `info` and return its `RelationId`. Its edges are connected with
+    /// `connect_multi` and can't have an inverse.
+    pub fn add_multi_relation<I>(&mut self, info: I, kind: RelationKind) -> RelationId
+    where
+        I: Into<AnyRelationInfo>,
+    {
+        let id = self.generate_relation_id();
+        self.put_relation(id, Relation::multi(info, kind));
+        id
+    }
+
+    /// Create a hyperedge `Relation` with `info` whose hyperedges are of
+    /// the given kind and return its `RelationId`. Its edges are connected
+    /// with `connect_hyperedge` and can't have an inverse.
+    pub fn add_hyper_relation<I>(&mut self, info: I, kind: HyperedgeKind) -> RelationId
+    where
+        I: Into<AnyRelationInfo>,
+    {
+        let id = self.generate_relation_id();
+        self.put_relation(id, Relation::hyper(info, kind));
+        id
+    }
+
+    /// Create a copy of `relation` without its edges and return its
+    /// `RelationId`. The copy keeps the name of the relation unless another
+    /// relation already has it.
+    pub(crate) fn add_relation_like(&mut self, relation: &Relation) -> RelationId {
+        let id = self.generate_relation_id();
+        let mut relation = relation.without_edges();
+        if let Some(name) = relation.name() {
+            if self.relation_names.contains_key(name) {
+                relation.set_name(None);
+            } else {
+                self.relation_names.insert(name.into(), id);
+            }
+        }
+        self.put_relation(id, relation);
+        id
+    }
+
+    /// Create a copy of `relation` without its edges at a known `relation_id`,
+    /// used when restoring a graph.
+    pub(crate) fn insert_relation(&mut self, relation_id: RelationId, relation: &Relation) {
+        self.relation_id_generator.reserve_sync(relation_id.get());
+        if let Some(name) = relation.name() {
+            self.relation_names.insert(name.into(), relation_id);
+        }
+        self.put_relation(relation_id, relation.without_edges());
+    }
+
+    /// Add a new relation at `relation_id`.
+    fn put_relation(&mut self, relation_id: RelationId, mut relation: Relation) {
+        if relation.is_multi() {
+            self.multi_relations += 1;
+        }
+        if self.is_shared() {
+            relation.share();
+        }
+        self.relations.insert(relation_id, relation);
+        self.observers.emit(GraphEvent::RelationAdded(relation_id));
+    }
+
+    /// Replace the state of the graph with `backup` but keep the subscriptions.
+    pub(crate) fn restore(&mut self, backup: Graph) {
+        let observers = std::mem::take(&mut self.observers);
+        *self = backup;
+        self.observers = observers;
+    }
+
+    /// Check if the graph keeps its entries in structurally shared storage,
+    /// which it does once it has been snapshotted or a transaction has begun.
+    fn is_shared(&self) -> bool {
+        self.node_info.is_shared()
+    }
+
+    pub(crate) fn observers_mut(&mut self) -> &mut Observers {
+        &mut self.observers
+    }
+
+    /// Get the `NodeId` the next call to `add_node` will return.
+    pub(crate) fn next_node_id(&self) -> NodeId {
+        NodeId::new(self.node_id_generator.peek_id())
+    }
+
+    /// Get the `RelationId` the next call to `add_relation` will return.
+    pub(crate) fn next_relation_id(&self) -> RelationId {
+        RelationId::new(self.relation_id_generator.peek_id())
+    }
+
+    /// Get the `EdgeId` the next call to `connect_multi` will return.
+    pub(crate) fn next_edge_id(&self) -> EdgeId {
+        EdgeId::new(self.edge_id_generator.peek_id())
+    }
+
+    /// Make sure ids up to `node_id` and `relation_id` are never generated again.
+    pub(crate) fn reserve_ids(&mut self, node_id: u64, relation_id: u64) {
+        self.node_id_generator.reserve_sync(node_id);
+        self.relation_id_generator.reserve_sync(relation_id);
+    }
+
+    /// Make sure edge ids up to `edge_id` are never generated again.
+    pub(crate) fn reserve_edge_id(&mut self, edge_id: u64) {
+        self.edge_id_generator.reserve_sync(edge_id);
+    }
+
+    /// Remove the `Relation` at `relation_id` and return its info if it was removed.
+    pub fn remove_relation(&mut self, relation_id: RelationId) -> Option<AnyRelationInfo> {
+        let relation = self.relations.remove(&relation_id)?;
+        if relation.is_multi() {
+            self.multi_relations -= 1;
+        }
+        self.remove_inverse(relation_id);
+        if let Some(name) = relation.name() {
+            self.relation_names.remove(name);
+        }
+
+        for (edge_id, _) in relation.iter_parallel_edges() {
+            self.edge_relations.remove(&edge_id);
+        }
+
+        for (edge_id, hyperedge) in relation.iter_hyperedges() {
+            self.unlink_hyperedge(edge_id, hyperedge);
+            self.observers.emit(GraphEvent::HyperedgeDisconnected {
+                edge_id,
+                relation_id,
+            });
+        }
+
+        for edge in relation.iter_edges() {
+            self.unlink(*edge, relation_id);
+            if relation.kind() == RelationKind::Undirected {
+                self.unlink(edge.reversed(), relation_id);
+            }
+            for _ in 0..relation.multiplicity(edge) {
+                self.observers.emit(GraphEvent::EdgeDisconnected {
+                    edge: *edge,
+                    relation_id,
+                });
+            }
+        }
+
+        self.observers
+            .emit(GraphEvent::RelationRemoved(relation_id));
+        Some(relation.into_info())
+    }
+
+    /// Connect two `Nodes` in the graph with a `Relation`.
+    ///
+    /// In an undirected relation the edge is the same as the one from
+    /// `dst` to `src`. In an antisymmetric relation connecting two distinct
+    /// nodes fails if they are already connected the other way. If the
+    /// relation has an inverse, the reverse edge is connected in it too.
+    pub fn connect(
+        &mut self,
+        src: NodeId,
+        dst: NodeId,
+        relation_id: RelationId,
+    ) -> Result<bool, ConnectError> {
+        self.can_connect(src, dst, relation_id)?;
+        let connected = self.insert_edge(src, dst, relation_id);
+        if let Some(inverse) = self.inverse_of(relation_id) {
+            self.insert_edge(dst, src, inverse);
+        }
+        Ok(connected)
+    }
+
+    /// Check that `connect` would succeed, without changing anything.
+    pub(crate) fn can_connect(
+        &self,
+        src: NodeId,
+        dst: NodeId,
+        relation_id: RelationId,
+    ) -> Result<(), ConnectError> {
+        self.check_connect(src, dst, relation_id)?;
+        let relation = &self.relations[&relation_id];
+        if relation.is_multi() {
+            return Err(ConnectError::MultiRelation);
+        }
+        if relation.is_hyper() {
+            return Err(ConnectError::HyperRelation);
+        }
+        match self.inverse_of(relation_id) {
+            Some(inverse) => self.check_connect(dst, src, inverse),
+            None => Ok(()),
+        }
+    }
+
+    /// Connect two `Nodes` with a `Relation` like `connect` and attach
+    /// `info` to the edge, replacing the info it had if it already existed.
+    /// The reverse edge in an inverse relation gets no info.
+    pub fn connect_with<I>(
+        &mut self,
+        src: NodeId,
+        dst: NodeId,
+        relation_id: RelationId,
+        info: I,
+    ) -> Result<bool, ConnectError>
+    where
+        I: Into<AnyEdgeInfo>,
+    {
+        let connected = self.connect(src, dst, relation_id)?;
+        let relation = self.relations.get_mut(&relation_id).unwrap();
+        relation.set_edge_info(&Edge::new(src, dst), info.into());
+        Ok(connected)
+    }
+
+    /// Get a shared reference to the info of an edge, if it has one.
+    pub fn edge_info(
+        &self,
+        src: NodeId,
+        dst: NodeId,
+        relation_id: RelationId,
+    ) -> Option<&dyn EdgeInfo> {
+        self.relations
+            .get(&relation_id)?
+            .edge_info(&Edge::new(src, dst))
+    }
+
+    /// Get an exclusive reference to the info of an edge, if it has one
+    /// and it is a `T`. An info shared with a snapshot or clone of the
+    /// graph is copied first.
+    pub fn edge_info_mut<T>(
+        &mut self,
+        src: NodeId,
+        dst: NodeId,
+        relation_id: RelationId,
+    ) -> Option<&mut T>
+    where
+        T: EdgeInfo + Clone,
+    {
+        self.relations
+            .get_mut(&relation_id)?
+            .edge_info_mut(&Edge::new(src, dst))
+    }
+
+    /// Detach the info of an edge and return it. The edge stays connected.
+    pub fn remove_edge_info(
+        &mut self,
+        src: NodeId,
+        dst: NodeId,
+        relation_id: RelationId,
+    ) -> Option<AnyEdgeInfo> {
+        self.relations
+            .get_mut(&relation_id)?
+            .take_edge_info(&Edge::new(src, dst))
+    }
+
+    /// Connect two `Nodes` with a new parallel edge in a multigraph
+    /// `Relation` and return its `EdgeId`.
+    ///
+    /// Unlike `connect`, this adds an edge every time it is called, even
+    /// if the nodes are already connected.
+    pub fn connect_multi(
+        &mut self,
+        src: NodeId,
+        dst: NodeId,
+        relation_id: RelationId,
+    ) -> Result<EdgeId, ConnectError> {
+        self.add_parallel_edge(src, dst, relation_id, None)
+    }
+
+    /// Connect two `Nodes` with a new parallel edge like `connect_multi`
+    /// and attach `info` to it.
+    pub fn connect_multi_with<I>(
+        &mut self,
+        src: NodeId,
+        dst: NodeId,
+        relation_id: RelationId,
+        info: I,
+    ) -> Result<EdgeId, ConnectError>
+    where
+        I: Into<AnyEdgeInfo>,
     {
-        let id = self.generate_node_id();
-        self.next_nodes.insert(id, HashMap::new());
-        self.prev_nodes.insert(id, HashMap::new());
-        self.node_info.insert(id, info.into());
-        id
+        self.add_parallel_edge(src, dst, relation_id, Some(info.into()))
     }
 
-    /// Remove the `Node` at `node_id` and return its info if it was removed.
-    pub fn remove_node(&mut self, node_id: NodeId) -> Option<AnyNodeInfo> {
-        let info = self.node_info.remove(&node_id)?;
+    fn add_parallel_edge(
+        &mut self,
+        src: NodeId,
+        dst: NodeId,
+        relation_id: RelationId,
+        info: Option<AnyEdgeInfo>,
+    ) -> Result<EdgeId, ConnectError> {
+        self.can_connect_multi(src, dst, relation_id)?;
+        let edge_id = self.generate_edge_id();
+        self.link_parallel_edge(edge_id, Edge::new(src, dst), relation_id, info);
+        Ok(edge_id)
+    }
 
-        let prev_nodes = self.prev_nodes.remove(&node_id).unwrap();
+    /// Add a parallel edge with a known `edge_id`, used when restoring a graph.
+    pub(crate) fn insert_parallel_edge(
+        &mut self,
+        edge_id: EdgeId,
+        edge: Edge,
+        relation_id: RelationId,
+        info: Option<AnyEdgeInfo>,
+    ) -> Result<(), ConnectError> {
+        self.can_connect_multi(edge.src(), edge.dst(), relation_id)?;
+        self.edge_id_generator.reserve_sync(*edge_id);
+        self.link_parallel_edge(edge_id, edge, relation_id, info);
+        Ok(())
+    }
 
-        for src_id in prev_nodes.keys() {
-            // Get all ids of relations with node as source
-            let relation_ids = self.next_nodes.get_mut(src_id)?.remove(&node_id)?;
+    /// Check that `connect_multi` would succeed, without changing anything.
+    pub(crate) fn can_connect_multi(
+        &self,
+        src: NodeId,
+        dst: NodeId,
+        relation_id: RelationId,
+    ) -> Result<(), ConnectError> {
+        if !self.validate_edge(src, dst, relation_id)?.is_multi() {
+            return Err(ConnectError::NotMultiRelation);
+        }
+        self.check_connect(src, dst, relation_id)
+    }
 
-            // Remove the edge from all relations
-            for relation_id in &relation_ids {
-                self.relations
-                    .get_mut(relation_id)?
-                    .remove_edge(&Edge::new(*src_id, node_id));
+    /// Add a checked parallel edge to a multigraph relation.
+    fn link_parallel_edge(
+        &mut self,
+        edge_id: EdgeId,
+        edge: Edge,
+        relation_id: RelationId,
+        info: Option<AnyEdgeInfo>,
+    ) {
+        let relation = self.relations.get_mut(&relation_id).unwrap();
+        let kind = relation.kind();
+        let edge = kind.normalize(edge);
+        self.edge_relations.insert(edge_id, relation_id);
+        if relation.insert_parallel_edge(edge_id, edge, info) {
+            self.link(edge, relation_id);
+            if kind == RelationKind::Undirected {
+                self.link(edge.reversed(), relation_id);
             }
         }
+        self.observers
+            .emit(GraphEvent::EdgeConnected { edge, relation_id });
+    }
 
-        let next_nodes = self.next_nodes.remove(&node_id).unwrap();
-
-        for dst_id in next_nodes.keys() {
-            // Get all ids of relations with node as destination
-            let relation_ids = self.prev_nodes.get_mut(dst_id)?.remove(&node_id)?;
-
-            // Remove the edge from all relations
-            for relation_id in &relation_ids {
-                self.relations
-                    .get_mut(relation_id)?
-                    .remove_edge(&Edge::new(node_id, *dst_id));
+    /// Disconnect a single parallel edge and return whether it existed.
+    /// Other edges between the same two nodes are kept.
+    pub fn disconnect_edge(&mut self, edge_id: EdgeId) -> bool {
+        let relation_id = match self.parallel_edge(edge_id) {
+            Some((_, relation_id)) => relation_id,
+            None => return false,
+        };
+        self.edge_relations.remove(&edge_id);
+        let relation = self.relations.get_mut(&relation_id).unwrap();
+        let kind = relation.kind();
+        let (edge, last) = relation.remove_parallel_edge(edge_id).unwrap();
+        if last {
+            self.unlink(edge, relation_id);
+            if kind == RelationKind::Undirected {
+                self.unlink(edge.reversed(), relation_id);
             }
         }
+        self.observers
+            .emit(GraphEvent::EdgeDisconnected { edge, relation_id });
+        true
+    }
 
-        Some(info)
+    /// Get the nodes connected by a parallel edge and the `RelationId`
+    /// of its relation.
+    pub fn parallel_edge(&self, edge_id: EdgeId) -> Option<(Edge, RelationId)> {
+        let relation_id = *self.edge_relations.get(&edge_id)?;
+        let edge = self.relations.get(&relation_id)?.parallel_edge(edge_id)?;
+        Some((edge, relation_id))
     }
 
-    /// Create a `Relation` in the graph with `info` and return its `RelationId`.
-    pub fn add_relation<I>(&mut self, info: I) -> RelationId
+    /// Get a shared reference to the info of a parallel edge, if it has one.
+    pub fn parallel_edge_info(&self, edge_id: EdgeId) -> Option<&dyn EdgeInfo> {
+        let (_, relation_id) = self.parallel_edge(edge_id)?;
+        self.relations[&relation_id].parallel_edge_info(edge_id)
+    }
+
+    /// Get an exclusive reference to the info of a parallel edge, if it has
+    /// one and it is a `T`. A shared info is copied first.
+    pub fn parallel_edge_info_mut<T>(&mut self, edge_id: EdgeId) -> Option<&mut T>
     where
-        I: Into<AnyRelationInfo>,
+        T: EdgeInfo + Clone,
     {
-        let id = self.generate_relation_id();
-        self.relations.insert(id, Relation::new(info));
-        id
+        let (_, relation_id) = self.parallel_edge(edge_id)?;
+        self.relations
+            .get_mut(&relation_id)?
+            .parallel_edge_info_mut(edge_id)
     }
 
-    /// Remove the `Relation` at `relation_id` and return its info if it was removed.
-    pub fn remove_relation(&mut self, relation_id: RelationId) -> Option<AnyRelationInfo> {
-        let relation = self.relations.remove(&relation_id)?;
+    /// Replace the info of a parallel edge, used when applying a diff.
+    pub(crate) fn set_parallel_edge_info(&mut self, edge_id: EdgeId, info: Option<AnyEdgeInfo>) {
+        if let Some((_, relation_id)) = self.parallel_edge(edge_id) {
+            if let Some(relation) = self.relations.get_mut(&relation_id) {
+                relation.set_parallel_edge_info(edge_id, info);
+            }
+        }
+    }
 
-        for edge in relation.iter_edges() {
-            self.prev_nodes
-                .get_mut(&edge.dst())?
-                .get_mut(&edge.src())?
-                .remove(&relation_id);
+    /// Connect the sources and targets of `hyperedge` with a hyperedge
+    /// `Relation` and return the `EdgeId` of the new hyperedge.
+    ///
+    /// Every call adds a new hyperedge, even if the relation already has
+    /// one with the same nodes.
+    pub fn connect_hyperedge(
+        &mut self,
+        relation_id: RelationId,
+        hyperedge: Hyperedge,
+    ) -> Result<EdgeId, ConnectError> {
+        self.can_connect_hyperedge(relation_id, &hyperedge)?;
+        let edge_id = self.generate_edge_id();
+        self.link_hyperedge(edge_id, relation_id, hyperedge);
+        Ok(edge_id)
+    }
 
-            self.next_nodes
-                .get_mut(&edge.src())?
-                .get_mut(&edge.dst())?
-                .remove(&relation_id);
+    /// Add a hyperedge with a known `edge_id`, used when restoring a graph.
+    pub(crate) fn insert_hyperedge(
+        &mut self,
+        edge_id: EdgeId,
+        relation_id: RelationId,
+        hyperedge: Hyperedge,
+    ) -> Result<(), ConnectError> {
+        self.can_connect_hyperedge(relation_id, &hyperedge)?;
+        self.edge_id_generator.reserve_sync(*edge_id);
+        self.link_hyperedge(edge_id, relation_id, hyperedge);
+        Ok(())
+    }
+
+    /// Check that `connect_hyperedge` would succeed, without changing anything.
+    pub(crate) fn can_connect_hyperedge(
+        &self,
+        relation_id: RelationId,
+        hyperedge: &Hyperedge,
+    ) -> Result<(), ConnectError> {
+        if !hyperedge.targets().iter().all(|id| self.contains_node(*id)) {
+            return Err(ConnectError::InvalidDstNodeId);
+        }
+        if !hyperedge.sources().iter().all(|id| self.contains_node(*id)) {
+            return Err(ConnectError::InvalidSrcNodeId);
+        }
+        match self.relations.get(&relation_id) {
+            Some(relation) if relation.is_hyper() => Ok(()),
+            Some(_) => Err(ConnectError::NotHyperRelation),
+            None => Err(ConnectError::InvalidRelationId),
         }
+    }
 
-        Some(relation.into_info())
+    /// Add a checked hyperedge to a hyperedge relation and index it by node
+    /// and by `EdgeId`.
+    fn link_hyperedge(&mut self, edge_id: EdgeId, relation_id: RelationId, hyperedge: Hyperedge) {
+        let relation = self.relations.get_mut(&relation_id).unwrap();
+        let hyperedge = relation.insert_hyperedge(edge_id, hyperedge);
+        self.edge_relations.insert(edge_id, relation_id);
+        for node_id in hyperedge.iter_nodes() {
+            self.hyper_incidence.insert((node_id, edge_id), relation_id);
+        }
+        self.observers.emit(GraphEvent::HyperedgeConnected {
+            edge_id,
+            relation_id,
+        });
     }
 
-    /// Connect two `Nodes` in the graph with a `Relation`.
-    pub fn connect(
+    /// Remove a hyperedge from the node and edge indexes.
+    fn unlink_hyperedge(&mut self, edge_id: EdgeId, hyperedge: &Hyperedge) {
+        self.edge_relations.remove(&edge_id);
+        for node_id in hyperedge.iter_nodes() {
+            self.hyper_incidence.remove(&(node_id, edge_id));
+        }
+    }
+
+    /// Disconnect a hyperedge and return whether it existed.
+    pub fn disconnect_hyperedge(&mut self, edge_id: EdgeId) -> bool {
+        match self.hyperedge(edge_id) {
+            Some((_, relation_id)) => {
+                self.remove_hyperedge(edge_id, relation_id);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Disconnect an existing hyperedge of the relation at `relation_id`.
+    fn remove_hyperedge(&mut self, edge_id: EdgeId, relation_id: RelationId) {
+        let relation = self.relations.get_mut(&relation_id).unwrap();
+        let hyperedge = relation.remove_hyperedge(edge_id).unwrap();
+        self.unlink_hyperedge(edge_id, &hyperedge);
+        self.observers.emit(GraphEvent::HyperedgeDisconnected {
+            edge_id,
+            relation_id,
+        });
+    }
+
+    /// Get a hyperedge and the `RelationId` of its relation.
+    pub fn hyperedge(&self, edge_id: EdgeId) -> Option<(&Hyperedge, RelationId)> {
+        let relation_id = *self.edge_relations.get(&edge_id)?;
+        let hyperedge = self.relations.get(&relation_id)?.hyperedge(edge_id)?;
+        Some((hyperedge, relation_id))
+    }
+
+    /// Get an iterator over the hyperedges a `Node` takes part in, as a
+    /// source or a target, together with the `RelationId` of each.
+    pub fn iter_hyperedges_of(
+        &self,
+        node_id: NodeId,
+    ) -> Option<impl Iterator<Item = (EdgeId, RelationId)> + '_> {
+        if !self.contains_node(node_id) {
+            return None;
+        }
+        Some(
+            self.hyper_incidence
+                .range((node_id, EdgeId::new(0))..=(node_id, EdgeId::new(u64::MAX)))
+                .map(|((_, edge_id), relation_id)| (*edge_id, *relation_id)),
+        )
+    }
+
+    /// Check that an edge from `src` to `dst` can be added to a relation.
+    fn check_connect(
+        &self,
+        src: NodeId,
+        dst: NodeId,
+        relation_id: RelationId,
+    ) -> Result<(), ConnectError> {
+        let relation = self.validate_edge(src, dst, relation_id)?;
+        if relation.kind() == RelationKind::Antisymmetric
+            && src != dst
+            && relation.contains_edge(&Edge::new(dst, src))
+        {
+            return Err(ConnectError::ReverseEdgeExists);
+        }
+        self.check_schema(src, dst, relation_id, relation)
+    }
+
+    /// Check that `edges` can be connected one after another, without
+    /// changing anything. Edges checked earlier count towards the
+    /// antisymmetry and cardinality of the later ones.
+    fn check_connect_all(&self, edges: &[(Edge, RelationId)]) -> Result<(), ConnectError> {
+        let mut pending = HashSet::new();
+        let mut out_degrees = HashMap::new();
+        let mut in_degrees = HashMap::new();
+        for (edge, relation_id) in edges {
+            let (src, dst, relation_id) = (edge.src(), edge.dst(), *relation_id);
+            self.check_connect(src, dst, relation_id)?;
+            let relation = &self.relations[&relation_id];
+            let kind = relation.kind();
+            let normalized = kind.normalize(*edge);
+            if relation.contains_edge(edge) || !pending.insert((normalized, relation_id)) {
+                continue;
+            }
+            if kind == RelationKind::Antisymmetric
+                && src != dst
+                && pending.contains(&(normalized.reversed(), relation_id))
+            {
+                return Err(ConnectError::ReverseEdgeExists);
+            }
+            if let Some(schema) = relation.schema() {
+                let cardinality = schema.cardinality();
+                let out_degree = out_degrees.get(&(src, relation_id)).copied();
+                let in_degree = in_degrees.get(&(dst, relation_id)).copied();
+                let violation = if cardinality.max_out_degree().is_some_and(|max| {
+                    self.count_relation(src, relation_id, true) + out_degree.unwrap_or(0) >= max
+                }) {
+                    Some(SchemaViolation::MaxOutDegree)
+                } else if cardinality.max_in_degree().is_some_and(|max| {
+                    self.count_relation(dst, relation_id, false) + in_degree.unwrap_or(0) >= max
+                }) {
+                    Some(SchemaViolation::MaxInDegree)
+                } else {
+                    None
+                };
+                if let Some(violation) = violation {
+                    return Err(ConnectError::SchemaViolation(violation));
+                }
+            }
+            *out_degrees.entry((src, relation_id)).or_insert(0) += 1;
+            *in_degrees.entry((dst, relation_id)).or_insert(0) += 1;
+            if kind == RelationKind::Undirected && src != dst {
+                *out_degrees.entry((dst, relation_id)).or_insert(0) += 1;
+                *in_degrees.entry((src, relation_id)).or_insert(0) += 1;
+            }
+        }
+        Ok(())
+    }
+
+    /// Add a checked edge to a relation and return whether it is new.
+    fn insert_edge(&mut self, src: NodeId, dst: NodeId, relation_id: RelationId) -> bool {
+        let relation = self.relations.get_mut(&relation_id).unwrap();
+        let kind = relation.kind();
+        let edge = kind.normalize(Edge::new(src, dst));
+        if !relation.insert_edge(edge) {
+            return false;
+        }
+        self.link(edge, relation_id);
+        if kind == RelationKind::Undirected {
+            self.link(edge.reversed(), relation_id);
+        }
+        self.observers
+            .emit(GraphEvent::EdgeConnected { edge, relation_id });
+        true
+    }
+
+    /// Remove an edge from an existing relation and return whether it was there.
+    fn remove_edge(&mut self, src: NodeId, dst: NodeId, relation_id: RelationId) -> bool {
+        let relation = self.relations.get_mut(&relation_id).unwrap();
+        let kind = relation.kind();
+        let edge = kind.normalize(Edge::new(src, dst));
+        let multiplicity = relation.multiplicity(&edge);
+        for edge_id in relation.iter_edge_ids(&edge) {
+            self.edge_relations.remove(&edge_id);
+        }
+        if !relation.remove_edge(&edge) {
+            return false;
+        }
+        self.unlink(edge, relation_id);
+        if kind == RelationKind::Undirected {
+            self.unlink(edge.reversed(), relation_id);
+        }
+        for _ in 0..multiplicity {
+            self.observers
+                .emit(GraphEvent::EdgeDisconnected { edge, relation_id });
+        }
+        true
+    }
+
+    /// Declare `a` and `b` to be each other's inverse, so that every edge
+    /// from `x` to `y` in one of them is matched by an edge from `y` to `x`
+    /// in the other.
+    ///
+    /// Edges that already exist are mirrored into the other relation. If one
+    /// of them can't be, nothing is changed and the error is returned.
+    pub fn set_inverse(&mut self, a: RelationId, b: RelationId) -> Result<(), ConnectError> {
+        if let Some(edges) = self.inverse_edges(a, b)? {
+            self.pair_inverses(a, b, edges);
+        }
+        Ok(())
+    }
+
+    /// Check that `a` and `b` can be declared each other's inverse and get
+    /// the edges that mirroring their existing edges adds, or `None` if they
+    /// already are each other's inverse.
+    pub(crate) fn inverse_edges(
+        &self,
+        a: RelationId,
+        b: RelationId,
+    ) -> Result<Option<Vec<(Edge, RelationId)>>, ConnectError> {
+        if !self.contains_relation(a) || !self.contains_relation(b) {
+            return Err(ConnectError::InvalidRelationId);
+        }
+        let paired = |relation: &Relation| !relation.is_multi() && !relation.is_hyper();
+        if !paired(&self.relations[&a]) || !paired(&self.relations[&b]) {
+            return Err(ConnectError::InverseConflict);
+        }
+        match (self.inverse_of(a), self.inverse_of(b)) {
+            (Some(inverse), _) if inverse == b => return Ok(None),
+            (None, None) if a != b => {}
+            _ => return Err(ConnectError::InverseConflict),
+        }
+
+        let mut edges: Vec<_> = self.relations[&a]
+            .iter_edges()
+            .map(|edge| (edge.reversed(), b))
+            .collect();
+        edges.extend(
+            self.relations[&b]
+                .iter_edges()
+                .map(|edge| (edge.reversed(), a)),
+        );
+        self.check_connect_all(&edges)?;
+        Ok(Some(edges))
+    }
+
+    /// Pair `a` and `b` as inverses and add the checked `edges` that
+    /// mirror their existing edges.
+    pub(crate) fn pair_inverses(
         &mut self,
+        a: RelationId,
+        b: RelationId,
+        edges: Vec<(Edge, RelationId)>,
+    ) {
+        for (edge, relation_id) in edges {
+            self.insert_edge(edge.src(), edge.dst(), relation_id);
+        }
+        self.inverses.insert(a, b);
+        self.inverses.insert(b, a);
+    }
+
+    /// Remove the pairing of a `Relation` with its inverse and return the
+    /// `RelationId` of the inverse. The edges of both relations are kept.
+    pub fn remove_inverse(&mut self, relation_id: RelationId) -> Option<RelationId> {
+        let inverse = self.inverses.remove(&relation_id)?;
+        self.inverses.remove(&inverse);
+        Some(inverse)
+    }
+
+    /// Get the `RelationId` of the inverse of a `Relation`, if it has one.
+    pub fn inverse_of(&self, relation_id: RelationId) -> Option<RelationId> {
+        if self.inverses.is_empty() {
+            return None;
+        }
+        self.inverses.get(&relation_id).copied()
+    }
+
+    /// Get an iterator over the pairs of inverse `Relations`, each pair
+    /// listed once in both orders.
+    pub(crate) fn iter_inverses(&self) -> impl Iterator<Item = (RelationId, RelationId)> + '_ {
+        self.inverses.iter().map(|(a, b)| (*a, *b))
+    }
+
+    /// Check that the endpoints and the relation of an edge exist.
+    fn validate_edge(
+        &self,
         src: NodeId,
         dst: NodeId,
         relation_id: RelationId,
-    ) -> Result<bool, ConnectError> {
-        let in_nodes = self
-            .prev_nodes
-            .get_mut(&dst)
-            .ok_or(ConnectError::InvalidDstNodeId)?;
+    ) -> Result<&Relation, ConnectError> {
+        if !self.node_info.contains_key(&dst) {
+            return Err(ConnectError::InvalidDstNodeId);
+        }
+        if !self.node_info.contains_key(&src) {
+            return Err(ConnectError::InvalidSrcNodeId);
+        }
+        self.relations
+            .get(&relation_id)
+            .ok_or(ConnectError::InvalidRelationId)
+    }
+
+    /// Add `relation_id` to the adjacency of both endpoints of `edge`.
+    fn link(&mut self, edge: Edge, relation_id: RelationId) {
+        self.next_nodes
+            .get_or_default(edge.src())
+            .get_or_default(edge.dst())
+            .insert(relation_id);
+        self.prev_nodes
+            .get_or_default(edge.dst())
+            .get_or_default(edge.src())
+            .insert(relation_id);
+    }
+
+    /// Remove `relation_id` from the adjacency of both endpoints of `edge`.
+    fn unlink(&mut self, edge: Edge, relation_id: RelationId) {
+        remove_adjacency(&mut self.next_nodes, edge.src(), edge.dst(), relation_id);
+        remove_adjacency(&mut self.prev_nodes, edge.dst(), edge.src(), relation_id);
+    }
+
+    /// Check that a new edge from `src` to `dst` satisfies the schema of
+    /// `relation`. Invalid node ids are left for the caller to report.
+    fn check_schema(
+        &self,
+        src: NodeId,
+        dst: NodeId,
+        relation_id: RelationId,
+        relation: &Relation,
+    ) -> Result<(), ConnectError> {
+        let schema = match relation.schema() {
+            Some(schema) => schema,
+            None => return Ok(()),
+        };
+        if !relation.is_multi() && relation.contains_edge(&Edge::new(src, dst)) {
+            return Ok(());
+        }
+        let (src_info, dst_info) = match (self.node_info.get(&src), self.node_info.get(&dst)) {
+            (Some(src_info), Some(dst_info)) => (src_info, dst_info),
+            _ => return Ok(()),
+        };
+
+        let violation = if !schema.allows_src(&**src_info) {
+            Some(SchemaViolation::SrcType)
+        } else if !schema.allows_dst(&**dst_info) {
+            Some(SchemaViolation::DstType)
+        } else if schema
+            .cardinality()
+            .max_out_degree()
+            .is_some_and(|max| self.count_relation(src, relation_id, true) >= max)
+        {
+            Some(SchemaViolation::MaxOutDegree)
+        } else if schema
+            .cardinality()
+            .max_in_degree()
+            .is_some_and(|max| self.count_relation(dst, relation_id, false) >= max)
+        {
+            Some(SchemaViolation::MaxInDegree)
+        } else {
+            None
+        };
+        violation.map_or(Ok(()), |violation| {
+            Err(ConnectError::SchemaViolation(violation))
+        })
+    }
 
-        let out_nodes = self
-            .next_nodes
-            .get_mut(&src)
-            .ok_or(ConnectError::InvalidSrcNodeId)?;
+    /// Set the schema that new edges of a `Relation` must satisfy and
+    /// return whether the relation exists. Edges that were connected
+    /// before are not checked.
+    pub fn set_relation_schema(&mut self, relation_id: RelationId, schema: RelationSchema) -> bool {
+        self.update_schema(relation_id, Some(schema))
+    }
 
-        let relations = self
-            .relations
-            .get_mut(&relation_id)
-            .ok_or(ConnectError::InvalidRelationId)?;
+    /// Remove the schema of a `Relation` and return whether the relation exists.
+    pub fn clear_relation_schema(&mut self, relation_id: RelationId) -> bool {
+        self.update_schema(relation_id, None)
+    }
 
-        in_nodes.entry(src).or_default().insert(relation_id);
-        out_nodes.entry(dst).or_default().insert(relation_id);
-        Ok(relations.insert_edge(Edge::new(src, dst)))
+    fn update_schema(&mut self, relation_id: RelationId, schema: Option<RelationSchema>) -> bool {
+        match self.relations.get_mut(&relation_id) {
+            Some(relation) => {
+                relation.set_schema(schema);
+                true
+            }
+            None => false,
+        }
     }
 
-    /// Disconnect the `Relation` between two `Nodes`.
+    /// Disconnect the `Relation` between two `Nodes`. If the relation has
+    /// an inverse, the reverse edge is disconnected in it too.
     pub fn disconnect(
         &mut self,
         src: NodeId,
         dst: NodeId,
         relation_id: RelationId,
     ) -> Result<bool, ConnectError> {
-        let in_nodes = self
-            .prev_nodes
-            .get_mut(&dst)
-            .ok_or(ConnectError::InvalidDstNodeId)?;
-
-        let out_nodes = self
-            .next_nodes
-            .get_mut(&src)
-            .ok_or(ConnectError::InvalidSrcNodeId)?;
-
-        let relation_data = self
-            .relations
-            .get_mut(&relation_id)
-            .ok_or(ConnectError::InvalidRelationId)?;
-
-        if relation_data.remove_edge(&Edge::new(src, dst)) {
-            in_nodes.get_mut(&src).unwrap().remove(&relation_id);
-            out_nodes.get_mut(&dst).unwrap().remove(&relation_id);
-            Ok(true)
-        } else {
-            Ok(false)
+        self.validate_edge(src, dst, relation_id)?;
+        let disconnected = self.remove_edge(src, dst, relation_id);
+        if let Some(inverse) = self.inverse_of(relation_id) {
+            self.remove_edge(dst, src, inverse);
         }
+        Ok(disconnected)
+    }
+
+    /// Call `callback` for every change made to the graph that passes `filter`.
+    ///
+    /// Events are delivered as the changes are made. Events of changes made
+    /// inside a `Transaction` are delivered when it commits, and dropped
+    /// when it is rolled back.
+    pub fn subscribe<F>(&mut self, filter: EventFilter, callback: F) -> SubscriptionId
+    where
+        F: Fn(&GraphEvent) + Send + Sync + 'static,
+    {
+        self.observers.subscribe(filter, callback)
+    }
+
+    /// Send every change made to the graph that passes `filter` to a channel
+    /// and return its receiving end. Dropping the receiver ends the subscription.
+    pub fn subscribe_channel(
+        &mut self,
+        filter: EventFilter,
+    ) -> (SubscriptionId, Receiver<GraphEvent>) {
+        self.observers.subscribe_channel(filter)
+    }
+
+    /// End a subscription and return whether it existed.
+    pub fn unsubscribe(&mut self, subscription_id: SubscriptionId) -> bool {
+        self.observers.unsubscribe(subscription_id)
     }
 
     /// Get the number of `Nodes` in the graph.
@@ -173,33 +1614,93 @@ impl Graph {
         self.relations.len()
     }
 
-    /// Get the in degree of a `Node`.
+    /// Check if the graph contains a `Node` with `node_id`.
+    pub fn contains_node(&self, node_id: NodeId) -> bool {
+        self.node_info.contains_key(&node_id)
+    }
+
+    /// Check if the graph contains a `Relation` with `relation_id`.
+    pub fn contains_relation(&self, relation_id: RelationId) -> bool {
+        self.relations.contains_key(&relation_id)
+    }
+
+    /// Get a shared reference to the info of a `Node`.
+    pub fn node_info(&self, node_id: NodeId) -> Option<&dyn NodeInfo> {
+        self.node_info.get(&node_id).map(|info| &**info)
+    }
+
+    pub(crate) fn any_node_info(&self, node_id: NodeId) -> Option<&AnyNodeInfo> {
+        self.node_info.get(&node_id)
+    }
+
+    /// Get a shared reference to a `Relation`.
+    pub fn relation(&self, relation_id: RelationId) -> Option<&Relation> {
+        self.relations.get(&relation_id)
+    }
+
+    /// Get the in degree of a `Node`. Parallel edges are each counted.
     pub fn in_degree_of(&self, node_id: NodeId) -> Option<usize> {
+        self.node_info.get(&node_id)?;
+        if self.multi_relations == 0 {
+            return Some(adjacency_len(&self.prev_nodes, node_id));
+        }
         Some(
-            self.prev_nodes
-                .get(&node_id)?
-                .values()
-                .map(|r| r.len())
+            iter_adjacency(&self.prev_nodes, node_id)
+                .map(|(src, r)| self.multiplicity(Edge::new(src, node_id), r))
                 .sum(),
         )
     }
 
-    /// Get the out degree of a `Node`.
+    /// Get the out degree of a `Node`. Parallel edges are each counted.
     pub fn out_degree_of(&self, node_id: NodeId) -> Option<usize> {
+        self.node_info.get(&node_id)?;
+        if self.multi_relations == 0 {
+            return Some(adjacency_len(&self.next_nodes, node_id));
+        }
         Some(
-            self.next_nodes
-                .get(&node_id)?
-                .values()
-                .map(|r| r.len())
+            iter_adjacency(&self.next_nodes, node_id)
+                .map(|(dst, r)| self.multiplicity(Edge::new(node_id, dst), r))
                 .sum(),
         )
     }
 
+    /// Count the edges from `src` to `dst` in the relation `relation_id`.
+    fn multiplicity(&self, edge: Edge, relation_id: RelationId) -> usize {
+        if self.multi_relations == 0 {
+            return 1;
+        }
+        match self.relations.get(&relation_id) {
+            Some(relation) if relation.is_multi() => relation.multiplicity(&edge),
+            _ => 1,
+        }
+    }
+
+    /// Count the edges of `relation_id` leaving or entering a `Node`.
+    fn count_relation(&self, node_id: NodeId, relation_id: RelationId, outgoing: bool) -> usize {
+        let adjacency = match outgoing {
+            true => &self.next_nodes,
+            false => &self.prev_nodes,
+        };
+        iter_adjacency(adjacency, node_id)
+            .filter(|(_, r)| *r == relation_id)
+            .map(|(other, _)| match outgoing {
+                true => Edge::new(node_id, other),
+                false => Edge::new(other, node_id),
+            })
+            .map(|edge| self.relations[&relation_id].multiplicity(&edge))
+            .sum()
+    }
+
     /// Get an iterator over all `Nodes` in the graph.
     pub fn iter_nodes(&self) -> impl Iterator<Item = &AnyNodeInfo> {
         self.node_info.values()
     }
 
+    /// Get an iterator over all `NodeIds` and node infos in the graph.
+    pub fn iter_node_ids(&self) -> impl Iterator<Item = (NodeId, &AnyNodeInfo)> {
+        self.node_info.iter().map(|(k, v)| (*k, v))
+    }
+
     /// Get an iterator over all `RelationIds` and `Relations` in the graph.
     pub fn iter_relations(&self) -> impl Iterator<Item = (RelationId, &Relation)> {
         self.relations.iter().map(|(k, v)| (*k, v))
@@ -212,4 +1713,331 @@ impl Graph {
     ) -> Option<impl Iterator<Item = &Edge>> {
         self.relations.get(&relation_id).map(|r| r.iter_edges())
     }
+
+    /// Get an immutable view of the current state of the graph.
+    ///
+    /// Later mutations of the graph are not visible through the snapshot.
+    /// The first snapshot or transaction moves the graph to persistent maps
+    /// and sets, which takes time linear in its size, and every snapshot
+    /// after it is cheap. Data that is only referenced by old snapshots is
+    /// freed as soon as the last of them is dropped.
+    pub fn snapshot(&mut self) -> GraphSnapshot {
+        self.share();
+        GraphSnapshot::new(self.clone())
+    }
+
+    /// Start a `Transaction` on the graph.
+    ///
+    /// All mutations made through the transaction are undone, including
+    /// the ids handed out, unless it is committed.
+    pub fn begin(&mut self) -> Transaction<'_> {
+        Transaction::new(self)
+    }
+
+    /// Run `f` in a `Transaction` that is committed if `f` returns `Ok`
+    /// and rolled back if it returns `Err` or panics.
+    pub fn transaction<F, T, E>(&mut self, f: F) -> Result<T, E>
+    where
+        F: FnOnce(&mut Transaction) -> Result<T, E>,
+    {
+        let mut transaction = self.begin();
+        let result = f(&mut transaction)?;
+        transaction.commit();
+        Ok(result)
+    }
+
+    /// Get the changes that turn this graph into `other`, matching
+    /// `Nodes` and `Relations` by their ids. Infos are compared by their
+    /// encoding with the `DefaultCodec`, see `diff_with_codec`.
+    pub fn diff(&self, other: &Graph) -> GraphDiff {
+        GraphDiff::between(self, other, &DefaultCodec)
+    }
+
+    /// Get the changes that turn this graph into `other` like `diff`, but
+    /// compare node and edge infos by their encoding with `codec`. Infos
+    /// that `codec` can't encode are only equal if they are shared.
+    pub fn diff_with_codec<C>(&self, other: &Graph, codec: &C) -> GraphDiff
+    where
+        C: InfoCodec,
+    {
+        GraphDiff::between(self, other, codec)
+    }
+
+    /// Replay the changes of `diff` on the graph.
+    ///
+    /// The diff is applied as a whole or, if one of its edges cannot be
+    /// connected or disconnected or one of its infos violates a unique
+    /// index, not at all.
+    pub fn apply(&mut self, diff: &GraphDiff) -> Result<(), WriteError> {
+        self.transaction(|transaction| diff.apply_to(transaction))
+    }
+
+    /// Copy the `Nodes` in `node_ids` and the edges between them into a new
+    /// graph, with all relations. Returns the graph and the map from the
+    /// old ids to the new ones.
+    ///
+    /// Node infos are cloned with `clone_info`, for example `|_, info|
+    /// info.clone()` to share them. Relation and edge infos are shared, and
+    /// mutating them in either graph copies them first. Labels and
+    /// properties are copied along; indexes, inverses and subscriptions are
+    /// not. Relations keep their schemas, but edges are copied without
+    /// checking them, so none are lost if a cloned info no longer fits.
+    /// Views and other backends can be copied with
+    /// `GraphRead::induced_subgraph`.
+    pub fn induced_subgraph<N, F>(&self, node_ids: N, clone_info: F) -> (Graph, IdMap)
+    where
+        N: IntoIterator<Item = NodeId>,
+        F: FnMut(NodeId, &AnyNodeInfo) -> AnyNodeInfo,
+    {
+        subgraph::induced(self, node_ids, clone_info)
+    }
+
+    /// Copy the edges in `edges`, their nodes and their relations into a
+    /// new graph, like `induced_subgraph`. Parallel edges between the same
+    /// two nodes are copied together, edges that don't exist are skipped.
+    pub fn edge_subgraph<E, F>(&self, edges: E, clone_info: F) -> (Graph, IdMap)
+    where
+        E: IntoIterator<Item = (Edge, RelationId)>,
+        F: FnMut(NodeId, &AnyNodeInfo) -> AnyNodeInfo,
+    {
+        subgraph::by_edges(self, edges, clone_info)
+    }
+
+    /// Copy the neighborhood of `center` into a new graph, like
+    /// `induced_subgraph`: every `Node` within `radius` edges of it in
+    /// either direction, following only the edges of `relation_ids`, or of
+    /// all relations if it is `None`. Returns `None` if `center` does not
+    /// exist.
+    pub fn ego_graph<F>(
+        &self,
+        center: NodeId,
+        radius: usize,
+        relation_ids: Option<&[RelationId]>,
+        clone_info: F,
+    ) -> Option<(Graph, IdMap)>
+    where
+        F: FnMut(NodeId, &AnyNodeInfo) -> AnyNodeInfo,
+    {
+        subgraph::ego(self, center, radius, relation_ids, clone_info)
+    }
+
+    /// Copy the `Nodes`, relations and edges of `other` into the graph and
+    /// return the map from the ids of `other` to those in the graph.
+    ///
+    /// A node of `other` is the same node as the oldest node of the graph
+    /// with the same key from `node_key`, or if there is none as the first
+    /// node of `other` with that key; nodes without a key are always
+    /// copied. The info of a matched node is replaced with the result of
+    /// `merge_info(info, other_info)`, and its labels and properties are
+    /// added, overwriting properties with the same key. Relations are
+    /// matched by name, unnamed relations of `other` are always copied as
+    /// new relations. Edges of matched relations are added to the existing
+    /// ones, so parallel edges add up.
+    ///
+    /// Fails without changing the graph if a merged or copied info violates
+    /// a unique index.
+    pub fn merge<K, KF, MF>(
+        &mut self,
+        other: &Graph,
+        node_key: KF,
+        merge_info: MF,
+    ) -> Result<IdMap, UniqueError>
+    where
+        K: Hash + Eq,
+        KF: Fn(&dyn NodeInfo) -> Option<K>,
+        MF: FnMut(&AnyNodeInfo, &AnyNodeInfo) -> AnyNodeInfo,
+    {
+        combine::merge(self, other, node_key, merge_info)
+    }
+
+    /// Merge a copy of the graph with `other`, see `merge`. The ids of the
+    /// graph are kept, the returned map is for the ids of `other`.
+    ///
+    /// Fails if a merged or copied info violates a unique index.
+    pub fn union<K, KF, MF>(
+        &self,
+        other: &Graph,
+        node_key: KF,
+        merge_info: MF,
+    ) -> Result<(Graph, IdMap), UniqueError>
+    where
+        K: Hash + Eq,
+        KF: Fn(&dyn NodeInfo) -> Option<K>,
+        MF: FnMut(&AnyNodeInfo, &AnyNodeInfo) -> AnyNodeInfo,
+    {
+        let mut graph = self.clone();
+        let ids = combine::merge(&mut graph, other, node_key, merge_info)?;
+        Ok((graph, ids))
+    }
+
+    /// Copy the graph with only the relations that `other` has as well,
+    /// matched like in `merge`, and only the edges of those relations that
+    /// `other` has between the nodes with the same keys. All nodes are
+    /// copied. Returns the graph and the map from the old ids to the new
+    /// ones. Hyperedges are left out.
+    pub fn intersection<K, KF>(&self, other: &Graph, node_key: KF) -> (Graph, IdMap)
+    where
+        K: Hash + Eq,
+        KF: Fn(&dyn NodeInfo) -> Option<K>,
+    {
+        combine::intersection(self, other, node_key)
+    }
+
+    /// Copy the graph without the edges that `other` has as well, matched
+    /// like in `intersection`. All nodes and relations are copied. Returns
+    /// the graph and the map from the old ids to the new ones. Hyperedges
+    /// are left out.
+    pub fn difference<K, KF>(&self, other: &Graph, node_key: KF) -> (Graph, IdMap)
+    where
+        K: Hash + Eq,
+        KF: Fn(&dyn NodeInfo) -> Option<K>,
+    {
+        combine::difference(self, other, node_key)
+    }
+
+    /// Merge the `Node` at `absorb` into the node at `keep`: move every
+    /// edge and hyperedge of `absorb`, in all relations, to `keep`, then
+    /// remove `absorb`.
+    ///
+    /// `keep` gets the info returned by `info_merge(keep_info,
+    /// absorb_info)` and the labels of both nodes. Properties of `absorb`
+    /// are added unless `keep` has one with the same key. Edges between
+    /// the two nodes become self-loops of `keep`. A moved edge that
+    /// `keep` already has keeps its info and properties; moved parallel
+    /// edges and hyperedges get new `EdgeIds`.
+    ///
+    /// Nothing is changed if a moved edge is rejected, for example by a
+    /// schema or an antisymmetric relation, or if the merged info
+    /// violates a unique index.
+    pub fn merge_nodes<F>(
+        &mut self,
+        keep: NodeId,
+        absorb: NodeId,
+        info_merge: F,
+    ) -> Result<(), MergeError>
+    where
+        F: FnOnce(&AnyNodeInfo, &AnyNodeInfo) -> AnyNodeInfo,
+    {
+        contract::merge_nodes(self, keep, absorb, info_merge, |_, _, _| true)
+    }
+
+    /// Contract the edge from `src` to `dst` in a `Relation`: merge `dst`
+    /// into `src` like `merge_nodes`, keeping the info of `src`, and
+    /// disconnect the edge. In a multigraph relation only the parallel edge
+    /// with the lowest `EdgeId` is contracted.
+    ///
+    /// The other edges between `src` and `dst`, including the other
+    /// parallel edges, become self-loops of `src` or are disconnected, as
+    /// `self_loops` says. Self-loops the nodes already had are kept either
+    /// way.
+    pub fn contract_edge(
+        &mut self,
+        src: NodeId,
+        dst: NodeId,
+        relation_id: RelationId,
+        self_loops: SelfLoops,
+    ) -> Result<(), MergeError> {
+        contract::contract_edge(self, src, dst, relation_id, self_loops)
+    }
+
+    /// Get an iterator over the destinations of all edges leaving a `Node`,
+    /// together with the `RelationId` of each edge.
+    pub fn iter_next_nodes(
+        &self,
+        node_id: NodeId,
+    ) -> Option<impl Iterator<Item = (NodeId, RelationId)> + '_> {
+        self.node_info.get(&node_id)?;
+        Some(iter_adjacency(&self.next_nodes, node_id))
+    }
+
+    /// Get an iterator over the sources of all edges entering a `Node`,
+    /// together with the `RelationId` of each edge.
+    pub fn iter_prev_nodes(
+        &self,
+        node_id: NodeId,
+    ) -> Option<impl Iterator<Item = (NodeId, RelationId)> + '_> {
+        self.node_info.get(&node_id)?;
+        Some(iter_adjacency(&self.prev_nodes, node_id))
+    }
+}
+
+impl Share for Graph {
+    fn share(&mut self) {
+        if self.is_shared() {
+            return;
+        }
+        for adjacency in [&mut self.next_nodes, &mut self.prev_nodes] {
+            adjacency.values_mut().for_each(Map::share_values);
+            adjacency.share();
+        }
+        self.node_info.share();
+        self.relations.share_values();
+        self.inverses.share();
+        self.edge_relations.share();
+        self.hyper_incidence.share();
+        self.relation_names.share();
+        self.node_labels.share();
+        self.label_index.share_values();
+        self.node_properties.share_values();
+        self.property_keys.share();
+        self.indexes.share_values();
+        self.text_indexes.share_values();
+    }
+}
+
+/// The adjacency of a graph in one direction: the relations of the edges
+/// between each node and each of its neighbors.
+type Adjacency = Map<NodeId, Map<NodeId, Set<RelationId>>>;
+
+fn iter_adjacency(
+    adjacency: &Adjacency,
+    node_id: NodeId,
+) -> impl Iterator<Item = (NodeId, RelationId)> + '_ {
+    adjacency
+        .get(&node_id)
+        .into_iter()
+        .flat_map(Map::iter)
+        .flat_map(|(other, relation_ids)| {
+            relation_ids
+                .iter()
+                .map(move |relation_id| (*other, *relation_id))
+        })
+}
+
+/// Count the `(neighbor, relation)` pairs of `node_id` in `adjacency`.
+fn adjacency_len(adjacency: &Adjacency, node_id: NodeId) -> usize {
+    adjacency
+        .get(&node_id)
+        .into_iter()
+        .flat_map(Map::values)
+        .map(Set::len)
+        .sum()
+}
+
+/// Remove the edge from `node_id` to `other` in `relation_id` from
+/// `adjacency`, dropping the neighbor once it has no edges left.
+fn remove_adjacency(
+    adjacency: &mut Adjacency,
+    node_id: NodeId,
+    other: NodeId,
+    relation_id: RelationId,
+) {
+    if let Some(nodes) = adjacency.get_mut(&node_id) {
+        if let Some(relation_ids) = nodes.get_mut(&other) {
+            relation_ids.remove(&relation_id);
+            if relation_ids.is_empty() {
+                nodes.remove(&other);
+            }
+        }
+    }
+}
+
+/// A `(node, label)` pair of the labels of a graph.
+type LabelKey = (NodeId, Label);
+
+/// Get the range of `Graph::node_labels` that holds the labels of `node_id`.
+fn label_range(node_id: NodeId) -> (Bound<LabelKey>, Bound<LabelKey>) {
+    let start = (node_id, Label::new(""));
+    let end = (NodeId::new(*node_id + 1), Label::new(""));
+    (Bound::Included(start), Bound::Excluded(end))
 }
@@ -0,0 +1,1001 @@
+use crate::edge::{AnyEdgeInfo, Edge, EdgeId};
+use crate::errors::{ConnectError, NameError, PropertyError, UniqueError};
+use crate::graph::Graph;
+use crate::hyperedge::{Hyperedge, HyperedgeKind};
+use crate::index::UniqueIndex;
+use crate::label::Label;
+use crate::node::{AnyNodeInfo, NodeId};
+use crate::property::{PropertyKey, PropertyOwner};
+use crate::relation::{AnyRelationInfo, Relation, RelationId, RelationKind};
+use crate::value::Value;
+use std::collections::VecDeque;
+use std::hash::Hash;
+use std::sync::Arc;
+
+/// Number of checkpoints kept by `History::new`.
+pub const DEFAULT_HISTORY_SIZE: usize = 100;
+
+/// An invertible mutation of a `Graph`.
+#[derive(Clone)]
+pub enum Operation {
+    /// A `Node` was created.
+    AddNode { node_id: NodeId, info: AnyNodeInfo },
+    /// A `Node` was removed together with its labels and all edges
+    /// incident to it.
+    RemoveNode {
+        node_id: NodeId,
+        info: AnyNodeInfo,
+        labels: Vec<Label>,
+        edges: Vec<(Edge, RelationId, Option<AnyEdgeInfo>)>,
+    },
+    /// The info of a `Node` was replaced.
+    ReplaceNodeInfo {
+        node_id: NodeId,
+        old: AnyNodeInfo,
+        new: AnyNodeInfo,
+    },
+    /// A label was added to a `Node`.
+    AddLabel { node_id: NodeId, label: Label },
+    /// A label was removed from a `Node`.
+    RemoveLabel { node_id: NodeId, label: Label },
+    /// A `Relation` was created.
+    AddRelation {
+        relation_id: RelationId,
+        relation: Relation,
+    },
+    /// A `Relation` was removed together with all its edges and the
+    /// pairing with its inverse.
+    RemoveRelation {
+        relation_id: RelationId,
+        relation: Relation,
+        inverse: Option<RelationId>,
+    },
+    /// The name of a `Relation` was replaced or removed.
+    RenameRelation {
+        relation_id: RelationId,
+        old: Option<Arc<str>>,
+        new: Option<Arc<str>>,
+    },
+    /// An edge was added to a `Relation`.
+    Connect {
+        edge: Edge,
+        relation_id: RelationId,
+        info: Option<AnyEdgeInfo>,
+    },
+    /// An edge was removed from a `Relation`.
+    Disconnect {
+        edge: Edge,
+        relation_id: RelationId,
+        info: Option<AnyEdgeInfo>,
+    },
+    /// The info of an existing edge was replaced or removed.
+    SetEdgeInfo {
+        edge: Edge,
+        relation_id: RelationId,
+        old: Option<AnyEdgeInfo>,
+        new: Option<AnyEdgeInfo>,
+    },
+    /// A parallel edge was added to a multigraph `Relation`.
+    ConnectParallel {
+        edge_id: EdgeId,
+        edge: Edge,
+        relation_id: RelationId,
+        info: Option<AnyEdgeInfo>,
+    },
+    /// A parallel edge was removed from a multigraph `Relation`.
+    DisconnectParallel {
+        edge_id: EdgeId,
+        edge: Edge,
+        relation_id: RelationId,
+        info: Option<AnyEdgeInfo>,
+    },
+    /// A hyperedge was added to a hyperedge `Relation`.
+    ConnectHyperedge {
+        edge_id: EdgeId,
+        relation_id: RelationId,
+        hyperedge: Hyperedge,
+    },
+    /// A hyperedge was removed from a hyperedge `Relation`.
+    DisconnectHyperedge {
+        edge_id: EdgeId,
+        relation_id: RelationId,
+        hyperedge: Hyperedge,
+    },
+    /// A property was set, replaced or removed.
+    SetProperty {
+        owner: PropertyOwner,
+        key: PropertyKey,
+        old: Option<Value>,
+        new: Option<Value>,
+    },
+}
+
+impl Operation {
+    /// Apply the operation to `graph`.
+    fn apply(&self, graph: &mut Graph) {
+        match self {
+            Self::AddNode { node_id, info } => graph.insert_node(*node_id, info.clone()),
+            Self::RemoveNode { node_id, .. } => {
+                graph.remove_node(*node_id);
+            }
+            Self::ReplaceNodeInfo { node_id, new, .. } => {
+                graph.set_node_info(*node_id, new.clone());
+            }
+            Self::AddLabel { node_id, label } => {
+                graph.add_label(*node_id, label);
+            }
+            Self::RemoveLabel { node_id, label } => {
+                graph.remove_label(*node_id, label);
+            }
+            Self::AddRelation {
+                relation_id,
+                relation,
+            } => graph.insert_relation(*relation_id, relation),
+            Self::RemoveRelation { relation_id, .. } => {
+                graph.remove_relation(*relation_id);
+            }
+            Self::RenameRelation {
+                relation_id, new, ..
+            } => {
+                let _ = graph.set_relation_name(*relation_id, new.clone());
+            }
+            Self::Connect {
+                edge,
+                relation_id,
+                info,
+            } => connect(graph, *edge, *relation_id, info),
+            Self::Disconnect {
+                edge, relation_id, ..
+            } => {
+                let _ = graph.disconnect(edge.src(), edge.dst(), *relation_id);
+            }
+            Self::SetEdgeInfo {
+                edge,
+                relation_id,
+                new,
+                ..
+            } => set_edge_info(graph, *edge, *relation_id, new),
+            Self::ConnectParallel {
+                edge_id,
+                edge,
+                relation_id,
+                info,
+            } => {
+                let _ = graph.insert_parallel_edge(*edge_id, *edge, *relation_id, info.clone());
+            }
+            Self::DisconnectParallel { edge_id, .. } => {
+                graph.disconnect_edge(*edge_id);
+            }
+            Self::ConnectHyperedge {
+                edge_id,
+                relation_id,
+                hyperedge,
+            } => {
+                let _ = graph.insert_hyperedge(*edge_id, *relation_id, hyperedge.clone());
+            }
+            Self::DisconnectHyperedge { edge_id, .. } => {
+                graph.disconnect_hyperedge(*edge_id);
+            }
+            Self::SetProperty {
+                owner, key, new, ..
+            } => set_property(graph, *owner, key, new),
+        }
+    }
+
+    /// Undo the effect of the operation on `graph`.
+    fn revert(&self, graph: &mut Graph) {
+        match self {
+            Self::AddNode { node_id, .. } => {
+                graph.remove_node(*node_id);
+            }
+            Self::RemoveNode {
+                node_id,
+                info,
+                labels,
+                edges,
+            } => {
+                graph.insert_node(*node_id, info.clone());
+                for label in labels {
+                    graph.add_label(*node_id, label);
+                }
+                for (edge, relation_id, info) in edges {
+                    connect(graph, *edge, *relation_id, info);
+                }
+            }
+            Self::ReplaceNodeInfo { node_id, old, .. } => {
+                graph.set_node_info(*node_id, old.clone());
+            }
+            Self::AddLabel { node_id, label } => {
+                graph.remove_label(*node_id, label);
+            }
+            Self::RemoveLabel { node_id, label } => {
+                graph.add_label(*node_id, label);
+            }
+            Self::AddRelation { relation_id, .. } => {
+                graph.remove_relation(*relation_id);
+            }
+            Self::RemoveRelation {
+                relation_id,
+                relation,
+                inverse,
+            } => {
+                graph.insert_relation(*relation_id, relation);
+                for (edge_id, hyperedge) in relation.iter_hyperedges() {
+                    let _ = graph.insert_hyperedge(edge_id, *relation_id, hyperedge.clone());
+                }
+                for (edge_id, edge) in relation.iter_parallel_edges() {
+                    let info = relation.any_parallel_edge_info(edge_id).cloned();
+                    let _ = graph.insert_parallel_edge(edge_id, edge, *relation_id, info);
+                }
+                for edge in relation.iter_edges().filter(|_| !relation.is_multi()) {
+                    let info = relation.any_edge_info(edge).cloned();
+                    connect(graph, *edge, *relation_id, &info);
+                }
+                for (edge, properties) in relation.iter_edge_properties() {
+                    let owner = PropertyOwner::Edge(*edge, *relation_id);
+                    for (key, value) in properties.iter() {
+                        let _ = graph.set_property(owner, key, value.clone());
+                    }
+                }
+                if let Some(inverse) = inverse {
+                    let _ = graph.set_inverse(*relation_id, *inverse);
+                }
+            }
+            Self::RenameRelation {
+                relation_id, old, ..
+            } => {
+                let _ = graph.set_relation_name(*relation_id, old.clone());
+            }
+            Self::Connect {
+                edge, relation_id, ..
+            } => {
+                let _ = graph.disconnect(edge.src(), edge.dst(), *relation_id);
+            }
+            Self::Disconnect {
+                edge,
+                relation_id,
+                info,
+            } => connect(graph, *edge, *relation_id, info),
+            Self::SetEdgeInfo {
+                edge,
+                relation_id,
+                old,
+                ..
+            } => set_edge_info(graph, *edge, *relation_id, old),
+            Self::ConnectParallel { edge_id, .. } => {
+                graph.disconnect_edge(*edge_id);
+            }
+            Self::DisconnectParallel {
+                edge_id,
+                edge,
+                relation_id,
+                info,
+            } => {
+                let _ = graph.insert_parallel_edge(*edge_id, *edge, *relation_id, info.clone());
+            }
+            Self::ConnectHyperedge { edge_id, .. } => {
+                graph.disconnect_hyperedge(*edge_id);
+            }
+            Self::DisconnectHyperedge {
+                edge_id,
+                relation_id,
+                hyperedge,
+            } => {
+                let _ = graph.insert_hyperedge(*edge_id, *relation_id, hyperedge.clone());
+            }
+            Self::SetProperty {
+                owner, key, old, ..
+            } => set_property(graph, *owner, key, old),
+        }
+    }
+}
+
+/// Connect `edge` in `graph`, with `info` if there is one.
+fn connect(graph: &mut Graph, edge: Edge, relation_id: RelationId, info: &Option<AnyEdgeInfo>) {
+    let _ = match info {
+        Some(info) => graph.connect_with(edge.src(), edge.dst(), relation_id, info.clone()),
+        None => graph.connect(edge.src(), edge.dst(), relation_id),
+    };
+}
+
+/// Attach `info` to an existing edge in `graph`, or detach its info if there is none.
+fn set_edge_info(
+    graph: &mut Graph,
+    edge: Edge,
+    relation_id: RelationId,
+    info: &Option<AnyEdgeInfo>,
+) {
+    match info {
+        Some(_) => connect(graph, edge, relation_id, info),
+        None => {
+            graph.remove_edge_info(edge.src(), edge.dst(), relation_id);
+        }
+    }
+}
+
+/// Set a property in `graph` to `value`, or remove it if there is none.
+fn set_property(graph: &mut Graph, owner: PropertyOwner, key: &str, value: &Option<Value>) {
+    match value {
+        Some(value) => {
+            let _ = graph.set_property(owner, key, value.clone());
+        }
+        None => {
+            graph.remove_property(owner, key);
+        }
+    }
+}
+
+/// A named group of operations that is undone and redone as a whole.
+struct Checkpoint {
+    name: String,
+    operations: Vec<Operation>,
+}
+
+/// A `Graph` that records its mutations so they can be undone and redone.
+///
+/// Mutations are collected until `checkpoint` seals them under a name.
+/// `undo` and `redo` then step through the checkpoints. Only the most
+/// recent `max_size` checkpoints are kept.
+pub struct History {
+    graph: Graph,
+    pending: Vec<Operation>,
+    undo_stack: VecDeque<Checkpoint>,
+    redo_stack: Vec<Checkpoint>,
+    max_size: usize,
+}
+
+impl History {
+    /// Start recording the mutations of `graph`, keeping `DEFAULT_HISTORY_SIZE` checkpoints.
+    pub fn new(graph: Graph) -> Self {
+        Self::with_max_size(graph, DEFAULT_HISTORY_SIZE)
+    }
+
+    /// Start recording the mutations of `graph`, keeping `max_size` checkpoints.
+    pub fn with_max_size(graph: Graph, max_size: usize) -> Self {
+        Self {
+            graph,
+            pending: Vec::new(),
+            undo_stack: VecDeque::new(),
+            redo_stack: Vec::new(),
+            max_size: max_size.max(1),
+        }
+    }
+
+    /// Get a shared reference to the graph.
+    pub fn graph(&self) -> &Graph {
+        &self.graph
+    }
+
+    /// Consume the history and return the graph.
+    pub fn into_graph(self) -> Graph {
+        self.graph
+    }
+
+    /// Set the number of checkpoints to keep, dropping the oldest ones.
+    pub fn set_max_size(&mut self, max_size: usize) {
+        self.max_size = max_size.max(1);
+        self.trim();
+    }
+
+    /// Get the number of checkpoints that can be undone.
+    pub fn nr_undo(&self) -> usize {
+        self.undo_stack.len() + usize::from(!self.pending.is_empty())
+    }
+
+    /// Get the number of checkpoints that can be redone.
+    pub fn nr_redo(&self) -> usize {
+        self.redo_stack.len()
+    }
+
+    /// Seal the operations recorded since the last checkpoint under `name`.
+    /// Returns `false` if there was nothing to seal.
+    pub fn checkpoint<S>(&mut self, name: S) -> bool
+    where
+        S: Into<String>,
+    {
+        if self.pending.is_empty() {
+            return false;
+        }
+        self.undo_stack.push_back(Checkpoint {
+            name: name.into(),
+            operations: std::mem::take(&mut self.pending),
+        });
+        self.trim();
+        true
+    }
+
+    /// Undo the most recent checkpoint and return its name.
+    ///
+    /// Operations that were not sealed by `checkpoint` yet are undone
+    /// first, as a checkpoint with an empty name.
+    pub fn undo(&mut self) -> Option<String> {
+        self.checkpoint("");
+        let checkpoint = self.undo_stack.pop_back()?;
+        for operation in checkpoint.operations.iter().rev() {
+            operation.revert(&mut self.graph);
+        }
+        let name = checkpoint.name.clone();
+        self.redo_stack.push(checkpoint);
+        Some(name)
+    }
+
+    /// Redo the most recently undone checkpoint and return its name.
+    pub fn redo(&mut self) -> Option<String> {
+        let checkpoint = self.redo_stack.pop()?;
+        for operation in &checkpoint.operations {
+            operation.apply(&mut self.graph);
+        }
+        let name = checkpoint.name.clone();
+        self.undo_stack.push_back(checkpoint);
+        self.trim();
+        Some(name)
+    }
+
+    /// Create a `Node` with `info` and return its `NodeId`. Unique indexes
+    /// are not checked, see `Graph::add_node`.
+    pub fn add_node<I>(&mut self, info: I) -> NodeId
+    where
+        I: Into<AnyNodeInfo>,
+    {
+        let info = info.into();
+        let node_id = self.graph.add_node(info.clone());
+        self.record(Operation::AddNode { node_id, info });
+        node_id
+    }
+
+    /// Create a `Node` with `info` and return its `NodeId`, or an error if
+    /// another node has the same key in a unique index.
+    pub fn try_add_node<I>(&mut self, info: I) -> Result<NodeId, UniqueError>
+    where
+        I: Into<AnyNodeInfo>,
+    {
+        let info = info.into();
+        let node_id = self.graph.try_add_node(info.clone())?;
+        self.record(Operation::AddNode { node_id, info });
+        Ok(node_id)
+    }
+
+    /// Remove the `Node` at `node_id` and return its info if it was removed.
+    pub fn remove_node(&mut self, node_id: NodeId) -> Option<AnyNodeInfo> {
+        // Parallel edges and hyperedges are recorded one by one, so they keep their ids
+        for edge_id in self.incident_edge_ids(node_id)? {
+            self.disconnect_edge(edge_id);
+        }
+        let hyperedges: Vec<_> = self.graph.iter_hyperedges_of(node_id)?.collect();
+        for (edge_id, _) in hyperedges {
+            self.disconnect_hyperedge(edge_id);
+        }
+        let graph = &self.graph;
+        let with_info = |edge: Edge, relation_id: RelationId| {
+            let relation = graph.relation(relation_id).unwrap();
+            (edge, relation_id, relation.any_edge_info(&edge).cloned())
+        };
+        let mut edges: Vec<_> = graph
+            .iter_next_nodes(node_id)?
+            .map(|(dst, relation_id)| with_info(Edge::new(node_id, dst), relation_id))
+            .collect();
+        edges.extend(
+            graph
+                .iter_prev_nodes(node_id)?
+                .filter(|(src, _)| *src != node_id)
+                .map(|(src, relation_id)| with_info(Edge::new(src, node_id), relation_id)),
+        );
+        self.record_dropped_properties(PropertyOwner::Node(node_id));
+        for (edge, relation_id, _) in &edges {
+            self.record_dropped_properties(PropertyOwner::Edge(*edge, *relation_id));
+        }
+        let labels = self.graph.labels_of(node_id)?.cloned().collect();
+        let info = self.graph.remove_node(node_id)?;
+        self.record(Operation::RemoveNode {
+            node_id,
+            info: info.clone(),
+            labels,
+            edges,
+        });
+        Some(info)
+    }
+
+    /// Replace the info of a `Node` and return the old info, or an error if
+    /// another node has the same key in a unique index.
+    pub fn replace_node_info<I>(
+        &mut self,
+        node_id: NodeId,
+        info: I,
+    ) -> Result<Option<AnyNodeInfo>, UniqueError>
+    where
+        I: Into<AnyNodeInfo>,
+    {
+        let new = info.into();
+        let old = match self.graph.replace_node_info(node_id, new.clone())? {
+            Some(old) => old,
+            None => return Ok(None),
+        };
+        self.record(Operation::ReplaceNodeInfo {
+            node_id,
+            old: old.clone(),
+            new,
+        });
+        Ok(Some(old))
+    }
+
+    /// Add a `Node` with `info`, or replace the info of the node that has the
+    /// same key in `index`, and return the `NodeId` of the node.
+    pub fn upsert_node<K, I>(
+        &mut self,
+        index: UniqueIndex<K>,
+        info: I,
+    ) -> Result<NodeId, UniqueError>
+    where
+        K: Hash + Eq + Clone + Send + Sync + 'static,
+        I: Into<AnyNodeInfo>,
+    {
+        let info = info.into();
+        match self.graph.unique_node(index, &info)? {
+            Some(node_id) => {
+                self.replace_node_info(node_id, info)?;
+                Ok(node_id)
+            }
+            None => self.try_add_node(info),
+        }
+    }
+
+    /// Add `label` to a `Node` and return whether it was added.
+    pub fn add_label(&mut self, node_id: NodeId, label: &str) -> bool {
+        if !self.graph.add_label(node_id, label) {
+            return false;
+        }
+        let label = self.interned_label(node_id, label);
+        self.record(Operation::AddLabel { node_id, label });
+        true
+    }
+
+    /// Remove `label` from a `Node` and return whether it had the label.
+    pub fn remove_label(&mut self, node_id: NodeId, label: &str) -> bool {
+        if !self.graph.has_label(node_id, label) {
+            return false;
+        }
+        let label = self.interned_label(node_id, label);
+        self.graph.remove_label(node_id, &label);
+        self.record(Operation::RemoveLabel { node_id, label });
+        true
+    }
+
+    fn interned_label(&self, node_id: NodeId, label: &str) -> Label {
+        let mut labels = self.graph.labels_of(node_id).unwrap();
+        labels
+            .find(|interned| interned.as_str() == label)
+            .unwrap()
+            .clone()
+    }
+
+    /// Create a `Relation` in the graph with `info` and return its `RelationId`.
+    pub fn add_relation<I>(&mut self, info: I) -> RelationId
+    where
+        I: Into<AnyRelationInfo>,
+    {
+        self.add_relation_of_kind(info, RelationKind::Directed)
+    }
+
+    /// Create a `Relation` in the graph with a unique `name` and `info`
+    /// and return its `RelationId`.
+    pub fn add_named_relation<S, I>(&mut self, name: S, info: I) -> Result<RelationId, NameError>
+    where
+        S: Into<String>,
+        I: Into<AnyRelationInfo>,
+    {
+        let relation_id = self.graph.add_named_relation(name, info)?;
+        let relation = self.graph.relation(relation_id).unwrap().clone();
+        self.record(Operation::AddRelation {
+            relation_id,
+            relation,
+        });
+        Ok(relation_id)
+    }
+
+    /// Give a `Relation` a new unique `name` and return its old name.
+    pub fn rename_relation<S>(
+        &mut self,
+        relation_id: RelationId,
+        name: S,
+    ) -> Result<Option<String>, NameError>
+    where
+        S: Into<String>,
+    {
+        let name: Arc<str> = name.into().into();
+        let old = self
+            .graph
+            .set_relation_name(relation_id, Some(name.clone()))?;
+        let result = old.as_ref().map(|old| old.to_string());
+        self.record(Operation::RenameRelation {
+            relation_id,
+            old,
+            new: Some(name),
+        });
+        Ok(result)
+    }
+
+    /// Remove the name of a `Relation` and return it.
+    pub fn clear_relation_name(&mut self, relation_id: RelationId) -> Option<String> {
+        let old = self.graph.set_relation_name(relation_id, None).ok()??;
+        let result = old.to_string();
+        self.record(Operation::RenameRelation {
+            relation_id,
+            old: Some(old),
+            new: None,
+        });
+        Some(result)
+    }
+
+    /// Create a `Relation` of the given kind in the graph with `info`
+    /// and return its `RelationId`.
+    pub fn add_relation_of_kind<I>(&mut self, info: I, kind: RelationKind) -> RelationId
+    where
+        I: Into<AnyRelationInfo>,
+    {
+        let relation_id = self.graph.add_relation_of_kind(info, kind);
+        let relation = self.graph.relation(relation_id).unwrap().clone();
+        self.record(Operation::AddRelation {
+            relation_id,
+            relation,
+        });
+        relation_id
+    }
+
+    /// Create a multigraph `Relation` of the given kind in the graph with
+    /// `info` and return its `RelationId`.
+    pub fn add_multi_relation<I>(&mut self, info: I, kind: RelationKind) -> RelationId
+    where
+        I: Into<AnyRelationInfo>,
+    {
+        let relation_id = self.graph.add_multi_relation(info, kind);
+        let relation = self.graph.relation(relation_id).unwrap().clone();
+        self.record(Operation::AddRelation {
+            relation_id,
+            relation,
+        });
+        relation_id
+    }
+
+    /// Create a hyperedge `Relation` with `info` whose hyperedges are of
+    /// the given kind and return its `RelationId`.
+    pub fn add_hyper_relation<I>(&mut self, info: I, kind: HyperedgeKind) -> RelationId
+    where
+        I: Into<AnyRelationInfo>,
+    {
+        let relation_id = self.graph.add_hyper_relation(info, kind);
+        let relation = self.graph.relation(relation_id).unwrap().clone();
+        self.record(Operation::AddRelation {
+            relation_id,
+            relation,
+        });
+        relation_id
+    }
+
+    /// Remove the `Relation` at `relation_id` and return its info if it was removed.
+    pub fn remove_relation(&mut self, relation_id: RelationId) -> Option<AnyRelationInfo> {
+        let relation = self.graph.relation(relation_id)?.clone();
+        let inverse = self.graph.inverse_of(relation_id);
+        let info = self.graph.remove_relation(relation_id)?;
+        self.record(Operation::RemoveRelation {
+            relation_id,
+            relation,
+            inverse,
+        });
+        Some(info)
+    }
+
+    /// Connect two `Nodes` in the graph with a `Relation`.
+    pub fn connect(
+        &mut self,
+        src: NodeId,
+        dst: NodeId,
+        relation_id: RelationId,
+    ) -> Result<bool, ConnectError> {
+        let connected = self.graph.connect(src, dst, relation_id)?;
+        if connected {
+            let edge = Edge::new(src, dst);
+            self.record(Operation::Connect {
+                edge,
+                relation_id,
+                info: None,
+            });
+        }
+        Ok(connected)
+    }
+
+    /// Connect two `Nodes` in the graph with a `Relation` and attach `info`
+    /// to the edge, replacing the info it had if it already existed.
+    pub fn connect_with<I>(
+        &mut self,
+        src: NodeId,
+        dst: NodeId,
+        relation_id: RelationId,
+        info: I,
+    ) -> Result<bool, ConnectError>
+    where
+        I: Into<AnyEdgeInfo>,
+    {
+        let edge = Edge::new(src, dst);
+        let info = info.into();
+        let old = self
+            .graph
+            .relation(relation_id)
+            .and_then(|relation| relation.any_edge_info(&edge).cloned());
+        let connected = self
+            .graph
+            .connect_with(src, dst, relation_id, info.clone())?;
+        self.record(if connected {
+            Operation::Connect {
+                edge,
+                relation_id,
+                info: Some(info),
+            }
+        } else {
+            Operation::SetEdgeInfo {
+                edge,
+                relation_id,
+                old,
+                new: Some(info),
+            }
+        });
+        Ok(connected)
+    }
+
+    /// Detach the info of an edge and return it. The edge stays connected.
+    pub fn remove_edge_info(
+        &mut self,
+        src: NodeId,
+        dst: NodeId,
+        relation_id: RelationId,
+    ) -> Option<AnyEdgeInfo> {
+        let info = self.graph.remove_edge_info(src, dst, relation_id)?;
+        self.record(Operation::SetEdgeInfo {
+            edge: Edge::new(src, dst),
+            relation_id,
+            old: Some(info.clone()),
+            new: None,
+        });
+        Some(info)
+    }
+
+    /// Connect two `Nodes` with a new parallel edge in a multigraph
+    /// `Relation` and return its `EdgeId`.
+    pub fn connect_multi(
+        &mut self,
+        src: NodeId,
+        dst: NodeId,
+        relation_id: RelationId,
+    ) -> Result<EdgeId, ConnectError> {
+        let edge_id = self.graph.connect_multi(src, dst, relation_id)?;
+        self.record_parallel_edge(edge_id, None);
+        Ok(edge_id)
+    }
+
+    /// Connect two `Nodes` with a new parallel edge in a multigraph
+    /// `Relation`, attach `info` to it and return its `EdgeId`.
+    pub fn connect_multi_with<I>(
+        &mut self,
+        src: NodeId,
+        dst: NodeId,
+        relation_id: RelationId,
+        info: I,
+    ) -> Result<EdgeId, ConnectError>
+    where
+        I: Into<AnyEdgeInfo>,
+    {
+        let info = info.into();
+        let edge_id = self
+            .graph
+            .connect_multi_with(src, dst, relation_id, info.clone())?;
+        self.record_parallel_edge(edge_id, Some(info));
+        Ok(edge_id)
+    }
+
+    fn record_parallel_edge(&mut self, edge_id: EdgeId, info: Option<AnyEdgeInfo>) {
+        let (edge, relation_id) = self.graph.parallel_edge(edge_id).unwrap();
+        self.record(Operation::ConnectParallel {
+            edge_id,
+            edge,
+            relation_id,
+            info,
+        });
+    }
+
+    /// Disconnect a single parallel edge and return whether it existed.
+    pub fn disconnect_edge(&mut self, edge_id: EdgeId) -> bool {
+        let (edge, relation_id) = match self.graph.parallel_edge(edge_id) {
+            Some(parallel_edge) => parallel_edge,
+            None => return false,
+        };
+        let relation = self.graph.relation(relation_id).unwrap();
+        let info = relation.any_parallel_edge_info(edge_id).cloned();
+        if relation.multiplicity(&edge) == 1 {
+            self.record_dropped_properties(PropertyOwner::Edge(edge, relation_id));
+        }
+        self.graph.disconnect_edge(edge_id);
+        self.record(Operation::DisconnectParallel {
+            edge_id,
+            edge,
+            relation_id,
+            info,
+        });
+        true
+    }
+
+    /// Connect the sources and targets of `hyperedge` with a hyperedge
+    /// `Relation` and return the `EdgeId` of the new hyperedge.
+    pub fn connect_hyperedge(
+        &mut self,
+        relation_id: RelationId,
+        hyperedge: Hyperedge,
+    ) -> Result<EdgeId, ConnectError> {
+        let edge_id = self.graph.connect_hyperedge(relation_id, hyperedge)?;
+        let (hyperedge, _) = self.graph.hyperedge(edge_id).unwrap();
+        let hyperedge = hyperedge.clone();
+        self.record(Operation::ConnectHyperedge {
+            edge_id,
+            relation_id,
+            hyperedge,
+        });
+        Ok(edge_id)
+    }
+
+    /// Disconnect a hyperedge and return whether it existed.
+    pub fn disconnect_hyperedge(&mut self, edge_id: EdgeId) -> bool {
+        let (hyperedge, relation_id) = match self.graph.hyperedge(edge_id) {
+            Some((hyperedge, relation_id)) => (hyperedge.clone(), relation_id),
+            None => return false,
+        };
+        self.graph.disconnect_hyperedge(edge_id);
+        self.record(Operation::DisconnectHyperedge {
+            edge_id,
+            relation_id,
+            hyperedge,
+        });
+        true
+    }
+
+    /// Set the property with `key` of a node, relation or edge to `value`
+    /// and return its previous value.
+    pub fn set_property<V>(
+        &mut self,
+        owner: PropertyOwner,
+        key: &str,
+        value: V,
+    ) -> Result<Option<Value>, PropertyError>
+    where
+        V: Into<Value>,
+    {
+        let value = value.into();
+        let old = self.graph.set_property(owner, key, value.clone())?;
+        self.record(Operation::SetProperty {
+            owner,
+            key: PropertyKey::new(key),
+            old: old.clone(),
+            new: Some(value),
+        });
+        Ok(old)
+    }
+
+    /// Remove the property with `key` of a node, relation or edge and
+    /// return its value.
+    pub fn remove_property(&mut self, owner: PropertyOwner, key: &str) -> Option<Value> {
+        let old = self.graph.remove_property(owner, key)?;
+        self.record(Operation::SetProperty {
+            owner,
+            key: PropertyKey::new(key),
+            old: Some(old.clone()),
+            new: None,
+        });
+        Some(old)
+    }
+
+    /// Record the removal of the properties of `owner`, which the graph
+    /// drops together with their owner.
+    fn record_dropped_properties(&mut self, owner: PropertyOwner) {
+        let properties = match self.graph.properties(owner) {
+            Some(properties) => properties.clone(),
+            None => return,
+        };
+        for (key, value) in properties.iter() {
+            self.record(Operation::SetProperty {
+                owner,
+                key: key.clone(),
+                old: Some(value.clone()),
+                new: None,
+            });
+        }
+    }
+
+    /// Get the `EdgeIds` of all parallel edges incident to a `Node`.
+    fn incident_edge_ids(&self, node_id: NodeId) -> Option<Vec<EdgeId>> {
+        let graph = &self.graph;
+        let edge_ids = |edge: Edge, relation_id: RelationId| {
+            let relation = graph.relation(relation_id).unwrap();
+            relation.iter_edge_ids(&edge).collect::<Vec<_>>()
+        };
+        let mut incident: Vec<_> = graph
+            .iter_next_nodes(node_id)?
+            .flat_map(|(dst, relation_id)| edge_ids(Edge::new(node_id, dst), relation_id))
+            .collect();
+        incident.extend(
+            graph
+                .iter_prev_nodes(node_id)?
+                .flat_map(|(src, relation_id)| edge_ids(Edge::new(src, node_id), relation_id)),
+        );
+        incident.sort();
+        incident.dedup();
+        Some(incident)
+    }
+
+    /// Disconnect the `Relation` between two `Nodes`. In a multigraph
+    /// relation all parallel edges between them are disconnected.
+    pub fn disconnect(
+        &mut self,
+        src: NodeId,
+        dst: NodeId,
+        relation_id: RelationId,
+    ) -> Result<bool, ConnectError> {
+        let edge = Edge::new(src, dst);
+        let edge_ids: Vec<_> = self
+            .graph
+            .relation(relation_id)
+            .map(|relation| relation.iter_edge_ids(&edge).collect())
+            .unwrap_or_default();
+        if !edge_ids.is_empty() {
+            for edge_id in edge_ids {
+                self.disconnect_edge(edge_id);
+            }
+            return Ok(true);
+        }
+        let info = self
+            .graph
+            .relation(relation_id)
+            .and_then(|relation| relation.any_edge_info(&edge).cloned());
+        let inverse = self.graph.inverse_of(relation_id);
+        let inverse_info = inverse.and_then(|inverse| {
+            let relation = self.graph.relation(inverse)?;
+            relation.any_edge_info(&edge.reversed()).cloned()
+        });
+        if self
+            .graph
+            .check_property_owner(PropertyOwner::Edge(edge, relation_id))
+            .is_ok()
+        {
+            self.record_dropped_properties(PropertyOwner::Edge(edge, relation_id));
+            if let Some(inverse) = self.graph.inverse_of(relation_id) {
+                self.record_dropped_properties(PropertyOwner::Edge(edge.reversed(), inverse));
+            }
+        }
+        let disconnected = self.graph.disconnect(src, dst, relation_id)?;
+        if disconnected {
+            // Undo reconnects the mirrored edge without its info, so the
+            // info is restored afterwards
+            if let (Some(inverse), Some(old)) = (inverse, inverse_info) {
+                self.record(Operation::SetEdgeInfo {
+                    edge: edge.reversed(),
+                    relation_id: inverse,
+                    old: Some(old),
+                    new: None,
+                });
+            }
+            self.record(Operation::Disconnect {
+                edge,
+                relation_id,
+                info,
+            });
+        }
+        Ok(disconnected)
+    }
+
+    fn record(&mut self, operation: Operation) {
+        self.redo_stack.clear();
+        self.pending.push(operation);
+    }
+
+    fn trim(&mut self) {
+        while self.undo_stack.len() > self.max_size {
+            self.undo_stack.pop_front();
+        }
+    }
+}
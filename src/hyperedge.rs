@@ -0,0 +1,97 @@
+use crate::node::NodeId;
+
+/// How the nodes of the hyperedges of a `Relation` are treated.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+pub enum HyperedgeKind {
+    /// Sources and targets keep the order they were given in, and a node
+    /// can appear more than once.
+    #[default]
+    Ordered,
+    /// Sources and targets are sets. They are stored sorted by `NodeId`
+    /// without duplicates, see `Hyperedge::normalized`.
+    Unordered,
+}
+
+impl HyperedgeKind {
+    /// Get the form in which `hyperedge` is stored in a relation of this kind.
+    pub fn normalize(&self, hyperedge: Hyperedge) -> Hyperedge {
+        match self {
+            Self::Ordered => hyperedge,
+            Self::Unordered => hyperedge.normalized(),
+        }
+    }
+
+    pub(crate) fn code(&self) -> u8 {
+        match self {
+            Self::Ordered => 0,
+            Self::Unordered => 1,
+        }
+    }
+
+    pub(crate) fn from_code(code: u8) -> Option<Self> {
+        match code {
+            0 => Some(Self::Ordered),
+            1 => Some(Self::Unordered),
+            _ => None,
+        }
+    }
+}
+
+/// Represents a connection between any number of source and target `Nodes`.
+///
+/// An n-ary relationship without a direction, such as the attendees of a
+/// meeting, can be modeled with sources only.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct Hyperedge {
+    sources: Vec<NodeId>,
+    targets: Vec<NodeId>,
+}
+
+impl Hyperedge {
+    /// Create a new `Hyperedge` from `sources` to `targets`.
+    pub fn new<S, T>(sources: S, targets: T) -> Self
+    where
+        S: IntoIterator<Item = NodeId>,
+        T: IntoIterator<Item = NodeId>,
+    {
+        Self {
+            sources: sources.into_iter().collect(),
+            targets: targets.into_iter().collect(),
+        }
+    }
+
+    /// Get the source nodes of the hyperedge.
+    pub fn sources(&self) -> &[NodeId] {
+        &self.sources
+    }
+
+    /// Get the target nodes of the hyperedge.
+    pub fn targets(&self) -> &[NodeId] {
+        &self.targets
+    }
+
+    /// Get an iterator over the sources followed by the targets.
+    pub fn iter_nodes(&self) -> impl Iterator<Item = NodeId> + '_ {
+        self.sources.iter().chain(&self.targets).copied()
+    }
+
+    /// Check if `node_id` is one of the sources or targets.
+    pub fn contains_node(&self, node_id: NodeId) -> bool {
+        self.iter_nodes().any(|id| id == node_id)
+    }
+
+    /// Get a copy of the hyperedge with its sources and targets sorted
+    /// and without duplicates.
+    pub fn normalized(&self) -> Self {
+        let set = |nodes: &[NodeId]| {
+            let mut nodes = nodes.to_vec();
+            nodes.sort_by_key(|id| **id);
+            nodes.dedup();
+            nodes
+        };
+        Self {
+            sources: set(&self.sources),
+            targets: set(&self.targets),
+        }
+    }
+}
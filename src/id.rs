@@ -1,3 +1,6 @@
+use crate::node::NodeId;
+use crate::relation::RelationId;
+use std::collections::HashMap;
 use std::sync::atomic::{AtomicU64, Ordering};
 
 /// Generates unique ids.
@@ -14,6 +17,14 @@ impl Default for IdGenerator {
     }
 }
 
+impl Clone for IdGenerator {
+    fn clone(&self) -> Self {
+        Self {
+            counter: AtomicU64::new(self.peek_id()),
+        }
+    }
+}
+
 impl IdGenerator {
     /// Atomically generate a unique id.
     pub fn generate_id(&self) -> u64 {
@@ -26,4 +37,67 @@ impl IdGenerator {
         *self.counter.get_mut() += 1;
         id
     }
+
+    /// Get the id that will be generated next.
+    pub fn peek_id(&self) -> u64 {
+        self.counter.load(Ordering::Relaxed)
+    }
+
+    /// Make sure `id` and all ids before it are never generated.
+    /// Requires exclusive access to `self`.
+    pub fn reserve_sync(&mut self, id: u64) {
+        let counter = self.counter.get_mut();
+        if *counter <= id {
+            *counter = id + 1;
+        }
+    }
+}
+
+/// Maps the `NodeIds` and `RelationIds` of one graph to those of a graph
+/// made from it, such as a subgraph.
+#[derive(Clone, Default, Debug)]
+pub struct IdMap {
+    nodes: HashMap<NodeId, NodeId>,
+    relations: HashMap<RelationId, RelationId>,
+}
+
+impl IdMap {
+    /// Get the new `NodeId` of the node at `node_id`.
+    pub fn node_id(&self, node_id: NodeId) -> Option<NodeId> {
+        self.nodes.get(&node_id).copied()
+    }
+
+    /// Get the new `RelationId` of the relation at `relation_id`.
+    pub fn relation_id(&self, relation_id: RelationId) -> Option<RelationId> {
+        self.relations.get(&relation_id).copied()
+    }
+
+    /// Get an iterator over the old and new `NodeIds` of all mapped nodes.
+    pub fn iter_node_ids(&self) -> impl Iterator<Item = (NodeId, NodeId)> + '_ {
+        self.nodes.iter().map(|(old, new)| (*old, *new))
+    }
+
+    /// Get an iterator over the old and new `RelationIds` of all mapped
+    /// relations.
+    pub fn iter_relation_ids(&self) -> impl Iterator<Item = (RelationId, RelationId)> + '_ {
+        self.relations.iter().map(|(old, new)| (*old, *new))
+    }
+
+    /// Get the number of mapped nodes.
+    pub fn nr_nodes(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Get the number of mapped relations.
+    pub fn nr_relations(&self) -> usize {
+        self.relations.len()
+    }
+
+    pub(crate) fn insert_node(&mut self, old: NodeId, new: NodeId) {
+        self.nodes.insert(old, new);
+    }
+
+    pub(crate) fn insert_relation(&mut self, old: RelationId, new: RelationId) {
+        self.relations.insert(old, new);
+    }
 }
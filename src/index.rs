@@ -0,0 +1,349 @@
+use crate::collections::{Map, Set, Share, SortedMap};
+use crate::graph::Graph;
+use crate::node::{NodeId, NodeInfo};
+use downcast_rs::{impl_downcast, DowncastSync};
+use std::hash::Hash;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+/// Extracts the key a `Node` is indexed under from its info, or `None`
+/// to leave the node out of the index.
+type KeyFn<K> = Arc<dyn Fn(&dyn NodeInfo) -> Option<K> + Send + Sync>;
+
+/// Uniquely identifies a secondary index within a graph.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct IndexId(u64);
+
+impl IndexId {
+    pub(crate) fn new(id: u64) -> Self {
+        Self(id)
+    }
+}
+
+/// A handle to a secondary index of a `Graph`, used to query it with
+/// `Graph::lookup`.
+pub trait NodeIndex: Copy {
+    /// The type of the keys of the index.
+    type Key;
+
+    /// Get the id of the index.
+    fn id(&self) -> IndexId;
+
+    /// Get an iterator over the `NodeIds` indexed under `key` in `graph`.
+    fn nodes<'a>(&self, graph: &'a Graph, key: &Self::Key)
+        -> Box<dyn Iterator<Item = NodeId> + 'a>;
+}
+
+/// A handle to a hash index, made with `Graph::add_hash_index`.
+#[derive(Debug)]
+pub struct HashIndex<K> {
+    id: IndexId,
+    key: PhantomData<fn() -> K>,
+}
+
+impl<K> Clone for HashIndex<K> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<K> Copy for HashIndex<K> {}
+
+impl<K> HashIndex<K> {
+    pub(crate) fn new(id: IndexId) -> Self {
+        Self {
+            id,
+            key: PhantomData,
+        }
+    }
+}
+
+impl<K> NodeIndex for HashIndex<K>
+where
+    K: Hash + Eq + Clone + Send + Sync + 'static,
+{
+    type Key = K;
+
+    fn id(&self) -> IndexId {
+        self.id
+    }
+
+    fn nodes<'a>(&self, graph: &'a Graph, key: &K) -> Box<dyn Iterator<Item = NodeId> + 'a> {
+        let nodes = graph
+            .index(self.id)
+            .and_then(|index| index.downcast_ref::<HashEntries<K>>())
+            .and_then(|index| index.entries.get(key));
+        Box::new(nodes.into_iter().flat_map(Set::iter).copied())
+    }
+}
+
+/// A handle to a unique hash index, made with `Graph::add_unique_index`.
+///
+/// No two `Nodes` can have the same key in a unique index. Adding a node
+/// with `Graph::try_add_node` or replacing its info fails if another node
+/// already has its key. `Graph::add_node` doesn't check unique indexes.
+#[derive(Debug)]
+pub struct UniqueIndex<K> {
+    id: IndexId,
+    key: PhantomData<fn() -> K>,
+}
+
+impl<K> Clone for UniqueIndex<K> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<K> Copy for UniqueIndex<K> {}
+
+impl<K> UniqueIndex<K> {
+    pub(crate) fn new(id: IndexId) -> Self {
+        Self {
+            id,
+            key: PhantomData,
+        }
+    }
+}
+
+impl<K> NodeIndex for UniqueIndex<K>
+where
+    K: Hash + Eq + Clone + Send + Sync + 'static,
+{
+    type Key = K;
+
+    fn id(&self) -> IndexId {
+        self.id
+    }
+
+    fn nodes<'a>(&self, graph: &'a Graph, key: &K) -> Box<dyn Iterator<Item = NodeId> + 'a> {
+        let nodes = self.entries(graph).and_then(|index| index.entries.get(key));
+        Box::new(nodes.into_iter().flat_map(Set::iter).copied())
+    }
+}
+
+impl<K> UniqueIndex<K>
+where
+    K: Hash + Eq + Clone + Send + Sync + 'static,
+{
+    pub(crate) fn entries<'a>(&self, graph: &'a Graph) -> Option<&'a HashEntries<K>> {
+        graph.index(self.id)?.downcast_ref::<HashEntries<K>>()
+    }
+}
+
+/// A handle to an ordered index, made with `Graph::add_ordered_index`.
+/// Besides `Graph::lookup` it can be queried with `Graph::range`.
+#[derive(Debug)]
+pub struct OrderedIndex<K> {
+    id: IndexId,
+    key: PhantomData<fn() -> K>,
+}
+
+impl<K> Clone for OrderedIndex<K> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<K> Copy for OrderedIndex<K> {}
+
+impl<K> OrderedIndex<K> {
+    pub(crate) fn new(id: IndexId) -> Self {
+        Self {
+            id,
+            key: PhantomData,
+        }
+    }
+}
+
+impl<K> NodeIndex for OrderedIndex<K>
+where
+    K: Ord + Clone + Send + Sync + 'static,
+{
+    type Key = K;
+
+    fn id(&self) -> IndexId {
+        self.id
+    }
+
+    fn nodes<'a>(&self, graph: &'a Graph, key: &K) -> Box<dyn Iterator<Item = NodeId> + 'a> {
+        let nodes = self.entries(graph).and_then(|index| index.entries.get(key));
+        Box::new(nodes.into_iter().flat_map(Set::iter).copied())
+    }
+}
+
+impl<K> OrderedIndex<K>
+where
+    K: Ord + Clone + Send + Sync + 'static,
+{
+    pub(crate) fn entries<'a>(&self, graph: &'a Graph) -> Option<&'a OrderedEntries<K>> {
+        graph.index(self.id)?.downcast_ref::<OrderedEntries<K>>()
+    }
+}
+
+/// The contents of a secondary index, kept current by the `Graph`.
+pub(crate) trait IndexData: DowncastSync {
+    fn insert(&mut self, node_id: NodeId, info: &dyn NodeInfo);
+
+    fn remove(&mut self, node_id: NodeId, info: &dyn NodeInfo);
+
+    /// Get the other `Node` that has the key of `info`, if the index is unique.
+    fn conflict(&self, node_id: Option<NodeId>, info: &dyn NodeInfo) -> Option<NodeId>;
+
+    fn clone_box(&self) -> Box<dyn IndexData>;
+
+    /// Move the entries to structurally shared storage, see `Share`.
+    fn share(&mut self);
+}
+
+impl_downcast!(sync IndexData);
+
+/// Type erased container for the contents of a secondary index.
+///
+/// The entries are kept in persistent maps once the graph is shared, so
+/// cloning it is cheap from then on.
+pub(crate) struct AnyIndex(Box<dyn IndexData>);
+
+impl AnyIndex {
+    pub(crate) fn new<I>(index: I) -> Self
+    where
+        I: IndexData,
+    {
+        Self(Box::new(index))
+    }
+
+    pub(crate) fn get(&self) -> &dyn IndexData {
+        &*self.0
+    }
+
+    pub(crate) fn get_mut(&mut self) -> &mut dyn IndexData {
+        &mut *self.0
+    }
+}
+
+impl Share for AnyIndex {
+    fn share(&mut self) {
+        self.0.share();
+    }
+}
+
+impl Clone for AnyIndex {
+    fn clone(&self) -> Self {
+        Self(self.0.clone_box())
+    }
+}
+
+/// The entries of a hash index, which may be unique.
+pub(crate) struct HashEntries<K> {
+    key_fn: KeyFn<K>,
+    unique: bool,
+    pub(crate) entries: Map<K, Set<NodeId>>,
+}
+
+impl<K> HashEntries<K> {
+    pub(crate) fn new(key_fn: KeyFn<K>, unique: bool) -> Self {
+        Self {
+            key_fn,
+            unique,
+            entries: Map::default(),
+        }
+    }
+
+    /// Get the key `info` is indexed under.
+    pub(crate) fn key(&self, info: &dyn NodeInfo) -> Option<K> {
+        (self.key_fn)(info)
+    }
+}
+
+impl<K> IndexData for HashEntries<K>
+where
+    K: Hash + Eq + Clone + Send + Sync + 'static,
+{
+    fn insert(&mut self, node_id: NodeId, info: &dyn NodeInfo) {
+        if let Some(key) = (self.key_fn)(info) {
+            self.entries.get_or_default(key).insert(node_id);
+        }
+    }
+
+    fn remove(&mut self, node_id: NodeId, info: &dyn NodeInfo) {
+        if let Some(key) = (self.key_fn)(info) {
+            if let Some(nodes) = self.entries.get_mut(&key) {
+                nodes.remove(&node_id);
+                if nodes.is_empty() {
+                    self.entries.remove(&key);
+                }
+            }
+        }
+    }
+
+    fn conflict(&self, node_id: Option<NodeId>, info: &dyn NodeInfo) -> Option<NodeId> {
+        if !self.unique {
+            return None;
+        }
+        let nodes = self.entries.get(&(self.key_fn)(info)?)?;
+        nodes.iter().copied().find(|other| Some(*other) != node_id)
+    }
+
+    fn clone_box(&self) -> Box<dyn IndexData> {
+        Box::new(Self {
+            key_fn: self.key_fn.clone(),
+            unique: self.unique,
+            entries: self.entries.clone(),
+        })
+    }
+
+    fn share(&mut self) {
+        self.entries.share_values();
+    }
+}
+
+/// The entries of an ordered index.
+pub(crate) struct OrderedEntries<K> {
+    key_fn: KeyFn<K>,
+    pub(crate) entries: SortedMap<K, Set<NodeId>>,
+}
+
+impl<K> OrderedEntries<K> {
+    pub(crate) fn new(key_fn: KeyFn<K>) -> Self {
+        Self {
+            key_fn,
+            entries: SortedMap::default(),
+        }
+    }
+}
+
+impl<K> IndexData for OrderedEntries<K>
+where
+    K: Ord + Clone + Send + Sync + 'static,
+{
+    fn insert(&mut self, node_id: NodeId, info: &dyn NodeInfo) {
+        if let Some(key) = (self.key_fn)(info) {
+            self.entries.get_or_default(key).insert(node_id);
+        }
+    }
+
+    fn remove(&mut self, node_id: NodeId, info: &dyn NodeInfo) {
+        if let Some(key) = (self.key_fn)(info) {
+            if let Some(nodes) = self.entries.get_mut(&key) {
+                nodes.remove(&node_id);
+                if nodes.is_empty() {
+                    self.entries.remove(&key);
+                }
+            }
+        }
+    }
+
+    fn conflict(&self, _node_id: Option<NodeId>, _info: &dyn NodeInfo) -> Option<NodeId> {
+        None
+    }
+
+    fn clone_box(&self) -> Box<dyn IndexData> {
+        Box::new(Self {
+            key_fn: self.key_fn.clone(),
+            entries: self.entries.clone(),
+        })
+    }
+
+    fn share(&mut self) {
+        self.entries.share_values();
+    }
+}
@@ -0,0 +1,47 @@
+use std::borrow::Borrow;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::ops::Deref;
+use std::sync::Arc;
+
+/// A string used to classify `Nodes`, independent of their info.
+///
+/// A graph interns its labels, so every node with the same label shares
+/// one allocation and cloning a `Label` is cheap.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct Label(Arc<str>);
+
+impl Label {
+    /// Create a new `Label`.
+    pub fn new<S>(label: S) -> Self
+    where
+        S: Into<Arc<str>>,
+    {
+        Self(label.into())
+    }
+
+    /// Get the label as a string slice.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Deref for Label {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl Borrow<str> for Label {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Display for Label {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
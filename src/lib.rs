@@ -1,6 +1,29 @@
+pub mod codec;
+mod collections;
+mod combine;
+pub mod concurrent;
+pub mod contract;
+pub mod diff;
 pub mod edge;
 pub mod errors;
+pub mod event;
 pub mod graph;
+pub mod history;
+pub mod hyperedge;
 pub mod id;
+pub mod index;
+pub mod label;
+pub mod mapped;
 pub mod node;
+pub mod property;
+pub mod read;
 pub mod relation;
+pub mod schema;
+pub mod snapshot;
+pub mod store;
+mod subgraph;
+pub mod text;
+pub mod transaction;
+pub mod value;
+pub mod view;
+pub mod write;
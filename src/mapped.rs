@@ -0,0 +1,672 @@
+use crate::codec::{write_u64, DefaultCodec, InfoCodec};
+use crate::edge::Edge;
+use crate::errors::StoreError;
+use crate::graph::Graph;
+use crate::node::{AnyNodeInfo, NodeId};
+use crate::relation::{AnyRelationInfo, Relation, RelationId, RelationKind};
+use crate::store::SnapshotSource;
+use memmap2::Mmap;
+use std::convert::TryFrom;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Seek, SeekFrom, Write};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+const MAGIC: &[u8; 8] = b"GFMAP002";
+const HEADER_LEN: usize = 64;
+const DESCRIPTOR_LEN: usize = 72;
+
+/// Read-only graph backed by a memory mapped snapshot file.
+///
+/// Nodes are stored sorted by `NodeId` and the edges of every relation are
+/// stored in compressed sparse row form, once by source and once by
+/// destination. Edges of undirected relations are stored in both
+/// directions, so they show up as neighbors of both endpoints. Parallel
+/// edges of multigraph relations are stored once each, without their ids.
+/// Hyperedges, relation names, node labels and properties are not stored.
+/// Queries read straight from the mapping and infos are only decoded when
+/// they are asked for, so the graph can be larger than RAM.
+pub struct MappedGraph<C = DefaultCodec> {
+    map: Mmap,
+    codec: C,
+    nr_nodes: usize,
+    nr_relations: usize,
+    node_ids_pos: usize,
+    node_info_offsets_pos: usize,
+    node_info_pos: usize,
+    relations_pos: usize,
+}
+
+/// Location of a relation's info and adjacency arrays within the mapping.
+struct Descriptor {
+    info: Range<usize>,
+    nr_targets: usize,
+    out_offsets_pos: usize,
+    out_targets_pos: usize,
+    in_offsets_pos: usize,
+    in_targets_pos: usize,
+    kind: Option<RelationKind>,
+}
+
+impl MappedGraph<DefaultCodec> {
+    /// Map the snapshot at `path` using the `DefaultCodec`.
+    pub fn open<P>(path: P) -> Result<Self, StoreError>
+    where
+        P: AsRef<Path>,
+    {
+        Self::open_with_codec(path, DefaultCodec)
+    }
+
+    /// Write the snapshot of the `PersistentGraph` store in `dir` to a file
+    /// at `path`, the same way `write` does, without loading the graph.
+    ///
+    /// Infos are copied as the store encoded them, so open the file with
+    /// the codec of the store. Records still in the log are not included,
+    /// so this fails with `StoreError::PendingLog` unless the store was
+    /// checkpointed after its last mutation.
+    pub fn write_store<P, Q>(dir: P, path: Q) -> Result<(), StoreError>
+    where
+        P: AsRef<Path>,
+        Q: AsRef<Path>,
+    {
+        write_source(&SnapshotSource::open(dir.as_ref())?, path.as_ref())
+    }
+}
+
+impl<C> MappedGraph<C>
+where
+    C: InfoCodec,
+{
+    /// Write `graph` to a snapshot file at `path` that can be opened with `open`.
+    ///
+    /// The file is written next to `path` and renamed over it once it is
+    /// complete, so a crash never leaves a truncated file at `path`.
+    pub fn write<P>(graph: &Graph, codec: &C, path: P) -> Result<(), StoreError>
+    where
+        P: AsRef<Path>,
+    {
+        write_source(&GraphSource::new(graph, codec), path.as_ref())
+    }
+
+    /// Map the snapshot at `path` using `codec` to decode infos.
+    pub fn open_with_codec<P>(path: P, codec: C) -> Result<Self, StoreError>
+    where
+        P: AsRef<Path>,
+    {
+        let file = File::open(path)?;
+        // Safety: the snapshot is treated as immutable while it is mapped.
+        // Every position read from it is bounds checked before it is used.
+        let map = unsafe { Mmap::map(&file)? };
+        if map.len() < HEADER_LEN || &map[..MAGIC.len()] != MAGIC {
+            return Err(StoreError::CorruptSnapshot);
+        }
+
+        let header: Vec<usize> = (0..7)
+            .map(|i| read_u64(&map, MAGIC.len() + i * 8) as usize)
+            .collect();
+        let graph = Self {
+            nr_nodes: header[0],
+            nr_relations: header[1],
+            node_ids_pos: header[2],
+            node_info_offsets_pos: header[3],
+            node_info_pos: header[4],
+            relations_pos: header[5],
+            codec,
+            map,
+        };
+        if header[6] != graph.map.len() || !graph.is_valid() {
+            return Err(StoreError::CorruptSnapshot);
+        }
+        Ok(graph)
+    }
+
+    /// Check that every array referenced by the header lies within the
+    /// mapping, and that every offset array is ascending and ends within
+    /// the array it indexes.
+    fn is_valid(&self) -> bool {
+        let len = self.map.len() as u64;
+        let fits = |pos: usize, count: usize| {
+            (count as u64)
+                .checked_mul(8)
+                .and_then(|size| size.checked_add(pos as u64))
+                .is_some_and(|end| end <= len)
+        };
+        let nr_offsets = self.nr_nodes.saturating_add(1);
+        let nodes_fit = fits(self.node_ids_pos, self.nr_nodes)
+            && fits(self.node_info_offsets_pos, nr_offsets)
+            && fits(
+                self.relations_pos,
+                self.nr_relations.saturating_mul(DESCRIPTOR_LEN / 8),
+            );
+        let node_info_len = self.map.len().saturating_sub(self.node_info_pos);
+        nodes_fit
+            && self.node_info_pos <= self.map.len()
+            && self.offsets_are_valid(self.node_info_offsets_pos, node_info_len)
+            && (0..self.nr_relations).all(|i| {
+                let d = self.descriptor(i);
+                d.info.start <= d.info.end
+                    && d.info.end as u64 <= len
+                    && fits(d.out_offsets_pos, nr_offsets)
+                    && fits(d.out_targets_pos, d.nr_targets)
+                    && fits(d.in_offsets_pos, nr_offsets)
+                    && fits(d.in_targets_pos, d.nr_targets)
+                    && self.offsets_are_valid(d.out_offsets_pos, d.nr_targets)
+                    && self.offsets_are_valid(d.in_offsets_pos, d.nr_targets)
+                    && d.kind.is_some()
+            })
+    }
+
+    /// Check that the `nr_nodes + 1` offsets at `pos` start at 0, never
+    /// decrease and end at most at `max`.
+    fn offsets_are_valid(&self, pos: usize, max: usize) -> bool {
+        let mut previous = 0;
+        (0..=self.nr_nodes).all(|i| {
+            let offset = self.u64_at(pos, i);
+            let valid = offset >= previous && offset <= max && (i > 0 || offset == 0);
+            previous = offset;
+            valid
+        })
+    }
+
+    /// Get the number of `Nodes` in the graph.
+    pub fn nr_nodes(&self) -> usize {
+        self.nr_nodes
+    }
+
+    /// Get the number of `Relations` in the graph.
+    pub fn nr_relations(&self) -> usize {
+        self.nr_relations
+    }
+
+    /// Check if the graph contains a `Node` with `node_id`.
+    pub fn contains_node(&self, node_id: NodeId) -> bool {
+        self.node_index(node_id).is_some()
+    }
+
+    /// Check if the graph contains a `Relation` with `relation_id`.
+    pub fn contains_relation(&self, relation_id: RelationId) -> bool {
+        self.relation_index(relation_id).is_some()
+    }
+
+    /// Decode the info of a `Node`.
+    pub fn node_info(&self, node_id: NodeId) -> Option<Result<AnyNodeInfo, StoreError>> {
+        let i = self.node_index(node_id)?;
+        let start = self.u64_at(self.node_info_offsets_pos, i);
+        let end = self.u64_at(self.node_info_offsets_pos, i + 1);
+        let range = self.node_info_pos.checked_add(start)?..self.node_info_pos.checked_add(end)?;
+        Some(match self.map.get(range) {
+            Some(bytes) => self.codec.decode_node_info(bytes).map_err(Into::into),
+            None => Err(StoreError::CorruptSnapshot),
+        })
+    }
+
+    /// Decode the info of a `Relation`.
+    pub fn relation_info(
+        &self,
+        relation_id: RelationId,
+    ) -> Option<Result<AnyRelationInfo, StoreError>> {
+        let descriptor = self.descriptor(self.relation_index(relation_id)?);
+        Some(
+            self.codec
+                .decode_relation_info(&self.map[descriptor.info])
+                .map_err(Into::into),
+        )
+    }
+
+    /// Get the kind of a `Relation`.
+    pub fn relation_kind(&self, relation_id: RelationId) -> Option<RelationKind> {
+        let descriptor = self.descriptor(self.relation_index(relation_id)?);
+        Some(descriptor.kind.unwrap_or_default())
+    }
+
+    /// Get the in degree of a `Node`.
+    pub fn in_degree_of(&self, node_id: NodeId) -> Option<usize> {
+        let i = self.node_index(node_id)?;
+        Some(
+            (0..self.nr_relations)
+                .map(|r| self.targets(self.descriptor(r).in_offsets_pos, i).len())
+                .sum(),
+        )
+    }
+
+    /// Get the out degree of a `Node`.
+    pub fn out_degree_of(&self, node_id: NodeId) -> Option<usize> {
+        let i = self.node_index(node_id)?;
+        Some(
+            (0..self.nr_relations)
+                .map(|r| self.targets(self.descriptor(r).out_offsets_pos, i).len())
+                .sum(),
+        )
+    }
+
+    /// Count the edges between the endpoints of `edge` in a `Relation`.
+    pub fn multiplicity(&self, edge: Edge, relation_id: RelationId) -> usize {
+        let (r, i) = match (
+            self.relation_index(relation_id),
+            self.node_index(edge.src()),
+        ) {
+            (Some(r), Some(i)) => (r, i),
+            _ => return 0,
+        };
+        let descriptor = self.descriptor(r);
+        self.targets(descriptor.out_offsets_pos, i)
+            .filter(|k| self.target_at(descriptor.out_targets_pos, *k) == edge.dst())
+            .count()
+    }
+
+    /// Get an iterator over all `NodeIds` in the graph, in ascending order.
+    pub fn iter_node_ids(&self) -> impl Iterator<Item = NodeId> + '_ {
+        (0..self.nr_nodes).map(move |i| self.node_id_at(i))
+    }
+
+    /// Get an iterator over all `RelationIds` in the graph, in ascending order.
+    pub fn iter_relation_ids(&self) -> impl Iterator<Item = RelationId> + '_ {
+        (0..self.nr_relations).map(move |r| self.relation_id_at(r))
+    }
+
+    /// Get an iterator over all edges with `relation_id`. Parallel edges
+    /// between the same two nodes are listed once, see `multiplicity`.
+    pub fn iter_relation_edges(
+        &self,
+        relation_id: RelationId,
+    ) -> Option<impl Iterator<Item = Edge> + '_> {
+        let descriptor = self.descriptor(self.relation_index(relation_id)?);
+        let (offsets_pos, targets_pos) = (descriptor.out_offsets_pos, descriptor.out_targets_pos);
+        let undirected = descriptor.kind == Some(RelationKind::Undirected);
+        Some((0..self.nr_nodes).flat_map(move |i| {
+            let src = self.node_id_at(i);
+            self.distinct_targets(offsets_pos, targets_pos, i)
+                .map(move |dst| Edge::new(src, dst))
+                .filter(move |edge| !undirected || edge.normalized() == *edge)
+        }))
+    }
+
+    /// Get an iterator over the destinations of all edges leaving a `Node`,
+    /// together with the `RelationId` of each edge. Parallel edges are
+    /// listed once.
+    pub fn iter_next_nodes(
+        &self,
+        node_id: NodeId,
+    ) -> Option<impl Iterator<Item = (NodeId, RelationId)> + '_> {
+        let i = self.node_index(node_id)?;
+        Some(self.neighbors(i, |d| (d.out_offsets_pos, d.out_targets_pos)))
+    }
+
+    /// Get an iterator over the sources of all edges entering a `Node`,
+    /// together with the `RelationId` of each edge. Parallel edges are
+    /// listed once.
+    pub fn iter_prev_nodes(
+        &self,
+        node_id: NodeId,
+    ) -> Option<impl Iterator<Item = (NodeId, RelationId)> + '_> {
+        let i = self.node_index(node_id)?;
+        Some(self.neighbors(i, |d| (d.in_offsets_pos, d.in_targets_pos)))
+    }
+
+    fn neighbors<F>(&self, i: usize, arrays: F) -> impl Iterator<Item = (NodeId, RelationId)> + '_
+    where
+        F: Fn(&Descriptor) -> (usize, usize) + 'static,
+    {
+        (0..self.nr_relations).flat_map(move |r| {
+            let relation_id = self.relation_id_at(r);
+            let (offsets_pos, targets_pos) = arrays(&self.descriptor(r));
+            self.distinct_targets(offsets_pos, targets_pos, i)
+                .map(move |target| (target, relation_id))
+        })
+    }
+
+    /// Get an iterator over the targets of node `i` in a CSR array, with the
+    /// repeats of parallel edges skipped. Targets are sorted, so repeats are
+    /// next to each other.
+    fn distinct_targets(
+        &self,
+        offsets_pos: usize,
+        targets_pos: usize,
+        i: usize,
+    ) -> impl Iterator<Item = NodeId> + '_ {
+        let targets = self.targets(offsets_pos, i);
+        let start = targets.start;
+        targets
+            .filter(move |k| {
+                *k == start || self.u64_at(targets_pos, k - 1) != self.u64_at(targets_pos, *k)
+            })
+            .map(move |k| self.target_at(targets_pos, k))
+    }
+
+    /// Get the range of positions in a CSR target array that belong to node `i`.
+    fn targets(&self, offsets_pos: usize, i: usize) -> Range<usize> {
+        let start = self.u64_at(offsets_pos, i);
+        let end = self.u64_at(offsets_pos, i + 1);
+        start..end.max(start)
+    }
+
+    fn target_at(&self, targets_pos: usize, k: usize) -> NodeId {
+        self.node_id_at(
+            self.u64_at(targets_pos, k)
+                .min(self.nr_nodes.saturating_sub(1)),
+        )
+    }
+
+    fn node_id_at(&self, i: usize) -> NodeId {
+        NodeId::new(self.u64_at(self.node_ids_pos, i) as u64)
+    }
+
+    fn relation_id_at(&self, r: usize) -> RelationId {
+        RelationId::new(
+            self.u64_at(self.relations_pos, r * DESCRIPTOR_LEN / 8)
+                .max(1) as u64,
+        )
+    }
+
+    fn node_index(&self, node_id: NodeId) -> Option<usize> {
+        binary_search(
+            self.nr_nodes,
+            |i| self.u64_at(self.node_ids_pos, i) as u64,
+            *node_id,
+        )
+    }
+
+    fn relation_index(&self, relation_id: RelationId) -> Option<usize> {
+        binary_search(
+            self.nr_relations,
+            |r| self.relation_id_at(r).get(),
+            relation_id.get(),
+        )
+    }
+
+    fn descriptor(&self, r: usize) -> Descriptor {
+        let field = |f: usize| self.u64_at(self.relations_pos, r * DESCRIPTOR_LEN / 8 + f);
+        Descriptor {
+            info: field(1)..field(2),
+            nr_targets: field(3),
+            out_offsets_pos: field(4),
+            out_targets_pos: field(5),
+            in_offsets_pos: field(6),
+            in_targets_pos: field(7),
+            kind: u8::try_from(field(8))
+                .ok()
+                .and_then(RelationKind::from_code),
+        }
+    }
+
+    /// Read the `i`th element of a `u64` array starting at `pos`, or 0 when
+    /// the element lies outside of the mapping.
+    fn u64_at(&self, pos: usize, i: usize) -> usize {
+        let at = i.checked_mul(8).and_then(|offset| pos.checked_add(offset));
+        match at.and_then(|at| Some((at, at.checked_add(8)?))) {
+            Some((at, end)) if end <= self.map.len() => {
+                usize::try_from(read_u64(&self.map, at)).unwrap_or(usize::MAX)
+            }
+            _ => 0,
+        }
+    }
+}
+
+/// The nodes and relations a mapped file is written from, each sorted by id.
+pub(crate) trait MappedSource {
+    /// Get the `NodeIds` of all nodes in ascending order.
+    fn node_ids(&self) -> &[NodeId];
+
+    /// Append the encoded info of the `i`th node to `bytes`.
+    fn write_node_info(&self, i: usize, bytes: &mut Vec<u8>) -> Result<(), StoreError>;
+
+    /// Get the `RelationIds` of all relations in ascending order.
+    fn relation_ids(&self) -> &[RelationId];
+
+    /// Get the kind of the `r`th relation.
+    fn relation_kind(&self, r: usize) -> RelationKind;
+
+    /// Append the encoded info of the `r`th relation to `bytes`.
+    fn write_relation_info(&self, r: usize, bytes: &mut Vec<u8>) -> Result<(), StoreError>;
+
+    /// Call `f` with every edge of the `r`th relation, once per parallel edge.
+    fn for_each_edge(
+        &self,
+        r: usize,
+        f: &mut dyn FnMut(Edge) -> Result<(), StoreError>,
+    ) -> Result<(), StoreError>;
+}
+
+/// A `Graph` as the source of a mapped file.
+struct GraphSource<'a, C> {
+    graph: &'a Graph,
+    codec: &'a C,
+    node_ids: Vec<NodeId>,
+    relation_ids: Vec<RelationId>,
+}
+
+impl<'a, C> GraphSource<'a, C> {
+    fn new(graph: &'a Graph, codec: &'a C) -> Self {
+        let mut node_ids: Vec<NodeId> = graph.iter_node_ids().map(|(id, _)| id).collect();
+        node_ids.sort_by_key(|id| **id);
+        let mut relation_ids: Vec<RelationId> = graph.iter_relations().map(|(id, _)| id).collect();
+        relation_ids.sort_by_key(|id| id.get());
+        Self {
+            graph,
+            codec,
+            node_ids,
+            relation_ids,
+        }
+    }
+
+    fn relation(&self, r: usize) -> &'a Relation {
+        self.graph.relation(self.relation_ids[r]).unwrap()
+    }
+}
+
+impl<'a, C> MappedSource for GraphSource<'a, C>
+where
+    C: InfoCodec,
+{
+    fn node_ids(&self) -> &[NodeId] {
+        &self.node_ids
+    }
+
+    fn write_node_info(&self, i: usize, bytes: &mut Vec<u8>) -> Result<(), StoreError> {
+        let info = self.graph.node_info(self.node_ids[i]).unwrap();
+        Ok(self.codec.encode_node_info(info, bytes)?)
+    }
+
+    fn relation_ids(&self) -> &[RelationId] {
+        &self.relation_ids
+    }
+
+    fn relation_kind(&self, r: usize) -> RelationKind {
+        self.relation(r).kind()
+    }
+
+    fn write_relation_info(&self, r: usize, bytes: &mut Vec<u8>) -> Result<(), StoreError> {
+        Ok(self
+            .codec
+            .encode_relation_info(self.relation(r).info(), bytes)?)
+    }
+
+    fn for_each_edge(
+        &self,
+        r: usize,
+        f: &mut dyn FnMut(Edge) -> Result<(), StoreError>,
+    ) -> Result<(), StoreError> {
+        let relation = self.relation(r);
+        for edge in relation.iter_edges() {
+            for _ in 0..relation.multiplicity(edge) {
+                f(*edge)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Buffered output that keeps track of the position it writes at.
+struct Output {
+    file: BufWriter<File>,
+    pos: u64,
+}
+
+impl Output {
+    fn write(&mut self, bytes: &[u8]) -> io::Result<()> {
+        self.file.write_all(bytes)?;
+        self.pos += bytes.len() as u64;
+        Ok(())
+    }
+
+    fn write_u64(&mut self, value: u64) -> io::Result<()> {
+        self.write(&value.to_le_bytes())
+    }
+
+    /// Write `(node, target)` pairs as CSR offset and target arrays, and
+    /// return the positions of both.
+    fn write_csr(&mut self, pairs: &mut [(u64, u64)], nr_nodes: usize) -> io::Result<(u64, u64)> {
+        pairs.sort_unstable();
+        let offsets_pos = self.pos;
+        let mut k = 0;
+        self.write_u64(0)?;
+        for i in 0..nr_nodes as u64 {
+            while k < pairs.len() && pairs[k].0 == i {
+                k += 1;
+            }
+            self.write_u64(k as u64)?;
+        }
+        let targets_pos = self.pos;
+        for (_, target) in pairs.iter() {
+            self.write_u64(*target)?;
+        }
+        Ok((offsets_pos, targets_pos))
+    }
+}
+
+/// Write `source` to a temporary file next to `path`, then rename it over `path`.
+fn write_source<S>(source: &S, path: &Path) -> Result<(), StoreError>
+where
+    S: MappedSource,
+{
+    let mut tmp_path = path.as_os_str().to_owned();
+    tmp_path.push(".tmp");
+    let tmp_path = PathBuf::from(tmp_path);
+    if let Err(err) = write_file(source, &tmp_path) {
+        let _ = fs::remove_file(&tmp_path);
+        return Err(err);
+    }
+    fs::rename(&tmp_path, path)?;
+    if let Some(dir) = path.parent().and_then(|dir| File::open(dir).ok()) {
+        // Persist the rename where the platform supports syncing directories.
+        let _ = dir.sync_all();
+    }
+    Ok(())
+}
+
+/// Stream the sections of `source` to `path`, then fill in the header.
+///
+/// Only the node ids, the node info offsets, the relation descriptors and
+/// the edges of one relation at a time are held in memory.
+fn write_file<S>(source: &S, path: &Path) -> Result<(), StoreError>
+where
+    S: MappedSource,
+{
+    let node_ids = source.node_ids();
+    let relation_ids = source.relation_ids();
+    let nr_nodes = node_ids.len();
+    let mut out = Output {
+        file: BufWriter::new(File::create(path)?),
+        pos: 0,
+    };
+    out.write(&[0; HEADER_LEN])?;
+
+    let node_ids_pos = out.pos;
+    for node_id in node_ids {
+        out.write_u64(**node_id)?;
+    }
+
+    let mut info = Vec::new();
+    let mut node_info_offsets = Vec::with_capacity(nr_nodes + 1);
+    node_info_offsets.push(0);
+    let node_info_pos = out.pos;
+    for i in 0..nr_nodes {
+        info.clear();
+        source.write_node_info(i, &mut info)?;
+        out.write(&info)?;
+        node_info_offsets.push(out.pos - node_info_pos);
+    }
+    let node_info_offsets_pos = out.pos;
+    for offset in node_info_offsets {
+        out.write_u64(offset)?;
+    }
+
+    let index = |node_id: NodeId| {
+        let i = node_ids.binary_search_by_key(&*node_id, |id| **id);
+        i.map(|i| i as u64).map_err(|_| StoreError::CorruptSnapshot)
+    };
+    let mut descriptors = Vec::with_capacity(relation_ids.len() * DESCRIPTOR_LEN);
+    for (r, relation_id) in relation_ids.iter().enumerate() {
+        write_u64(&mut descriptors, relation_id.get());
+
+        info.clear();
+        source.write_relation_info(r, &mut info)?;
+        write_u64(&mut descriptors, out.pos);
+        out.write(&info)?;
+        write_u64(&mut descriptors, out.pos);
+
+        let kind = source.relation_kind(r);
+        let mut pairs = Vec::new();
+        source.for_each_edge(r, &mut |edge| {
+            let (src, dst) = (index(edge.src())?, index(edge.dst())?);
+            pairs.push((src, dst));
+            if kind == RelationKind::Undirected && src != dst {
+                pairs.push((dst, src));
+            }
+            Ok(())
+        })?;
+        write_u64(&mut descriptors, pairs.len() as u64);
+        let (offsets_pos, targets_pos) = out.write_csr(&mut pairs, nr_nodes)?;
+        write_u64(&mut descriptors, offsets_pos);
+        write_u64(&mut descriptors, targets_pos);
+        for pair in pairs.iter_mut() {
+            *pair = (pair.1, pair.0);
+        }
+        let (offsets_pos, targets_pos) = out.write_csr(&mut pairs, nr_nodes)?;
+        write_u64(&mut descriptors, offsets_pos);
+        write_u64(&mut descriptors, targets_pos);
+        write_u64(&mut descriptors, u64::from(kind.code()));
+    }
+    let relations_pos = out.pos;
+    out.write(&descriptors)?;
+
+    let mut header = MAGIC.to_vec();
+    for value in &[
+        nr_nodes as u64,
+        relation_ids.len() as u64,
+        node_ids_pos,
+        node_info_offsets_pos,
+        node_info_pos,
+        relations_pos,
+        out.pos,
+    ] {
+        write_u64(&mut header, *value);
+    }
+    let mut file = out.file.into_inner().map_err(|err| err.into_error())?;
+    file.seek(SeekFrom::Start(0))?;
+    file.write_all(&header)?;
+    file.sync_all()?;
+    Ok(())
+}
+
+fn binary_search<F>(len: usize, key_at: F, key: u64) -> Option<usize>
+where
+    F: Fn(usize) -> u64,
+{
+    let (mut lo, mut hi) = (0, len);
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        match key_at(mid).cmp(&key) {
+            std::cmp::Ordering::Less => lo = mid + 1,
+            std::cmp::Ordering::Greater => hi = mid,
+            std::cmp::Ordering::Equal => return Some(mid),
+        }
+    }
+    None
+}
+
+fn read_u64(bytes: &[u8], pos: usize) -> u64 {
+    let mut raw = [0; 8];
+    raw.copy_from_slice(&bytes[pos..pos + 8]);
+    u64::from_le_bytes(raw)
+}
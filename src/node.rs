@@ -2,16 +2,48 @@ use downcast_rs::{impl_downcast, DowncastSync};
 use std::fmt;
 use std::fmt::{Display, Formatter};
 use std::ops::{Deref, DerefMut};
+use std::sync::Arc;
 
 /// Type erased container for a node info.
-pub struct AnyNodeInfo(Box<dyn NodeInfo>);
+///
+/// The info is reference counted so graph snapshots can share it,
+/// which makes cloning an `AnyNodeInfo` cheap. A shared info can only be
+/// mutated through `make_mut`, which copies it first so the other holders
+/// keep seeing the old info.
+#[derive(Clone)]
+pub struct AnyNodeInfo(Arc<dyn NodeInfo>);
 
 impl<I> From<I> for AnyNodeInfo
 where
     I: NodeInfo,
 {
     fn from(info: I) -> Self {
-        Self(Box::new(info))
+        Self(Arc::new(info))
+    }
+}
+
+impl AnyNodeInfo {
+    /// Get an exclusive reference to the info, unless it is shared
+    /// with another `AnyNodeInfo`, e.g. one held by a snapshot.
+    pub fn get_mut(&mut self) -> Option<&mut dyn NodeInfo> {
+        Arc::get_mut(&mut self.0)
+    }
+
+    /// Get an exclusive reference to the info if it is a `T`. An info
+    /// shared with another `AnyNodeInfo` is copied first.
+    pub fn make_mut<T>(&mut self) -> Option<&mut T>
+    where
+        T: NodeInfo + Clone,
+    {
+        if Arc::get_mut(&mut self.0).is_none() {
+            self.0 = Arc::new(self.0.downcast_ref::<T>()?.clone());
+        }
+        Arc::get_mut(&mut self.0)?.downcast_mut::<T>()
+    }
+
+    /// Check if both containers hold the same info, rather than equal ones.
+    pub(crate) fn ptr_eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
     }
 }
 
@@ -19,18 +51,20 @@ impl Deref for AnyNodeInfo {
     type Target = dyn NodeInfo;
 
     fn deref(&self) -> &Self::Target {
-        Box::as_ref(&self.0)
+        Arc::as_ref(&self.0)
     }
 }
 
+/// Panics if the info is shared with another `AnyNodeInfo`, which
+/// `get_mut` and `make_mut` handle instead.
 impl DerefMut for AnyNodeInfo {
     fn deref_mut(&mut self) -> &mut Self::Target {
-        Box::as_mut(&mut self.0)
+        Arc::get_mut(&mut self.0).expect("the node info is shared")
     }
 }
 
 /// Uniquely identifies a node withing a graph.
-#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
 pub struct NodeId(u64);
 
 impl NodeId {
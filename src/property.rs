@@ -0,0 +1,114 @@
+use crate::collections::{Map, Share};
+use crate::edge::Edge;
+use crate::node::NodeId;
+use crate::relation::RelationId;
+use crate::value::Value;
+use std::borrow::Borrow;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::ops::Deref;
+use std::sync::Arc;
+
+/// The name of a property.
+///
+/// A graph interns its property keys, so every property with the same key
+/// shares one allocation and cloning a `PropertyKey` is cheap.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct PropertyKey(Arc<str>);
+
+impl PropertyKey {
+    /// Create a new `PropertyKey`.
+    pub fn new<S>(key: S) -> Self
+    where
+        S: Into<Arc<str>>,
+    {
+        Self(key.into())
+    }
+
+    /// Get the key as a string slice.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Deref for PropertyKey {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl Borrow<str> for PropertyKey {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Display for PropertyKey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// The part of a graph that a property belongs to.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum PropertyOwner {
+    /// A `Node`.
+    Node(NodeId),
+    /// A `Relation` as a whole.
+    Relation(RelationId),
+    /// The edge between two nodes in a relation. The parallel edges of a
+    /// multigraph relation between the same two nodes share their properties.
+    Edge(Edge, RelationId),
+}
+
+/// The properties of a node, relation or edge.
+///
+/// The map becomes persistent once the graph that holds it is shared, so
+/// cloning it is cheap from then on.
+#[derive(Clone, Default, PartialEq, Debug)]
+pub struct Properties {
+    values: Map<PropertyKey, Value>,
+}
+
+impl Properties {
+    /// Get the value of the property with `key`.
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.values.get(key)
+    }
+
+    /// Check if there is a property with `key`.
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.values.contains_key(key)
+    }
+
+    /// Get an iterator over the keys and values of the properties.
+    pub fn iter(&self) -> impl Iterator<Item = (&PropertyKey, &Value)> {
+        self.values.iter()
+    }
+
+    /// Get the number of properties.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Check if there are no properties.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    pub(crate) fn insert(&mut self, key: PropertyKey, value: Value) -> Option<Value> {
+        self.values.insert(key, value)
+    }
+
+    pub(crate) fn remove(&mut self, key: &str) -> Option<Value> {
+        self.values.remove(key)
+    }
+}
+
+impl Share for Properties {
+    fn share(&mut self) {
+        self.values.share();
+    }
+}
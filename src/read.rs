@@ -0,0 +1,706 @@
+use crate::codec::InfoCodec;
+use crate::concurrent::ConcurrentGraph;
+use crate::edge::{AnyEdgeInfo, Edge};
+use crate::graph::Graph;
+use crate::history::History;
+use crate::hyperedge::Hyperedge;
+use crate::id::IdMap;
+use crate::label::Label;
+use crate::mapped::MappedGraph;
+use crate::node::{AnyNodeInfo, NodeId, NodeInfo};
+use crate::property::{Properties, PropertyOwner};
+use crate::relation::{Relation, RelationId, RelationInfo, RelationKind};
+use crate::store::PersistentGraph;
+use crate::subgraph;
+use crate::view::{NodeFilter, RelationFilter, Reversed};
+
+/// The read API shared by a `Graph`, the views over it, `History`,
+/// `PersistentGraph`, `ConcurrentGraph` and `MappedGraph`, so code that only
+/// reads a graph can be written once for all of them. A `GraphSnapshot` or
+/// `Transaction` can be passed as the `Graph` it derefs to.
+///
+/// Infos are passed to a callback instead of being returned, so backends
+/// that lock or decode their contents can implement the trait as well. The
+/// iterators of a `ConcurrentGraph` run over a copy taken when they are made.
+///
+/// Views are made with `reversed`, `filter_nodes` and `filter_relations`
+/// and borrow the graph, so making one copies nothing. Views implement
+/// this trait as well, so they can be stacked.
+pub trait GraphRead {
+    /// Get the number of visible `Nodes`.
+    fn nr_nodes(&self) -> usize;
+
+    /// Get the number of visible `Relations`.
+    fn nr_relations(&self) -> usize {
+        self.relation_ids().count()
+    }
+
+    /// Check if a `Node` with `node_id` is visible.
+    fn contains_node(&self, node_id: NodeId) -> bool;
+
+    /// Check if a `Relation` with `relation_id` is visible.
+    fn contains_relation(&self, relation_id: RelationId) -> bool;
+
+    /// Call `f` with the info of a visible `Node`.
+    fn with_node_info<F, T>(&self, node_id: NodeId, f: F) -> Option<T>
+    where
+        F: FnOnce(&dyn NodeInfo) -> T;
+
+    /// Call `f` with the info of a visible `Relation`.
+    fn with_relation_info<F, T>(&self, relation_id: RelationId, f: F) -> Option<T>
+    where
+        F: FnOnce(&dyn RelationInfo) -> T;
+
+    /// Get the `RelationKind` of a visible `Relation`.
+    fn relation_kind(&self, relation_id: RelationId) -> Option<RelationKind>;
+
+    /// Get the info of a visible `Node`. The info is shared with the graph
+    /// until one of them mutates it.
+    fn cloned_node_info(&self, node_id: NodeId) -> Option<AnyNodeInfo>;
+
+    /// Get the labels of a visible `Node`.
+    fn node_labels(&self, _node_id: NodeId) -> Vec<Label> {
+        Vec::new()
+    }
+
+    /// Get the properties of a visible node, relation or edge, if it has any.
+    fn properties_of(&self, _owner: PropertyOwner) -> Option<Properties> {
+        None
+    }
+
+    /// Get a copy of a visible `Relation` without its edges.
+    fn relation_without_edges(&self, relation_id: RelationId) -> Option<Relation>;
+
+    /// Get the infos of the visible edges between the endpoints of `edge`
+    /// in a `Relation`, one for each parallel edge. The infos are shared
+    /// with the graph until one of them mutates them.
+    fn edge_infos(&self, edge: Edge, relation_id: RelationId) -> Vec<Option<AnyEdgeInfo>> {
+        vec![None; self.multiplicity(edge, relation_id)]
+    }
+
+    /// Get the visible `Hyperedges` of a `Relation`.
+    fn hyperedges(&self, _relation_id: RelationId) -> Vec<Hyperedge> {
+        Vec::new()
+    }
+
+    /// Get an iterator over the `NodeIds` of all visible `Nodes`.
+    fn node_ids(&self) -> impl Iterator<Item = NodeId> + '_;
+
+    /// Get an iterator over the `RelationIds` of all visible `Relations`.
+    fn relation_ids(&self) -> impl Iterator<Item = RelationId> + '_;
+
+    /// Get an iterator over the destinations of all visible edges leaving a
+    /// `Node`, together with the `RelationId` of each edge.
+    fn iter_next_nodes(
+        &self,
+        node_id: NodeId,
+    ) -> Option<impl Iterator<Item = (NodeId, RelationId)> + '_>;
+
+    /// Get an iterator over the sources of all visible edges entering a
+    /// `Node`, together with the `RelationId` of each edge.
+    fn iter_prev_nodes(
+        &self,
+        node_id: NodeId,
+    ) -> Option<impl Iterator<Item = (NodeId, RelationId)> + '_>;
+
+    /// Get an iterator over all visible edges with `relation_id`.
+    fn iter_relation_edges(
+        &self,
+        relation_id: RelationId,
+    ) -> Option<impl Iterator<Item = Edge> + '_>;
+
+    /// Get the in degree of a `Node`. Parallel edges are each counted.
+    fn in_degree_of(&self, node_id: NodeId) -> Option<usize> {
+        let prev_nodes = self.iter_prev_nodes(node_id)?;
+        Some(
+            prev_nodes
+                .map(|(src, r)| self.multiplicity(Edge::new(src, node_id), r))
+                .sum(),
+        )
+    }
+
+    /// Get the out degree of a `Node`. Parallel edges are each counted.
+    fn out_degree_of(&self, node_id: NodeId) -> Option<usize> {
+        let next_nodes = self.iter_next_nodes(node_id)?;
+        Some(
+            next_nodes
+                .map(|(dst, r)| self.multiplicity(Edge::new(node_id, dst), r))
+                .sum(),
+        )
+    }
+
+    /// Check if a visible `Relation` contains a visible edge. In an
+    /// undirected relation the direction of `edge` does not matter.
+    fn contains_edge(&self, edge: Edge, relation_id: RelationId) -> bool {
+        self.multiplicity(edge, relation_id) > 0
+    }
+
+    /// Count the visible edges between the endpoints of `edge` in a `Relation`.
+    fn multiplicity(&self, edge: Edge, relation_id: RelationId) -> usize;
+
+    /// Copy the visible `Nodes` in `node_ids` and the visible edges between
+    /// them into a new graph, like `Graph::induced_subgraph`.
+    fn induced_subgraph<N, F>(&self, node_ids: N, clone_info: F) -> (Graph, IdMap)
+    where
+        Self: Sized,
+        N: IntoIterator<Item = NodeId>,
+        F: FnMut(NodeId, &AnyNodeInfo) -> AnyNodeInfo,
+    {
+        subgraph::induced(self, node_ids, clone_info)
+    }
+
+    /// Copy the visible edges in `edges`, their nodes and their relations
+    /// into a new graph, like `Graph::edge_subgraph`.
+    fn edge_subgraph<E, F>(&self, edges: E, clone_info: F) -> (Graph, IdMap)
+    where
+        Self: Sized,
+        E: IntoIterator<Item = (Edge, RelationId)>,
+        F: FnMut(NodeId, &AnyNodeInfo) -> AnyNodeInfo,
+    {
+        subgraph::by_edges(self, edges, clone_info)
+    }
+
+    /// Copy the visible neighborhood of `center` into a new graph, like
+    /// `Graph::ego_graph`.
+    fn ego_graph<F>(
+        &self,
+        center: NodeId,
+        radius: usize,
+        relation_ids: Option<&[RelationId]>,
+        clone_info: F,
+    ) -> Option<(Graph, IdMap)>
+    where
+        Self: Sized,
+        F: FnMut(NodeId, &AnyNodeInfo) -> AnyNodeInfo,
+    {
+        subgraph::ego(self, center, radius, relation_ids, clone_info)
+    }
+
+    /// Get a view of the graph with the direction of every edge reversed.
+    fn reversed(&self) -> Reversed<'_, Self>
+    where
+        Self: Sized,
+    {
+        Reversed::new(self)
+    }
+
+    /// Get a view of the graph with only the `Nodes` for which `pred`
+    /// returns `true`, and the edges between them.
+    fn filter_nodes<F>(&self, pred: F) -> NodeFilter<'_, Self, F>
+    where
+        Self: Sized,
+        F: Fn(NodeId, &dyn NodeInfo) -> bool,
+    {
+        NodeFilter::new(self, pred)
+    }
+
+    /// Get a view of the graph with only the `Relations` in `relation_ids`.
+    fn filter_relations<I>(&self, relation_ids: I) -> RelationFilter<'_, Self>
+    where
+        Self: Sized,
+        I: IntoIterator<Item = RelationId>,
+    {
+        RelationFilter::new(self, relation_ids.into_iter().collect())
+    }
+}
+
+impl GraphRead for Graph {
+    fn nr_nodes(&self) -> usize {
+        Graph::nr_nodes(self)
+    }
+
+    fn nr_relations(&self) -> usize {
+        Graph::nr_relations(self)
+    }
+
+    fn contains_node(&self, node_id: NodeId) -> bool {
+        Graph::contains_node(self, node_id)
+    }
+
+    fn contains_relation(&self, relation_id: RelationId) -> bool {
+        Graph::contains_relation(self, relation_id)
+    }
+
+    fn with_node_info<F, T>(&self, node_id: NodeId, f: F) -> Option<T>
+    where
+        F: FnOnce(&dyn NodeInfo) -> T,
+    {
+        Graph::node_info(self, node_id).map(f)
+    }
+
+    fn with_relation_info<F, T>(&self, relation_id: RelationId, f: F) -> Option<T>
+    where
+        F: FnOnce(&dyn RelationInfo) -> T,
+    {
+        Some(f(Graph::relation(self, relation_id)?.info()))
+    }
+
+    fn relation_kind(&self, relation_id: RelationId) -> Option<RelationKind> {
+        Some(Graph::relation(self, relation_id)?.kind())
+    }
+
+    fn cloned_node_info(&self, node_id: NodeId) -> Option<AnyNodeInfo> {
+        self.any_node_info(node_id).cloned()
+    }
+
+    fn node_labels(&self, node_id: NodeId) -> Vec<Label> {
+        self.labels_of(node_id)
+            .into_iter()
+            .flatten()
+            .cloned()
+            .collect()
+    }
+
+    fn properties_of(&self, owner: PropertyOwner) -> Option<Properties> {
+        self.properties(owner).cloned()
+    }
+
+    fn relation_without_edges(&self, relation_id: RelationId) -> Option<Relation> {
+        Some(Graph::relation(self, relation_id)?.without_edges())
+    }
+
+    fn edge_infos(&self, edge: Edge, relation_id: RelationId) -> Vec<Option<AnyEdgeInfo>> {
+        Graph::relation(self, relation_id).map_or_else(Vec::new, |r| r.edge_infos(&edge))
+    }
+
+    fn hyperedges(&self, relation_id: RelationId) -> Vec<Hyperedge> {
+        let hyperedges = Graph::relation(self, relation_id).into_iter();
+        hyperedges
+            .flat_map(|relation| relation.iter_hyperedges())
+            .map(|(_, hyperedge)| hyperedge.clone())
+            .collect()
+    }
+
+    fn node_ids(&self) -> impl Iterator<Item = NodeId> + '_ {
+        self.iter_node_ids().map(|(node_id, _)| node_id)
+    }
+
+    fn relation_ids(&self) -> impl Iterator<Item = RelationId> + '_ {
+        self.iter_relations().map(|(relation_id, _)| relation_id)
+    }
+
+    fn iter_next_nodes(
+        &self,
+        node_id: NodeId,
+    ) -> Option<impl Iterator<Item = (NodeId, RelationId)> + '_> {
+        Graph::iter_next_nodes(self, node_id)
+    }
+
+    fn iter_prev_nodes(
+        &self,
+        node_id: NodeId,
+    ) -> Option<impl Iterator<Item = (NodeId, RelationId)> + '_> {
+        Graph::iter_prev_nodes(self, node_id)
+    }
+
+    fn iter_relation_edges(
+        &self,
+        relation_id: RelationId,
+    ) -> Option<impl Iterator<Item = Edge> + '_> {
+        Some(Graph::iter_relation_edges(self, relation_id)?.copied())
+    }
+
+    fn in_degree_of(&self, node_id: NodeId) -> Option<usize> {
+        Graph::in_degree_of(self, node_id)
+    }
+
+    fn out_degree_of(&self, node_id: NodeId) -> Option<usize> {
+        Graph::out_degree_of(self, node_id)
+    }
+
+    fn multiplicity(&self, edge: Edge, relation_id: RelationId) -> usize {
+        Graph::relation(self, relation_id).map_or(0, |relation| relation.multiplicity(&edge))
+    }
+}
+
+impl GraphRead for History {
+    fn nr_nodes(&self) -> usize {
+        self.graph().nr_nodes()
+    }
+
+    fn nr_relations(&self) -> usize {
+        self.graph().nr_relations()
+    }
+
+    fn contains_node(&self, node_id: NodeId) -> bool {
+        self.graph().contains_node(node_id)
+    }
+
+    fn contains_relation(&self, relation_id: RelationId) -> bool {
+        self.graph().contains_relation(relation_id)
+    }
+
+    fn with_node_info<F, T>(&self, node_id: NodeId, f: F) -> Option<T>
+    where
+        F: FnOnce(&dyn NodeInfo) -> T,
+    {
+        self.graph().with_node_info(node_id, f)
+    }
+
+    fn with_relation_info<F, T>(&self, relation_id: RelationId, f: F) -> Option<T>
+    where
+        F: FnOnce(&dyn RelationInfo) -> T,
+    {
+        self.graph().with_relation_info(relation_id, f)
+    }
+
+    fn relation_kind(&self, relation_id: RelationId) -> Option<RelationKind> {
+        GraphRead::relation_kind(self.graph(), relation_id)
+    }
+
+    fn cloned_node_info(&self, node_id: NodeId) -> Option<AnyNodeInfo> {
+        self.graph().cloned_node_info(node_id)
+    }
+
+    fn node_labels(&self, node_id: NodeId) -> Vec<Label> {
+        self.graph().node_labels(node_id)
+    }
+
+    fn properties_of(&self, owner: PropertyOwner) -> Option<Properties> {
+        self.graph().properties_of(owner)
+    }
+
+    fn relation_without_edges(&self, relation_id: RelationId) -> Option<Relation> {
+        self.graph().relation_without_edges(relation_id)
+    }
+
+    fn edge_infos(&self, edge: Edge, relation_id: RelationId) -> Vec<Option<AnyEdgeInfo>> {
+        GraphRead::edge_infos(self.graph(), edge, relation_id)
+    }
+
+    fn hyperedges(&self, relation_id: RelationId) -> Vec<Hyperedge> {
+        GraphRead::hyperedges(self.graph(), relation_id)
+    }
+
+    fn node_ids(&self) -> impl Iterator<Item = NodeId> + '_ {
+        GraphRead::node_ids(self.graph())
+    }
+
+    fn relation_ids(&self) -> impl Iterator<Item = RelationId> + '_ {
+        GraphRead::relation_ids(self.graph())
+    }
+
+    fn iter_next_nodes(
+        &self,
+        node_id: NodeId,
+    ) -> Option<impl Iterator<Item = (NodeId, RelationId)> + '_> {
+        self.graph().iter_next_nodes(node_id)
+    }
+
+    fn iter_prev_nodes(
+        &self,
+        node_id: NodeId,
+    ) -> Option<impl Iterator<Item = (NodeId, RelationId)> + '_> {
+        self.graph().iter_prev_nodes(node_id)
+    }
+
+    fn iter_relation_edges(
+        &self,
+        relation_id: RelationId,
+    ) -> Option<impl Iterator<Item = Edge> + '_> {
+        GraphRead::iter_relation_edges(self.graph(), relation_id)
+    }
+
+    fn in_degree_of(&self, node_id: NodeId) -> Option<usize> {
+        self.graph().in_degree_of(node_id)
+    }
+
+    fn out_degree_of(&self, node_id: NodeId) -> Option<usize> {
+        self.graph().out_degree_of(node_id)
+    }
+
+    fn multiplicity(&self, edge: Edge, relation_id: RelationId) -> usize {
+        GraphRead::multiplicity(self.graph(), edge, relation_id)
+    }
+}
+
+impl<C> GraphRead for PersistentGraph<C>
+where
+    C: InfoCodec,
+{
+    fn nr_nodes(&self) -> usize {
+        self.graph().nr_nodes()
+    }
+
+    fn nr_relations(&self) -> usize {
+        self.graph().nr_relations()
+    }
+
+    fn contains_node(&self, node_id: NodeId) -> bool {
+        self.graph().contains_node(node_id)
+    }
+
+    fn contains_relation(&self, relation_id: RelationId) -> bool {
+        self.graph().contains_relation(relation_id)
+    }
+
+    fn with_node_info<F, T>(&self, node_id: NodeId, f: F) -> Option<T>
+    where
+        F: FnOnce(&dyn NodeInfo) -> T,
+    {
+        self.graph().with_node_info(node_id, f)
+    }
+
+    fn with_relation_info<F, T>(&self, relation_id: RelationId, f: F) -> Option<T>
+    where
+        F: FnOnce(&dyn RelationInfo) -> T,
+    {
+        self.graph().with_relation_info(relation_id, f)
+    }
+
+    fn relation_kind(&self, relation_id: RelationId) -> Option<RelationKind> {
+        GraphRead::relation_kind(self.graph(), relation_id)
+    }
+
+    fn cloned_node_info(&self, node_id: NodeId) -> Option<AnyNodeInfo> {
+        self.graph().cloned_node_info(node_id)
+    }
+
+    fn node_labels(&self, node_id: NodeId) -> Vec<Label> {
+        self.graph().node_labels(node_id)
+    }
+
+    fn properties_of(&self, owner: PropertyOwner) -> Option<Properties> {
+        self.graph().properties_of(owner)
+    }
+
+    fn relation_without_edges(&self, relation_id: RelationId) -> Option<Relation> {
+        self.graph().relation_without_edges(relation_id)
+    }
+
+    fn edge_infos(&self, edge: Edge, relation_id: RelationId) -> Vec<Option<AnyEdgeInfo>> {
+        GraphRead::edge_infos(self.graph(), edge, relation_id)
+    }
+
+    fn hyperedges(&self, relation_id: RelationId) -> Vec<Hyperedge> {
+        GraphRead::hyperedges(self.graph(), relation_id)
+    }
+
+    fn node_ids(&self) -> impl Iterator<Item = NodeId> + '_ {
+        GraphRead::node_ids(self.graph())
+    }
+
+    fn relation_ids(&self) -> impl Iterator<Item = RelationId> + '_ {
+        GraphRead::relation_ids(self.graph())
+    }
+
+    fn iter_next_nodes(
+        &self,
+        node_id: NodeId,
+    ) -> Option<impl Iterator<Item = (NodeId, RelationId)> + '_> {
+        self.graph().iter_next_nodes(node_id)
+    }
+
+    fn iter_prev_nodes(
+        &self,
+        node_id: NodeId,
+    ) -> Option<impl Iterator<Item = (NodeId, RelationId)> + '_> {
+        self.graph().iter_prev_nodes(node_id)
+    }
+
+    fn iter_relation_edges(
+        &self,
+        relation_id: RelationId,
+    ) -> Option<impl Iterator<Item = Edge> + '_> {
+        GraphRead::iter_relation_edges(self.graph(), relation_id)
+    }
+
+    fn in_degree_of(&self, node_id: NodeId) -> Option<usize> {
+        self.graph().in_degree_of(node_id)
+    }
+
+    fn out_degree_of(&self, node_id: NodeId) -> Option<usize> {
+        self.graph().out_degree_of(node_id)
+    }
+
+    fn multiplicity(&self, edge: Edge, relation_id: RelationId) -> usize {
+        GraphRead::multiplicity(self.graph(), edge, relation_id)
+    }
+}
+
+impl GraphRead for ConcurrentGraph {
+    fn nr_nodes(&self) -> usize {
+        ConcurrentGraph::nr_nodes(self)
+    }
+
+    fn nr_relations(&self) -> usize {
+        ConcurrentGraph::nr_relations(self)
+    }
+
+    fn contains_node(&self, node_id: NodeId) -> bool {
+        ConcurrentGraph::contains_node(self, node_id)
+    }
+
+    fn contains_relation(&self, relation_id: RelationId) -> bool {
+        ConcurrentGraph::contains_relation(self, relation_id)
+    }
+
+    fn with_node_info<F, T>(&self, node_id: NodeId, f: F) -> Option<T>
+    where
+        F: FnOnce(&dyn NodeInfo) -> T,
+    {
+        ConcurrentGraph::with_node_info(self, node_id, f)
+    }
+
+    fn with_relation_info<F, T>(&self, relation_id: RelationId, f: F) -> Option<T>
+    where
+        F: FnOnce(&dyn RelationInfo) -> T,
+    {
+        self.with_relation(relation_id, |relation| f(relation.info()))
+    }
+
+    fn relation_kind(&self, relation_id: RelationId) -> Option<RelationKind> {
+        self.with_relation(relation_id, |relation| relation.kind())
+    }
+
+    fn cloned_node_info(&self, node_id: NodeId) -> Option<AnyNodeInfo> {
+        self.any_node_info(node_id)
+    }
+
+    fn relation_without_edges(&self, relation_id: RelationId) -> Option<Relation> {
+        self.with_relation(relation_id, |relation| relation.without_edges())
+    }
+
+    fn edge_infos(&self, edge: Edge, relation_id: RelationId) -> Vec<Option<AnyEdgeInfo>> {
+        self.with_relation(relation_id, |relation| relation.edge_infos(&edge))
+            .unwrap_or_default()
+    }
+
+    fn node_ids(&self) -> impl Iterator<Item = NodeId> + '_ {
+        ConcurrentGraph::node_ids(self).into_iter()
+    }
+
+    fn relation_ids(&self) -> impl Iterator<Item = RelationId> + '_ {
+        ConcurrentGraph::relation_ids(self).into_iter()
+    }
+
+    fn iter_next_nodes(
+        &self,
+        node_id: NodeId,
+    ) -> Option<impl Iterator<Item = (NodeId, RelationId)> + '_> {
+        Some(self.next_nodes(node_id)?.into_iter())
+    }
+
+    fn iter_prev_nodes(
+        &self,
+        node_id: NodeId,
+    ) -> Option<impl Iterator<Item = (NodeId, RelationId)> + '_> {
+        Some(self.prev_nodes(node_id)?.into_iter())
+    }
+
+    fn iter_relation_edges(
+        &self,
+        relation_id: RelationId,
+    ) -> Option<impl Iterator<Item = Edge> + '_> {
+        Some(self.relation_edges(relation_id)?.into_iter())
+    }
+
+    fn in_degree_of(&self, node_id: NodeId) -> Option<usize> {
+        ConcurrentGraph::in_degree_of(self, node_id)
+    }
+
+    fn out_degree_of(&self, node_id: NodeId) -> Option<usize> {
+        ConcurrentGraph::out_degree_of(self, node_id)
+    }
+
+    fn multiplicity(&self, edge: Edge, relation_id: RelationId) -> usize {
+        self.with_relation(relation_id, |relation| relation.multiplicity(&edge))
+            .unwrap_or(0)
+    }
+}
+
+/// Infos that can't be decoded are treated like missing ones, so the
+/// methods that return them give `None`. The graph stores no labels,
+/// properties, edge infos or hyperedges. Parallel edges are listed once
+/// like in a `Graph` and counted by `multiplicity`, but their relation
+/// comes back as one that is not a multigraph relation.
+impl<C> GraphRead for MappedGraph<C>
+where
+    C: InfoCodec,
+{
+    fn nr_nodes(&self) -> usize {
+        MappedGraph::nr_nodes(self)
+    }
+
+    fn nr_relations(&self) -> usize {
+        MappedGraph::nr_relations(self)
+    }
+
+    fn contains_node(&self, node_id: NodeId) -> bool {
+        MappedGraph::contains_node(self, node_id)
+    }
+
+    fn contains_relation(&self, relation_id: RelationId) -> bool {
+        MappedGraph::contains_relation(self, relation_id)
+    }
+
+    fn with_node_info<F, T>(&self, node_id: NodeId, f: F) -> Option<T>
+    where
+        F: FnOnce(&dyn NodeInfo) -> T,
+    {
+        let info = self.node_info(node_id)?.ok()?;
+        Some(f(&*info))
+    }
+
+    fn with_relation_info<F, T>(&self, relation_id: RelationId, f: F) -> Option<T>
+    where
+        F: FnOnce(&dyn RelationInfo) -> T,
+    {
+        let info = self.relation_info(relation_id)?.ok()?;
+        Some(f(&*info))
+    }
+
+    fn relation_kind(&self, relation_id: RelationId) -> Option<RelationKind> {
+        MappedGraph::relation_kind(self, relation_id)
+    }
+
+    fn cloned_node_info(&self, node_id: NodeId) -> Option<AnyNodeInfo> {
+        self.node_info(node_id)?.ok()
+    }
+
+    fn relation_without_edges(&self, relation_id: RelationId) -> Option<Relation> {
+        let info = self.relation_info(relation_id)?.ok()?;
+        Some(Relation::with_kind(info, self.relation_kind(relation_id)?))
+    }
+
+    fn node_ids(&self) -> impl Iterator<Item = NodeId> + '_ {
+        self.iter_node_ids()
+    }
+
+    fn relation_ids(&self) -> impl Iterator<Item = RelationId> + '_ {
+        self.iter_relation_ids()
+    }
+
+    fn iter_next_nodes(
+        &self,
+        node_id: NodeId,
+    ) -> Option<impl Iterator<Item = (NodeId, RelationId)> + '_> {
+        MappedGraph::iter_next_nodes(self, node_id)
+    }
+
+    fn iter_prev_nodes(
+        &self,
+        node_id: NodeId,
+    ) -> Option<impl Iterator<Item = (NodeId, RelationId)> + '_> {
+        MappedGraph::iter_prev_nodes(self, node_id)
+    }
+
+    fn iter_relation_edges(
+        &self,
+        relation_id: RelationId,
+    ) -> Option<impl Iterator<Item = Edge> + '_> {
+        MappedGraph::iter_relation_edges(self, relation_id)
+    }
+
+    fn in_degree_of(&self, node_id: NodeId) -> Option<usize> {
+        MappedGraph::in_degree_of(self, node_id)
+    }
+
+    fn out_degree_of(&self, node_id: NodeId) -> Option<usize> {
+        MappedGraph::out_degree_of(self, node_id)
+    }
+
+    fn multiplicity(&self, edge: Edge, relation_id: RelationId) -> usize {
+        MappedGraph::multiplicity(self, edge, relation_id)
+    }
+}
@@ -1,54 +1,520 @@
-use crate::edge::Edge;
+use crate::collections::{Map, Set, Share, SortedSet};
+use crate::edge::{AnyEdgeInfo, Edge, EdgeId, EdgeInfo};
+use crate::hyperedge::{Hyperedge, HyperedgeKind};
+use crate::property::{Properties, PropertyKey};
+use crate::schema::RelationSchema;
+use crate::value::Value;
 use downcast_rs::{impl_downcast, DowncastSync};
-use std::collections::HashSet;
 use std::fmt;
 use std::fmt::{Display, Formatter};
 use std::num::NonZeroU64;
-use std::ops::{Deref, DerefMut};
+use std::ops::{Deref, DerefMut, RangeInclusive};
+use std::sync::Arc;
+
+/// How the direction of the edges of a `Relation` is treated.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+pub enum RelationKind {
+    /// Edges go from their source to their destination.
+    #[default]
+    Directed,
+    /// Edges have no direction, so an edge from `a` to `b` is the same
+    /// edge as one from `b` to `a`. Edges are stored normalized, see
+    /// `Edge::normalized`.
+    Undirected,
+    /// Edges are directed, but two distinct nodes can't be connected in
+    /// both directions.
+    Antisymmetric,
+}
+
+impl RelationKind {
+    /// Get the form in which `edge` is stored in a relation of this kind.
+    pub fn normalize(&self, edge: Edge) -> Edge {
+        match self {
+            Self::Undirected => edge.normalized(),
+            Self::Directed | Self::Antisymmetric => edge,
+        }
+    }
+
+    pub(crate) fn code(&self) -> u8 {
+        match self {
+            Self::Directed => 0,
+            Self::Undirected => 1,
+            Self::Antisymmetric => 2,
+        }
+    }
+
+    pub(crate) fn from_code(code: u8) -> Option<Self> {
+        match code {
+            0 => Some(Self::Directed),
+            1 => Some(Self::Undirected),
+            2 => Some(Self::Antisymmetric),
+            _ => None,
+        }
+    }
+}
+
+/// The parallel edges of a multigraph `Relation`, with their infos.
+///
+/// `by_pair` is sorted by edge, so the `EdgeIds` of the parallel edges
+/// between two nodes are a range of it, see `pair_range`.
+#[derive(Clone, Default)]
+struct ParallelEdges {
+    by_pair: SortedSet<(Edge, EdgeId)>,
+    by_id: Map<EdgeId, (Edge, Option<AnyEdgeInfo>)>,
+}
+
+/// Get the range of `ParallelEdges::by_pair` that holds the parallel edges
+/// between the nodes of `edge`.
+fn pair_range(edge: Edge) -> RangeInclusive<(Edge, EdgeId)> {
+    (edge, EdgeId::new(0))..=(edge, EdgeId::new(u64::MAX))
+}
+
+/// The hyperedges of a hyperedge `Relation`.
+#[derive(Clone, Default)]
+struct Hyperedges {
+    kind: HyperedgeKind,
+    by_id: Map<EdgeId, Hyperedge>,
+}
 
 /// Models a relation between nodes in a graph.
+///
+/// A multigraph relation, made with `Relation::multi`, can connect the same
+/// two nodes any number of times. Each of these parallel edges has its own
+/// `EdgeId` and info, while `iter_edges` and `contains_edge` see every
+/// connected pair of nodes once.
+///
+/// A hyperedge relation, made with `Relation::hyper`, has no binary edges.
+/// Instead, each of its `Hyperedges` connects any number of nodes and has
+/// its own `EdgeId`.
+///
+/// Once the graph that holds a relation is shared by a snapshot or a
+/// transaction, its edges are kept in persistent sets and maps, so cloning
+/// the relation is cheap and a clone shares its edges until one of them
+/// changes.
+#[derive(Clone)]
 pub struct Relation {
     info: AnyRelationInfo,
-    edges: HashSet<Edge>,
+    edges: Set<Edge>,
+    edge_info: Map<Edge, AnyEdgeInfo>,
+    schema: Option<Arc<RelationSchema>>,
+    kind: RelationKind,
+    parallel: Option<ParallelEdges>,
+    hyperedges: Option<Hyperedges>,
+    name: Option<Arc<str>>,
+    properties: Properties,
+    edge_properties: Map<Edge, Properties>,
 }
 
 impl Relation {
     /// Create a `Relation` with an id, and info.
     pub fn new<I>(info: I) -> Self
+    where
+        I: Into<AnyRelationInfo>,
+    {
+        Self::with_kind(info, RelationKind::Directed)
+    }
+
+    /// Create a `Relation` of the given kind with info.
+    pub fn with_kind<I>(info: I, kind: RelationKind) -> Self
     where
         I: Into<AnyRelationInfo>,
     {
         Self {
             info: info.into(),
-            edges: HashSet::new(),
+            edges: Set::default(),
+            edge_info: Map::default(),
+            schema: None,
+            kind,
+            parallel: None,
+            hyperedges: None,
+            name: None,
+            properties: Properties::default(),
+            edge_properties: Map::default(),
+        }
+    }
+
+    /// Create a multigraph `Relation` of the given kind with info.
+    pub fn multi<I>(info: I, kind: RelationKind) -> Self
+    where
+        I: Into<AnyRelationInfo>,
+    {
+        Self {
+            parallel: Some(ParallelEdges::default()),
+            ..Self::with_kind(info, kind)
+        }
+    }
+
+    /// Create a hyperedge `Relation` of the given kind with info.
+    pub fn hyper<I>(info: I, kind: HyperedgeKind) -> Self
+    where
+        I: Into<AnyRelationInfo>,
+    {
+        Self {
+            hyperedges: Some(Hyperedges {
+                kind,
+                by_id: Map::default(),
+            }),
+            ..Self::new(info)
         }
     }
 
+    /// Get a copy of the relation without its edges.
+    pub(crate) fn without_edges(&self) -> Self {
+        Self {
+            edges: Set::default(),
+            edge_info: Map::default(),
+            edge_properties: Map::default(),
+            parallel: self.parallel.as_ref().map(|_| ParallelEdges::default()),
+            hyperedges: self.hyperedges.as_ref().map(|hyperedges| Hyperedges {
+                kind: hyperedges.kind,
+                by_id: Map::default(),
+            }),
+            ..self.clone()
+        }
+    }
+
+    /// Check if the relation is a multigraph relation.
+    pub fn is_multi(&self) -> bool {
+        self.parallel.is_some()
+    }
+
+    /// Get a code for whether the relation is a plain, multigraph or
+    /// hyperedge relation, and the kind of its hyperedges.
+    pub(crate) fn shape_code(&self) -> u8 {
+        match (&self.parallel, &self.hyperedges) {
+            (_, Some(hyperedges)) => 2 + hyperedges.kind.code(),
+            (Some(_), None) => 1,
+            (None, None) => 0,
+        }
+    }
+
+    /// Create a relation without edges from its info, the code of its kind,
+    /// its `shape_code` and its name.
+    pub(crate) fn from_codes(
+        info: AnyRelationInfo,
+        kind: u8,
+        shape: u8,
+        name: Option<String>,
+    ) -> Option<Self> {
+        let kind = RelationKind::from_code(kind)?;
+        let mut relation = match shape {
+            0 => Self::with_kind(info, kind),
+            1 => Self::multi(info, kind),
+            _ => Self::hyper(info, HyperedgeKind::from_code(shape - 2)?),
+        };
+        relation.name = name.map(Arc::from);
+        Some(relation)
+    }
+
+    /// Check if the relation is a hyperedge relation.
+    pub fn is_hyper(&self) -> bool {
+        self.hyperedges.is_some()
+    }
+
+    /// Get the kind of the hyperedges, if the relation is a hyperedge relation.
+    pub fn hyperedge_kind(&self) -> Option<HyperedgeKind> {
+        self.hyperedges.as_ref().map(|hyperedges| hyperedges.kind)
+    }
+
+    /// Add a hyperedge to a hyperedge relation and return it as stored.
+    pub(crate) fn insert_hyperedge(&mut self, edge_id: EdgeId, hyperedge: Hyperedge) -> &Hyperedge {
+        let hyperedges = self.hyperedges.as_mut().unwrap();
+        let hyperedge = hyperedges.kind.normalize(hyperedge);
+        hyperedges.by_id.insert(edge_id, hyperedge);
+        &hyperedges.by_id[&edge_id]
+    }
+
+    /// Remove a hyperedge and return it.
+    pub(crate) fn remove_hyperedge(&mut self, edge_id: EdgeId) -> Option<Hyperedge> {
+        self.hyperedges.as_mut()?.by_id.remove(&edge_id)
+    }
+
+    /// Get a hyperedge of a hyperedge relation.
+    pub fn hyperedge(&self, edge_id: EdgeId) -> Option<&Hyperedge> {
+        self.hyperedges.as_ref()?.by_id.get(&edge_id)
+    }
+
+    /// Get an iterator over all hyperedges of a hyperedge relation.
+    pub fn iter_hyperedges(&self) -> impl Iterator<Item = (EdgeId, &Hyperedge)> {
+        self.hyperedges
+            .iter()
+            .flat_map(|hyperedges| hyperedges.by_id.iter())
+            .map(|(edge_id, hyperedge)| (*edge_id, hyperedge))
+    }
+
+    /// Get the kind of the relation.
+    pub fn kind(&self) -> RelationKind {
+        self.kind
+    }
+
     /// Get a shared reference to the type erased info.
     pub fn info(&self) -> &dyn RelationInfo {
         &*self.info
     }
 
     /// Get an exclusive reference to the type erased info.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the info is shared with a clone of the relation, e.g. one
+    /// held by a snapshot.
+    #[deprecated(
+        since = "0.1.0",
+        note = "panics on a shared info, use `try_info_mut` or `make_info_mut` instead"
+    )]
     pub fn info_mut(&mut self) -> &mut dyn RelationInfo {
         &mut *self.info
     }
 
+    /// Get an exclusive reference to the type erased info, unless it is
+    /// shared with a clone of the relation.
+    pub fn try_info_mut(&mut self) -> Option<&mut dyn RelationInfo> {
+        self.info.get_mut()
+    }
+
+    /// Get an exclusive reference to the info if it is a `T`. An info
+    /// shared with a clone of the relation is copied first.
+    pub fn make_info_mut<T>(&mut self) -> Option<&mut T>
+    where
+        T: RelationInfo + Clone,
+    {
+        self.info.make_mut()
+    }
+
+    /// Get the schema the edges of the relation must satisfy, if any.
+    pub fn schema(&self) -> Option<&RelationSchema> {
+        self.schema.as_deref()
+    }
+
+    pub(crate) fn set_schema(&mut self, schema: Option<RelationSchema>) {
+        self.schema = schema.map(Arc::new);
+    }
+
+    /// Get the name of the relation, if it has one.
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    pub(crate) fn set_name(&mut self, name: Option<Arc<str>>) -> Option<Arc<str>> {
+        std::mem::replace(&mut self.name, name)
+    }
+
+    /// Get the properties of the relation.
+    pub fn properties(&self) -> &Properties {
+        &self.properties
+    }
+
+    pub(crate) fn properties_mut(&mut self) -> &mut Properties {
+        &mut self.properties
+    }
+
+    /// Get the properties of an edge, if it has any.
+    pub fn edge_properties(&self, edge: &Edge) -> Option<&Properties> {
+        self.edge_properties.get(&self.kind.normalize(*edge))
+    }
+
+    /// Get an iterator over the edges that have properties, together with
+    /// their properties.
+    pub fn iter_edge_properties(&self) -> impl Iterator<Item = (&Edge, &Properties)> {
+        self.edge_properties.iter()
+    }
+
+    pub(crate) fn set_edge_property(
+        &mut self,
+        edge: &Edge,
+        key: PropertyKey,
+        value: Value,
+    ) -> Option<Value> {
+        let edge = self.kind.normalize(*edge);
+        self.edge_properties.get_or_default(edge).insert(key, value)
+    }
+
+    pub(crate) fn remove_edge_property(&mut self, edge: &Edge, key: &str) -> Option<Value> {
+        let edge = self.kind.normalize(*edge);
+        let properties = self.edge_properties.get_mut(&edge)?;
+        let value = properties.remove(key);
+        if properties.is_empty() {
+            self.edge_properties.remove(&edge);
+        }
+        value
+    }
+
     /// Insert a new edge to the relation and return whether
     /// or not the edge did not exist previously.
     pub fn insert_edge(&mut self, edge: Edge) -> bool {
-        self.edges.insert(edge)
+        self.edges.insert(self.kind.normalize(edge))
     }
 
     /// Remove an edge from the relation and return whether
     /// or not there was anything to remove.
+    /// In a multigraph relation all parallel edges are removed.
     pub fn remove_edge(&mut self, edge: &Edge) -> bool {
-        self.edges.remove(edge)
+        let edge = self.kind.normalize(*edge);
+        self.edge_info.remove(&edge);
+        self.edge_properties.remove(&edge);
+        if let Some(parallel) = &mut self.parallel {
+            let pairs: Vec<_> = parallel.by_pair.range(pair_range(edge)).cloned().collect();
+            for (edge, edge_id) in pairs {
+                parallel.by_pair.remove(&(edge, edge_id));
+                parallel.by_id.remove(&edge_id);
+            }
+        }
+        self.edges.remove(&edge)
+    }
+
+    /// Add a parallel edge to a multigraph relation and return whether
+    /// its two nodes were not connected before.
+    pub(crate) fn insert_parallel_edge(
+        &mut self,
+        edge_id: EdgeId,
+        edge: Edge,
+        info: Option<AnyEdgeInfo>,
+    ) -> bool {
+        let edge = self.kind.normalize(edge);
+        let parallel = self.parallel.as_mut().unwrap();
+        parallel.by_pair.insert((edge, edge_id));
+        parallel.by_id.insert(edge_id, (edge, info));
+        self.edges.insert(edge)
+    }
+
+    /// Remove a parallel edge and return it, together with whether it was
+    /// the last edge between its two nodes.
+    pub(crate) fn remove_parallel_edge(&mut self, edge_id: EdgeId) -> Option<(Edge, bool)> {
+        let parallel = self.parallel.as_mut()?;
+        let (edge, _) = parallel.by_id.remove(&edge_id)?;
+        parallel.by_pair.remove(&(edge, edge_id));
+        let last = parallel.by_pair.range(pair_range(edge)).next().is_none();
+        if last {
+            self.edges.remove(&edge);
+            self.edge_properties.remove(&edge);
+        }
+        Some((edge, last))
+    }
+
+    /// Get the number of edges between two nodes, which is at most 1
+    /// unless the relation is a multigraph relation.
+    pub fn multiplicity(&self, edge: &Edge) -> usize {
+        let edge = self.kind.normalize(*edge);
+        match &self.parallel {
+            Some(parallel) => parallel.by_pair.range(pair_range(edge)).count(),
+            None => usize::from(self.edges.contains(&edge)),
+        }
+    }
+
+    /// Get an iterator over the `EdgeIds` of the parallel edges between
+    /// two nodes, in ascending order.
+    pub fn iter_edge_ids(&self, edge: &Edge) -> impl Iterator<Item = EdgeId> + '_ {
+        let edge = self.kind.normalize(*edge);
+        self.parallel
+            .iter()
+            .flat_map(move |parallel| parallel.by_pair.range(pair_range(edge)))
+            .map(|(_, edge_id)| *edge_id)
+    }
+
+    /// Get an iterator over all parallel edges of a multigraph relation.
+    pub fn iter_parallel_edges(&self) -> impl Iterator<Item = (EdgeId, Edge)> + '_ {
+        self.parallel
+            .iter()
+            .flat_map(|parallel| parallel.by_id.iter())
+            .map(|(edge_id, (edge, _))| (*edge_id, *edge))
+    }
+
+    /// Get the nodes connected by a parallel edge.
+    pub fn parallel_edge(&self, edge_id: EdgeId) -> Option<Edge> {
+        Some(self.parallel.as_ref()?.by_id.get(&edge_id)?.0)
+    }
+
+    /// Get a shared reference to the info of a parallel edge, if it has one.
+    pub fn parallel_edge_info(&self, edge_id: EdgeId) -> Option<&dyn EdgeInfo> {
+        self.any_parallel_edge_info(edge_id).map(|info| &**info)
+    }
+
+    /// Get an exclusive reference to the info of a parallel edge, if it has
+    /// one and it is a `T`. A shared info is copied first.
+    pub fn parallel_edge_info_mut<T>(&mut self, edge_id: EdgeId) -> Option<&mut T>
+    where
+        T: EdgeInfo + Clone,
+    {
+        let info = self
+            .parallel
+            .as_mut()?
+            .by_id
+            .get_mut(&edge_id)?
+            .1
+            .as_mut()?;
+        info.make_mut()
+    }
+
+    pub(crate) fn any_parallel_edge_info(&self, edge_id: EdgeId) -> Option<&AnyEdgeInfo> {
+        self.parallel.as_ref()?.by_id.get(&edge_id)?.1.as_ref()
+    }
+
+    /// Replace the info of a parallel edge and return whether the edge exists.
+    pub(crate) fn set_parallel_edge_info(
+        &mut self,
+        edge_id: EdgeId,
+        info: Option<AnyEdgeInfo>,
+    ) -> bool {
+        match self
+            .parallel
+            .as_mut()
+            .and_then(|p| p.by_id.get_mut(&edge_id))
+        {
+            Some(entry) => {
+                entry.1 = info;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Attach `info` to an existing edge and return the info it replaced.
+    pub(crate) fn set_edge_info(&mut self, edge: &Edge, info: AnyEdgeInfo) -> Option<AnyEdgeInfo> {
+        self.edge_info.insert(self.kind.normalize(*edge), info)
+    }
+
+    /// Detach the info of an edge and return it.
+    pub(crate) fn take_edge_info(&mut self, edge: &Edge) -> Option<AnyEdgeInfo> {
+        self.edge_info.remove(&self.kind.normalize(*edge))
+    }
+
+    /// Get a shared reference to the info of an edge, if it has one.
+    pub fn edge_info(&self, edge: &Edge) -> Option<&dyn EdgeInfo> {
+        self.any_edge_info(edge).map(|info| &**info)
+    }
+
+    /// Get an exclusive reference to the info of an edge, if it has one
+    /// and it is a `T`. A shared info is copied first.
+    pub fn edge_info_mut<T>(&mut self, edge: &Edge) -> Option<&mut T>
+    where
+        T: EdgeInfo + Clone,
+    {
+        self.edge_info
+            .get_mut(&self.kind.normalize(*edge))?
+            .make_mut()
     }
 
-    /// Check if the relation contains an edge.
+    pub(crate) fn any_edge_info(&self, edge: &Edge) -> Option<&AnyEdgeInfo> {
+        self.edge_info.get(&self.kind.normalize(*edge))
+    }
+
+    /// Get the infos of the edges between two nodes, one for each parallel
+    /// edge in ascending `EdgeId` order. The infos are shared, not copied.
+    pub(crate) fn edge_infos(&self, edge: &Edge) -> Vec<Option<AnyEdgeInfo>> {
+        match self.parallel {
+            Some(_) => self
+                .iter_edge_ids(edge)
+                .map(|edge_id| self.any_parallel_edge_info(edge_id).cloned())
+                .collect(),
+            None if self.contains_edge(edge) => vec![self.any_edge_info(edge).cloned()],
+            None => Vec::new(),
+        }
+    }
+
+    /// Check if the relation contains an edge. In an undirected relation
+    /// the direction of `edge` does not matter.
     pub fn contains_edge(&self, edge: &Edge) -> bool {
-        self.edges.contains(edge)
+        self.edges.contains(&self.kind.normalize(*edge))
     }
 
     /// Get an iterator over all the edges that belong to the relation.
@@ -62,15 +528,57 @@ impl Relation {
     }
 }
 
+impl Share for Relation {
+    fn share(&mut self) {
+        self.edges.share();
+        self.edge_info.share();
+        self.properties.share();
+        self.edge_properties.share_values();
+        if let Some(parallel) = &mut self.parallel {
+            parallel.by_pair.share();
+            parallel.by_id.share();
+        }
+        if let Some(hyperedges) = &mut self.hyperedges {
+            hyperedges.by_id.share();
+        }
+    }
+}
+
 /// Type erased container for a relation info.
-pub struct AnyRelationInfo(Box<dyn RelationInfo>);
+///
+/// The info is reference counted so graph snapshots can share it,
+/// which makes cloning an `AnyRelationInfo` cheap. A shared info can only
+/// be mutated through `make_mut`, which copies it first so the other
+/// holders keep seeing the old info.
+#[derive(Clone)]
+pub struct AnyRelationInfo(Arc<dyn RelationInfo>);
 
 impl<I> From<I> for AnyRelationInfo
 where
     I: RelationInfo,
 {
     fn from(info: I) -> Self {
-        AnyRelationInfo(Box::new(info))
+        AnyRelationInfo(Arc::new(info))
+    }
+}
+
+impl AnyRelationInfo {
+    /// Get an exclusive reference to the info, unless it is shared
+    /// with another `AnyRelationInfo`, e.g. one held by a snapshot.
+    pub fn get_mut(&mut self) -> Option<&mut dyn RelationInfo> {
+        Arc::get_mut(&mut self.0)
+    }
+
+    /// Get an exclusive reference to the info if it is a `T`. An info
+    /// shared with another `AnyRelationInfo` is copied first.
+    pub fn make_mut<T>(&mut self) -> Option<&mut T>
+    where
+        T: RelationInfo + Clone,
+    {
+        if Arc::get_mut(&mut self.0).is_none() {
+            self.0 = Arc::new(self.0.downcast_ref::<T>()?.clone());
+        }
+        Arc::get_mut(&mut self.0)?.downcast_mut::<T>()
     }
 }
 
@@ -78,18 +586,20 @@ impl Deref for AnyRelationInfo {
     type Target = dyn RelationInfo;
 
     fn deref(&self) -> &Self::Target {
-        Box::as_ref(&self.0)
+        Arc::as_ref(&self.0)
     }
 }
 
+/// Panics if the info is shared with another `AnyRelationInfo`, which
+/// `get_mut` and `make_mut` handle instead.
 impl DerefMut for AnyRelationInfo {
     fn deref_mut(&mut self) -> &mut Self::Target {
-        Box::as_mut(&mut self.0)
+        Arc::get_mut(&mut self.0).expect("the relation info is shared")
     }
 }
 
 /// Uniquely identifies a relation within a graph.
-#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
 pub struct RelationId(NonZeroU64);
 
 impl RelationId {
@@ -0,0 +1,106 @@
+use crate::node::NodeInfo;
+use std::any::{Any, TypeId};
+use std::collections::HashSet;
+
+/// How many edges of a `Relation` a `Node` may take part in.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+pub enum Cardinality {
+    /// Any number of edges in both directions.
+    #[default]
+    ManyToMany,
+    /// Every node has at most one outgoing and one incoming edge.
+    OneToOne,
+    /// Every node has at most one incoming edge.
+    OneToMany,
+    /// Every node has at most one outgoing edge.
+    ManyToOne,
+    /// Every node has at most the given number of outgoing edges.
+    MaxOutDegree(usize),
+}
+
+impl Cardinality {
+    /// Get the maximum number of outgoing edges of a node, if limited.
+    pub fn max_out_degree(&self) -> Option<usize> {
+        match self {
+            Self::ManyToMany | Self::OneToMany => None,
+            Self::OneToOne | Self::ManyToOne => Some(1),
+            Self::MaxOutDegree(max) => Some(*max),
+        }
+    }
+
+    /// Get the maximum number of incoming edges of a node, if limited.
+    pub fn max_in_degree(&self) -> Option<usize> {
+        match self {
+            Self::OneToOne | Self::OneToMany => Some(1),
+            Self::ManyToMany | Self::ManyToOne | Self::MaxOutDegree(_) => None,
+        }
+    }
+}
+
+/// Constraints on the edges that can be added to a `Relation`.
+///
+/// The source and destination of an edge can be limited to nodes whose
+/// info has one of a set of concrete types. Without any allowed types,
+/// every node is accepted.
+#[derive(Clone, Default, Debug)]
+pub struct RelationSchema {
+    src_types: Option<HashSet<TypeId>>,
+    dst_types: Option<HashSet<TypeId>>,
+    cardinality: Cardinality,
+}
+
+impl RelationSchema {
+    /// Create a schema that accepts every edge.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Allow nodes with info of type `T` as source.
+    pub fn allow_src<T>(mut self) -> Self
+    where
+        T: NodeInfo,
+    {
+        self.src_types
+            .get_or_insert_with(HashSet::new)
+            .insert(TypeId::of::<T>());
+        self
+    }
+
+    /// Allow nodes with info of type `T` as destination.
+    pub fn allow_dst<T>(mut self) -> Self
+    where
+        T: NodeInfo,
+    {
+        self.dst_types
+            .get_or_insert_with(HashSet::new)
+            .insert(TypeId::of::<T>());
+        self
+    }
+
+    /// Set the `Cardinality` of the relation.
+    pub fn with_cardinality(mut self, cardinality: Cardinality) -> Self {
+        self.cardinality = cardinality;
+        self
+    }
+
+    /// Get the `Cardinality` of the relation.
+    pub fn cardinality(&self) -> Cardinality {
+        self.cardinality
+    }
+
+    /// Check if a node with `info` may be the source of an edge.
+    pub fn allows_src(&self, info: &dyn NodeInfo) -> bool {
+        allows(&self.src_types, info)
+    }
+
+    /// Check if a node with `info` may be the destination of an edge.
+    pub fn allows_dst(&self, info: &dyn NodeInfo) -> bool {
+        allows(&self.dst_types, info)
+    }
+}
+
+fn allows(types: &Option<HashSet<TypeId>>, info: &dyn NodeInfo) -> bool {
+    types
+        .as_ref()
+        .map_or(true, |types| types.contains(&Any::type_id(info.as_any())))
+}
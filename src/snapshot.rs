@@ -0,0 +1,25 @@
+use crate::graph::Graph;
+use std::ops::Deref;
+use std::sync::Arc;
+
+/// Immutable view of a `Graph` at the time `Graph::snapshot` was called.
+///
+/// A snapshot derefs to a `Graph`, so it supports the whole read API,
+/// and cloning it is cheap. It is `Send` and `Sync`, so long running
+/// readers can use it on other threads while the graph keeps changing.
+#[derive(Clone)]
+pub struct GraphSnapshot(Arc<Graph>);
+
+impl GraphSnapshot {
+    pub(crate) fn new(graph: Graph) -> Self {
+        Self(Arc::new(graph))
+    }
+}
+
+impl Deref for GraphSnapshot {
+    type Target = Graph;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
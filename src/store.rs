@@ -0,0 +1,1283 @@
+use crate::codec::{
+    crc32, read_edge_info, read_hyperedge, read_name, read_property_owner, read_value, write_bytes,
+    write_edge_info, write_hyperedge, write_name, write_property_owner, write_u32, write_u64,
+    write_value, DefaultCodec, InfoCodec, Reader,
+};
+use crate::edge::{AnyEdgeInfo, Edge, EdgeId};
+use crate::errors::{CodecError, ConnectError, NameError, StoreError, UniqueError};
+use crate::graph::Graph;
+use crate::hyperedge::{Hyperedge, HyperedgeKind};
+use crate::index::{HashIndex, NodeIndex, OrderedIndex, UniqueIndex};
+use crate::mapped::MappedSource;
+use crate::node::{AnyNodeInfo, NodeId, NodeInfo};
+use crate::property::PropertyOwner;
+use crate::relation::{AnyRelationInfo, Relation, RelationId, RelationKind};
+use crate::schema::RelationSchema;
+use crate::text::TextIndex;
+use crate::value::Value;
+use memmap2::Mmap;
+use std::fs::{self, File, OpenOptions};
+use std::hash::Hash;
+use std::io::{self, Read, Write};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+const SNAPSHOT_FILE: &str = "snapshot";
+const SNAPSHOT_TMP_FILE: &str = "snapshot.tmp";
+const LOG_FILE: &str = "wal";
+const SNAPSHOT_MAGIC: &[u8; 8] = b"GFSNAP09";
+const RECORD_HEADER_LEN: usize = 12;
+
+const OP_ADD_NODE: u8 = 1;
+const OP_REMOVE_NODE: u8 = 2;
+const OP_ADD_RELATION: u8 = 3;
+const OP_REMOVE_RELATION: u8 = 4;
+const OP_CONNECT: u8 = 5;
+const OP_DISCONNECT: u8 = 6;
+const OP_CONNECT_WITH: u8 = 7;
+const OP_CONNECT_MULTI: u8 = 8;
+const OP_DISCONNECT_EDGE: u8 = 9;
+const OP_CONNECT_HYPEREDGE: u8 = 10;
+const OP_DISCONNECT_HYPEREDGE: u8 = 11;
+const OP_RENAME_RELATION: u8 = 12;
+const OP_ADD_LABEL: u8 = 13;
+const OP_REMOVE_LABEL: u8 = 14;
+const OP_SET_PROPERTY: u8 = 15;
+const OP_REMOVE_PROPERTY: u8 = 16;
+const OP_REPLACE_NODE_INFO: u8 = 17;
+const OP_SET_INVERSE: u8 = 18;
+const OP_REMOVE_INVERSE: u8 = 19;
+
+/// Number of log records after which a snapshot is written by default.
+pub const DEFAULT_CHECKPOINT_INTERVAL: usize = 1024;
+
+/// A `Graph` whose mutations are stored in a write-ahead log on disk.
+///
+/// Every mutation is appended to the log and synced before it is applied,
+/// so the graph survives a restart. The log is periodically folded into a
+/// snapshot file. Opening the store loads the snapshot and replays the log,
+/// discarding a trailing record that was only partially written. A record
+/// damaged anywhere else fails the open with `StoreError::CorruptLog`.
+///
+/// If appending a record fails, the log is truncated back to its previous
+/// length. If even that fails, the store is poisoned and rejects all
+/// further mutations, since later records could be lost behind the torn one.
+///
+/// Indexes and relation schemas are not persisted, so they have to be
+/// created again after opening the store.
+pub struct PersistentGraph<C = DefaultCodec> {
+    graph: Graph,
+    codec: C,
+    dir: PathBuf,
+    log: File,
+    log_len: u64,
+    poisoned: bool,
+    lsn: u64,
+    nr_log_records: usize,
+    checkpoint_interval: usize,
+    checkpoint_error: Option<StoreError>,
+}
+
+impl PersistentGraph<DefaultCodec> {
+    /// Open the store in `dir` using the `DefaultCodec`, creating it if needed.
+    pub fn open<P>(dir: P) -> Result<Self, StoreError>
+    where
+        P: AsRef<Path>,
+    {
+        Self::open_with_codec(dir, DefaultCodec)
+    }
+}
+
+impl<C> PersistentGraph<C>
+where
+    C: InfoCodec,
+{
+    /// Open the store in `dir` using `codec` for infos, creating it if needed.
+    pub fn open_with_codec<P>(dir: P, codec: C) -> Result<Self, StoreError>
+    where
+        P: AsRef<Path>,
+    {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir)?;
+
+        let snapshot_path = dir.join(SNAPSHOT_FILE);
+        let (mut graph, snapshot_lsn) = if snapshot_path.exists() {
+            read_snapshot(&fs::read(&snapshot_path)?, &codec)?
+        } else {
+            (Graph::new(), 0)
+        };
+
+        let mut log = OpenOptions::new()
+            .read(true)
+            .append(true)
+            .create(true)
+            .open(dir.join(LOG_FILE))?;
+        let mut bytes = Vec::new();
+        log.read_to_end(&mut bytes)?;
+
+        let mut lsn = snapshot_lsn;
+        let mut nr_log_records = 0;
+        let mut offset = 0;
+        while let Some((payload, len)) = next_record(&bytes[offset..])? {
+            let mut reader = Reader::new(payload);
+            let record_lsn = reader.read_u64()?;
+            if record_lsn > snapshot_lsn {
+                apply_record(&mut graph, &codec, reader)?;
+                lsn = record_lsn;
+            }
+            nr_log_records += 1;
+            offset += len;
+        }
+
+        // Drop the torn last record, if any.
+        if offset < bytes.len() {
+            log.set_len(offset as u64)?;
+            log.sync_all()?;
+        }
+
+        Ok(Self {
+            graph,
+            codec,
+            dir,
+            log,
+            log_len: offset as u64,
+            poisoned: false,
+            lsn,
+            nr_log_records,
+            checkpoint_interval: DEFAULT_CHECKPOINT_INTERVAL,
+            checkpoint_error: None,
+        })
+    }
+
+    /// Get a shared reference to the in-memory graph.
+    pub fn graph(&self) -> &Graph {
+        &self.graph
+    }
+
+    /// Consume the store and return the in-memory graph.
+    pub fn into_graph(self) -> Graph {
+        self.graph
+    }
+
+    /// Set the number of log records after which a snapshot is written.
+    pub fn set_checkpoint_interval(&mut self, interval: usize) {
+        self.checkpoint_interval = interval.max(1);
+    }
+
+    /// Take the error of the last snapshot that was due after a mutation
+    /// but could not be written, if any.
+    ///
+    /// The mutation itself succeeded, since its record is in the log. The
+    /// snapshot is tried again after the next mutation.
+    pub fn take_checkpoint_error(&mut self) -> Option<StoreError> {
+        self.checkpoint_error.take()
+    }
+
+    /// Create a `Node` with `info`, log it and return its `NodeId`. Fails if
+    /// another node has the same key in a unique index.
+    pub fn add_node<I>(&mut self, info: I) -> Result<NodeId, StoreError>
+    where
+        I: Into<AnyNodeInfo>,
+    {
+        let info = info.into();
+        self.graph.check_unique(None, &info)?;
+        let node_id = self.graph.next_node_id();
+        let mut payload = vec![OP_ADD_NODE];
+        write_u64(&mut payload, *node_id);
+        self.codec.encode_node_info(&*info, &mut payload)?;
+        self.commit(payload, |graph| graph.add_node(info))
+    }
+
+    /// Remove the `Node` at `node_id`, log it and return its info if it was removed.
+    pub fn remove_node(&mut self, node_id: NodeId) -> Result<Option<AnyNodeInfo>, StoreError> {
+        if !self.graph.contains_node(node_id) {
+            return Ok(None);
+        }
+        let mut payload = vec![OP_REMOVE_NODE];
+        write_u64(&mut payload, *node_id);
+        self.commit(payload, |graph| graph.remove_node(node_id))
+    }
+
+    /// Replace the info of a `Node`, log it and return the old info. Fails if
+    /// another node has the same key in a unique index.
+    pub fn replace_node_info<I>(
+        &mut self,
+        node_id: NodeId,
+        info: I,
+    ) -> Result<Option<AnyNodeInfo>, StoreError>
+    where
+        I: Into<AnyNodeInfo>,
+    {
+        if !self.graph.contains_node(node_id) {
+            return Ok(None);
+        }
+        let info = info.into();
+        self.graph.check_unique(Some(node_id), &info)?;
+        let mut payload = vec![OP_REPLACE_NODE_INFO];
+        write_u64(&mut payload, *node_id);
+        self.codec.encode_node_info(&*info, &mut payload)?;
+        Ok(self.commit(payload, |graph| graph.replace_node_info(node_id, info))??)
+    }
+
+    /// Create a hash index of the `Nodes`, see `Graph::add_hash_index`.
+    pub fn add_hash_index<K, F>(&mut self, key_fn: F) -> HashIndex<K>
+    where
+        K: Hash + Eq + Clone + Send + Sync + 'static,
+        F: Fn(&dyn NodeInfo) -> Option<K> + Send + Sync + 'static,
+    {
+        self.graph.add_hash_index(key_fn)
+    }
+
+    /// Create a unique hash index of the `Nodes`, see `Graph::add_unique_index`.
+    pub fn add_unique_index<K, F>(&mut self, key_fn: F) -> Result<UniqueIndex<K>, UniqueError>
+    where
+        K: Hash + Eq + Clone + Send + Sync + 'static,
+        F: Fn(&dyn NodeInfo) -> Option<K> + Send + Sync + 'static,
+    {
+        self.graph.add_unique_index(key_fn)
+    }
+
+    /// Add a `Node` with `info`, or replace the info of the node that has the
+    /// same key in `index`, log it and return the `NodeId` of the node.
+    ///
+    /// Fails with `UniqueError::UnknownIndex` if `index` was created before
+    /// the store was last opened.
+    pub fn upsert_node<K, I>(
+        &mut self,
+        index: UniqueIndex<K>,
+        info: I,
+    ) -> Result<NodeId, StoreError>
+    where
+        K: Hash + Eq + Clone + Send + Sync + 'static,
+        I: Into<AnyNodeInfo>,
+    {
+        let info = info.into();
+        match self.graph.unique_node(index, &info)? {
+            Some(node_id) => {
+                self.replace_node_info(node_id, info)?;
+                Ok(node_id)
+            }
+            None => self.add_node(info),
+        }
+    }
+
+    /// Create an ordered index of the `Nodes`, see `Graph::add_ordered_index`.
+    pub fn add_ordered_index<K, F>(&mut self, key_fn: F) -> OrderedIndex<K>
+    where
+        K: Ord + Clone + Send + Sync + 'static,
+        F: Fn(&dyn NodeInfo) -> Option<K> + Send + Sync + 'static,
+    {
+        self.graph.add_ordered_index(key_fn)
+    }
+
+    /// Create a full-text index of the `Nodes`, see `Graph::add_text_index`.
+    pub fn add_text_index<F>(&mut self, text_fn: F) -> TextIndex
+    where
+        F: Fn(&dyn NodeInfo) -> Option<String> + Send + Sync + 'static,
+    {
+        self.graph.add_text_index(text_fn)
+    }
+
+    /// Remove a secondary index and return whether it existed.
+    pub fn remove_index<I>(&mut self, index: I) -> bool
+    where
+        I: NodeIndex,
+    {
+        self.graph.remove_index(index)
+    }
+
+    /// Add `label` to a `Node`, log it and return whether it was added.
+    pub fn add_label(&mut self, node_id: NodeId, label: &str) -> Result<bool, StoreError> {
+        if !self.graph.contains_node(node_id) || self.graph.has_label(node_id, label) {
+            return Ok(false);
+        }
+        let payload = label_payload(OP_ADD_LABEL, node_id, label);
+        self.commit(payload, |graph| graph.add_label(node_id, label))
+    }
+
+    /// Remove `label` from a `Node`, log it and return whether it had the label.
+    pub fn remove_label(&mut self, node_id: NodeId, label: &str) -> Result<bool, StoreError> {
+        if !self.graph.has_label(node_id, label) {
+            return Ok(false);
+        }
+        let payload = label_payload(OP_REMOVE_LABEL, node_id, label);
+        self.commit(payload, |graph| graph.remove_label(node_id, label))
+    }
+
+    /// Create a `Relation` with `info`, log it and return its `RelationId`.
+    pub fn add_relation<I>(&mut self, info: I) -> Result<RelationId, StoreError>
+    where
+        I: Into<AnyRelationInfo>,
+    {
+        self.add_relation_of_kind(info, RelationKind::Directed)
+    }
+
+    /// Create a `Relation` with a unique `name` and `info`, log it and
+    /// return its `RelationId`.
+    pub fn add_named_relation<S, I>(&mut self, name: S, info: I) -> Result<RelationId, StoreError>
+    where
+        S: Into<String>,
+        I: Into<AnyRelationInfo>,
+    {
+        let name: Arc<str> = name.into().into();
+        if self.graph.relation_by_name(&name).is_some() {
+            return Err(NameError::NameTaken.into());
+        }
+        let mut relation = Relation::new(info);
+        relation.set_name(Some(name));
+        self.insert_relation(relation)
+    }
+
+    /// Give a `Relation` a new unique `name`, log it and return its old name.
+    pub fn rename_relation<S>(
+        &mut self,
+        relation_id: RelationId,
+        name: S,
+    ) -> Result<Option<String>, StoreError>
+    where
+        S: Into<String>,
+    {
+        let name: Arc<str> = name.into().into();
+        if !self.graph.contains_relation(relation_id) {
+            return Err(NameError::InvalidRelationId.into());
+        }
+        if self
+            .graph
+            .relation_by_name(&name)
+            .is_some_and(|owner| owner != relation_id)
+        {
+            return Err(NameError::NameTaken.into());
+        }
+        let old = self.log_name(relation_id, Some(name))?;
+        Ok(old.map(|old| old.to_string()))
+    }
+
+    /// Remove the name of a `Relation`, log it and return the name.
+    pub fn clear_relation_name(
+        &mut self,
+        relation_id: RelationId,
+    ) -> Result<Option<String>, StoreError> {
+        match self.graph.relation(relation_id).and_then(Relation::name) {
+            Some(_) => Ok(self.log_name(relation_id, None)?.map(|old| old.to_string())),
+            None => Ok(None),
+        }
+    }
+
+    fn log_name(
+        &mut self,
+        relation_id: RelationId,
+        name: Option<Arc<str>>,
+    ) -> Result<Option<Arc<str>>, StoreError> {
+        let mut payload = vec![OP_RENAME_RELATION];
+        write_u64(&mut payload, relation_id.get());
+        write_name(&mut payload, name.as_deref());
+        Ok(self.commit(payload, |graph| graph.set_relation_name(relation_id, name))??)
+    }
+
+    /// Create a `Relation` of the given kind with `info`, log it and
+    /// return its `RelationId`.
+    pub fn add_relation_of_kind<I>(
+        &mut self,
+        info: I,
+        kind: RelationKind,
+    ) -> Result<RelationId, StoreError>
+    where
+        I: Into<AnyRelationInfo>,
+    {
+        self.insert_relation(Relation::with_kind(info, kind))
+    }
+
+    /// Create a multigraph `Relation` of the given kind with `info`, log it
+    /// and return its `RelationId`.
+    pub fn add_multi_relation<I>(
+        &mut self,
+        info: I,
+        kind: RelationKind,
+    ) -> Result<RelationId, StoreError>
+    where
+        I: Into<AnyRelationInfo>,
+    {
+        self.insert_relation(Relation::multi(info, kind))
+    }
+
+    /// Create a hyperedge `Relation` with `info` whose hyperedges are of
+    /// the given kind, log it and return its `RelationId`.
+    pub fn add_hyper_relation<I>(
+        &mut self,
+        info: I,
+        kind: HyperedgeKind,
+    ) -> Result<RelationId, StoreError>
+    where
+        I: Into<AnyRelationInfo>,
+    {
+        self.insert_relation(Relation::hyper(info, kind))
+    }
+
+    /// Log a new relation without edges and add it to the graph.
+    fn insert_relation(&mut self, relation: Relation) -> Result<RelationId, StoreError> {
+        let relation_id = self.graph.next_relation_id();
+        let mut payload = vec![OP_ADD_RELATION];
+        write_u64(&mut payload, relation_id.get());
+        payload.push(relation.kind().code());
+        payload.push(relation.shape_code());
+        write_name(&mut payload, relation.name());
+        self.codec
+            .encode_relation_info(relation.info(), &mut payload)?;
+        self.commit(payload, |graph| {
+            graph.insert_relation(relation_id, &relation)
+        })?;
+        Ok(relation_id)
+    }
+
+    /// Remove the `Relation` at `relation_id`, log it and return its info if it was removed.
+    pub fn remove_relation(
+        &mut self,
+        relation_id: RelationId,
+    ) -> Result<Option<AnyRelationInfo>, StoreError> {
+        if !self.graph.contains_relation(relation_id) {
+            return Ok(None);
+        }
+        let mut payload = vec![OP_REMOVE_RELATION];
+        write_u64(&mut payload, relation_id.get());
+        self.commit(payload, |graph| graph.remove_relation(relation_id))
+    }
+
+    /// Declare `a` and `b` to be each other's inverse and log it, see
+    /// `Graph::set_inverse`.
+    pub fn set_inverse(&mut self, a: RelationId, b: RelationId) -> Result<(), StoreError> {
+        let edges = match self.graph.inverse_edges(a, b)? {
+            Some(edges) => edges,
+            None => return Ok(()),
+        };
+        let mut payload = vec![OP_SET_INVERSE];
+        write_u64(&mut payload, a.get());
+        write_u64(&mut payload, b.get());
+        self.commit(payload, |graph| graph.pair_inverses(a, b, edges))
+    }
+
+    /// Remove the pairing of a `Relation` with its inverse, log it and
+    /// return the `RelationId` of the inverse.
+    pub fn remove_inverse(
+        &mut self,
+        relation_id: RelationId,
+    ) -> Result<Option<RelationId>, StoreError> {
+        if self.graph.inverse_of(relation_id).is_none() {
+            return Ok(None);
+        }
+        let mut payload = vec![OP_REMOVE_INVERSE];
+        write_u64(&mut payload, relation_id.get());
+        self.commit(payload, |graph| graph.remove_inverse(relation_id))
+    }
+
+    /// Set the schema of a `Relation`, see `Graph::set_relation_schema`.
+    /// Schemas name the types of infos, which can't be persisted, so they
+    /// have to be set again after opening the store.
+    pub fn set_relation_schema(&mut self, relation_id: RelationId, schema: RelationSchema) -> bool {
+        self.graph.set_relation_schema(relation_id, schema)
+    }
+
+    /// Remove the schema of a `Relation` and return whether the relation exists.
+    pub fn clear_relation_schema(&mut self, relation_id: RelationId) -> bool {
+        self.graph.clear_relation_schema(relation_id)
+    }
+
+    /// Connect two `Nodes` with a `Relation` and log it.
+    pub fn connect(
+        &mut self,
+        src: NodeId,
+        dst: NodeId,
+        relation_id: RelationId,
+    ) -> Result<bool, StoreError> {
+        if self.contains_edge(src, dst, relation_id)? {
+            return Ok(false);
+        }
+        self.graph.can_connect(src, dst, relation_id)?;
+        let payload = edge_payload(OP_CONNECT, src, dst, relation_id);
+        Ok(self.commit(payload, |graph| graph.connect(src, dst, relation_id))??)
+    }
+
+    /// Connect two `Nodes` with a `Relation`, attach `info` to the edge
+    /// and log it.
+    pub fn connect_with<I>(
+        &mut self,
+        src: NodeId,
+        dst: NodeId,
+        relation_id: RelationId,
+        info: I,
+    ) -> Result<bool, StoreError>
+    where
+        I: Into<AnyEdgeInfo>,
+    {
+        self.contains_edge(src, dst, relation_id)?;
+        self.graph.can_connect(src, dst, relation_id)?;
+        let info = info.into();
+        let mut payload = edge_payload(OP_CONNECT_WITH, src, dst, relation_id);
+        self.codec.encode_edge_info(&*info, &mut payload)?;
+        Ok(self.commit(payload, |graph| {
+            graph.connect_with(src, dst, relation_id, info)
+        })??)
+    }
+
+    /// Connect two `Nodes` with a new parallel edge in a multigraph
+    /// `Relation`, log it and return its `EdgeId`.
+    pub fn connect_multi(
+        &mut self,
+        src: NodeId,
+        dst: NodeId,
+        relation_id: RelationId,
+    ) -> Result<EdgeId, StoreError> {
+        self.add_parallel_edge(src, dst, relation_id, None)
+    }
+
+    /// Connect two `Nodes` with a new parallel edge in a multigraph
+    /// `Relation`, attach `info` to it, log it and return its `EdgeId`.
+    pub fn connect_multi_with<I>(
+        &mut self,
+        src: NodeId,
+        dst: NodeId,
+        relation_id: RelationId,
+        info: I,
+    ) -> Result<EdgeId, StoreError>
+    where
+        I: Into<AnyEdgeInfo>,
+    {
+        self.add_parallel_edge(src, dst, relation_id, Some(info.into()))
+    }
+
+    fn add_parallel_edge(
+        &mut self,
+        src: NodeId,
+        dst: NodeId,
+        relation_id: RelationId,
+        info: Option<AnyEdgeInfo>,
+    ) -> Result<EdgeId, StoreError> {
+        self.graph.can_connect_multi(src, dst, relation_id)?;
+        let edge_id = self.graph.next_edge_id();
+        let mut payload = vec![OP_CONNECT_MULTI];
+        write_u64(&mut payload, *edge_id);
+        write_u64(&mut payload, *src);
+        write_u64(&mut payload, *dst);
+        write_u64(&mut payload, relation_id.get());
+        write_edge_info(&mut payload, &info, &self.codec)?;
+        let edge = Edge::new(src, dst);
+        self.commit(payload, |graph| {
+            graph.insert_parallel_edge(edge_id, edge, relation_id, info)
+        })??;
+        Ok(edge_id)
+    }
+
+    /// Disconnect a single parallel edge, log it and return whether it existed.
+    pub fn disconnect_edge(&mut self, edge_id: EdgeId) -> Result<bool, StoreError> {
+        if self.graph.parallel_edge(edge_id).is_none() {
+            return Ok(false);
+        }
+        let mut payload = vec![OP_DISCONNECT_EDGE];
+        write_u64(&mut payload, *edge_id);
+        self.commit(payload, |graph| graph.disconnect_edge(edge_id))
+    }
+
+    /// Connect the sources and targets of `hyperedge` with a hyperedge
+    /// `Relation`, log it and return the `EdgeId` of the new hyperedge.
+    pub fn connect_hyperedge(
+        &mut self,
+        relation_id: RelationId,
+        hyperedge: Hyperedge,
+    ) -> Result<EdgeId, StoreError> {
+        self.graph.can_connect_hyperedge(relation_id, &hyperedge)?;
+        let edge_id = self.graph.next_edge_id();
+        let mut payload = vec![OP_CONNECT_HYPEREDGE];
+        write_u64(&mut payload, *edge_id);
+        write_u64(&mut payload, relation_id.get());
+        write_hyperedge(&mut payload, &hyperedge);
+        self.commit(payload, |graph| {
+            graph.insert_hyperedge(edge_id, relation_id, hyperedge)
+        })??;
+        Ok(edge_id)
+    }
+
+    /// Disconnect a hyperedge, log it and return whether it existed.
+    pub fn disconnect_hyperedge(&mut self, edge_id: EdgeId) -> Result<bool, StoreError> {
+        if self.graph.hyperedge(edge_id).is_none() {
+            return Ok(false);
+        }
+        let mut payload = vec![OP_DISCONNECT_HYPEREDGE];
+        write_u64(&mut payload, *edge_id);
+        self.commit(payload, |graph| graph.disconnect_hyperedge(edge_id))
+    }
+
+    /// Disconnect the `Relation` between two `Nodes` and log it.
+    pub fn disconnect(
+        &mut self,
+        src: NodeId,
+        dst: NodeId,
+        relation_id: RelationId,
+    ) -> Result<bool, StoreError> {
+        if !self.contains_edge(src, dst, relation_id)? {
+            return Ok(false);
+        }
+        let payload = edge_payload(OP_DISCONNECT, src, dst, relation_id);
+        Ok(self.commit(payload, |graph| graph.disconnect(src, dst, relation_id))??)
+    }
+
+    /// Set the property with `key` of a node, relation or edge to `value`,
+    /// log it and return its previous value.
+    pub fn set_property<V>(
+        &mut self,
+        owner: PropertyOwner,
+        key: &str,
+        value: V,
+    ) -> Result<Option<Value>, StoreError>
+    where
+        V: Into<Value>,
+    {
+        self.graph.check_property_owner(owner)?;
+        let value = value.into();
+        let mut payload = property_payload(OP_SET_PROPERTY, owner, key);
+        write_value(&mut payload, &value);
+        Ok(self.commit(payload, |graph| graph.set_property(owner, key, value))??)
+    }
+
+    /// Remove the property with `key` of a node, relation or edge, log it
+    /// and return its value.
+    pub fn remove_property(
+        &mut self,
+        owner: PropertyOwner,
+        key: &str,
+    ) -> Result<Option<Value>, StoreError> {
+        if self.graph.property(owner, key).is_none() {
+            return Ok(None);
+        }
+        let payload = property_payload(OP_REMOVE_PROPERTY, owner, key);
+        self.commit(payload, |graph| graph.remove_property(owner, key))
+    }
+
+    /// Write the whole graph to the snapshot file and empty the log.
+    pub fn checkpoint(&mut self) -> Result<(), StoreError> {
+        let bytes = write_snapshot(&self.graph, &self.codec, self.lsn)?;
+
+        let tmp_path = self.dir.join(SNAPSHOT_TMP_FILE);
+        let mut file = File::create(&tmp_path)?;
+        file.write_all(&bytes)?;
+        file.sync_all()?;
+        fs::rename(&tmp_path, self.dir.join(SNAPSHOT_FILE))?;
+        if let Ok(dir) = File::open(&self.dir) {
+            // Persist the rename where the platform supports syncing directories.
+            let _ = dir.sync_all();
+        }
+
+        // Records that are still in the log after a crash at this point are
+        // skipped on replay, since their sequence numbers are in the snapshot.
+        self.log.set_len(0)?;
+        self.log_len = 0;
+        self.nr_log_records = 0;
+        self.log.sync_all()?;
+        Ok(())
+    }
+
+    /// Validate the arguments of `connect` and `disconnect` the same way `Graph` does.
+    fn contains_edge(
+        &self,
+        src: NodeId,
+        dst: NodeId,
+        relation_id: RelationId,
+    ) -> Result<bool, ConnectError> {
+        if !self.graph.contains_node(dst) {
+            return Err(ConnectError::InvalidDstNodeId);
+        }
+        if !self.graph.contains_node(src) {
+            return Err(ConnectError::InvalidSrcNodeId);
+        }
+        let relation = self
+            .graph
+            .relation(relation_id)
+            .ok_or(ConnectError::InvalidRelationId)?;
+        Ok(relation.contains_edge(&Edge::new(src, dst)))
+    }
+
+    /// Append an operation to the log and sync it to disk, then apply it
+    /// to the in-memory graph and write a snapshot if one is due. A failed
+    /// snapshot is kept for `take_checkpoint_error`.
+    fn commit<F, T>(&mut self, op: Vec<u8>, apply: F) -> Result<T, StoreError>
+    where
+        F: FnOnce(&mut Graph) -> T,
+    {
+        if self.poisoned {
+            return Err(StoreError::Poisoned);
+        }
+        let lsn = self.lsn + 1;
+        let mut payload = Vec::with_capacity(op.len() + 8);
+        write_u64(&mut payload, lsn);
+        payload.extend_from_slice(&op);
+
+        let mut record = Vec::with_capacity(payload.len() + RECORD_HEADER_LEN);
+        let len = payload.len() as u32;
+        write_u32(&mut record, len);
+        write_u32(&mut record, crc32(&len.to_le_bytes()));
+        write_u32(&mut record, crc32(&payload));
+        record.extend_from_slice(&payload);
+        if let Err(err) = self.append(&record) {
+            // Remove the torn record, so records appended later are not
+            // discarded along with it when the store is opened again.
+            if self.log.set_len(self.log_len).is_err() || self.log.sync_all().is_err() {
+                self.poisoned = true;
+            }
+            return Err(err.into());
+        }
+
+        self.log_len += record.len() as u64;
+        self.lsn = lsn;
+        self.nr_log_records += 1;
+        let result = apply(&mut self.graph);
+        if self.nr_log_records >= self.checkpoint_interval {
+            self.checkpoint_error = self.checkpoint().err();
+        }
+        Ok(result)
+    }
+
+    fn append(&mut self, record: &[u8]) -> io::Result<()> {
+        self.log.write_all(record)?;
+        self.log.sync_data()
+    }
+}
+
+fn edge_payload(op: u8, src: NodeId, dst: NodeId, relation_id: RelationId) -> Vec<u8> {
+    let mut payload = vec![op];
+    write_u64(&mut payload, *src);
+    write_u64(&mut payload, *dst);
+    write_u64(&mut payload, relation_id.get());
+    payload
+}
+
+fn label_payload(op: u8, node_id: NodeId, label: &str) -> Vec<u8> {
+    let mut payload = vec![op];
+    write_u64(&mut payload, *node_id);
+    write_bytes(&mut payload, label.as_bytes());
+    payload
+}
+
+fn property_payload(op: u8, owner: PropertyOwner, key: &str) -> Vec<u8> {
+    let mut payload = vec![op];
+    write_property_owner(&mut payload, owner);
+    write_bytes(&mut payload, key.as_bytes());
+    payload
+}
+
+/// Read the record at the start of `bytes` and return its payload and
+/// length. Returns `None` at the end of the log or if the record is torn.
+///
+/// The length is covered by its own checksum, so it can be trusted once
+/// that matches. A record is torn if its header or payload is cut short,
+/// if it is the last record and its payload doesn't match, or if only
+/// zeros follow, as a file extended by a crash can end in them. Anything
+/// else is damage in the middle of the log.
+fn next_record(bytes: &[u8]) -> Result<Option<(&[u8], usize)>, StoreError> {
+    let mut reader = Reader::new(bytes);
+    let (len, header_crc, crc) = match (reader.read_u32(), reader.read_u32(), reader.read_u32()) {
+        (Ok(len), Ok(header_crc), Ok(crc)) => (len, header_crc, crc),
+        _ => return Ok(None),
+    };
+    if crc32(&len.to_le_bytes()) != header_crc {
+        return match bytes.iter().all(|byte| *byte == 0) {
+            true => Ok(None),
+            false => Err(StoreError::CorruptLog),
+        };
+    }
+    let payload = match reader.read_slice(len as usize) {
+        Ok(payload) => payload,
+        Err(_) => return Ok(None),
+    };
+    let record_len = len as usize + RECORD_HEADER_LEN;
+    if crc32(payload) != crc {
+        return match record_len == bytes.len() {
+            true => Ok(None),
+            false => Err(StoreError::CorruptLog),
+        };
+    }
+    Ok(Some((payload, record_len)))
+}
+
+fn apply_record<C>(graph: &mut Graph, codec: &C, mut reader: Reader) -> Result<(), CodecError>
+where
+    C: InfoCodec,
+{
+    match reader.read_u8()? {
+        OP_ADD_NODE => {
+            let node_id = NodeId::new(reader.read_u64()?);
+            let info = codec.decode_node_info(reader.read_slice(reader.len())?)?;
+            graph.insert_node(node_id, info);
+        }
+        OP_REMOVE_NODE => {
+            graph.remove_node(NodeId::new(reader.read_u64()?));
+        }
+        OP_REPLACE_NODE_INFO => {
+            let node_id = NodeId::new(reader.read_u64()?);
+            let info = codec.decode_node_info(reader.read_slice(reader.len())?)?;
+            graph.set_node_info(node_id, info);
+        }
+        OP_ADD_RELATION => {
+            let relation_id = read_relation_id(&mut reader)?;
+            let (kind, shape) = (reader.read_u8()?, reader.read_u8()?);
+            let name = read_name(&mut reader)?;
+            let info = codec.decode_relation_info(reader.read_slice(reader.len())?)?;
+            let relation = Relation::from_codes(info, kind, shape, name);
+            graph.insert_relation(relation_id, &relation.ok_or(CodecError::Malformed)?);
+        }
+        op @ (OP_ADD_LABEL | OP_REMOVE_LABEL) => {
+            let node_id = NodeId::new(reader.read_u64()?);
+            let label = reader.read_string()?;
+            reader.finish()?;
+            if op == OP_ADD_LABEL {
+                graph.add_label(node_id, &label);
+            } else {
+                graph.remove_label(node_id, &label);
+            }
+        }
+        OP_SET_PROPERTY => {
+            let owner = read_property_owner(&mut reader)?;
+            let key = reader.read_string()?;
+            let value = read_value(&mut reader)?;
+            reader.finish()?;
+            let _ = graph.set_property(owner, &key, value);
+        }
+        OP_REMOVE_PROPERTY => {
+            let owner = read_property_owner(&mut reader)?;
+            let key = reader.read_string()?;
+            reader.finish()?;
+            graph.remove_property(owner, &key);
+        }
+        OP_RENAME_RELATION => {
+            let relation_id = read_relation_id(&mut reader)?;
+            let name = read_name(&mut reader)?;
+            reader.finish()?;
+            let _ = graph.set_relation_name(relation_id, name.map(Arc::from));
+        }
+        OP_REMOVE_RELATION => {
+            graph.remove_relation(read_relation_id(&mut reader)?);
+        }
+        OP_SET_INVERSE => {
+            let a = read_relation_id(&mut reader)?;
+            let b = read_relation_id(&mut reader)?;
+            reader.finish()?;
+            let _ = graph.set_inverse(a, b);
+        }
+        OP_REMOVE_INVERSE => {
+            let relation_id = read_relation_id(&mut reader)?;
+            reader.finish()?;
+            graph.remove_inverse(relation_id);
+        }
+        OP_CONNECT_WITH => {
+            let src = NodeId::new(reader.read_u64()?);
+            let dst = NodeId::new(reader.read_u64()?);
+            let relation_id = read_relation_id(&mut reader)?;
+            let info = codec.decode_edge_info(reader.read_slice(reader.len())?)?;
+            let _ = graph.connect_with(src, dst, relation_id, info);
+        }
+        OP_CONNECT_MULTI => {
+            let edge_id = EdgeId::new(reader.read_u64()?);
+            let src = NodeId::new(reader.read_u64()?);
+            let dst = NodeId::new(reader.read_u64()?);
+            let relation_id = read_relation_id(&mut reader)?;
+            let info = read_edge_info(&mut reader, codec)?;
+            reader.finish()?;
+            let _ = graph.insert_parallel_edge(edge_id, Edge::new(src, dst), relation_id, info);
+        }
+        OP_DISCONNECT_EDGE => {
+            graph.disconnect_edge(EdgeId::new(reader.read_u64()?));
+        }
+        OP_CONNECT_HYPEREDGE => {
+            let edge_id = EdgeId::new(reader.read_u64()?);
+            let relation_id = read_relation_id(&mut reader)?;
+            let hyperedge = read_hyperedge(&mut reader)?;
+            reader.finish()?;
+            let _ = graph.insert_hyperedge(edge_id, relation_id, hyperedge);
+        }
+        OP_DISCONNECT_HYPEREDGE => {
+            graph.disconnect_hyperedge(EdgeId::new(reader.read_u64()?));
+        }
+        op @ (OP_CONNECT | OP_DISCONNECT) => {
+            let src = NodeId::new(reader.read_u64()?);
+            let dst = NodeId::new(reader.read_u64()?);
+            let relation_id = read_relation_id(&mut reader)?;
+            reader.finish()?;
+            // Only valid operations are logged, so replaying them can't fail.
+            let _ = if op == OP_CONNECT {
+                graph.connect(src, dst, relation_id)
+            } else {
+                graph.disconnect(src, dst, relation_id)
+            };
+        }
+        _ => return Err(CodecError::Malformed),
+    }
+    Ok(())
+}
+
+fn read_relation_id(reader: &mut Reader) -> Result<RelationId, CodecError> {
+    match reader.read_u64()? {
+        0 => Err(CodecError::Malformed),
+        id => Ok(RelationId::new(id)),
+    }
+}
+
+fn write_snapshot<C>(graph: &Graph, codec: &C, lsn: u64) -> Result<Vec<u8>, CodecError>
+where
+    C: InfoCodec,
+{
+    let mut body = Vec::new();
+    write_u64(&mut body, lsn);
+    write_u64(&mut body, *graph.next_node_id());
+    write_u64(&mut body, graph.next_relation_id().get());
+    write_u64(&mut body, *graph.next_edge_id());
+
+    let mut info = Vec::new();
+    write_u64(&mut body, graph.nr_nodes() as u64);
+    for (node_id, node_info) in graph.iter_node_ids() {
+        info.clear();
+        codec.encode_node_info(&**node_info, &mut info)?;
+        write_u64(&mut body, *node_id);
+        write_bytes(&mut body, &info);
+        let labels: Vec<_> = graph.labels_of(node_id).into_iter().flatten().collect();
+        write_u64(&mut body, labels.len() as u64);
+        for label in labels {
+            write_bytes(&mut body, label.as_bytes());
+        }
+    }
+
+    write_u64(&mut body, graph.nr_relations() as u64);
+    for (relation_id, relation) in graph.iter_relations() {
+        info.clear();
+        codec.encode_relation_info(relation.info(), &mut info)?;
+        write_u64(&mut body, relation_id.get());
+        body.push(relation.kind().code());
+        body.push(relation.shape_code());
+        write_name(&mut body, relation.name());
+        write_bytes(&mut body, &info);
+        if relation.is_hyper() {
+            write_u64(&mut body, relation.iter_hyperedges().count() as u64);
+            for (edge_id, hyperedge) in relation.iter_hyperedges() {
+                write_u64(&mut body, *edge_id);
+                write_hyperedge(&mut body, hyperedge);
+            }
+            continue;
+        }
+        if relation.is_multi() {
+            write_u64(&mut body, relation.iter_parallel_edges().count() as u64);
+            for (edge_id, edge) in relation.iter_parallel_edges() {
+                write_u64(&mut body, *edge_id);
+                write_u64(&mut body, *edge.src());
+                write_u64(&mut body, *edge.dst());
+                let edge_info = relation.any_parallel_edge_info(edge_id).cloned();
+                write_edge_info(&mut body, &edge_info, codec)?;
+            }
+            continue;
+        }
+        write_u64(&mut body, relation.iter_edges().count() as u64);
+        for edge in relation.iter_edges() {
+            write_u64(&mut body, *edge.src());
+            write_u64(&mut body, *edge.dst());
+            let edge_info = relation.any_edge_info(edge).cloned();
+            write_edge_info(&mut body, &edge_info, codec)?;
+        }
+    }
+
+    let inverses: Vec<_> = graph
+        .iter_inverses()
+        .filter(|(a, b)| a.get() < b.get())
+        .collect();
+    write_u64(&mut body, inverses.len() as u64);
+    for (a, b) in inverses {
+        write_u64(&mut body, a.get());
+        write_u64(&mut body, b.get());
+    }
+
+    let owners: Vec<_> = graph.iter_properties().collect();
+    write_u64(&mut body, owners.len() as u64);
+    for (owner, properties) in owners {
+        write_property_owner(&mut body, owner);
+        write_u64(&mut body, properties.len() as u64);
+        for (key, value) in properties.iter() {
+            write_bytes(&mut body, key.as_bytes());
+            write_value(&mut body, value);
+        }
+    }
+
+    let mut bytes = SNAPSHOT_MAGIC.to_vec();
+    write_u32(&mut bytes, crc32(&body));
+    bytes.extend_from_slice(&body);
+    Ok(bytes)
+}
+
+fn read_snapshot<C>(bytes: &[u8], codec: &C) -> Result<(Graph, u64), StoreError>
+where
+    C: InfoCodec,
+{
+    let mut reader = Reader::new(bytes);
+    let magic = reader.read_slice(SNAPSHOT_MAGIC.len());
+    let crc = reader.read_u32();
+    let body = reader.read_slice(reader.len());
+    match (magic, crc, body) {
+        (Ok(magic), Ok(crc), Ok(body)) if magic == SNAPSHOT_MAGIC && crc32(body) == crc => {
+            read_snapshot_body(Reader::new(body), codec).map_err(|_| StoreError::CorruptSnapshot)
+        }
+        _ => Err(StoreError::CorruptSnapshot),
+    }
+}
+
+fn read_snapshot_body<C>(mut reader: Reader, codec: &C) -> Result<(Graph, u64), CodecError>
+where
+    C: InfoCodec,
+{
+    let mut graph = Graph::new();
+    let lsn = reader.read_u64()?;
+    let next_node_id = reader.read_u64()?;
+    let next_relation_id = reader.read_u64()?;
+    let next_edge_id = reader.read_u64()?;
+
+    for _ in 0..reader.read_u64()? {
+        let node_id = NodeId::new(reader.read_u64()?);
+        graph.insert_node(node_id, codec.decode_node_info(reader.read_bytes()?)?);
+        for _ in 0..reader.read_u64()? {
+            graph.add_label(node_id, &reader.read_string()?);
+        }
+    }
+
+    for _ in 0..reader.read_u64()? {
+        let relation_id = read_relation_id(&mut reader)?;
+        let (kind, shape) = (reader.read_u8()?, reader.read_u8()?);
+        let name = read_name(&mut reader)?;
+        let info = codec.decode_relation_info(reader.read_bytes()?)?;
+        let relation = Relation::from_codes(info, kind, shape, name);
+        let relation = relation.ok_or(CodecError::Malformed)?;
+        graph.insert_relation(relation_id, &relation);
+        if relation.is_hyper() {
+            for _ in 0..reader.read_u64()? {
+                let edge_id = EdgeId::new(reader.read_u64()?);
+                let hyperedge = read_hyperedge(&mut reader)?;
+                graph
+                    .insert_hyperedge(edge_id, relation_id, hyperedge)
+                    .map_err(|_| CodecError::Malformed)?;
+            }
+            continue;
+        }
+        for _ in 0..reader.read_u64()? {
+            let edge_id = match relation.is_multi() {
+                true => Some(EdgeId::new(reader.read_u64()?)),
+                false => None,
+            };
+            let src = NodeId::new(reader.read_u64()?);
+            let dst = NodeId::new(reader.read_u64()?);
+            let info = read_edge_info(&mut reader, codec)?;
+            match (edge_id, info) {
+                (Some(edge_id), info) => {
+                    graph.insert_parallel_edge(edge_id, Edge::new(src, dst), relation_id, info)
+                }
+                (None, Some(info)) => graph.connect_with(src, dst, relation_id, info).map(|_| ()),
+                (None, None) => graph.connect(src, dst, relation_id).map(|_| ()),
+            }
+            .map_err(|_| CodecError::Malformed)?;
+        }
+    }
+
+    for _ in 0..reader.read_u64()? {
+        let a = read_relation_id(&mut reader)?;
+        let b = read_relation_id(&mut reader)?;
+        graph.set_inverse(a, b).map_err(|_| CodecError::Malformed)?;
+    }
+
+    for _ in 0..reader.read_u64()? {
+        let owner = read_property_owner(&mut reader)?;
+        for _ in 0..reader.read_u64()? {
+            let key = reader.read_string()?;
+            graph
+                .set_property(owner, &key, read_value(&mut reader)?)
+                .map_err(|_| CodecError::Malformed)?;
+        }
+    }
+    reader.finish()?;
+
+    graph.reserve_ids(
+        next_node_id.saturating_sub(1),
+        next_relation_id.saturating_sub(1),
+    );
+    graph.reserve_edge_id(next_edge_id.saturating_sub(1));
+    Ok((graph, lsn))
+}
+
+/// The nodes and relations of a snapshot file, found without decoding
+/// their infos, as the source of a mapped file.
+pub(crate) struct SnapshotSource {
+    map: Option<Mmap>,
+    node_ids: Vec<NodeId>,
+    node_infos: Vec<Range<usize>>,
+    relation_ids: Vec<RelationId>,
+    relations: Vec<SnapshotRelation>,
+}
+
+/// Where a relation's info and edges are in a snapshot file.
+struct SnapshotRelation {
+    kind: RelationKind,
+    shape: u8,
+    info: Range<usize>,
+    edges_pos: usize,
+}
+
+impl SnapshotSource {
+    /// Map the snapshot of the store in `dir`, failing with
+    /// `StoreError::PendingLog` if the log holds records past it.
+    pub(crate) fn open(dir: &Path) -> Result<Self, StoreError> {
+        let snapshot_path = dir.join(SNAPSHOT_FILE);
+        let mut source = Self {
+            map: None,
+            node_ids: Vec::new(),
+            node_infos: Vec::new(),
+            relation_ids: Vec::new(),
+            relations: Vec::new(),
+        };
+        let mut snapshot_lsn = 0;
+        if snapshot_path.exists() {
+            let file = File::open(&snapshot_path)?;
+            // Safety: checkpoints replace the snapshot by renaming a new file
+            // over it, so the mapped file is never written to.
+            let map = unsafe { Mmap::map(&file)? };
+            snapshot_lsn = source.scan(&map).map_err(|_| StoreError::CorruptSnapshot)?;
+            source.map = Some(map);
+        }
+
+        let bytes = fs::read(dir.join(LOG_FILE))?;
+        let mut offset = 0;
+        while let Some((payload, len)) = next_record(&bytes[offset..])? {
+            if Reader::new(payload).read_u64()? > snapshot_lsn {
+                return Err(StoreError::PendingLog);
+            }
+            offset += len;
+        }
+        Ok(source)
+    }
+
+    /// Find the nodes and relations in `map` and return the sequence number
+    /// of the snapshot.
+    fn scan(&mut self, map: &[u8]) -> Result<u64, CodecError> {
+        let mut reader = Reader::new(map);
+        let magic = reader.read_slice(SNAPSHOT_MAGIC.len())?;
+        let crc = reader.read_u32()?;
+        if magic != SNAPSHOT_MAGIC || crc32(&map[map.len() - reader.len()..]) != crc {
+            return Err(CodecError::Malformed);
+        }
+        let lsn = reader.read_u64()?;
+        for _ in 0..3 {
+            reader.read_u64()?;
+        }
+
+        let mut nodes = Vec::new();
+        for _ in 0..reader.read_u64()? {
+            let node_id = NodeId::new(reader.read_u64()?);
+            let info = reader.read_bytes()?.len();
+            let end = map.len() - reader.len();
+            nodes.push((node_id, end - info..end));
+            for _ in 0..reader.read_u64()? {
+                reader.read_bytes()?;
+            }
+        }
+        nodes.sort_by_key(|(node_id, _)| **node_id);
+        let (node_ids, node_infos) = nodes.into_iter().unzip();
+        self.node_ids = node_ids;
+        self.node_infos = node_infos;
+
+        let mut relations = Vec::new();
+        for _ in 0..reader.read_u64()? {
+            let relation_id = read_relation_id(&mut reader)?;
+            let kind = RelationKind::from_code(reader.read_u8()?).ok_or(CodecError::Malformed)?;
+            let shape = reader.read_u8()?;
+            read_name(&mut reader)?;
+            let info = reader.read_bytes()?.len();
+            let edges_pos = map.len() - reader.len();
+            let info = edges_pos - info..edges_pos;
+            for _ in 0..reader.read_u64()? {
+                match shape {
+                    0 | 1 => {
+                        let nr_ids = if shape == 1 { 3 } else { 2 };
+                        for _ in 0..nr_ids {
+                            reader.read_u64()?;
+                        }
+                        skip_edge_info(&mut reader)?;
+                    }
+                    _ => {
+                        reader.read_u64()?;
+                        read_hyperedge(&mut reader)?;
+                    }
+                }
+            }
+            let relation = SnapshotRelation {
+                kind,
+                shape,
+                info,
+                edges_pos,
+            };
+            relations.push((relation_id, relation));
+        }
+        relations.sort_by_key(|(relation_id, _)| relation_id.get());
+        let (relation_ids, relations) = relations.into_iter().unzip();
+        self.relation_ids = relation_ids;
+        self.relations = relations;
+        Ok(lsn)
+    }
+
+    fn bytes(&self) -> &[u8] {
+        self.map.as_deref().unwrap_or(&[])
+    }
+}
+
+impl MappedSource for SnapshotSource {
+    fn node_ids(&self) -> &[NodeId] {
+        &self.node_ids
+    }
+
+    fn write_node_info(&self, i: usize, bytes: &mut Vec<u8>) -> Result<(), StoreError> {
+        bytes.extend_from_slice(&self.bytes()[self.node_infos[i].clone()]);
+        Ok(())
+    }
+
+    fn relation_ids(&self) -> &[RelationId] {
+        &self.relation_ids
+    }
+
+    fn relation_kind(&self, r: usize) -> RelationKind {
+        self.relations[r].kind
+    }
+
+    fn write_relation_info(&self, r: usize, bytes: &mut Vec<u8>) -> Result<(), StoreError> {
+        bytes.extend_from_slice(&self.bytes()[self.relations[r].info.clone()]);
+        Ok(())
+    }
+
+    fn for_each_edge(
+        &self,
+        r: usize,
+        f: &mut dyn FnMut(Edge) -> Result<(), StoreError>,
+    ) -> Result<(), StoreError> {
+        let relation = &self.relations[r];
+        if relation.shape > 1 {
+            return Ok(());
+        }
+        let mut reader = Reader::new(&self.bytes()[relation.edges_pos..]);
+        for _ in 0..reader.read_u64()? {
+            if relation.shape == 1 {
+                reader.read_u64()?;
+            }
+            let src = NodeId::new(reader.read_u64()?);
+            let dst = NodeId::new(reader.read_u64()?);
+            skip_edge_info(&mut reader)?;
+            f(Edge::new(src, dst))?;
+        }
+        Ok(())
+    }
+}
+
+/// Skip an edge info written with `write_edge_info`.
+fn skip_edge_info(reader: &mut Reader) -> Result<(), CodecError> {
+    match reader.read_u8()? {
+        0 => Ok(()),
+        1 => reader.read_bytes().map(|_| ()),
+        _ => Err(CodecError::Malformed),
+    }
+}
@@ -0,0 +1,327 @@
+use crate::edge::Edge;
+use crate::errors::UniqueError;
+use crate::graph::Graph;
+use crate::hyperedge::Hyperedge;
+use crate::id::IdMap;
+use crate::node::{AnyNodeInfo, NodeId};
+use crate::property::PropertyOwner;
+use crate::read::GraphRead;
+use crate::relation::{Relation, RelationId};
+use crate::schema::RelationSchema;
+use std::borrow::BorrowMut;
+use std::collections::{HashSet, VecDeque};
+
+/// Copies parts of a source graph, or of a view over one, into a target
+/// `Graph`, or a graph it borrows mutably, recording the new ids.
+///
+/// Node infos are cloned through `clone_info`, relation and edge infos are
+/// shared, so mutating them in either graph copies them first. Labels and
+/// properties are copied along. Copied relations get their schema back in
+/// `finish`, so their edges are copied as they are even if the cloned
+/// infos no longer satisfy it. Nodes and relations already in `ids` are
+/// not copied again, their edges are added to the nodes and relations they
+/// are mapped to.
+pub(crate) struct Extractor<'a, G, F, T = Graph> {
+    source: &'a G,
+    target: T,
+    ids: IdMap,
+    copied_edges: HashSet<(Edge, RelationId)>,
+    schemas: Vec<(RelationId, RelationSchema)>,
+    clone_info: F,
+}
+
+impl<'a, G, F> Extractor<'a, G, F>
+where
+    G: GraphRead,
+    F: FnMut(NodeId, &AnyNodeInfo) -> AnyNodeInfo,
+{
+    pub(crate) fn new(source: &'a G, clone_info: F) -> Self {
+        Self::with_target(source, Graph::new(), IdMap::default(), clone_info)
+    }
+}
+
+impl<'a, G, F, T> Extractor<'a, G, F, T>
+where
+    G: GraphRead,
+    F: FnMut(NodeId, &AnyNodeInfo) -> AnyNodeInfo,
+    T: BorrowMut<Graph>,
+{
+    pub(crate) fn with_target(source: &'a G, target: T, ids: IdMap, clone_info: F) -> Self {
+        Self {
+            source,
+            target,
+            ids,
+            copied_edges: HashSet::new(),
+            schemas: Vec::new(),
+            clone_info,
+        }
+    }
+
+    /// Copy a `Node` and return its new `NodeId`, or an error if its info
+    /// violates a unique index of the target.
+    pub(crate) fn add_node(&mut self, node_id: NodeId) -> Result<Option<NodeId>, UniqueError> {
+        if let Some(new_id) = self.ids.node_id(node_id) {
+            return Ok(Some(new_id));
+        }
+        let info = match self.source.cloned_node_info(node_id) {
+            Some(info) => (self.clone_info)(node_id, &info),
+            None => return Ok(None),
+        };
+        let new_id = self.target.borrow_mut().try_add_node(info)?;
+        self.copy_node_data(node_id, new_id);
+        self.ids.insert_node(node_id, new_id);
+        Ok(Some(new_id))
+    }
+
+    /// Map a `Node` to the existing node at `new_id` instead of copying it,
+    /// and copy its labels and properties there.
+    pub(crate) fn map_node(&mut self, node_id: NodeId, new_id: NodeId) {
+        self.copy_node_data(node_id, new_id);
+        self.ids.insert_node(node_id, new_id);
+    }
+
+    /// Get the target graph.
+    pub(crate) fn target_mut(&mut self) -> &mut Graph {
+        self.target.borrow_mut()
+    }
+
+    /// Copy the labels and properties of a `Node` to the node at `new_id`.
+    fn copy_node_data(&mut self, node_id: NodeId, new_id: NodeId) {
+        for label in self.source.node_labels(node_id) {
+            self.target.borrow_mut().add_label(new_id, &label);
+        }
+        let owner = PropertyOwner::Node(node_id);
+        for (key, value) in self
+            .source
+            .properties_of(owner)
+            .iter()
+            .flat_map(|p| p.iter())
+        {
+            let _ = self.target.borrow_mut().set_property(
+                PropertyOwner::Node(new_id),
+                key,
+                value.clone(),
+            );
+        }
+    }
+
+    /// Copy a `Relation` without its edges and return its new `RelationId`.
+    pub(crate) fn add_relation(&mut self, relation_id: RelationId) -> Option<RelationId> {
+        if let Some(new_id) = self.ids.relation_id(relation_id) {
+            return Some(new_id);
+        }
+        let mut relation = self.source.relation_without_edges(relation_id)?;
+        let schema = relation.schema().cloned();
+        relation.set_schema(None);
+        let new_id = self.target.borrow_mut().add_relation_like(&relation);
+        if let Some(schema) = schema {
+            self.schemas.push((new_id, schema));
+        }
+        self.ids.insert_relation(relation_id, new_id);
+        Some(new_id)
+    }
+
+    /// Copy an edge between two copied `Nodes`, with all its parallel edges,
+    /// its info and its properties.
+    pub(crate) fn add_edge(&mut self, edge: Edge, relation_id: RelationId) {
+        let edge = match self.source.relation_kind(relation_id) {
+            Some(kind) => kind.normalize(edge),
+            None => return,
+        };
+        if self.copied_edges.contains(&(edge, relation_id)) {
+            return;
+        }
+        let infos = self.source.edge_infos(edge, relation_id);
+        if infos.is_empty() {
+            return;
+        }
+        let (src, dst) = match (self.ids.node_id(edge.src()), self.ids.node_id(edge.dst())) {
+            (Some(src), Some(dst)) => (src, dst),
+            _ => return,
+        };
+        let new_relation_id = match self.add_relation(relation_id) {
+            Some(new_relation_id) => new_relation_id,
+            None => return,
+        };
+        self.copied_edges.insert((edge, relation_id));
+        let target = self.target.borrow_mut();
+        if target
+            .relation(new_relation_id)
+            .is_some_and(Relation::is_multi)
+        {
+            for info in infos {
+                let _ = match info {
+                    Some(info) => {
+                        target.connect_multi_with(src, dst, new_relation_id, info.clone())
+                    }
+                    None => target.connect_multi(src, dst, new_relation_id),
+                };
+            }
+        } else {
+            let connected = match &infos[0] {
+                Some(info) => target.connect_with(src, dst, new_relation_id, info.clone()),
+                None => target.connect(src, dst, new_relation_id),
+            };
+            if connected.is_err() {
+                return;
+            }
+        }
+        let owner = PropertyOwner::Edge(Edge::new(src, dst), new_relation_id);
+        for (key, value) in self
+            .source
+            .properties_of(PropertyOwner::Edge(edge, relation_id))
+            .iter()
+            .flat_map(|p| p.iter())
+        {
+            let _ = target.set_property(owner, key, value.clone());
+        }
+    }
+
+    /// Copy the hyperedges of a `Relation` whose nodes have all been copied.
+    pub(crate) fn add_hyperedges(&mut self, relation_id: RelationId) {
+        for hyperedge in self.source.hyperedges(relation_id) {
+            let map = |nodes: &[NodeId]| -> Option<Vec<NodeId>> {
+                nodes
+                    .iter()
+                    .map(|node_id| self.ids.node_id(*node_id))
+                    .collect()
+            };
+            let (sources, targets) = match (map(hyperedge.sources()), map(hyperedge.targets())) {
+                (Some(sources), Some(targets)) => (sources, targets),
+                _ => continue,
+            };
+            let new_relation_id = match self.add_relation(relation_id) {
+                Some(new_relation_id) => new_relation_id,
+                None => return,
+            };
+            let _ = self
+                .target
+                .borrow_mut()
+                .connect_hyperedge(new_relation_id, Hyperedge::new(sources, targets));
+        }
+    }
+
+    /// Copy every edge and hyperedge of the relations in `relation_ids`
+    /// whose nodes have all been copied.
+    pub(crate) fn add_induced_edges<I>(&mut self, relation_ids: I)
+    where
+        I: IntoIterator<Item = RelationId>,
+    {
+        for relation_id in relation_ids {
+            if self.add_relation(relation_id).is_none() {
+                continue;
+            }
+            let edges: Vec<_> = self
+                .source
+                .iter_relation_edges(relation_id)
+                .into_iter()
+                .flatten()
+                .collect();
+            for edge in edges {
+                self.add_edge(edge, relation_id);
+            }
+            self.add_hyperedges(relation_id);
+        }
+    }
+
+    /// Set the schemas of the copied relations, without checking the
+    /// copied edges against them.
+    pub(crate) fn finish(mut self) -> (T, IdMap) {
+        for (relation_id, schema) in self.schemas.drain(..) {
+            self.target
+                .borrow_mut()
+                .set_relation_schema(relation_id, schema);
+        }
+        (self.target, self.ids)
+    }
+}
+
+/// Copy the `Nodes` in `node_ids`, all relations and the edges between
+/// the copied nodes.
+pub(crate) fn induced<G, N, F>(graph: &G, node_ids: N, clone_info: F) -> (Graph, IdMap)
+where
+    G: GraphRead,
+    N: IntoIterator<Item = NodeId>,
+    F: FnMut(NodeId, &AnyNodeInfo) -> AnyNodeInfo,
+{
+    let mut extractor = Extractor::new(graph, clone_info);
+    // A new graph has no unique indexes, so copying a node can't fail.
+    for node_id in node_ids {
+        let _ = extractor.add_node(node_id);
+    }
+    extractor.add_induced_edges(sorted_relation_ids(graph));
+    extractor.finish()
+}
+
+/// Copy the edges in `edges`, their endpoints and their relations.
+pub(crate) fn by_edges<G, E, F>(graph: &G, edges: E, clone_info: F) -> (Graph, IdMap)
+where
+    G: GraphRead,
+    E: IntoIterator<Item = (Edge, RelationId)>,
+    F: FnMut(NodeId, &AnyNodeInfo) -> AnyNodeInfo,
+{
+    let mut extractor = Extractor::new(graph, clone_info);
+    for (edge, relation_id) in edges {
+        if graph.contains_edge(edge, relation_id) {
+            let _ = extractor.add_node(edge.src());
+            let _ = extractor.add_node(edge.dst());
+            extractor.add_edge(edge, relation_id);
+        }
+    }
+    extractor.finish()
+}
+
+/// Copy the `Nodes` within `radius` edges of `center` in either direction,
+/// following only `relation_ids`, and the edges between them in those
+/// relations.
+pub(crate) fn ego<G, F>(
+    graph: &G,
+    center: NodeId,
+    radius: usize,
+    relation_ids: Option<&[RelationId]>,
+    clone_info: F,
+) -> Option<(Graph, IdMap)>
+where
+    G: GraphRead,
+    F: FnMut(NodeId, &AnyNodeInfo) -> AnyNodeInfo,
+{
+    if !graph.contains_node(center) {
+        return None;
+    }
+    let relation_ids: Vec<_> = match relation_ids {
+        Some(relation_ids) => relation_ids.to_vec(),
+        None => sorted_relation_ids(graph),
+    };
+    let view = graph.filter_relations(relation_ids.iter().copied());
+    let mut extractor = Extractor::new(graph, clone_info);
+    let mut seen = HashSet::new();
+    let mut queue = VecDeque::new();
+    seen.insert(center);
+    queue.push_back((center, 0));
+    while let Some((node_id, distance)) = queue.pop_front() {
+        let _ = extractor.add_node(node_id);
+        if distance == radius {
+            continue;
+        }
+        let next_nodes = view.iter_next_nodes(node_id).into_iter().flatten();
+        let prev_nodes = view.iter_prev_nodes(node_id).into_iter().flatten();
+        for (neighbor, _) in next_nodes.chain(prev_nodes) {
+            if seen.insert(neighbor) {
+                queue.push_back((neighbor, distance + 1));
+            }
+        }
+    }
+    extractor.add_induced_edges(relation_ids);
+    Some(extractor.finish())
+}
+
+/// Get the `RelationIds` of a graph in ascending order, so relations are
+/// copied in the order they were created.
+pub(crate) fn sorted_relation_ids<G>(graph: &G) -> Vec<RelationId>
+where
+    G: GraphRead,
+{
+    let mut relation_ids: Vec<_> = graph.relation_ids().collect();
+    relation_ids.sort_by_key(|relation_id| relation_id.get());
+    relation_ids
+}
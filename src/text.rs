@@ -0,0 +1,157 @@
+use crate::collections::{Map, Share, SortedMap};
+use crate::graph::Graph;
+use crate::index::IndexId;
+use crate::node::{NodeId, NodeInfo};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Term frequency saturation of the BM25 ranking.
+const K1: f64 = 1.2;
+/// Document length normalization of the BM25 ranking.
+const B: f64 = 0.75;
+
+/// Extracts the text a `Node` is indexed by from its info, or `None` to
+/// leave the node out of the index.
+type TextFn = Arc<dyn Fn(&dyn NodeInfo) -> Option<String> + Send + Sync>;
+
+/// Split `text` into lowercase terms at every character that is not
+/// alphanumeric.
+pub fn tokenize(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|term| !term.is_empty())
+        .map(str::to_lowercase)
+}
+
+/// A handle to a full-text index, made with `Graph::add_text_index` and
+/// queried with `Graph::search` and `Graph::search_prefix`.
+#[derive(Copy, Clone, Debug)]
+pub struct TextIndex {
+    id: IndexId,
+}
+
+impl TextIndex {
+    pub(crate) fn new(id: IndexId) -> Self {
+        Self { id }
+    }
+
+    /// Get the id of the index.
+    pub fn id(&self) -> IndexId {
+        self.id
+    }
+
+    pub(crate) fn entries<'a>(&self, graph: &'a Graph) -> Option<&'a TextEntries> {
+        graph.text_index(self.id)
+    }
+}
+
+/// The postings of a full-text index.
+#[derive(Clone)]
+pub(crate) struct TextEntries {
+    text_fn: TextFn,
+    /// The number of times each term occurs in the text of each node.
+    postings: SortedMap<String, Map<NodeId, u32>>,
+    /// The number of terms in the text of each node.
+    lengths: Map<NodeId, u32>,
+    total_length: u64,
+}
+
+impl TextEntries {
+    pub(crate) fn new(text_fn: TextFn) -> Self {
+        Self {
+            text_fn,
+            postings: SortedMap::default(),
+            lengths: Map::default(),
+            total_length: 0,
+        }
+    }
+
+    fn term_counts(&self, info: &dyn NodeInfo) -> Option<HashMap<String, u32>> {
+        let mut counts = HashMap::new();
+        for term in tokenize(&(self.text_fn)(info)?) {
+            *counts.entry(term).or_insert(0) += 1;
+        }
+        Some(counts)
+    }
+
+    /// Rank the nodes that contain any of `terms` with BM25, best first.
+    fn rank<'a, T>(&self, terms: T) -> Vec<(NodeId, f64)>
+    where
+        T: IntoIterator<Item = &'a Map<NodeId, u32>>,
+    {
+        let nr_nodes = self.lengths.len() as f64;
+        let avg_length = self.total_length as f64 / nr_nodes.max(1.0);
+        let mut scores: HashMap<NodeId, f64> = HashMap::new();
+        for nodes in terms {
+            let df = nodes.len() as f64;
+            let idf = (1.0 + (nr_nodes - df + 0.5) / (df + 0.5)).ln();
+            for (node_id, tf) in nodes.iter() {
+                let tf = f64::from(*tf);
+                let length = f64::from(self.lengths[node_id]);
+                let norm = K1 * (1.0 - B + B * length / avg_length.max(1.0));
+                *scores.entry(*node_id).or_insert(0.0) += idf * tf * (K1 + 1.0) / (tf + norm);
+            }
+        }
+        let mut hits: Vec<_> = scores.into_iter().collect();
+        hits.sort_by(|(a, a_score), (b, b_score)| {
+            b_score.total_cmp(a_score).then_with(|| (**a).cmp(&**b))
+        });
+        hits
+    }
+
+    /// Index the text of a `Node`.
+    pub(crate) fn insert(&mut self, node_id: NodeId, info: &dyn NodeInfo) {
+        let counts = match self.term_counts(info) {
+            Some(counts) => counts,
+            None => return,
+        };
+        let length = counts.values().sum::<u32>();
+        for (term, count) in counts {
+            self.postings.get_or_default(term).insert(node_id, count);
+        }
+        self.lengths.insert(node_id, length);
+        self.total_length += u64::from(length);
+    }
+
+    /// Remove a `Node` indexed with `info` from the index.
+    pub(crate) fn remove(&mut self, node_id: NodeId, info: &dyn NodeInfo) {
+        let length = match self.lengths.remove(&node_id) {
+            Some(length) => length,
+            None => return,
+        };
+        self.total_length -= u64::from(length);
+        for term in self.term_counts(info).unwrap_or_default().keys() {
+            if let Some(nodes) = self.postings.get_mut(term) {
+                nodes.remove(&node_id);
+                if nodes.is_empty() {
+                    self.postings.remove(term);
+                }
+            }
+        }
+    }
+
+    /// Rank the nodes that contain any term of `query`.
+    pub(crate) fn search(&self, query: &str) -> Vec<(NodeId, f64)> {
+        let mut terms: Vec<_> = tokenize(query).collect();
+        terms.sort();
+        terms.dedup();
+        self.rank(terms.iter().filter_map(|term| self.postings.get(term)))
+    }
+
+    /// Rank the nodes that contain a term starting with `prefix`.
+    pub(crate) fn search_prefix(&self, prefix: &str) -> Vec<(NodeId, f64)> {
+        let prefix = prefix.to_lowercase();
+        let terms = self
+            .postings
+            .range(prefix.clone()..)
+            .take_while(|(term, _)| term.starts_with(&prefix))
+            .map(|(_, nodes)| nodes);
+        self.rank(terms)
+    }
+}
+
+impl Share for TextEntries {
+    fn share(&mut self) {
+        self.postings.share_values();
+        self.lengths.share();
+    }
+}
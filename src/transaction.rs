@@ -0,0 +1,57 @@
+use crate::collections::Share;
+use crate::graph::Graph;
+use std::ops::{Deref, DerefMut};
+
+/// Guard that groups mutations of a `Graph` so they take effect all at once.
+///
+/// The transaction derefs to the graph, so the whole `Graph` API can be used
+/// through it. Dropping it without calling `commit` restores the graph to
+/// the state it was in when the transaction started.
+///
+/// Subscribers of the graph receive the events of the transaction when it
+/// commits, and never receive those of a transaction that is rolled back.
+pub struct Transaction<'a> {
+    graph: &'a mut Graph,
+    backup: Option<Graph>,
+}
+
+impl<'a> Transaction<'a> {
+    pub(crate) fn new(graph: &'a mut Graph) -> Self {
+        graph.share();
+        let backup = Some(graph.clone());
+        graph.observers_mut().begin_transaction();
+        Self { graph, backup }
+    }
+
+    /// Keep all mutations made through the transaction.
+    pub fn commit(mut self) {
+        self.backup = None;
+        self.graph.observers_mut().commit_transaction();
+    }
+
+    /// Undo all mutations made through the transaction.
+    pub fn rollback(self) {}
+}
+
+impl Deref for Transaction<'_> {
+    type Target = Graph;
+
+    fn deref(&self) -> &Self::Target {
+        self.graph
+    }
+}
+
+impl DerefMut for Transaction<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.graph
+    }
+}
+
+impl Drop for Transaction<'_> {
+    fn drop(&mut self) {
+        if let Some(backup) = self.backup.take() {
+            self.graph.restore(backup);
+            self.graph.observers_mut().rollback_transaction();
+        }
+    }
+}
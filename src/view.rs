@@ -0,0 +1,437 @@
+use crate::edge::{AnyEdgeInfo, Edge};
+use crate::hyperedge::Hyperedge;
+use crate::label::Label;
+use crate::node::{AnyNodeInfo, NodeId, NodeInfo};
+use crate::property::{Properties, PropertyOwner};
+use crate::read::GraphRead;
+use crate::relation::{Relation, RelationId, RelationInfo, RelationKind};
+use im::HashSet;
+
+/// View of a graph with the direction of every edge reversed, made with
+/// `GraphRead::reversed`. Edges of undirected relations are unchanged.
+#[derive(Copy, Clone)]
+pub struct Reversed<'a, G> {
+    graph: &'a G,
+}
+
+impl<'a, G> Reversed<'a, G>
+where
+    G: GraphRead,
+{
+    pub(crate) fn new(graph: &'a G) -> Self {
+        Self { graph }
+    }
+}
+
+impl<G> GraphRead for Reversed<'_, G>
+where
+    G: GraphRead,
+{
+    fn nr_nodes(&self) -> usize {
+        self.graph.nr_nodes()
+    }
+
+    fn contains_node(&self, node_id: NodeId) -> bool {
+        self.graph.contains_node(node_id)
+    }
+
+    fn contains_relation(&self, relation_id: RelationId) -> bool {
+        self.graph.contains_relation(relation_id)
+    }
+
+    fn with_node_info<F, T>(&self, node_id: NodeId, f: F) -> Option<T>
+    where
+        F: FnOnce(&dyn NodeInfo) -> T,
+    {
+        self.graph.with_node_info(node_id, f)
+    }
+
+    fn with_relation_info<F, T>(&self, relation_id: RelationId, f: F) -> Option<T>
+    where
+        F: FnOnce(&dyn RelationInfo) -> T,
+    {
+        self.graph.with_relation_info(relation_id, f)
+    }
+
+    fn relation_kind(&self, relation_id: RelationId) -> Option<RelationKind> {
+        self.graph.relation_kind(relation_id)
+    }
+
+    fn cloned_node_info(&self, node_id: NodeId) -> Option<AnyNodeInfo> {
+        self.graph.cloned_node_info(node_id)
+    }
+
+    fn node_labels(&self, node_id: NodeId) -> Vec<Label> {
+        self.graph.node_labels(node_id)
+    }
+
+    fn properties_of(&self, owner: PropertyOwner) -> Option<Properties> {
+        match owner {
+            PropertyOwner::Edge(edge, r) => self
+                .graph
+                .properties_of(PropertyOwner::Edge(edge.reversed(), r)),
+            owner => self.graph.properties_of(owner),
+        }
+    }
+
+    fn relation_without_edges(&self, relation_id: RelationId) -> Option<Relation> {
+        self.graph.relation_without_edges(relation_id)
+    }
+
+    fn edge_infos(&self, edge: Edge, relation_id: RelationId) -> Vec<Option<AnyEdgeInfo>> {
+        self.graph.edge_infos(edge.reversed(), relation_id)
+    }
+
+    fn hyperedges(&self, relation_id: RelationId) -> Vec<Hyperedge> {
+        let hyperedges = self.graph.hyperedges(relation_id).into_iter();
+        hyperedges
+            .map(|h| Hyperedge::new(h.targets().to_vec(), h.sources().to_vec()))
+            .collect()
+    }
+
+    fn node_ids(&self) -> impl Iterator<Item = NodeId> + '_ {
+        self.graph.node_ids()
+    }
+
+    fn relation_ids(&self) -> impl Iterator<Item = RelationId> + '_ {
+        self.graph.relation_ids()
+    }
+
+    fn iter_next_nodes(
+        &self,
+        node_id: NodeId,
+    ) -> Option<impl Iterator<Item = (NodeId, RelationId)> + '_> {
+        self.graph.iter_prev_nodes(node_id)
+    }
+
+    fn iter_prev_nodes(
+        &self,
+        node_id: NodeId,
+    ) -> Option<impl Iterator<Item = (NodeId, RelationId)> + '_> {
+        self.graph.iter_next_nodes(node_id)
+    }
+
+    fn iter_relation_edges(
+        &self,
+        relation_id: RelationId,
+    ) -> Option<impl Iterator<Item = Edge> + '_> {
+        let undirected = self.graph.relation_kind(relation_id)? == RelationKind::Undirected;
+        let edges = self.graph.iter_relation_edges(relation_id)?;
+        Some(edges.map(move |edge| match undirected {
+            true => edge,
+            false => edge.reversed(),
+        }))
+    }
+
+    fn in_degree_of(&self, node_id: NodeId) -> Option<usize> {
+        self.graph.out_degree_of(node_id)
+    }
+
+    fn out_degree_of(&self, node_id: NodeId) -> Option<usize> {
+        self.graph.in_degree_of(node_id)
+    }
+
+    fn multiplicity(&self, edge: Edge, relation_id: RelationId) -> usize {
+        self.graph.multiplicity(edge.reversed(), relation_id)
+    }
+}
+
+/// View of a graph with only the `Nodes` that match a predicate and the
+/// edges between them, made with `GraphRead::filter_nodes`.
+#[derive(Copy, Clone)]
+pub struct NodeFilter<'a, G, P> {
+    graph: &'a G,
+    pred: P,
+}
+
+impl<'a, G, P> NodeFilter<'a, G, P>
+where
+    G: GraphRead,
+    P: Fn(NodeId, &dyn NodeInfo) -> bool,
+{
+    pub(crate) fn new(graph: &'a G, pred: P) -> Self {
+        Self { graph, pred }
+    }
+
+    fn is_visible(&self, node_id: NodeId) -> bool {
+        self.graph
+            .with_node_info(node_id, |info| (self.pred)(node_id, info))
+            .unwrap_or(false)
+    }
+}
+
+impl<G, P> GraphRead for NodeFilter<'_, G, P>
+where
+    G: GraphRead,
+    P: Fn(NodeId, &dyn NodeInfo) -> bool,
+{
+    fn nr_nodes(&self) -> usize {
+        self.node_ids().count()
+    }
+
+    fn contains_node(&self, node_id: NodeId) -> bool {
+        self.is_visible(node_id)
+    }
+
+    fn contains_relation(&self, relation_id: RelationId) -> bool {
+        self.graph.contains_relation(relation_id)
+    }
+
+    fn with_node_info<F, T>(&self, node_id: NodeId, f: F) -> Option<T>
+    where
+        F: FnOnce(&dyn NodeInfo) -> T,
+    {
+        self.graph
+            .with_node_info(node_id, |info| match (self.pred)(node_id, info) {
+                true => Some(f(info)),
+                false => None,
+            })
+            .flatten()
+    }
+
+    fn with_relation_info<F, T>(&self, relation_id: RelationId, f: F) -> Option<T>
+    where
+        F: FnOnce(&dyn RelationInfo) -> T,
+    {
+        self.graph.with_relation_info(relation_id, f)
+    }
+
+    fn relation_kind(&self, relation_id: RelationId) -> Option<RelationKind> {
+        self.graph.relation_kind(relation_id)
+    }
+
+    fn cloned_node_info(&self, node_id: NodeId) -> Option<AnyNodeInfo> {
+        self.graph
+            .cloned_node_info(node_id)
+            .filter(|info| (self.pred)(node_id, &**info))
+    }
+
+    fn node_labels(&self, node_id: NodeId) -> Vec<Label> {
+        match self.is_visible(node_id) {
+            true => self.graph.node_labels(node_id),
+            false => Vec::new(),
+        }
+    }
+
+    fn properties_of(&self, owner: PropertyOwner) -> Option<Properties> {
+        let visible = match owner {
+            PropertyOwner::Node(node_id) => self.is_visible(node_id),
+            PropertyOwner::Relation(_) => true,
+            PropertyOwner::Edge(edge, _) => {
+                self.is_visible(edge.src()) && self.is_visible(edge.dst())
+            }
+        };
+        match visible {
+            true => self.graph.properties_of(owner),
+            false => None,
+        }
+    }
+
+    fn relation_without_edges(&self, relation_id: RelationId) -> Option<Relation> {
+        self.graph.relation_without_edges(relation_id)
+    }
+
+    fn edge_infos(&self, edge: Edge, relation_id: RelationId) -> Vec<Option<AnyEdgeInfo>> {
+        match self.is_visible(edge.src()) && self.is_visible(edge.dst()) {
+            true => self.graph.edge_infos(edge, relation_id),
+            false => Vec::new(),
+        }
+    }
+
+    fn hyperedges(&self, relation_id: RelationId) -> Vec<Hyperedge> {
+        let mut hyperedges = self.graph.hyperedges(relation_id);
+        hyperedges.retain(|hyperedge| hyperedge.iter_nodes().all(|id| self.is_visible(id)));
+        hyperedges
+    }
+
+    fn node_ids(&self) -> impl Iterator<Item = NodeId> + '_ {
+        self.graph
+            .node_ids()
+            .filter(move |node_id| self.is_visible(*node_id))
+    }
+
+    fn relation_ids(&self) -> impl Iterator<Item = RelationId> + '_ {
+        self.graph.relation_ids()
+    }
+
+    fn iter_next_nodes(
+        &self,
+        node_id: NodeId,
+    ) -> Option<impl Iterator<Item = (NodeId, RelationId)> + '_> {
+        if !self.is_visible(node_id) {
+            return None;
+        }
+        let next_nodes = self.graph.iter_next_nodes(node_id)?;
+        Some(next_nodes.filter(move |(dst, _)| self.is_visible(*dst)))
+    }
+
+    fn iter_prev_nodes(
+        &self,
+        node_id: NodeId,
+    ) -> Option<impl Iterator<Item = (NodeId, RelationId)> + '_> {
+        if !self.is_visible(node_id) {
+            return None;
+        }
+        let prev_nodes = self.graph.iter_prev_nodes(node_id)?;
+        Some(prev_nodes.filter(move |(src, _)| self.is_visible(*src)))
+    }
+
+    fn iter_relation_edges(
+        &self,
+        relation_id: RelationId,
+    ) -> Option<impl Iterator<Item = Edge> + '_> {
+        let edges = self.graph.iter_relation_edges(relation_id)?;
+        Some(edges.filter(move |edge| self.is_visible(edge.src()) && self.is_visible(edge.dst())))
+    }
+
+    fn multiplicity(&self, edge: Edge, relation_id: RelationId) -> usize {
+        match self.is_visible(edge.src()) && self.is_visible(edge.dst()) {
+            true => self.graph.multiplicity(edge, relation_id),
+            false => 0,
+        }
+    }
+}
+
+/// View of a graph with only some of its `Relations`, made with
+/// `GraphRead::filter_relations`. All nodes stay visible.
+#[derive(Clone)]
+pub struct RelationFilter<'a, G> {
+    graph: &'a G,
+    relation_ids: HashSet<RelationId>,
+}
+
+impl<'a, G> RelationFilter<'a, G>
+where
+    G: GraphRead,
+{
+    pub(crate) fn new(graph: &'a G, relation_ids: HashSet<RelationId>) -> Self {
+        Self {
+            graph,
+            relation_ids,
+        }
+    }
+}
+
+impl<G> GraphRead for RelationFilter<'_, G>
+where
+    G: GraphRead,
+{
+    fn nr_nodes(&self) -> usize {
+        self.graph.nr_nodes()
+    }
+
+    fn contains_node(&self, node_id: NodeId) -> bool {
+        self.graph.contains_node(node_id)
+    }
+
+    fn contains_relation(&self, relation_id: RelationId) -> bool {
+        self.relation_ids.contains(&relation_id) && self.graph.contains_relation(relation_id)
+    }
+
+    fn with_node_info<F, T>(&self, node_id: NodeId, f: F) -> Option<T>
+    where
+        F: FnOnce(&dyn NodeInfo) -> T,
+    {
+        self.graph.with_node_info(node_id, f)
+    }
+
+    fn with_relation_info<F, T>(&self, relation_id: RelationId, f: F) -> Option<T>
+    where
+        F: FnOnce(&dyn RelationInfo) -> T,
+    {
+        match self.relation_ids.contains(&relation_id) {
+            true => self.graph.with_relation_info(relation_id, f),
+            false => None,
+        }
+    }
+
+    fn relation_kind(&self, relation_id: RelationId) -> Option<RelationKind> {
+        match self.relation_ids.contains(&relation_id) {
+            true => self.graph.relation_kind(relation_id),
+            false => None,
+        }
+    }
+
+    fn cloned_node_info(&self, node_id: NodeId) -> Option<AnyNodeInfo> {
+        self.graph.cloned_node_info(node_id)
+    }
+
+    fn node_labels(&self, node_id: NodeId) -> Vec<Label> {
+        self.graph.node_labels(node_id)
+    }
+
+    fn properties_of(&self, owner: PropertyOwner) -> Option<Properties> {
+        match owner {
+            PropertyOwner::Relation(r) | PropertyOwner::Edge(_, r)
+                if !self.relation_ids.contains(&r) =>
+            {
+                None
+            }
+            owner => self.graph.properties_of(owner),
+        }
+    }
+
+    fn relation_without_edges(&self, relation_id: RelationId) -> Option<Relation> {
+        match self.relation_ids.contains(&relation_id) {
+            true => self.graph.relation_without_edges(relation_id),
+            false => None,
+        }
+    }
+
+    fn edge_infos(&self, edge: Edge, relation_id: RelationId) -> Vec<Option<AnyEdgeInfo>> {
+        match self.relation_ids.contains(&relation_id) {
+            true => self.graph.edge_infos(edge, relation_id),
+            false => Vec::new(),
+        }
+    }
+
+    fn hyperedges(&self, relation_id: RelationId) -> Vec<Hyperedge> {
+        match self.relation_ids.contains(&relation_id) {
+            true => self.graph.hyperedges(relation_id),
+            false => Vec::new(),
+        }
+    }
+
+    fn node_ids(&self) -> impl Iterator<Item = NodeId> + '_ {
+        self.graph.node_ids()
+    }
+
+    fn relation_ids(&self) -> impl Iterator<Item = RelationId> + '_ {
+        self.graph
+            .relation_ids()
+            .filter(move |relation_id| self.relation_ids.contains(relation_id))
+    }
+
+    fn iter_next_nodes(
+        &self,
+        node_id: NodeId,
+    ) -> Option<impl Iterator<Item = (NodeId, RelationId)> + '_> {
+        let next_nodes = self.graph.iter_next_nodes(node_id)?;
+        Some(next_nodes.filter(move |(_, r)| self.relation_ids.contains(r)))
+    }
+
+    fn iter_prev_nodes(
+        &self,
+        node_id: NodeId,
+    ) -> Option<impl Iterator<Item = (NodeId, RelationId)> + '_> {
+        let prev_nodes = self.graph.iter_prev_nodes(node_id)?;
+        Some(prev_nodes.filter(move |(_, r)| self.relation_ids.contains(r)))
+    }
+
+    fn iter_relation_edges(
+        &self,
+        relation_id: RelationId,
+    ) -> Option<impl Iterator<Item = Edge> + '_> {
+        if !self.relation_ids.contains(&relation_id) {
+            return None;
+        }
+        self.graph.iter_relation_edges(relation_id)
+    }
+
+    fn multiplicity(&self, edge: Edge, relation_id: RelationId) -> usize {
+        match self.relation_ids.contains(&relation_id) {
+            true => self.graph.multiplicity(edge, relation_id),
+            false => 0,
+        }
+    }
+}
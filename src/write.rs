@@ -0,0 +1,252 @@
+use crate::codec::InfoCodec;
+use crate::concurrent::ConcurrentGraph;
+use crate::errors::{ConnectError, StoreError, WriteError};
+use crate::graph::Graph;
+use crate::history::History;
+use crate::node::{AnyNodeInfo, NodeId};
+use crate::relation::{AnyRelationInfo, RelationId};
+use crate::store::PersistentGraph;
+
+/// The basic mutations shared by `Graph`, `History`, `PersistentGraph`
+/// and `&ConcurrentGraph`, so code that builds or edits a graph can be
+/// written once for all of them. A `Transaction` can be passed as the
+/// `Graph` it derefs to.
+///
+/// Every method returns a `Result`, since a `PersistentGraph` can fail to
+/// log any mutation. Backends that can't fail that way only return errors
+/// where their own methods do. `add_node` of a `Graph` or `History` checks
+/// unique indexes like their `try_add_node`.
+pub trait GraphWrite {
+    /// The error returned when a mutation fails.
+    type Error;
+
+    /// Create a `Node` with `info` and return its `NodeId`.
+    fn add_node<I>(&mut self, info: I) -> Result<NodeId, Self::Error>
+    where
+        I: Into<AnyNodeInfo>;
+
+    /// Remove the `Node` at `node_id` and return its info if it was removed.
+    fn remove_node(&mut self, node_id: NodeId) -> Result<Option<AnyNodeInfo>, Self::Error>;
+
+    /// Create a directed `Relation` with `info` and return its `RelationId`.
+    fn add_relation<I>(&mut self, info: I) -> Result<RelationId, Self::Error>
+    where
+        I: Into<AnyRelationInfo>;
+
+    /// Remove the `Relation` at `relation_id` and return its info if it was
+    /// removed.
+    fn remove_relation(
+        &mut self,
+        relation_id: RelationId,
+    ) -> Result<Option<AnyRelationInfo>, Self::Error>;
+
+    /// Connect two `Nodes` with a `Relation` and return whether the edge is new.
+    fn connect(
+        &mut self,
+        src: NodeId,
+        dst: NodeId,
+        relation_id: RelationId,
+    ) -> Result<bool, Self::Error>;
+
+    /// Disconnect two `Nodes` in a `Relation` and return whether the edge
+    /// was there.
+    fn disconnect(
+        &mut self,
+        src: NodeId,
+        dst: NodeId,
+        relation_id: RelationId,
+    ) -> Result<bool, Self::Error>;
+}
+
+impl GraphWrite for Graph {
+    type Error = WriteError;
+
+    fn add_node<I>(&mut self, info: I) -> Result<NodeId, WriteError>
+    where
+        I: Into<AnyNodeInfo>,
+    {
+        Ok(Graph::try_add_node(self, info)?)
+    }
+
+    fn remove_node(&mut self, node_id: NodeId) -> Result<Option<AnyNodeInfo>, WriteError> {
+        Ok(Graph::remove_node(self, node_id))
+    }
+
+    fn add_relation<I>(&mut self, info: I) -> Result<RelationId, WriteError>
+    where
+        I: Into<AnyRelationInfo>,
+    {
+        Ok(Graph::add_relation(self, info))
+    }
+
+    fn remove_relation(
+        &mut self,
+        relation_id: RelationId,
+    ) -> Result<Option<AnyRelationInfo>, WriteError> {
+        Ok(Graph::remove_relation(self, relation_id))
+    }
+
+    fn connect(
+        &mut self,
+        src: NodeId,
+        dst: NodeId,
+        relation_id: RelationId,
+    ) -> Result<bool, WriteError> {
+        Ok(Graph::connect(self, src, dst, relation_id)?)
+    }
+
+    fn disconnect(
+        &mut self,
+        src: NodeId,
+        dst: NodeId,
+        relation_id: RelationId,
+    ) -> Result<bool, WriteError> {
+        Ok(Graph::disconnect(self, src, dst, relation_id)?)
+    }
+}
+
+impl GraphWrite for History {
+    type Error = WriteError;
+
+    fn add_node<I>(&mut self, info: I) -> Result<NodeId, WriteError>
+    where
+        I: Into<AnyNodeInfo>,
+    {
+        Ok(History::try_add_node(self, info)?)
+    }
+
+    fn remove_node(&mut self, node_id: NodeId) -> Result<Option<AnyNodeInfo>, WriteError> {
+        Ok(History::remove_node(self, node_id))
+    }
+
+    fn add_relation<I>(&mut self, info: I) -> Result<RelationId, WriteError>
+    where
+        I: Into<AnyRelationInfo>,
+    {
+        Ok(History::add_relation(self, info))
+    }
+
+    fn remove_relation(
+        &mut self,
+        relation_id: RelationId,
+    ) -> Result<Option<AnyRelationInfo>, WriteError> {
+        Ok(History::remove_relation(self, relation_id))
+    }
+
+    fn connect(
+        &mut self,
+        src: NodeId,
+        dst: NodeId,
+        relation_id: RelationId,
+    ) -> Result<bool, WriteError> {
+        Ok(History::connect(self, src, dst, relation_id)?)
+    }
+
+    fn disconnect(
+        &mut self,
+        src: NodeId,
+        dst: NodeId,
+        relation_id: RelationId,
+    ) -> Result<bool, WriteError> {
+        Ok(History::disconnect(self, src, dst, relation_id)?)
+    }
+}
+
+impl<C> GraphWrite for PersistentGraph<C>
+where
+    C: InfoCodec,
+{
+    type Error = StoreError;
+
+    fn add_node<I>(&mut self, info: I) -> Result<NodeId, StoreError>
+    where
+        I: Into<AnyNodeInfo>,
+    {
+        PersistentGraph::add_node(self, info)
+    }
+
+    fn remove_node(&mut self, node_id: NodeId) -> Result<Option<AnyNodeInfo>, StoreError> {
+        PersistentGraph::remove_node(self, node_id)
+    }
+
+    fn add_relation<I>(&mut self, info: I) -> Result<RelationId, StoreError>
+    where
+        I: Into<AnyRelationInfo>,
+    {
+        PersistentGraph::add_relation(self, info)
+    }
+
+    fn remove_relation(
+        &mut self,
+        relation_id: RelationId,
+    ) -> Result<Option<AnyRelationInfo>, StoreError> {
+        PersistentGraph::remove_relation(self, relation_id)
+    }
+
+    fn connect(
+        &mut self,
+        src: NodeId,
+        dst: NodeId,
+        relation_id: RelationId,
+    ) -> Result<bool, StoreError> {
+        PersistentGraph::connect(self, src, dst, relation_id)
+    }
+
+    fn disconnect(
+        &mut self,
+        src: NodeId,
+        dst: NodeId,
+        relation_id: RelationId,
+    ) -> Result<bool, StoreError> {
+        PersistentGraph::disconnect(self, src, dst, relation_id)
+    }
+}
+
+/// Implemented for shared references, so several threads can write to the
+/// same `ConcurrentGraph` through this trait.
+impl GraphWrite for &ConcurrentGraph {
+    type Error = ConnectError;
+
+    fn add_node<I>(&mut self, info: I) -> Result<NodeId, ConnectError>
+    where
+        I: Into<AnyNodeInfo>,
+    {
+        Ok(ConcurrentGraph::add_node(self, info))
+    }
+
+    fn remove_node(&mut self, node_id: NodeId) -> Result<Option<AnyNodeInfo>, ConnectError> {
+        Ok(ConcurrentGraph::remove_node(self, node_id))
+    }
+
+    fn add_relation<I>(&mut self, info: I) -> Result<RelationId, ConnectError>
+    where
+        I: Into<AnyRelationInfo>,
+    {
+        Ok(ConcurrentGraph::add_relation(self, info))
+    }
+
+    fn remove_relation(
+        &mut self,
+        relation_id: RelationId,
+    ) -> Result<Option<AnyRelationInfo>, ConnectError> {
+        Ok(ConcurrentGraph::remove_relation(self, relation_id))
+    }
+
+    fn connect(
+        &mut self,
+        src: NodeId,
+        dst: NodeId,
+        relation_id: RelationId,
+    ) -> Result<bool, ConnectError> {
+        ConcurrentGraph::connect(self, src, dst, relation_id)
+    }
+
+    fn disconnect(
+        &mut self,
+        src: NodeId,
+        dst: NodeId,
+        relation_id: RelationId,
+    ) -> Result<bool, ConnectError> {
+        ConcurrentGraph::disconnect(self, src, dst, relation_id)
+    }
+}
@@ -0,0 +1,173 @@
+use graphfruit::edge::Edge;
+use graphfruit::graph::Graph;
+use graphfruit::node::{AnyNodeInfo, NodeId, NodeInfo};
+use graphfruit::property::PropertyOwner;
+use graphfruit::relation::RelationKind;
+use graphfruit::value::Value;
+
+fn name_key(info: &dyn NodeInfo) -> Option<String> {
+    info.downcast_ref::<String>().cloned()
+}
+
+fn keep_first(info: &AnyNodeInfo, _: &AnyNodeInfo) -> AnyNodeInfo {
+    info.clone()
+}
+
+fn named(graph: &mut Graph, names: &[&str]) -> Vec<NodeId> {
+    names
+        .iter()
+        .map(|name| graph.add_node(name.to_string()))
+        .collect()
+}
+
+#[test]
+fn test_merge() {
+    let mut graph = Graph::new();
+    let a = named(&mut graph, &["a", "b"]);
+    let knows = graph.add_named_relation("knows", 0).unwrap();
+    graph.connect(a[0], a[1], knows).unwrap();
+
+    let mut other = Graph::new();
+    let b = named(&mut other, &["b", "c"]);
+    let likes = other.add_named_relation("likes", 0).unwrap();
+    let other_knows = other.add_named_relation("knows", 0).unwrap();
+    other.connect(b[0], b[1], other_knows).unwrap();
+    other.connect(b[1], b[0], likes).unwrap();
+    other.add_label(b[0], "worker");
+    other
+        .set_property(PropertyOwner::Node(b[1]), "age", 3)
+        .unwrap();
+
+    let ids = graph.merge(&other, name_key, keep_first).unwrap();
+    assert_eq!(graph.nr_nodes(), 3);
+    assert_eq!(graph.nr_relations(), 2);
+    assert_eq!(ids.node_id(b[0]), Some(a[1]));
+    assert_eq!(ids.relation_id(other_knows), Some(knows));
+    let c = ids.node_id(b[1]).unwrap();
+    let new_likes = ids.relation_id(likes).unwrap();
+    assert_eq!(graph.relation_by_name("likes"), Some(new_likes));
+    assert!(graph.has_label(a[1], "worker"));
+    assert_eq!(
+        graph.property(PropertyOwner::Node(c), "age"),
+        Some(&Value::from(3))
+    );
+    let knows = graph.relation(knows).unwrap();
+    assert!(knows.contains_edge(&Edge::new(a[0], a[1])));
+    assert!(knows.contains_edge(&Edge::new(a[1], c)));
+    assert!(graph
+        .relation(new_likes)
+        .unwrap()
+        .contains_edge(&Edge::new(c, a[1])));
+}
+
+#[test]
+fn test_union_merges_infos() {
+    let mut graph = Graph::new();
+    let a = graph.add_node(1);
+    graph.add_node(2);
+    let mut other = Graph::new();
+    let b = other.add_node(1);
+    other.add_node(3);
+
+    let key = |info: &dyn NodeInfo| info.downcast_ref::<i32>().map(|n| n % 2);
+    let (union, ids) = graph
+        .union(&other, key, |info, other_info| {
+            let sum =
+                info.downcast_ref::<i32>().unwrap() + other_info.downcast_ref::<i32>().unwrap();
+            AnyNodeInfo::from(sum)
+        })
+        .unwrap();
+    // Both odd nodes of `other` merge into the node of 1: 1 + 1 + 3
+    assert_eq!(union.nr_nodes(), 2);
+    assert_eq!(ids.node_id(b), Some(a));
+    assert_eq!(union.node_info(a).unwrap().downcast_ref::<i32>(), Some(&5));
+    assert_eq!(graph.node_info(a).unwrap().downcast_ref::<i32>(), Some(&1));
+}
+
+#[test]
+fn test_intersection() {
+    let mut graph = Graph::new();
+    let a = named(&mut graph, &["a", "b", "c"]);
+    let r = graph.add_named_relation("r", 0).unwrap();
+    let s = graph.add_named_relation("s", 0).unwrap();
+    graph.connect(a[0], a[1], r).unwrap();
+    graph.connect(a[1], a[2], r).unwrap();
+    graph.connect(a[0], a[2], s).unwrap();
+
+    let mut other = Graph::new();
+    let b = named(&mut other, &["c", "b", "a"]);
+    let other_r = other.add_named_relation("r", 0).unwrap();
+    other.connect(b[2], b[1], other_r).unwrap();
+    other.connect(b[0], b[1], other_r).unwrap();
+
+    let (common, ids) = graph.intersection(&other, name_key);
+    assert_eq!(common.nr_nodes(), 3);
+    assert_eq!(common.nr_relations(), 1);
+    assert!(ids.relation_id(s).is_none());
+    let new_r = common.relation(ids.relation_id(r).unwrap()).unwrap();
+    let node = |i: usize| ids.node_id(a[i]).unwrap();
+    assert_eq!(new_r.iter_edges().count(), 1);
+    assert!(new_r.contains_edge(&Edge::new(node(0), node(1))));
+}
+
+#[test]
+fn test_difference() {
+    let mut graph = Graph::new();
+    let a = named(&mut graph, &["a", "b", "c"]);
+    let r = graph.add_relation_of_kind(0, RelationKind::Undirected);
+    graph.rename_relation(r, "r").unwrap();
+    let s = graph.add_named_relation("s", 0).unwrap();
+    let u = graph.add_relation(0);
+    graph.connect(a[0], a[1], r).unwrap();
+    graph.connect(a[1], a[2], r).unwrap();
+    graph.connect(a[0], a[2], s).unwrap();
+    graph.connect(a[0], a[1], u).unwrap();
+
+    let mut other = Graph::new();
+    let b = named(&mut other, &["a", "b"]);
+    let other_r = other.add_relation_of_kind(0, RelationKind::Undirected);
+    other.rename_relation(other_r, "r").unwrap();
+    other.add_named_relation("s", 0).unwrap();
+    let other_u = other.add_relation(0);
+    other.connect(b[1], b[0], other_r).unwrap();
+    other.connect(b[0], b[1], other_u).unwrap();
+    assert_eq!(other_u, u);
+
+    let (rest, ids) = graph.difference(&other, name_key);
+    assert_eq!(rest.nr_nodes(), 3);
+    assert_eq!(rest.nr_relations(), 3);
+    let node = |i: usize| ids.node_id(a[i]).unwrap();
+    let new_r = rest.relation(ids.relation_id(r).unwrap()).unwrap();
+    assert_eq!(new_r.iter_edges().count(), 1);
+    assert!(new_r.contains_edge(&Edge::new(node(1), node(2))));
+    let new_s = rest.relation(ids.relation_id(s).unwrap()).unwrap();
+    assert!(new_s.contains_edge(&Edge::new(node(0), node(2))));
+    // Unnamed relations are not matched, even if their ids are the same
+    let new_u = rest.relation(ids.relation_id(u).unwrap()).unwrap();
+    assert!(new_u.contains_edge(&Edge::new(node(0), node(1))));
+}
+
+#[test]
+fn test_merge_matches_within_other() {
+    let mut graph = Graph::new();
+    named(&mut graph, &["a"]);
+    let directed = graph.add_relation(0);
+
+    let mut other = Graph::new();
+    let b = named(&mut other, &["x", "x", "y"]);
+    let undirected = other.add_relation_of_kind(0, RelationKind::Undirected);
+    assert_eq!(undirected, directed);
+    other.connect(b[0], b[2], undirected).unwrap();
+    other.connect(b[2], b[1], undirected).unwrap();
+
+    let ids = graph.merge(&other, name_key, keep_first).unwrap();
+    assert_eq!(graph.nr_nodes(), 3);
+    assert_eq!(ids.node_id(b[0]), ids.node_id(b[1]));
+    // Unnamed relations are not matched
+    let copied = ids.relation_id(undirected).unwrap();
+    assert_ne!(copied, directed);
+    assert_eq!(graph.relation(directed).unwrap().iter_edges().count(), 0);
+    let copied = graph.relation(copied).unwrap();
+    assert_eq!(copied.kind(), RelationKind::Undirected);
+    assert_eq!(copied.iter_edges().count(), 1);
+}
@@ -0,0 +1,155 @@
+use graphfruit::concurrent::ConcurrentGraph;
+use graphfruit::edge::Edge;
+use graphfruit::errors::ConnectError;
+use graphfruit::node::NodeId;
+use std::thread;
+
+fn nr_threads() -> usize {
+    thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(4)
+        .clamp(2, 8)
+}
+
+#[test]
+fn test_send_sync() {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<ConcurrentGraph>();
+}
+
+#[test]
+fn test_short() {
+    let graph = ConcurrentGraph::with_shards(2);
+    let n1 = graph.add_node("Node1".to_string());
+    let n2 = graph.add_node("Node2".to_string());
+    let r1 = graph.add_relation(1);
+    assert!(graph.connect(n1, n2, r1).unwrap());
+    assert!(!graph.connect(n1, n2, r1).unwrap());
+    assert!(graph.connect(n1, n1, r1).unwrap());
+    assert_eq!(graph.out_degree_of(n1), Some(2));
+    assert_eq!(graph.in_degree_of(n1), Some(1));
+    assert_eq!(graph.next_nodes(n2), Some(Vec::new()));
+    assert_eq!(graph.prev_nodes(n2), Some(vec![(n1, r1)]));
+    assert_eq!(
+        graph.with_node_info(n2, |info| info.downcast_ref::<String>().cloned()),
+        Some(Some("Node2".to_string()))
+    );
+    assert!(graph.disconnect(n1, n1, r1).unwrap());
+    assert!(!graph.disconnect(n1, n1, r1).unwrap());
+    assert!(matches!(
+        graph.connect(n1, NodeId::new(100), r1),
+        Err(ConnectError::InvalidDstNodeId)
+    ));
+
+    assert!(graph.remove_node(n2).is_some());
+    assert!(graph.remove_node(n2).is_none());
+    assert_eq!(graph.out_degree_of(n1), Some(0));
+    assert_eq!(graph.relation_edges(r1), Some(Vec::new()));
+    assert!(graph.remove_relation(r1).is_some());
+    assert!(matches!(
+        graph.connect(n1, n1, r1),
+        Err(ConnectError::InvalidRelationId)
+    ));
+}
+
+#[test]
+fn test_parallel_add_connect() {
+    let graph = ConcurrentGraph::new();
+    let hub = graph.add_node(0);
+    let relation_id = graph.add_relation("next".to_string());
+    let threads = nr_threads();
+
+    let chains: Vec<Vec<NodeId>> = thread::scope(|scope| {
+        let handles: Vec<_> = (0..threads)
+            .map(|t| {
+                let graph = &graph;
+                scope.spawn(move || {
+                    let mut node_ids = Vec::new();
+                    for i in 0..1000 {
+                        let node_id = graph.add_node((t * 1000 + i) as i32);
+                        assert!(graph.connect(node_id, hub, relation_id).unwrap());
+                        if let Some(prev) = node_ids.last() {
+                            assert!(graph.connect(*prev, node_id, relation_id).unwrap());
+                        }
+                        node_ids.push(node_id);
+                    }
+                    node_ids
+                })
+            })
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    });
+
+    assert_eq!(graph.nr_nodes(), threads * 1000 + 1);
+    assert_eq!(graph.in_degree_of(hub), Some(threads * 1000));
+    assert_eq!(
+        graph.relation_edges(relation_id).unwrap().len(),
+        threads * 1000 + threads * 999
+    );
+    for chain in &chains {
+        for pair in chain.windows(2) {
+            assert!(graph.contains_edge(&Edge::new(pair[0], pair[1]), relation_id));
+        }
+    }
+}
+
+#[test]
+fn test_parallel_mixed_mutations() {
+    let graph = ConcurrentGraph::with_shards(8);
+    let relations: Vec<_> = (0..4).map(|i| graph.add_relation(i)).collect();
+    let node_ids: Vec<_> = (0..400).map(|i| graph.add_node(i)).collect();
+    let threads = nr_threads();
+
+    thread::scope(|scope| {
+        for t in 0..threads {
+            let (graph, node_ids, relations) = (&graph, &node_ids, &relations);
+            scope.spawn(move || {
+                for round in 0..2000 {
+                    let i = (round * 31 + t * 17) % node_ids.len();
+                    let j = (round * 7 + t * 3) % node_ids.len();
+                    let relation_id = relations[(round + t) % relations.len()];
+                    // Errors are expected once nodes start to disappear.
+                    let _ = graph.connect(node_ids[i], node_ids[j], relation_id);
+                    if round % 3 == 0 {
+                        let _ = graph.disconnect(node_ids[j], node_ids[i], relation_id);
+                    }
+                    if round % 50 == t {
+                        graph.remove_node(node_ids[(round * 13 + t) % node_ids.len()]);
+                    }
+                }
+            });
+        }
+    });
+
+    // Every edge must connect live nodes and be mirrored in the adjacency.
+    let mut nr_edges = 0;
+    for relation_id in &relations {
+        for edge in graph.relation_edges(*relation_id).unwrap() {
+            assert!(graph.contains_node(edge.src()));
+            assert!(graph.contains_node(edge.dst()));
+            assert!(graph
+                .next_nodes(edge.src())
+                .unwrap()
+                .contains(&(edge.dst(), *relation_id)));
+            nr_edges += 1;
+        }
+    }
+    let out_degrees: usize = node_ids
+        .iter()
+        .filter_map(|id| graph.out_degree_of(*id))
+        .sum();
+    let in_degrees: usize = node_ids
+        .iter()
+        .filter_map(|id| graph.in_degree_of(*id))
+        .sum();
+    assert_eq!(out_degrees, nr_edges);
+    assert_eq!(in_degrees, nr_edges);
+
+    let mut graph = graph.into_graph();
+    let edges: usize = relations
+        .iter()
+        .map(|r| graph.iter_relation_edges(*r).unwrap().count())
+        .sum();
+    assert_eq!(edges, nr_edges);
+    assert!(!node_ids.contains(&graph.add_node(0)));
+}
@@ -0,0 +1,212 @@
+use graphfruit::contract::SelfLoops;
+use graphfruit::edge::Edge;
+use graphfruit::errors::{ConnectError, MergeError};
+use graphfruit::graph::Graph;
+use graphfruit::hyperedge::{Hyperedge, HyperedgeKind};
+use graphfruit::node::AnyNodeInfo;
+use graphfruit::property::PropertyOwner;
+use graphfruit::relation::RelationKind;
+use graphfruit::value::Value;
+
+fn sum(keep: &AnyNodeInfo, absorb: &AnyNodeInfo) -> AnyNodeInfo {
+    let keep = keep.downcast_ref::<i32>().unwrap();
+    let absorb = absorb.downcast_ref::<i32>().unwrap();
+    AnyNodeInfo::from(keep + absorb)
+}
+
+#[test]
+fn test_merge_nodes() {
+    let mut graph = Graph::new();
+    let (keep, absorb, x, y) = (
+        graph.add_node(1),
+        graph.add_node(2),
+        graph.add_node(3),
+        graph.add_node(4),
+    );
+    let r = graph.add_relation(0);
+    let m = graph.add_multi_relation(0, RelationKind::Directed);
+    graph.connect_with(keep, x, r, 1u32).unwrap();
+    graph.connect_with(absorb, x, r, 2u32).unwrap();
+    graph.connect_with(y, absorb, r, 3u32).unwrap();
+    graph.connect_multi(absorb, x, m).unwrap();
+    graph.connect_multi_with(absorb, x, m, 4u32).unwrap();
+    graph.add_label(absorb, "dup");
+    graph
+        .set_property(PropertyOwner::Node(keep), "name", "keep")
+        .unwrap();
+    graph
+        .set_property(PropertyOwner::Node(absorb), "name", "absorb")
+        .unwrap();
+    graph
+        .set_property(PropertyOwner::Node(absorb), "age", 7)
+        .unwrap();
+
+    graph.merge_nodes(keep, absorb, sum).unwrap();
+    assert!(!graph.contains_node(absorb));
+    assert_eq!(graph.nr_nodes(), 3);
+    assert_eq!(
+        graph.node_info(keep).unwrap().downcast_ref::<i32>(),
+        Some(&3)
+    );
+    assert!(graph.has_label(keep, "dup"));
+    assert_eq!(
+        graph.property(PropertyOwner::Node(keep), "name"),
+        Some(&Value::from("keep"))
+    );
+    assert_eq!(
+        graph.property(PropertyOwner::Node(keep), "age"),
+        Some(&Value::from(7))
+    );
+    let info = |src, dst| {
+        graph
+            .edge_info(src, dst, r)
+            .and_then(|info| info.downcast_ref::<u32>())
+            .copied()
+    };
+    assert_eq!(info(keep, x), Some(1));
+    assert_eq!(info(y, keep), Some(3));
+    let multi = graph.relation(m).unwrap();
+    assert_eq!(multi.multiplicity(&Edge::new(keep, x)), 2);
+    assert_eq!(graph.out_degree_of(keep), Some(3));
+    assert_eq!(graph.in_degree_of(keep), Some(1));
+    assert_eq!(graph.in_degree_of(x), Some(3));
+}
+
+#[test]
+fn test_contract_edge_self_loops() {
+    let mut graph = Graph::new();
+    let nodes: Vec<_> = (0..3).map(|i| graph.add_node(i)).collect();
+    let (a, b, c) = (nodes[0], nodes[1], nodes[2]);
+    let road = graph.add_relation_of_kind(0, RelationKind::Undirected);
+    let rail = graph.add_relation(0);
+    graph.connect(a, b, road).unwrap();
+    graph.connect(b, c, road).unwrap();
+    graph.connect(b, a, rail).unwrap();
+    graph.connect(a, a, rail).unwrap();
+
+    let mut dropped = graph.clone();
+    dropped.contract_edge(b, a, road, SelfLoops::Drop).unwrap();
+    assert!(!dropped.contains_node(a));
+    assert_eq!(
+        dropped.node_info(b).unwrap().downcast_ref::<i32>(),
+        Some(&1)
+    );
+    assert!(!dropped
+        .relation(road)
+        .unwrap()
+        .contains_edge(&Edge::new(b, b)));
+    assert!(dropped
+        .relation(road)
+        .unwrap()
+        .contains_edge(&Edge::new(c, b)));
+    // The self-loop of `a` was not made by the contraction
+    assert_eq!(dropped.relation(rail).unwrap().iter_edges().count(), 1);
+    assert!(dropped
+        .relation(rail)
+        .unwrap()
+        .contains_edge(&Edge::new(b, b)));
+
+    graph.contract_edge(a, b, road, SelfLoops::Keep).unwrap();
+    assert!(!graph.contains_node(b));
+    assert!(!graph
+        .relation(road)
+        .unwrap()
+        .contains_edge(&Edge::new(a, a)));
+    assert!(graph
+        .relation(road)
+        .unwrap()
+        .contains_edge(&Edge::new(a, c)));
+    assert!(graph
+        .relation(rail)
+        .unwrap()
+        .contains_edge(&Edge::new(a, a)));
+}
+
+#[test]
+fn test_merge_nodes_errors() {
+    let mut graph = Graph::new();
+    let (a, b, c) = (graph.add_node(1), graph.add_node(2), graph.add_node(3));
+    let r = graph.add_relation_of_kind(0, RelationKind::Antisymmetric);
+    graph.connect(a, c, r).unwrap();
+    graph.connect(c, b, r).unwrap();
+
+    assert!(matches!(
+        graph.merge_nodes(a, a, sum),
+        Err(MergeError::SameNode)
+    ));
+    assert!(matches!(
+        graph.contract_edge(a, b, r, SelfLoops::Drop),
+        Err(MergeError::InvalidEdge)
+    ));
+    // Moving c -> b onto a would reverse the edge a -> c
+    assert!(matches!(
+        graph.merge_nodes(a, b, sum),
+        Err(MergeError::Connect(ConnectError::ReverseEdgeExists))
+    ));
+    assert!(graph.contains_node(b));
+    assert_eq!(graph.node_info(a).unwrap().downcast_ref::<i32>(), Some(&1));
+    assert!(graph.relation(r).unwrap().contains_edge(&Edge::new(c, b)));
+    graph.remove_node(c);
+    assert!(matches!(
+        graph.merge_nodes(a, c, sum),
+        Err(MergeError::InvalidNodeId)
+    ));
+}
+
+#[test]
+fn test_merge_nodes_moves_hyperedges() {
+    let mut graph = Graph::new();
+    let nodes: Vec<_> = (0..4).map(|i| graph.add_node(i)).collect();
+    let h = graph.add_hyper_relation(0, HyperedgeKind::Ordered);
+    graph
+        .connect_hyperedge(h, Hyperedge::new(vec![nodes[0]], vec![nodes[1], nodes[2]]))
+        .unwrap();
+    graph
+        .connect_hyperedge(h, Hyperedge::new(vec![nodes[2]], vec![nodes[3]]))
+        .unwrap();
+
+    graph.merge_nodes(nodes[3], nodes[2], sum).unwrap();
+    let mut hyperedges: Vec<_> = graph
+        .iter_hyperedges_of(nodes[3])
+        .unwrap()
+        .map(|(edge_id, _)| graph.hyperedge(edge_id).unwrap().0.clone())
+        .collect();
+    hyperedges.sort_by_key(|hyperedge| *hyperedge.sources()[0]);
+    assert_eq!(
+        hyperedges,
+        vec![
+            Hyperedge::new(vec![nodes[0]], vec![nodes[1], nodes[3]]),
+            Hyperedge::new(vec![nodes[3]], vec![nodes[3]]),
+        ]
+    );
+}
+
+#[test]
+fn test_contract_parallel_edge() {
+    let mut graph = Graph::new();
+    let a = graph.add_node(1);
+    let b = graph.add_node(2);
+    let r = graph.add_multi_relation(0, RelationKind::Directed);
+    graph.connect_multi_with(a, b, r, 10u32).unwrap();
+    graph.connect_multi_with(a, b, r, 20u32).unwrap();
+    graph.connect_multi(b, a, r).unwrap();
+
+    let mut dropped = graph.clone();
+    dropped.contract_edge(a, b, r, SelfLoops::Drop).unwrap();
+    assert_eq!(dropped.relation(r).unwrap().iter_edges().count(), 0);
+
+    // Only the oldest parallel edge from `a` to `b` is contracted
+    graph.contract_edge(a, b, r, SelfLoops::Keep).unwrap();
+    let relation = graph.relation(r).unwrap();
+    let loop_edge = Edge::new(a, a);
+    assert_eq!(relation.multiplicity(&loop_edge), 2);
+    let mut weights: Vec<_> = relation
+        .iter_edge_ids(&loop_edge)
+        .map(|edge_id| {
+            let info = relation.parallel_edge_info(edge_id)?;
+            info.downcast_ref::<u32>().copied()
+        })
+        .collect();
+    weights.sort();
+    assert_eq!(weights, [None, Some(20)]);
+}
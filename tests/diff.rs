@@ -0,0 +1,247 @@
+use graphfruit::codec::DefaultCodec;
+use graphfruit::diff::GraphDiff;
+use graphfruit::edge::Edge;
+use graphfruit::errors::{NameError, PropertyError, WriteError};
+use graphfruit::graph::Graph;
+use graphfruit::property::PropertyOwner;
+use std::collections::HashSet;
+
+fn edges(graph: &Graph) -> HashSet<(Edge, u64)> {
+    graph
+        .iter_relations()
+        .flat_map(|(id, relation)| relation.iter_edges().map(move |e| (*e, id.get())))
+        .collect()
+}
+
+fn build() -> Graph {
+    let mut graph = Graph::new();
+    let nodes: Vec<_> = (0..5).map(|i| graph.add_node(i)).collect();
+    let r1 = graph.add_relation(1);
+    let r2 = graph.add_relation("two".to_string());
+    for i in 0..4 {
+        graph.connect(nodes[i], nodes[i + 1], r1).unwrap();
+        graph.connect(nodes[i + 1], nodes[i], r2).unwrap();
+    }
+    graph
+}
+
+#[test]
+fn test_diff() {
+    let old = build();
+    let mut new = old.clone();
+    assert!(old.diff(&new).is_empty());
+
+    let ids: Vec<_> = new.iter_node_ids().map(|(id, _)| id).collect();
+    let r1 = new
+        .iter_relations()
+        .map(|(id, _)| id)
+        .min_by_key(|id| id.get());
+    let r1 = r1.unwrap();
+    let n5 = new.add_node(5);
+    let r3 = new.add_relation(3);
+    new.connect(ids[0], n5, r1).unwrap();
+    new.connect(n5, n5, r3).unwrap();
+    new.remove_node(ids[1]);
+
+    let diff = old.diff(&new);
+    assert_eq!(
+        diff.iter_added_nodes()
+            .map(|(id, _)| id)
+            .collect::<Vec<_>>(),
+        vec![n5]
+    );
+    assert_eq!(diff.iter_removed_nodes().collect::<Vec<_>>(), vec![ids[1]]);
+    assert_eq!(
+        diff.iter_added_relations()
+            .map(|(id, _)| id)
+            .collect::<Vec<_>>(),
+        vec![r3]
+    );
+    assert_eq!(diff.iter_removed_relations().count(), 0);
+    assert_eq!(diff.iter_added_edges().count(), 2);
+    assert_eq!(diff.iter_removed_edges().count(), 0);
+
+    let reverse = new.diff(&old);
+    assert_eq!(
+        reverse.iter_removed_relations().collect::<Vec<_>>(),
+        vec![r3]
+    );
+    assert_eq!(reverse.iter_removed_edges().count(), 0);
+    assert_eq!(
+        reverse
+            .iter_added_nodes()
+            .map(|(id, _)| id)
+            .collect::<Vec<_>>(),
+        vec![ids[1]]
+    );
+}
+
+#[test]
+fn test_apply() {
+    let old = build();
+    let mut new = old.clone();
+    let (r1, _) = new.iter_relations().next().unwrap();
+    new.remove_relation(r1);
+    let ids: Vec<_> = new.iter_node_ids().map(|(id, _)| id).collect();
+    new.remove_node(ids[2]);
+    let n = new.add_node("new".to_string());
+    let r = new.add_relation(4);
+    new.connect(n, ids[0], r).unwrap();
+
+    let mut replica = old.clone();
+    replica.apply(&old.diff(&new)).unwrap();
+    assert_eq!(edges(&replica), edges(&new));
+    assert_eq!(replica.nr_nodes(), new.nr_nodes());
+    assert_eq!(replica.nr_relations(), new.nr_relations());
+    assert_eq!(
+        replica
+            .node_info(n)
+            .unwrap()
+            .downcast_ref::<String>()
+            .unwrap(),
+        "new"
+    );
+    assert!(replica.diff(&new).is_empty());
+
+    replica.apply(&new.diff(&old)).unwrap();
+    assert_eq!(edges(&replica), edges(&old));
+    assert!(replica.diff(&old).is_empty());
+}
+
+#[test]
+fn test_apply_is_atomic() {
+    let old = build();
+    let mut new = old.clone();
+    let ids: Vec<_> = new.iter_node_ids().map(|(id, _)| id).collect();
+    let (r1, _) = new.iter_relations().next().unwrap();
+    let n = new.add_node(9);
+    new.connect(ids[0], n, r1).unwrap();
+    let diff = old.diff(&new);
+
+    let mut other = Graph::new();
+    other.add_node(0);
+    assert!(other.apply(&diff).is_err());
+    assert_eq!(other.nr_nodes(), 1);
+    assert!(edges(&other).is_empty());
+}
+
+#[test]
+fn test_apply_taken_name() {
+    let old = build();
+    let mut relation_ids: Vec<_> = old.iter_relations().map(|(id, _)| id).collect();
+    relation_ids.sort_by_key(|id| id.get());
+    let (r1, r2) = (relation_ids[0], relation_ids[1]);
+    let mut new = old.clone();
+    new.rename_relation(r1, "knows").unwrap();
+    let diff = old.diff(&new);
+
+    let mut other = old.clone();
+    other.rename_relation(r2, "knows").unwrap();
+    assert!(matches!(
+        other.apply(&diff),
+        Err(WriteError::Name(NameError::NameTaken))
+    ));
+    assert_eq!(other.relation_by_name("knows"), Some(r2));
+}
+
+#[test]
+fn test_apply_missing_property_owner() {
+    let old = build();
+    let mut ids: Vec<_> = old.iter_node_ids().map(|(id, _)| id).collect();
+    ids.sort_by_key(|id| **id);
+    let mut new = old.clone();
+    new.set_property(PropertyOwner::Node(ids[0]), "age", 36)
+        .unwrap();
+    let diff = old.diff(&new);
+
+    let mut other = old.clone();
+    other.remove_node(ids[0]);
+    assert!(matches!(
+        other.apply(&diff),
+        Err(WriteError::Property(PropertyError::InvalidNodeId))
+    ));
+    assert_eq!(other.nr_nodes(), 4);
+}
+
+#[test]
+fn test_encode_decode() {
+    let old = build();
+    let mut new = old.clone();
+    let ids: Vec<_> = new.iter_node_ids().map(|(id, _)| id).collect();
+    let (r1, _) = new.iter_relations().next().unwrap();
+    new.disconnect(ids[0], ids[1], r1).unwrap();
+    new.disconnect(ids[1], ids[0], r1).unwrap();
+    new.remove_node(ids[4]);
+    let n = new.add_node("remote".to_string());
+    let r = new.add_relation(7u32);
+    new.connect(n, n, r).unwrap();
+
+    let codec = DefaultCodec;
+    let bytes = old.diff(&new).encode(&codec).unwrap();
+    let diff = GraphDiff::decode(&bytes, &codec).unwrap();
+    let mut replica = old.clone();
+    replica.apply(&diff).unwrap();
+    assert_eq!(edges(&replica), edges(&new));
+    assert_eq!(
+        *replica
+            .relation(r)
+            .unwrap()
+            .info()
+            .downcast_ref::<u32>()
+            .unwrap(),
+        7
+    );
+
+    assert!(GraphDiff::decode(&bytes[..bytes.len() - 1], &codec).is_err());
+    assert!(GraphDiff::decode(b"not a diff", &codec).is_err());
+}
+
+#[test]
+fn test_diff_infos_by_value_and_inverses() {
+    let old = build();
+    assert!(old.diff(&build()).is_empty());
+
+    let mut new = old.clone();
+    let mut ids: Vec<_> = new.iter_node_ids().map(|(id, _)| id).collect();
+    ids.sort_by_key(|id| **id);
+    let mut relation_ids: Vec<_> = new.iter_relations().map(|(id, _)| id).collect();
+    relation_ids.sort_by_key(|id| id.get());
+    let (r1, r2) = (relation_ids[0], relation_ids[1]);
+    new.replace_node_info(ids[0], 0).unwrap();
+    new.connect_with(ids[0], ids[1], r1, 7u32).unwrap();
+    new.set_inverse(r1, r2).unwrap();
+
+    let codec = DefaultCodec;
+    let diff = old.diff(&new);
+    assert_eq!(diff.iter_changed_nodes().count(), 0);
+    assert_eq!(
+        diff.iter_changed_edges().collect::<Vec<_>>(),
+        [(Edge::new(ids[0], ids[1]), r1)]
+    );
+    assert_eq!(diff.iter_set_inverses().collect::<Vec<_>>(), [(r1, r2)]);
+    let diff = GraphDiff::decode(&diff.encode(&codec).unwrap(), &codec).unwrap();
+    let mut replica = old.clone();
+    replica.apply(&diff).unwrap();
+    assert_eq!(replica.inverse_of(r2), Some(r1));
+    let info = replica.edge_info(ids[0], ids[1], r1).unwrap();
+    assert_eq!(info.downcast_ref::<u32>(), Some(&7));
+    assert!(replica.diff(&new).is_empty());
+
+    let reverse = new.diff(&old);
+    assert_eq!(reverse.iter_removed_inverses().count(), 1);
+    replica.apply(&reverse).unwrap();
+    assert_eq!(replica.inverse_of(r1), None);
+    assert!(replica.edge_info(ids[0], ids[1], r1).is_none());
+    assert!(replica.diff(&old).is_empty());
+
+    // Applying checks unique indexes and leaves the graph unchanged on error
+    let mut indexed = old.clone();
+    indexed
+        .add_unique_index(|info| info.downcast_ref::<i32>().copied())
+        .unwrap();
+    let mut new = old.clone();
+    new.replace_node_info(ids[1], 9).unwrap();
+    new.add_node(0);
+    assert!(indexed.apply(&old.diff(&new)).is_err());
+    assert!(indexed.diff(&old).is_empty());
+}
@@ -0,0 +1,111 @@
+use graphfruit::edge::Edge;
+use graphfruit::graph::Graph;
+use graphfruit::history::History;
+use graphfruit::node::NodeId;
+use graphfruit::relation::{RelationId, RelationKind};
+use graphfruit::store::PersistentGraph;
+
+fn weight(graph: &Graph, src: u64, dst: u64, relation_id: u64) -> Option<u32> {
+    graph
+        .edge_info(
+            NodeId::new(src),
+            NodeId::new(dst),
+            RelationId::new(relation_id),
+        )
+        .map(|info| *info.downcast_ref::<u32>().unwrap())
+}
+
+#[test]
+fn test_connect_with() {
+    let mut graph = Graph::new();
+    let a = graph.add_node(1);
+    let b = graph.add_node(2);
+    let r1 = graph.add_relation(1);
+    let r2 = graph.add_relation_of_kind(2, RelationKind::Undirected);
+
+    assert!(graph.connect_with(a, b, r1, 5u32).unwrap());
+    assert!(graph.connect(b, a, r1).unwrap());
+    assert_eq!(weight(&graph, *a, *b, r1.get()), Some(5));
+    assert_eq!(weight(&graph, *b, *a, r1.get()), None);
+
+    assert!(!graph.connect_with(a, b, r1, 7u32).unwrap());
+    assert_eq!(weight(&graph, *a, *b, r1.get()), Some(7));
+    *graph.edge_info_mut::<u32>(a, b, r1).unwrap() += 1;
+    assert_eq!(weight(&graph, *a, *b, r1.get()), Some(8));
+
+    graph.connect_with(b, a, r2, 3u32).unwrap();
+    assert_eq!(weight(&graph, *a, *b, r2.get()), Some(3));
+
+    assert!(graph.remove_edge_info(a, b, r1).is_some());
+    assert!(graph.relation(r1).unwrap().contains_edge(&Edge::new(a, b)));
+    graph.connect_with(a, b, r1, 1u32).unwrap();
+    graph.disconnect(a, b, r1).unwrap();
+    graph.connect(a, b, r1).unwrap();
+    assert_eq!(weight(&graph, *a, *b, r1.get()), None);
+}
+
+#[test]
+fn test_snapshot_shares_edge_info() {
+    let mut graph = Graph::new();
+    let a = graph.add_node(1);
+    let r1 = graph.add_relation(1);
+    graph.connect_with(a, a, r1, "label".to_string()).unwrap();
+
+    let snapshot = graph.snapshot();
+    graph.edge_info_mut::<String>(a, a, r1).unwrap().push('!');
+    let info = snapshot.edge_info(a, a, r1).unwrap();
+    assert_eq!(info.downcast_ref::<String>().unwrap(), "label");
+    let info = graph.edge_info(a, a, r1).unwrap();
+    assert_eq!(info.downcast_ref::<String>().unwrap(), "label!");
+}
+
+#[test]
+fn test_undo_restores_edge_info() {
+    let mut history = History::new(Graph::new());
+    let a = history.add_node(1);
+    let b = history.add_node(2);
+    let r1 = history.add_relation(1);
+    history.connect_with(a, b, r1, 1u32).unwrap();
+    history.checkpoint("connect");
+    history.connect_with(a, b, r1, 2u32).unwrap();
+    history.checkpoint("update");
+    history.remove_node(b);
+    history.checkpoint("remove");
+
+    history.undo();
+    assert_eq!(weight(history.graph(), *a, *b, r1.get()), Some(2));
+    history.undo();
+    assert_eq!(weight(history.graph(), *a, *b, r1.get()), Some(1));
+    history.redo();
+    assert_eq!(weight(history.graph(), *a, *b, r1.get()), Some(2));
+
+    history.disconnect(a, b, r1).unwrap();
+    history.undo();
+    assert_eq!(weight(history.graph(), *a, *b, r1.get()), Some(2));
+    history.remove_edge_info(a, b, r1);
+    assert_eq!(weight(history.graph(), *a, *b, r1.get()), None);
+    history.undo();
+    assert_eq!(weight(history.graph(), *a, *b, r1.get()), Some(2));
+}
+
+#[test]
+fn test_persisted_edge_info() {
+    let dir = tempfile::tempdir().unwrap();
+    let (a, b, r1) = {
+        let mut store = PersistentGraph::open(dir.path()).unwrap();
+        let a = store.add_node(1).unwrap();
+        let b = store.add_node(2).unwrap();
+        let r1 = store.add_relation(1).unwrap();
+        store.connect_with(a, b, r1, 4u32).unwrap();
+        store.connect(b, a, r1).unwrap();
+        (a, b, r1)
+    };
+
+    let mut store = PersistentGraph::open(dir.path()).unwrap();
+    assert_eq!(weight(store.graph(), *a, *b, r1.get()), Some(4));
+    store.connect_with(b, a, r1, 9u32).unwrap();
+    store.checkpoint().unwrap();
+    let store = PersistentGraph::open(dir.path()).unwrap();
+    assert_eq!(weight(store.graph(), *a, *b, r1.get()), Some(4));
+    assert_eq!(weight(store.graph(), *b, *a, r1.get()), Some(9));
+}
@@ -0,0 +1,118 @@
+use graphfruit::edge::Edge;
+use graphfruit::event::{EventFilter, GraphEvent};
+use graphfruit::graph::Graph;
+use std::sync::{Arc, Mutex};
+
+#[test]
+fn test_callback() {
+    let mut graph = Graph::new();
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let sink = events.clone();
+    let subscription = graph.subscribe(EventFilter::all(), move |event| {
+        sink.lock().unwrap().push(*event)
+    });
+
+    let n1 = graph.add_node(1);
+    let n2 = graph.add_node(2);
+    let r1 = graph.add_relation(1);
+    graph.connect(n1, n2, r1).unwrap();
+    graph.connect(n1, n2, r1).unwrap();
+    graph.disconnect(n1, n2, r1).unwrap();
+    graph.disconnect(n1, n2, r1).unwrap();
+    graph.connect(n2, n1, r1).unwrap();
+    graph.remove_node(n1);
+    graph.remove_relation(r1);
+
+    assert!(graph.unsubscribe(subscription));
+    assert!(!graph.unsubscribe(subscription));
+    graph.add_node(3);
+
+    let edge = Edge::new(n1, n2);
+    let back = Edge::new(n2, n1);
+    assert_eq!(
+        *events.lock().unwrap(),
+        vec![
+            GraphEvent::NodeAdded(n1),
+            GraphEvent::NodeAdded(n2),
+            GraphEvent::RelationAdded(r1),
+            GraphEvent::EdgeConnected {
+                edge,
+                relation_id: r1
+            },
+            GraphEvent::EdgeDisconnected {
+                edge,
+                relation_id: r1
+            },
+            GraphEvent::EdgeConnected {
+                edge: back,
+                relation_id: r1
+            },
+            GraphEvent::EdgeDisconnected {
+                edge: back,
+                relation_id: r1
+            },
+            GraphEvent::NodeRemoved(n1),
+            GraphEvent::RelationRemoved(r1),
+        ]
+    );
+}
+
+#[test]
+fn test_channel_filter() {
+    let mut graph = Graph::new();
+    let n1 = graph.add_node(1);
+    let r1 = graph.add_relation(1);
+    let r2 = graph.add_relation(2);
+    let (_, receiver) = graph.subscribe_channel(EventFilter::relations(vec![r2]));
+
+    graph.add_node(2);
+    graph.connect(n1, n1, r1).unwrap();
+    graph.connect(n1, n1, r2).unwrap();
+    graph.remove_relation(r2);
+    graph.remove_relation(r1);
+
+    let events: Vec<_> = receiver.try_iter().collect();
+    assert_eq!(
+        events,
+        vec![
+            GraphEvent::EdgeConnected {
+                edge: Edge::new(n1, n1),
+                relation_id: r2
+            },
+            GraphEvent::EdgeDisconnected {
+                edge: Edge::new(n1, n1),
+                relation_id: r2
+            },
+            GraphEvent::RelationRemoved(r2),
+        ]
+    );
+}
+
+#[test]
+fn test_dropped_receiver() {
+    let mut graph = Graph::new();
+    let (subscription, receiver) = graph.subscribe_channel(EventFilter::all());
+    graph.add_node(1);
+    assert_eq!(receiver.try_iter().count(), 1);
+    drop(receiver);
+    graph.add_node(2);
+    assert!(!graph.unsubscribe(subscription));
+}
+
+#[test]
+fn test_subscriptions_and_copies() {
+    let mut graph = Graph::new();
+    let (_, receiver) = graph.subscribe_channel(EventFilter::all());
+
+    let mut clone = graph.clone();
+    clone.add_node(1);
+    assert_eq!(receiver.try_iter().count(), 0);
+
+    let result: Result<(), ()> = graph.transaction(|tx| {
+        tx.add_node(1);
+        Err(())
+    });
+    assert!(result.is_err());
+    graph.add_node(1);
+    assert_eq!(receiver.try_iter().count(), 1);
+}
@@ -37,8 +37,8 @@ fn short_tests() {
     assert!(graph.disconnect(n1, n4, r1).is_ok());
     assert_eq!(graph.out_degree_of(n1).unwrap(), 2);
     assert_eq!(graph.in_degree_of(n4).unwrap(), 1);
-    assert_eq!(graph.disconnect(n1, n4, r1).unwrap(), false);
-    assert_eq!(graph.disconnect(n3, n2, r1).unwrap(), false);
+    assert!(!graph.disconnect(n1, n4, r1).unwrap());
+    assert!(!graph.disconnect(n3, n2, r1).unwrap());
 
     assert!(graph.remove_node(n2).is_some());
     assert!(graph.remove_node(n2).is_none());
@@ -134,34 +134,22 @@ fn test_connect() {
     assert_eq!(sum, 4950);
     for i in 0..100 {
         for j in 0..100 {
-            assert_eq!(
-                graph
-                    .connect(node_ids[i], node_ids[j], rel_ids[(i + j) % 10])
-                    .unwrap(),
-                true
-            );
+            assert!(graph
+                .connect(node_ids[i], node_ids[j], rel_ids[(i + j) % 10])
+                .unwrap());
         }
     }
-    for i in 0..100 {
-        assert_eq!(
-            graph.in_degree_of(node_ids[i]),
-            graph.out_degree_of(node_ids[i])
-        );
+    for &node_id in &node_ids[..100] {
+        assert_eq!(graph.in_degree_of(node_id), graph.out_degree_of(node_id));
     }
     for i in 0..100 {
         for j in 0..100 {
-            assert_eq!(
-                graph
-                    .connect(node_ids[i], node_ids[j], rel_ids[(i + j) % 10])
-                    .unwrap(),
-                false
-            );
-            assert_eq!(
-                graph
-                    .connect(node_ids[i], node_ids[j], rel_ids[(i + j) % 10 + 1])
-                    .unwrap(),
-                true
-            );
+            assert!(!graph
+                .connect(node_ids[i], node_ids[j], rel_ids[(i + j) % 10])
+                .unwrap());
+            assert!(graph
+                .connect(node_ids[i], node_ids[j], rel_ids[(i + j) % 10 + 1])
+                .unwrap());
         }
     }
 
@@ -215,64 +203,34 @@ fn test_disconnect() {
     assert_eq!(count, 100);
     assert_eq!(sum, 4950);
     for i in 0..100 {
-        assert_eq!(
-            graph.connect(node_ids[i], node_ids[0], rel_ids[0]).unwrap(),
-            true
-        );
-        assert_eq!(
-            graph
-                .connect(node_ids[i], node_ids[10], rel_ids[10])
-                .unwrap(),
-            true
-        );
-        assert_eq!(
-            graph
-                .connect(node_ids[i], node_ids[20], rel_ids[20])
-                .unwrap(),
-            true
-        );
-        assert_eq!(
-            graph
-                .connect(node_ids[i], node_ids[30], rel_ids[30])
-                .unwrap(),
-            true
-        );
-        assert_eq!(
-            graph
-                .connect(node_ids[i], node_ids[40], rel_ids[40])
-                .unwrap(),
-            true
-        );
-        assert_eq!(
-            graph
-                .connect(node_ids[0], node_ids[(i + 15) % 100], rel_ids[5])
-                .unwrap(),
-            true
-        );
-        assert_eq!(
-            graph
-                .connect(node_ids[10], node_ids[(i + 15) % 100], rel_ids[15])
-                .unwrap(),
-            true
-        );
-        assert_eq!(
-            graph
-                .connect(node_ids[20], node_ids[(i + 15) % 100], rel_ids[25])
-                .unwrap(),
-            true
-        );
-        assert_eq!(
-            graph
-                .connect(node_ids[30], node_ids[(i + 15) % 100], rel_ids[35])
-                .unwrap(),
-            true
-        );
-        assert_eq!(
-            graph
-                .connect(node_ids[40], node_ids[(i + 15) % 100], rel_ids[45])
-                .unwrap(),
-            true
-        );
+        assert!(graph.connect(node_ids[i], node_ids[0], rel_ids[0]).unwrap());
+        assert!(graph
+            .connect(node_ids[i], node_ids[10], rel_ids[10])
+            .unwrap());
+        assert!(graph
+            .connect(node_ids[i], node_ids[20], rel_ids[20])
+            .unwrap());
+        assert!(graph
+            .connect(node_ids[i], node_ids[30], rel_ids[30])
+            .unwrap());
+        assert!(graph
+            .connect(node_ids[i], node_ids[40], rel_ids[40])
+            .unwrap());
+        assert!(graph
+            .connect(node_ids[0], node_ids[(i + 15) % 100], rel_ids[5])
+            .unwrap());
+        assert!(graph
+            .connect(node_ids[10], node_ids[(i + 15) % 100], rel_ids[15])
+            .unwrap());
+        assert!(graph
+            .connect(node_ids[20], node_ids[(i + 15) % 100], rel_ids[25])
+            .unwrap());
+        assert!(graph
+            .connect(node_ids[30], node_ids[(i + 15) % 100], rel_ids[35])
+            .unwrap());
+        assert!(graph
+            .connect(node_ids[40], node_ids[(i + 15) % 100], rel_ids[45])
+            .unwrap());
     }
     assert_eq!(graph.in_degree_of(node_ids[0]).unwrap(), 105);
     assert_eq!(graph.in_degree_of(node_ids[10]).unwrap(), 105);
@@ -285,54 +243,31 @@ fn test_disconnect() {
     assert_eq!(graph.out_degree_of(node_ids[30]).unwrap(), 105);
     assert_eq!(graph.out_degree_of(node_ids[40]).unwrap(), 105);
     for i in 0..100 {
-        assert_eq!(
-            graph
-                .disconnect(node_ids[i], node_ids[0], rel_ids[0])
-                .unwrap(),
-            true
-        );
-        assert_eq!(
-            graph
-                .disconnect(node_ids[0], node_ids[(i + 15) % 100], rel_ids[5])
-                .unwrap(),
-            true
-        );
-        assert_eq!(
-            graph
-                .disconnect(node_ids[i], node_ids[0], rel_ids[0])
-                .unwrap(),
-            false
-        );
-        assert_eq!(
-            graph
-                .disconnect(node_ids[0], node_ids[(i + 15) % 100], rel_ids[5])
-                .unwrap(),
-            false
-        );
-        assert_eq!(
-            graph
-                .disconnect(node_ids[i], node_ids[0], rel_ids[1])
-                .unwrap(),
-            false
-        );
-        assert_eq!(
-            graph
-                .disconnect(node_ids[0], node_ids[(i + 15) % 100], rel_ids[1])
-                .unwrap(),
-            false
-        );
+        assert!(graph
+            .disconnect(node_ids[i], node_ids[0], rel_ids[0])
+            .unwrap());
+        assert!(graph
+            .disconnect(node_ids[0], node_ids[(i + 15) % 100], rel_ids[5])
+            .unwrap());
+        assert!(!graph
+            .disconnect(node_ids[i], node_ids[0], rel_ids[0])
+            .unwrap());
+        assert!(!graph
+            .disconnect(node_ids[0], node_ids[(i + 15) % 100], rel_ids[5])
+            .unwrap());
+        assert!(!graph
+            .disconnect(node_ids[i], node_ids[0], rel_ids[1])
+            .unwrap());
+        assert!(!graph
+            .disconnect(node_ids[0], node_ids[(i + 15) % 100], rel_ids[1])
+            .unwrap());
     }
     assert_eq!(graph.in_degree_of(node_ids[0]).unwrap(), 4);
     assert_eq!(graph.out_degree_of(node_ids[0]).unwrap(), 4);
     for i in 1..10 {
         for j in 1..10 {
-            for k in 1..100 {
-                assert_eq!(
-                    graph
-                        .disconnect(node_ids[i], node_ids[j], rel_ids[k])
-                        .unwrap(),
-                    false
-                );
+            for &rel_id in &rel_ids[1..100] {
+                assert!(!graph.disconnect(node_ids[i], node_ids[j], rel_id).unwrap());
             }
         }
     }
@@ -387,12 +322,9 @@ fn test_remove_node() {
     assert_eq!(sum, 45);
     for i in 0..90 {
         for j in (i / 10 + 1) * 10..(i / 10 + 2) * 10 {
-            assert_eq!(
-                graph
-                    .connect(node_ids[i], node_ids[j], rel_ids[i / 10])
-                    .unwrap(),
-                true
-            );
+            assert!(graph
+                .connect(node_ids[i], node_ids[j], rel_ids[i / 10])
+                .unwrap());
         }
     }
     for i in 0..10 {
@@ -401,24 +333,24 @@ fn test_remove_node() {
         assert_eq!(graph.out_degree_of(node_ids[i + 90]).unwrap(), 0);
         assert_eq!(graph.in_degree_of(node_ids[i + 90]).unwrap(), 10);
     }
-    for i in 10..90 {
-        assert_eq!(graph.in_degree_of(node_ids[i]).unwrap(), 10);
-        assert_eq!(graph.out_degree_of(node_ids[i]).unwrap(), 10);
+    for &node_id in &node_ids[10..90] {
+        assert_eq!(graph.in_degree_of(node_id).unwrap(), 10);
+        assert_eq!(graph.out_degree_of(node_id).unwrap(), 10);
     }
     assert!(graph.remove_node(node_ids[0]).is_some());
     assert!(graph.out_degree_of(node_ids[0]).is_none());
     assert!(graph.in_degree_of(node_ids[0]).is_none());
     assert!(graph.remove_node(node_ids[0]).is_none());
-    for i in 10..20 {
-        assert_eq!(graph.in_degree_of(node_ids[i]).unwrap(), 9);
+    for &node_id in &node_ids[10..20] {
+        assert_eq!(graph.in_degree_of(node_id).unwrap(), 9);
     }
     for i in (10..90).step_by(10) {
         assert!(graph.remove_node(node_ids[i + i / 10]).is_some());
         assert!(graph.out_degree_of(node_ids[i + i / 10]).is_none());
         assert!(graph.in_degree_of(node_ids[i + i / 10]).is_none());
         assert!(graph.remove_node(node_ids[i + i / 10]).is_none());
-        for j in (i + 10)..(i + 20) {
-            assert_eq!(graph.in_degree_of(node_ids[j]).unwrap(), 9);
+        for &node_id in &node_ids[(i + 10)..(i + 20)] {
+            assert_eq!(graph.in_degree_of(node_id).unwrap(), 9);
         }
     }
     assert!(graph.remove_node(node_ids[99]).is_some());
@@ -459,12 +391,9 @@ fn test_remove_relation() {
     let mut in_deg: [usize; 10] = [0; 10];
     let mut out_deg: [usize; 10] = [0; 10];
     for i in 0..9 {
-        for j in i * 10..(i * 10 + 10) {
+        for &rel_id in &rel_ids[i * 10..(i * 10 + 10)] {
             for k in (i + 1)..10 {
-                assert_eq!(
-                    graph.connect(node_ids[i], node_ids[k], rel_ids[j]).unwrap(),
-                    true
-                );
+                assert!(graph.connect(node_ids[i], node_ids[k], rel_id).unwrap());
                 out_deg[i] += 1;
                 in_deg[k] += 1;
             }
@@ -478,8 +407,8 @@ fn test_remove_relation() {
         assert!(graph.remove_relation(rel_ids[i]).is_some());
         assert!(graph.remove_relation(rel_ids[i]).is_none());
         out_deg[i / 10] -= 9 - i / 10;
-        for j in (i / 10 + 1)..10 {
-            in_deg[j] -= 1;
+        for deg in &mut in_deg[(i / 10 + 1)..10] {
+            *deg -= 1;
         }
         for i in 0..10 {
             assert_eq!(graph.out_degree_of(node_ids[i]).unwrap(), out_deg[i]);
@@ -519,75 +448,64 @@ fn test_quantity() {
     assert_eq!(sum, 4999950000);
     for i in 0..100 {
         for j in 0..100 {
-            for k in 0..100 {
-                assert_eq!(
-                    graph.connect(node_ids[i], node_ids[j], rel_ids[k]).unwrap(),
-                    true
-                );
+            for &rel_id in &rel_ids[..100] {
+                assert!(graph.connect(node_ids[i], node_ids[j], rel_id).unwrap());
             }
         }
     }
-    for i in 0..100 {
-        assert_eq!(graph.in_degree_of(node_ids[i]).unwrap(), 10000);
-        assert_eq!(graph.out_degree_of(node_ids[i]).unwrap(), 10000);
+    for &node_id in &node_ids[..100] {
+        assert_eq!(graph.in_degree_of(node_id).unwrap(), 10000);
+        assert_eq!(graph.out_degree_of(node_id).unwrap(), 10000);
     }
-    for i in 0..50 {
-        assert!(graph.remove_relation(rel_ids[i]).is_some());
-        assert!(graph.remove_relation(rel_ids[i]).is_none());
+    for &rel_id in &rel_ids[..50] {
+        assert!(graph.remove_relation(rel_id).is_some());
+        assert!(graph.remove_relation(rel_id).is_none());
     }
-    for i in 0..100 {
-        assert_eq!(graph.in_degree_of(node_ids[i]).unwrap(), 5000);
-        assert_eq!(graph.out_degree_of(node_ids[i]).unwrap(), 5000);
+    for &node_id in &node_ids[..100] {
+        assert_eq!(graph.in_degree_of(node_id).unwrap(), 5000);
+        assert_eq!(graph.out_degree_of(node_id).unwrap(), 5000);
     }
     for i in 100..200 {
         for j in 0..100 {
-            for k in 1000..1050 {
-                assert_eq!(
-                    graph.connect(node_ids[i], node_ids[j], rel_ids[k]).unwrap(),
-                    true
-                );
+            for &rel_id in &rel_ids[1000..1050] {
+                assert!(graph.connect(node_ids[i], node_ids[j], rel_id).unwrap());
             }
         }
     }
-    for i in 0..100 {
-        assert_eq!(graph.in_degree_of(node_ids[i]).unwrap(), 10000);
-        assert_eq!(graph.out_degree_of(node_ids[i]).unwrap(), 5000);
+    for &node_id in &node_ids[..100] {
+        assert_eq!(graph.in_degree_of(node_id).unwrap(), 10000);
+        assert_eq!(graph.out_degree_of(node_id).unwrap(), 5000);
     }
-    for i in 100..200 {
-        assert_eq!(graph.in_degree_of(node_ids[i]).unwrap(), 0);
-        assert_eq!(graph.out_degree_of(node_ids[i]).unwrap(), 5000);
+    for &node_id in &node_ids[100..200] {
+        assert_eq!(graph.in_degree_of(node_id).unwrap(), 0);
+        assert_eq!(graph.out_degree_of(node_id).unwrap(), 5000);
     }
-    for i in 200..100000 {
-        assert_eq!(graph.in_degree_of(node_ids[i]).unwrap(), 0);
-        assert_eq!(graph.out_degree_of(node_ids[i]).unwrap(), 0);
+    for &node_id in &node_ids[200..100000] {
+        assert_eq!(graph.in_degree_of(node_id).unwrap(), 0);
+        assert_eq!(graph.out_degree_of(node_id).unwrap(), 0);
     }
-    for i in 0..50 {
-        assert!(graph.remove_node(node_ids[i]).is_some());
-        assert!(graph.remove_node(node_ids[i]).is_none());
+    for &node_id in &node_ids[..50] {
+        assert!(graph.remove_node(node_id).is_some());
+        assert!(graph.remove_node(node_id).is_none());
     }
-    for i in 50..100 {
-        assert_eq!(graph.in_degree_of(node_ids[i]).unwrap(), 7500);
-        assert_eq!(graph.out_degree_of(node_ids[i]).unwrap(), 2500);
+    for &node_id in &node_ids[50..100] {
+        assert_eq!(graph.in_degree_of(node_id).unwrap(), 7500);
+        assert_eq!(graph.out_degree_of(node_id).unwrap(), 2500);
     }
-    for i in 100..200 {
-        assert_eq!(graph.in_degree_of(node_ids[i]).unwrap(), 0);
-        assert_eq!(graph.out_degree_of(node_ids[i]).unwrap(), 2500);
+    for &node_id in &node_ids[100..200] {
+        assert_eq!(graph.in_degree_of(node_id).unwrap(), 0);
+        assert_eq!(graph.out_degree_of(node_id).unwrap(), 2500);
     }
     for i in 100..200 {
         for j in 50..100 {
-            for k in 1000..1050 {
-                assert_eq!(
-                    graph
-                        .disconnect(node_ids[i], node_ids[j], rel_ids[k])
-                        .unwrap(),
-                    true
-                );
+            for &rel_id in &rel_ids[1000..1050] {
+                assert!(graph.disconnect(node_ids[i], node_ids[j], rel_id).unwrap());
             }
         }
     }
-    for i in 50..100 {
-        assert_eq!(graph.in_degree_of(node_ids[i]).unwrap(), 2500);
-        assert_eq!(graph.out_degree_of(node_ids[i]).unwrap(), 2500);
+    for &node_id in &node_ids[50..100] {
+        assert_eq!(graph.in_degree_of(node_id).unwrap(), 2500);
+        assert_eq!(graph.out_degree_of(node_id).unwrap(), 2500);
     }
 }
 
@@ -620,19 +538,16 @@ fn test_iterator() {
     }
     assert_eq!(count, 100000);
     assert_eq!(sum, 4999950000);
-    for k in 0..100 {
+    for &rel_id in &rel_ids[..100] {
         for i in 0..10 {
             for j in 1000..1020 {
-                assert_eq!(
-                    graph.connect(node_ids[i], node_ids[j], rel_ids[k]).unwrap(),
-                    true
-                );
+                assert!(graph.connect(node_ids[i], node_ids[j], rel_id).unwrap());
             }
         }
     }
-    for k in 0..100 {
+    for &rel_id in &rel_ids[..100] {
         let mut count = 0;
-        for _ in graph.iter_relation_edges(rel_ids[k]).unwrap() {
+        for _ in graph.iter_relation_edges(rel_id).unwrap() {
             count += 1;
         }
         assert_eq!(count, 200);
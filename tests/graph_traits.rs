@@ -0,0 +1,147 @@
+use graphfruit::codec::DefaultCodec;
+use graphfruit::concurrent::ConcurrentGraph;
+use graphfruit::edge::Edge;
+use graphfruit::graph::Graph;
+use graphfruit::history::History;
+use graphfruit::mapped::MappedGraph;
+use graphfruit::node::NodeId;
+use graphfruit::read::GraphRead;
+use graphfruit::store::PersistentGraph;
+use graphfruit::write::GraphWrite;
+use std::thread;
+
+/// Build a path of `len` nodes, written once for every backend.
+fn build_path<G: GraphWrite>(graph: &mut G, len: i32) -> Result<Vec<NodeId>, G::Error> {
+    let relation_id = graph.add_relation(0)?;
+    let mut nodes = vec![graph.add_node(0)?];
+    for i in 1..len {
+        let node_id = graph.add_node(i)?;
+        graph.connect(*nodes.last().unwrap(), node_id, relation_id)?;
+        nodes.push(node_id);
+    }
+    Ok(nodes)
+}
+
+/// Count the edges of a graph, written once for every backend.
+fn nr_edges<G: GraphRead>(graph: &G) -> usize {
+    graph
+        .relation_ids()
+        .map(|relation_id| graph.iter_relation_edges(relation_id).unwrap().count())
+        .sum()
+}
+
+/// Count the nodes reachable from `start`, written once for every backend.
+fn reachable<G: GraphRead>(graph: &G, start: NodeId) -> usize {
+    let mut seen = vec![start];
+    let mut stack = vec![start];
+    while let Some(node_id) = stack.pop() {
+        for (next, _) in graph.iter_next_nodes(node_id).into_iter().flatten() {
+            if !seen.contains(&next) {
+                seen.push(next);
+                stack.push(next);
+            }
+        }
+    }
+    seen.len()
+}
+
+#[test]
+fn test_graph_read() {
+    let mut graph = Graph::new();
+    let nodes = build_path(&mut graph, 4).unwrap();
+    let relation_id = GraphRead::relation_ids(&graph).next().unwrap();
+
+    assert_eq!(GraphRead::nr_nodes(&graph), 4);
+    assert_eq!(GraphRead::nr_relations(&graph), 1);
+    assert_eq!(nr_edges(&graph), 3);
+    assert!(graph.contains_edge(Edge::new(nodes[0], nodes[1]), relation_id));
+    assert!(!graph.contains_edge(Edge::new(nodes[1], nodes[0]), relation_id));
+    assert!(graph
+        .reversed()
+        .contains_edge(Edge::new(nodes[1], nodes[0]), relation_id));
+    assert_eq!(GraphRead::out_degree_of(&graph, nodes[3]), Some(0));
+
+    let snapshot = graph.snapshot();
+    assert_eq!(nr_edges(&*snapshot), 3);
+    let mut transaction = graph.begin();
+    build_path(&mut *transaction, 2).unwrap();
+    assert_eq!(nr_edges(&*transaction), 4);
+}
+
+#[test]
+fn test_history() {
+    let mut history = History::new(Graph::new());
+    let nodes = build_path(&mut history, 3).unwrap();
+    history.checkpoint("path");
+    assert_eq!(nr_edges(&history), 2);
+    assert_eq!(GraphRead::in_degree_of(&history, nodes[1]), Some(1));
+
+    GraphWrite::remove_node(&mut history, nodes[1]).unwrap();
+    history.checkpoint("remove");
+    assert_eq!(nr_edges(&history), 0);
+    history.undo();
+    assert_eq!(nr_edges(&history), 2);
+}
+
+#[test]
+fn test_persistent_graph() {
+    let dir = tempfile::tempdir().unwrap();
+    {
+        let mut store = PersistentGraph::open(dir.path()).unwrap();
+        let nodes = build_path(&mut store, 5).unwrap();
+        let relation_id = GraphRead::relation_ids(&store).next().unwrap();
+        GraphWrite::disconnect(&mut store, nodes[3], nodes[4], relation_id).unwrap();
+        assert!(GraphWrite::connect(&mut store, nodes[0], NodeId::new(99), relation_id).is_err());
+    }
+    let store = PersistentGraph::open(dir.path()).unwrap();
+    assert_eq!(GraphRead::nr_nodes(&store), 5);
+    assert_eq!(nr_edges(&store), 3);
+}
+
+#[test]
+fn test_concurrent_graph() {
+    let graph = ConcurrentGraph::new();
+    let paths: Vec<_> = thread::scope(|scope| {
+        let handles: Vec<_> = (0..4)
+            .map(|_| scope.spawn(|| build_path(&mut &graph, 3).unwrap()))
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    });
+    assert_eq!(GraphRead::nr_nodes(&graph), 12);
+    assert_eq!(GraphRead::nr_relations(&graph), 4);
+    assert_eq!(nr_edges(&graph), 8);
+    let nodes = &paths[0];
+    assert_eq!(GraphRead::out_degree_of(&graph, nodes[0]), Some(1));
+    let info =
+        GraphRead::with_node_info(&graph, nodes[2], |info| info.downcast_ref::<i32>().copied());
+    assert_eq!(info, Some(Some(2)));
+    assert_eq!(reachable(&graph.reversed(), nodes[2]), 3);
+
+    let graph = graph.into_graph();
+    assert_eq!(nr_edges(&graph), 8);
+}
+
+#[test]
+fn test_mapped_graph() {
+    let mut graph = Graph::new();
+    let nodes = build_path(&mut graph, 4).unwrap();
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("graph.map");
+    MappedGraph::write(&graph, &DefaultCodec, &path).unwrap();
+
+    let mapped = MappedGraph::open(&path).unwrap();
+    let relation_id = GraphRead::relation_ids(&mapped).next().unwrap();
+    assert_eq!(nr_edges(&mapped), 3);
+    assert!(GraphRead::contains_edge(
+        &mapped,
+        Edge::new(nodes[1], nodes[2]),
+        relation_id
+    ));
+    let odd = mapped.filter_nodes(|_, info| info.downcast_ref::<i32>() != Some(&2));
+    assert_eq!(reachable(&odd, nodes[0]), 2);
+    assert_eq!(reachable(&mapped.reversed(), nodes[3]), 4);
+    let info = GraphRead::with_relation_info(&mapped, relation_id, |info| {
+        info.downcast_ref::<i32>().copied()
+    });
+    assert_eq!(info, Some(Some(0)));
+}
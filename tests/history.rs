@@ -0,0 +1,119 @@
+use graphfruit::edge::Edge;
+use graphfruit::graph::Graph;
+use graphfruit::history::History;
+use std::collections::HashSet;
+
+fn edges(graph: &Graph) -> HashSet<(Edge, u64)> {
+    graph
+        .iter_relations()
+        .flat_map(|(id, relation)| relation.iter_edges().map(move |e| (*e, id.get())))
+        .collect()
+}
+
+#[test]
+fn test_undo_redo() {
+    let mut history = History::new(Graph::new());
+    let n1 = history.add_node(1);
+    let n2 = history.add_node(2);
+    let r1 = history.add_relation(1);
+    history.connect(n1, n2, r1).unwrap();
+    assert!(history.checkpoint("setup"));
+    assert!(!history.checkpoint("empty"));
+
+    history.connect(n2, n1, r1).unwrap();
+    history.disconnect(n1, n2, r1).unwrap();
+    assert!(history.checkpoint("flip"));
+
+    assert_eq!(history.nr_undo(), 2);
+    assert_eq!(history.undo().as_deref(), Some("flip"));
+    assert!(history
+        .graph()
+        .relation(r1)
+        .unwrap()
+        .contains_edge(&Edge::new(n1, n2)));
+    assert!(!history
+        .graph()
+        .relation(r1)
+        .unwrap()
+        .contains_edge(&Edge::new(n2, n1)));
+
+    assert_eq!(history.undo().as_deref(), Some("setup"));
+    assert_eq!(history.graph().nr_nodes(), 0);
+    assert_eq!(history.graph().nr_relations(), 0);
+    assert_eq!(history.undo(), None);
+
+    assert_eq!(history.redo().as_deref(), Some("setup"));
+    assert_eq!(history.redo().as_deref(), Some("flip"));
+    assert_eq!(history.redo(), None);
+    assert_eq!(history.graph().nr_nodes(), 2);
+    assert_eq!(history.graph().out_degree_of(n2), Some(1));
+    assert_eq!(history.graph().out_degree_of(n1), Some(0));
+}
+
+#[test]
+fn test_undo_remove_node() {
+    let mut history = History::new(Graph::new());
+    let hub = history.add_node("hub".to_string());
+    let r1 = history.add_relation(1);
+    let r2 = history.add_relation(2);
+    let others: Vec<_> = (0..5).map(|i| history.add_node(i)).collect();
+    for (i, other) in others.iter().enumerate() {
+        history.connect(hub, *other, r1).unwrap();
+        if i % 2 == 0 {
+            history.connect(*other, hub, r2).unwrap();
+        }
+    }
+    history.connect(hub, hub, r1).unwrap();
+    history.connect(hub, hub, r2).unwrap();
+    history.checkpoint("build");
+    let before = edges(history.graph());
+
+    assert!(history.remove_node(hub).is_some());
+    assert_eq!(history.graph().nr_nodes(), 5);
+    assert!(edges(history.graph()).is_empty());
+    history.checkpoint("remove hub");
+
+    assert_eq!(history.undo().as_deref(), Some("remove hub"));
+    assert_eq!(edges(history.graph()), before);
+    assert_eq!(
+        history
+            .graph()
+            .node_info(hub)
+            .unwrap()
+            .downcast_ref::<String>()
+            .unwrap(),
+        "hub"
+    );
+    assert_eq!(history.graph().out_degree_of(hub), Some(7));
+    assert_eq!(history.graph().in_degree_of(hub), Some(5));
+
+    history.redo();
+    assert!(!history.graph().contains_node(hub));
+    history.undo();
+
+    // A new mutation drops everything that could be redone.
+    history.remove_relation(r2);
+    assert_eq!(history.nr_redo(), 0);
+    assert_eq!(history.undo().as_deref(), Some(""));
+    assert_eq!(edges(history.graph()), before);
+}
+
+#[test]
+fn test_bounded_history() {
+    let mut history = History::with_max_size(Graph::new(), 3);
+    for i in 0..10 {
+        history.add_node(i);
+        history.checkpoint(format!("step {}", i));
+    }
+    assert_eq!(history.nr_undo(), 3);
+    assert_eq!(history.undo().as_deref(), Some("step 9"));
+    assert_eq!(history.undo().as_deref(), Some("step 8"));
+    assert_eq!(history.undo().as_deref(), Some("step 7"));
+    assert_eq!(history.undo(), None);
+    assert_eq!(history.graph().nr_nodes(), 7);
+
+    history.redo();
+    history.set_max_size(1);
+    assert_eq!(history.nr_undo(), 1);
+    assert_eq!(history.nr_redo(), 2);
+}
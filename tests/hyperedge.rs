@@ -0,0 +1,215 @@
+use graphfruit::codec::DefaultCodec;
+use graphfruit::diff::GraphDiff;
+use graphfruit::errors::ConnectError;
+use graphfruit::event::{EventFilter, GraphEvent};
+use graphfruit::graph::Graph;
+use graphfruit::history::History;
+use graphfruit::hyperedge::{Hyperedge, HyperedgeKind};
+use graphfruit::node::NodeId;
+use graphfruit::store::PersistentGraph;
+use std::collections::HashSet;
+
+fn hyperedges_of(graph: &Graph, node_id: NodeId) -> HashSet<u64> {
+    graph
+        .iter_hyperedges_of(node_id)
+        .unwrap()
+        .map(|(edge_id, _)| *edge_id)
+        .collect()
+}
+
+#[test]
+fn test_connect_hyperedge() {
+    let mut graph = Graph::new();
+    let a = graph.add_node(1);
+    let b = graph.add_node(2);
+    let c = graph.add_node(3);
+    let reaction = graph.add_hyper_relation("reaction".to_string(), HyperedgeKind::Ordered);
+    let meeting = graph.add_hyper_relation("meeting".to_string(), HyperedgeKind::Unordered);
+    let binary = graph.add_relation(1);
+
+    let e1 = graph
+        .connect_hyperedge(reaction, Hyperedge::new(vec![b, a, a], vec![c]))
+        .unwrap();
+    let e2 = graph
+        .connect_hyperedge(meeting, Hyperedge::new(vec![c, a, c], vec![]))
+        .unwrap();
+    let (hyperedge, relation_id) = graph.hyperedge(e1).unwrap();
+    assert_eq!(relation_id, reaction);
+    assert_eq!(hyperedge.sources(), &[b, a, a]);
+    assert_eq!(hyperedge.targets(), &[c]);
+    assert_eq!(graph.hyperedge(e2).unwrap().0.sources(), &[a, c]);
+
+    assert_eq!(
+        hyperedges_of(&graph, a),
+        [*e1, *e2].iter().copied().collect()
+    );
+    assert_eq!(hyperedges_of(&graph, b), [*e1].iter().copied().collect());
+    assert!(graph
+        .relation(reaction)
+        .unwrap()
+        .iter_edges()
+        .next()
+        .is_none());
+
+    assert!(matches!(
+        graph.connect(a, b, reaction),
+        Err(ConnectError::HyperRelation)
+    ));
+    assert!(matches!(
+        graph.connect_hyperedge(binary, Hyperedge::new(vec![a], vec![b])),
+        Err(ConnectError::NotHyperRelation)
+    ));
+    assert!(matches!(
+        graph.connect_hyperedge(reaction, Hyperedge::new(vec![a], vec![NodeId::new(9)])),
+        Err(ConnectError::InvalidDstNodeId)
+    ));
+
+    assert!(graph.disconnect_hyperedge(e1));
+    assert!(!graph.disconnect_hyperedge(e1));
+    assert!(hyperedges_of(&graph, b).is_empty());
+}
+
+#[test]
+fn test_remove_node_and_relation() {
+    let mut graph = Graph::new();
+    let a = graph.add_node(1);
+    let b = graph.add_node(2);
+    let c = graph.add_node(3);
+    let r1 = graph.add_hyper_relation(1, HyperedgeKind::Ordered);
+    let r2 = graph.add_hyper_relation(2, HyperedgeKind::Ordered);
+    let e1 = graph
+        .connect_hyperedge(r1, Hyperedge::new(vec![a, b], vec![c]))
+        .unwrap();
+    let e2 = graph
+        .connect_hyperedge(r1, Hyperedge::new(vec![b], vec![c]))
+        .unwrap();
+    let e3 = graph
+        .connect_hyperedge(r2, Hyperedge::new(vec![c], vec![]))
+        .unwrap();
+    let (_, events) = graph.subscribe_channel(EventFilter::all());
+
+    graph.remove_node(a);
+    assert!(graph.hyperedge(e1).is_none());
+    assert!(graph.hyperedge(e2).is_some());
+    assert_eq!(
+        hyperedges_of(&graph, c),
+        [*e2, *e3].iter().copied().collect()
+    );
+
+    graph.remove_relation(r1);
+    assert_eq!(hyperedges_of(&graph, b), HashSet::new());
+    assert_eq!(hyperedges_of(&graph, c), [*e3].iter().copied().collect());
+    assert!(graph.hyperedge(e2).is_none());
+    assert!(!graph.disconnect_hyperedge(e2));
+    assert_eq!(graph.hyperedge(e3).unwrap().1, r2);
+
+    let events: Vec<_> = events.try_iter().collect();
+    assert_eq!(
+        events[0],
+        GraphEvent::HyperedgeDisconnected {
+            edge_id: e1,
+            relation_id: r1
+        }
+    );
+    assert_eq!(events[1], GraphEvent::NodeRemoved(a));
+}
+
+#[test]
+fn test_undo_hyperedges() {
+    let mut history = History::new(Graph::new());
+    let a = history.add_node(1);
+    let b = history.add_node(2);
+    let r1 = history.add_hyper_relation(1, HyperedgeKind::Unordered);
+    let e1 = history
+        .connect_hyperedge(r1, Hyperedge::new(vec![b, a], vec![b]))
+        .unwrap();
+    history.checkpoint("connect");
+
+    history.remove_node(a);
+    history.checkpoint("remove node");
+    history.remove_relation(r1);
+    history.checkpoint("remove relation");
+    assert!(history.graph().hyperedge(e1).is_none());
+
+    history.undo();
+    history.undo();
+    let (hyperedge, _) = history.graph().hyperedge(e1).unwrap();
+    assert_eq!(hyperedge.sources(), &[a, b]);
+    assert_eq!(
+        hyperedges_of(history.graph(), a),
+        [*e1].iter().copied().collect()
+    );
+
+    history.disconnect_hyperedge(e1);
+    history.undo();
+    assert!(history.graph().hyperedge(e1).is_some());
+    history.undo();
+    assert!(history.graph().hyperedge(e1).is_none());
+}
+
+#[test]
+fn test_persisted_hyperedges() {
+    let dir = tempfile::tempdir().unwrap();
+    let (a, b, r1, e1, e2) = {
+        let mut store = PersistentGraph::open(dir.path()).unwrap();
+        let a = store.add_node(1).unwrap();
+        let b = store.add_node(2).unwrap();
+        let r1 = store
+            .add_hyper_relation(1, HyperedgeKind::Unordered)
+            .unwrap();
+        let e1 = store
+            .connect_hyperedge(r1, Hyperedge::new(vec![b, a], vec![]))
+            .unwrap();
+        let e2 = store
+            .connect_hyperedge(r1, Hyperedge::new(vec![a], vec![b]))
+            .unwrap();
+        (a, b, r1, e1, e2)
+    };
+
+    let mut store = PersistentGraph::open(dir.path()).unwrap();
+    let relation = store.graph().relation(r1).unwrap();
+    assert_eq!(relation.hyperedge_kind(), Some(HyperedgeKind::Unordered));
+    assert_eq!(relation.hyperedge(e1).unwrap().sources(), &[a, b]);
+    store.checkpoint().unwrap();
+    let mut store = PersistentGraph::open(dir.path()).unwrap();
+    assert!(store.disconnect_hyperedge(e1).unwrap());
+    assert_eq!(
+        hyperedges_of(store.graph(), b),
+        [*e2].iter().copied().collect()
+    );
+    let e3 = store
+        .connect_hyperedge(r1, Hyperedge::new(vec![b], vec![]))
+        .unwrap();
+    assert!(e3 > e2);
+}
+
+#[test]
+fn test_diff_hyperedges() {
+    let mut graph = Graph::new();
+    let a = graph.add_node(1);
+    let b = graph.add_node(2);
+    let r1 = graph.add_hyper_relation(1, HyperedgeKind::Unordered);
+    let e1 = graph
+        .connect_hyperedge(r1, Hyperedge::new(vec![b, a], vec![]))
+        .unwrap();
+    let e2 = graph
+        .connect_hyperedge(r1, Hyperedge::new(vec![a], vec![b]))
+        .unwrap();
+    let mut replica = Graph::new();
+    replica.apply(&replica.diff(&graph)).unwrap();
+
+    assert!(graph.disconnect_hyperedge(e1));
+    let e3 = graph
+        .connect_hyperedge(r1, Hyperedge::new(vec![b], vec![]))
+        .unwrap();
+    let bytes = replica.diff(&graph).encode(&DefaultCodec).unwrap();
+    replica
+        .apply(&GraphDiff::decode(&bytes, &DefaultCodec).unwrap())
+        .unwrap();
+    assert!(replica.hyperedge(e1).is_none());
+    assert_eq!(replica.hyperedge(e2).unwrap().0.targets(), &[b]);
+    assert_eq!(
+        hyperedges_of(&replica, b),
+        [*e2, *e3].iter().copied().collect()
+    );
+}
@@ -0,0 +1,152 @@
+use graphfruit::graph::Graph;
+use graphfruit::history::History;
+use graphfruit::node::{NodeId, NodeInfo};
+use graphfruit::store::PersistentGraph;
+use std::panic::{self, AssertUnwindSafe};
+
+fn user_id(info: &dyn NodeInfo) -> Option<u32> {
+    info.downcast_ref::<u32>().copied()
+}
+
+fn sorted(nodes: impl Iterator<Item = NodeId>) -> Vec<NodeId> {
+    let mut nodes: Vec<_> = nodes.collect();
+    nodes.sort_by_key(|id| **id);
+    nodes
+}
+
+#[test]
+fn test_hash_index() {
+    let mut graph = Graph::new();
+    let index = graph.add_hash_index(user_id);
+    let a = graph.add_node(42u32);
+    let b = graph.add_node(7u32);
+    let c = graph.add_node(42u32);
+    graph.add_node("not a user".to_string());
+
+    assert_eq!(sorted(graph.lookup(index, &42)), [a, c]);
+    assert_eq!(sorted(graph.lookup(index, &7)), [b]);
+    assert_eq!(graph.lookup(index, &1).count(), 0);
+
+    graph.remove_node(c);
+    assert_eq!(sorted(graph.lookup(index, &42)), [a]);
+
+    let old = graph.replace_node_info(a, 8u32).unwrap().unwrap();
+    assert_eq!(old.downcast_ref::<u32>(), Some(&42));
+    assert_eq!(graph.lookup(index, &42).count(), 0);
+    assert_eq!(sorted(graph.lookup(index, &8)), [a]);
+
+    let doubled = graph.with_node_info_mut(b, |id: &mut u32| {
+        *id *= 2;
+        *id
+    });
+    assert_eq!(doubled.unwrap(), Some(14));
+    assert_eq!(graph.lookup(index, &7).count(), 0);
+    assert_eq!(sorted(graph.lookup(index, &14)), [b]);
+
+    let snapshot = graph.snapshot();
+    graph
+        .with_node_info_mut(b, |id: &mut u32| *id += 1)
+        .unwrap();
+    assert_eq!(sorted(graph.lookup(index, &15)), [b]);
+    assert_eq!(sorted(snapshot.lookup(index, &14)), [b]);
+    assert!(graph
+        .replace_node_info(NodeId::new(99), 1u32)
+        .unwrap()
+        .is_none());
+}
+
+#[test]
+fn test_panicking_info_mutation() {
+    let mut graph = Graph::new();
+    let index = graph.add_hash_index(user_id);
+    let a = graph.add_node(1u32);
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        graph.with_node_info_mut(a, |id: &mut u32| {
+            *id = 2;
+            panic!("mutation failed");
+        })
+    }));
+    assert!(result.is_err());
+    assert_eq!(graph.node_info(a).unwrap().downcast_ref::<u32>(), Some(&1));
+    assert_eq!(sorted(graph.lookup(index, &1)), [a]);
+    assert!(graph.remove_node(a).is_some());
+}
+
+#[test]
+fn test_ordered_index() {
+    let mut graph = Graph::new();
+    let nodes: Vec<_> = (0..10u32).map(|id| graph.add_node(id * 10)).collect();
+    let index = graph.add_ordered_index(user_id);
+
+    let range: Vec<_> = graph.range(index, 20..50).collect();
+    assert_eq!(range, nodes[2..5]);
+    let range: Vec<_> = graph.range(index, 75..).collect();
+    assert_eq!(range, nodes[8..]);
+    assert_eq!(sorted(graph.lookup(index, &30)), [nodes[3]]);
+
+    graph.replace_node_info(nodes[0], 45u32).unwrap();
+    let range: Vec<_> = graph.range(index, 40..=50).collect();
+    assert_eq!(range, [nodes[4], nodes[0], nodes[5]]);
+
+    assert!(graph.remove_index(index));
+    assert!(!graph.remove_index(index));
+    assert_eq!(graph.range(index, ..).count(), 0);
+}
+
+#[test]
+fn test_index_follows_rollback_and_undo() {
+    let mut graph = Graph::new();
+    let index = graph.add_hash_index(user_id);
+    let a = graph.add_node(1u32);
+    let result: Result<(), ()> = graph.transaction(|transaction| {
+        transaction.add_node(2u32);
+        transaction.replace_node_info(a, 3u32).unwrap();
+        Err(())
+    });
+    assert!(result.is_err());
+    assert_eq!(sorted(graph.lookup(index, &1)), [a]);
+    assert_eq!(graph.lookup(index, &2).count(), 0);
+    assert_eq!(graph.lookup(index, &3).count(), 0);
+
+    let mut history = History::new(graph);
+    history.replace_node_info(a, 5u32).unwrap();
+    history.checkpoint("replace");
+    assert_eq!(sorted(history.graph().lookup(index, &5)), [a]);
+    history.undo();
+    assert_eq!(sorted(history.graph().lookup(index, &1)), [a]);
+    history.redo();
+    assert_eq!(history.graph().lookup(index, &1).count(), 0);
+}
+
+#[test]
+fn test_index_after_reopen() {
+    let dir = tempfile::tempdir().unwrap();
+    let (a, b) = {
+        let mut store = PersistentGraph::open(dir.path()).unwrap();
+        let a = store.add_node(1u32).unwrap();
+        let b = store.add_node(2u32).unwrap();
+        store.replace_node_info(a, 10u32).unwrap();
+        (a, b)
+    };
+
+    let mut store = PersistentGraph::open(dir.path()).unwrap();
+    let index = store.add_hash_index(user_id);
+    assert_eq!(sorted(store.graph().lookup(index, &10)), [a]);
+    store.replace_node_info(b, 20u32).unwrap();
+    assert_eq!(sorted(store.graph().lookup(index, &20)), [b]);
+    assert_eq!(store.graph().lookup(index, &2).count(), 0);
+}
+
+#[test]
+fn test_index_follows_applied_diff() {
+    let mut graph = Graph::new();
+    let index = graph.add_hash_index(user_id);
+    let a = graph.add_node(1u32);
+    graph.add_node(2u32);
+
+    let mut changed = graph.clone();
+    changed.replace_node_info(a, 20u32).unwrap();
+    graph.apply(&graph.diff(&changed)).unwrap();
+    assert_eq!(sorted(graph.lookup(index, &20)), [a]);
+    assert_eq!(graph.lookup(index, &1).count(), 0);
+}
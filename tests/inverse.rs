@@ -0,0 +1,218 @@
+use graphfruit::edge::Edge;
+use graphfruit::errors::{ConnectError, SchemaViolation};
+use graphfruit::graph::Graph;
+use graphfruit::history::History;
+use graphfruit::relation::RelationKind;
+use graphfruit::schema::{Cardinality, RelationSchema};
+use graphfruit::store::PersistentGraph;
+
+#[test]
+fn test_connect_and_disconnect() {
+    let mut graph = Graph::new();
+    let parent = graph.add_node("parent".to_string());
+    let child = graph.add_node("child".to_string());
+    let parent_of = graph.add_relation(1);
+    let child_of = graph.add_relation(2);
+    graph.set_inverse(parent_of, child_of).unwrap();
+    assert_eq!(graph.inverse_of(parent_of), Some(child_of));
+    assert_eq!(graph.inverse_of(child_of), Some(parent_of));
+
+    assert!(graph.connect(parent, child, parent_of).unwrap());
+    assert!(graph
+        .relation(child_of)
+        .unwrap()
+        .contains_edge(&Edge::new(child, parent)));
+    assert!(!graph.connect(child, parent, child_of).unwrap());
+
+    assert!(graph.disconnect(child, parent, child_of).unwrap());
+    assert_eq!(graph.relation(parent_of).unwrap().iter_edges().count(), 0);
+
+    graph.connect(child, parent, child_of).unwrap();
+    graph.remove_node(child);
+    assert_eq!(graph.relation(parent_of).unwrap().iter_edges().count(), 0);
+    assert_eq!(graph.relation(child_of).unwrap().iter_edges().count(), 0);
+
+    graph.remove_relation(child_of);
+    assert_eq!(graph.inverse_of(parent_of), None);
+}
+
+#[test]
+fn test_existing_edges_are_mirrored() {
+    let mut graph = Graph::new();
+    let a = graph.add_node(1);
+    let b = graph.add_node(2);
+    let c = graph.add_node(3);
+    let r1 = graph.add_relation(1);
+    let r2 = graph.add_relation(2);
+    graph.connect(a, b, r1).unwrap();
+    graph.connect(c, b, r2).unwrap();
+
+    graph.set_inverse(r1, r2).unwrap();
+    let r1_edges = graph.relation(r1).unwrap();
+    assert!(r1_edges.contains_edge(&Edge::new(b, c)));
+    let r2_edges = graph.relation(r2).unwrap();
+    assert!(r2_edges.contains_edge(&Edge::new(b, a)));
+
+    assert_eq!(graph.remove_inverse(r2), Some(r1));
+    graph.connect(a, c, r1).unwrap();
+    assert!(!graph.relation(r2).unwrap().contains_edge(&Edge::new(c, a)));
+}
+
+#[test]
+fn test_rejected_pairings() {
+    let mut graph = Graph::new();
+    let a = graph.add_node(1);
+    let b = graph.add_node(2);
+    let r1 = graph.add_relation(1);
+    let r2 = graph.add_relation(2);
+    let r3 = graph.add_relation_of_kind(3, RelationKind::Antisymmetric);
+
+    assert!(matches!(
+        graph.set_inverse(r1, r1),
+        Err(ConnectError::InverseConflict)
+    ));
+    graph.set_inverse(r1, r2).unwrap();
+    graph.set_inverse(r2, r1).unwrap();
+    assert!(matches!(
+        graph.set_inverse(r1, r3),
+        Err(ConnectError::InverseConflict)
+    ));
+    graph.remove_inverse(r1);
+
+    // Mirroring `a -> b` into `r3` would break its antisymmetry.
+    graph.connect(a, b, r1).unwrap();
+    graph.connect(a, b, r3).unwrap();
+    assert!(matches!(
+        graph.set_inverse(r1, r3),
+        Err(ConnectError::ReverseEdgeExists)
+    ));
+    assert_eq!(graph.inverse_of(r1), None);
+    assert_eq!(graph.relation(r3).unwrap().iter_edges().count(), 1);
+
+    // A connect that fails in the inverse changes neither relation.
+    let r4 = graph.add_relation(4);
+    graph.set_inverse(r4, r3).unwrap();
+    assert!(graph.connect(b, a, r3).is_err());
+    assert!(graph.connect(a, b, r4).is_err());
+    assert!(!graph.relation(r4).unwrap().contains_edge(&Edge::new(a, b)));
+
+    // The mirrored edges conflict with each other rather than with r5 or r6
+    let c = graph.add_node(3);
+    let r5 = graph.add_relation_of_kind(5, RelationKind::Antisymmetric);
+    let r6 = graph.add_relation(6);
+    graph.set_relation_schema(
+        r6,
+        RelationSchema::new().with_cardinality(Cardinality::ManyToOne),
+    );
+    let r7 = graph.add_relation(7);
+    graph.connect(a, b, r7).unwrap();
+    graph.connect(b, a, r7).unwrap();
+    assert!(matches!(
+        graph.set_inverse(r7, r5),
+        Err(ConnectError::ReverseEdgeExists)
+    ));
+    graph.connect(c, b, r7).unwrap();
+    assert!(matches!(
+        graph.set_inverse(r7, r6),
+        Err(ConnectError::SchemaViolation(SchemaViolation::MaxOutDegree))
+    ));
+    assert_eq!(graph.relation(r5).unwrap().iter_edges().count(), 0);
+    assert_eq!(graph.relation(r6).unwrap().iter_edges().count(), 0);
+}
+
+#[test]
+fn test_undo_remove_relation() {
+    let mut graph = Graph::new();
+    let a = graph.add_node(1);
+    let b = graph.add_node(2);
+    let r1 = graph.add_relation(1);
+    let r2 = graph.add_relation(2);
+    graph.set_inverse(r1, r2).unwrap();
+    graph.connect(a, b, r1).unwrap();
+
+    let mut history = History::new(graph);
+    history.remove_relation(r1);
+    assert_eq!(history.graph().inverse_of(r2), None);
+    history.undo();
+    assert_eq!(history.graph().inverse_of(r2), Some(r1));
+    history.connect(b, a, r1).unwrap();
+    assert!(history
+        .graph()
+        .relation(r2)
+        .unwrap()
+        .contains_edge(&Edge::new(a, b)));
+}
+
+#[test]
+fn test_undo_disconnect_restores_inverse_info() {
+    let mut graph = Graph::new();
+    let a = graph.add_node(1);
+    let b = graph.add_node(2);
+    let r1 = graph.add_relation(1);
+    let r2 = graph.add_relation(2);
+    graph.set_inverse(r1, r2).unwrap();
+    graph.connect_with(a, b, r1, 10u32).unwrap();
+    graph.connect_with(b, a, r2, 20u32).unwrap();
+
+    let mut history = History::new(graph);
+    history.disconnect(a, b, r1).unwrap();
+    assert!(!history
+        .graph()
+        .relation(r2)
+        .unwrap()
+        .contains_edge(&Edge::new(b, a)));
+    history.undo();
+    let info = |graph: &Graph, src, dst, relation_id| {
+        let info = graph.edge_info(src, dst, relation_id)?;
+        info.downcast_ref::<u32>().copied()
+    };
+    assert_eq!(info(history.graph(), a, b, r1), Some(10));
+    assert_eq!(info(history.graph(), b, a, r2), Some(20));
+    history.redo();
+    assert!(!history
+        .graph()
+        .relation(r2)
+        .unwrap()
+        .contains_edge(&Edge::new(b, a)));
+    history.undo();
+    assert_eq!(info(history.graph(), b, a, r2), Some(20));
+}
+
+#[test]
+fn test_persisted_inverse() {
+    let dir = tempfile::tempdir().unwrap();
+    let (a, b, r1, r2);
+    {
+        let mut store = PersistentGraph::open(dir.path()).unwrap();
+        a = store.add_node(1).unwrap();
+        b = store.add_node(2).unwrap();
+        r1 = store.add_relation(1).unwrap();
+        r2 = store.add_relation(2).unwrap();
+        store.connect(a, b, r1).unwrap();
+        store.set_inverse(r1, r2).unwrap();
+        assert!(store
+            .graph()
+            .relation(r2)
+            .unwrap()
+            .contains_edge(&Edge::new(b, a)));
+    }
+
+    let mut store = PersistentGraph::open(dir.path()).unwrap();
+    assert_eq!(store.graph().inverse_of(r1), Some(r2));
+    store.checkpoint().unwrap();
+    drop(store);
+
+    let mut store = PersistentGraph::open(dir.path()).unwrap();
+    assert_eq!(store.graph().inverse_of(r2), Some(r1));
+    store.disconnect(b, a, r2).unwrap();
+    assert!(!store
+        .graph()
+        .relation(r1)
+        .unwrap()
+        .contains_edge(&Edge::new(a, b)));
+    assert_eq!(store.remove_inverse(r1).unwrap(), Some(r2));
+    drop(store);
+
+    let store = PersistentGraph::open(dir.path()).unwrap();
+    assert_eq!(store.graph().inverse_of(r1), None);
+}
@@ -0,0 +1,137 @@
+use graphfruit::codec::DefaultCodec;
+use graphfruit::diff::GraphDiff;
+use graphfruit::graph::Graph;
+use graphfruit::history::History;
+use graphfruit::node::NodeId;
+use graphfruit::store::PersistentGraph;
+
+fn sorted(nodes: impl Iterator<Item = NodeId>) -> Vec<NodeId> {
+    let mut nodes: Vec<_> = nodes.collect();
+    nodes.sort_by_key(|id| **id);
+    nodes
+}
+
+fn labels(graph: &Graph, node_id: NodeId) -> Vec<String> {
+    let mut labels: Vec<_> = graph
+        .labels_of(node_id)
+        .into_iter()
+        .flatten()
+        .map(|label| label.to_string())
+        .collect();
+    labels.sort();
+    labels
+}
+
+#[test]
+fn test_labels() {
+    let mut graph = Graph::new();
+    let a = graph.add_node(1);
+    let b = graph.add_node(2);
+    let c = graph.add_node(3);
+
+    assert!(graph.add_label(a, "Person"));
+    assert!(graph.add_label(a, "Admin"));
+    assert!(!graph.add_label(a, "Person"));
+    assert!(graph.add_label(b, "Person"));
+    assert!(!graph.add_label(NodeId::new(99), "Person"));
+
+    assert!(graph.has_label(a, "Admin"));
+    assert!(!graph.has_label(c, "Person"));
+    assert_eq!(labels(&graph, a), ["Admin", "Person"]);
+    assert_eq!(labels(&graph, c), Vec::<String>::new());
+    assert!(graph.labels_of(NodeId::new(99)).is_none());
+    assert_eq!(sorted(graph.nodes_with_label("Person")), [a, b]);
+    assert_eq!(graph.nodes_with_label("Missing").count(), 0);
+    assert_eq!(graph.iter_labels().count(), 2);
+
+    assert!(graph.remove_label(a, "Admin"));
+    assert!(!graph.remove_label(a, "Admin"));
+    assert_eq!(graph.nodes_with_label("Admin").count(), 0);
+    assert_eq!(graph.iter_labels().count(), 1);
+}
+
+#[test]
+fn test_remove_node_removes_labels() {
+    let mut graph = Graph::new();
+    let a = graph.add_node(1);
+    let b = graph.add_node(2);
+    graph.add_label(a, "Person");
+    graph.add_label(a, "Admin");
+    graph.add_label(b, "Person");
+
+    graph.remove_node(a);
+    assert_eq!(sorted(graph.nodes_with_label("Person")), [b]);
+    assert_eq!(graph.nodes_with_label("Admin").count(), 0);
+    assert_eq!(graph.iter_labels().count(), 1);
+}
+
+#[test]
+fn test_undo_labels() {
+    let mut history = History::new(Graph::new());
+    let a = history.add_node(1);
+    history.add_label(a, "Person");
+    history.checkpoint("label");
+
+    history.add_label(a, "Admin");
+    history.remove_label(a, "Person");
+    history.checkpoint("relabel");
+    assert_eq!(labels(history.graph(), a), ["Admin"]);
+    history.undo();
+    assert_eq!(labels(history.graph(), a), ["Person"]);
+    history.redo();
+    assert_eq!(labels(history.graph(), a), ["Admin"]);
+
+    history.remove_node(a);
+    history.checkpoint("remove");
+    assert_eq!(history.graph().nodes_with_label("Admin").count(), 0);
+    history.undo();
+    assert_eq!(sorted(history.graph().nodes_with_label("Admin")), [a]);
+}
+
+#[test]
+fn test_persisted_labels() {
+    let dir = tempfile::tempdir().unwrap();
+    let (a, b) = {
+        let mut store = PersistentGraph::open(dir.path()).unwrap();
+        let a = store.add_node(1).unwrap();
+        let b = store.add_node(2).unwrap();
+        assert!(store.add_label(a, "Person").unwrap());
+        assert!(!store.add_label(a, "Person").unwrap());
+        store.add_label(b, "Person").unwrap();
+        store.add_label(b, "Admin").unwrap();
+        (a, b)
+    };
+
+    let mut store = PersistentGraph::open(dir.path()).unwrap();
+    assert_eq!(sorted(store.graph().nodes_with_label("Person")), [a, b]);
+    assert!(store.remove_label(b, "Person").unwrap());
+    store.checkpoint().unwrap();
+    let store = PersistentGraph::open(dir.path()).unwrap();
+    assert_eq!(labels(store.graph(), a), ["Person"]);
+    assert_eq!(labels(store.graph(), b), ["Admin"]);
+}
+
+#[test]
+fn test_diff_labels() {
+    let mut old = Graph::new();
+    let a = old.add_node(1);
+    let b = old.add_node(2);
+    old.add_label(a, "Person");
+    old.add_label(b, "Admin");
+    let mut new = old.clone();
+    new.remove_label(a, "Person");
+    new.add_label(a, "Admin");
+    let c = new.add_node(3);
+    new.add_label(c, "Person");
+
+    let mut replica = Graph::new();
+    for diff in [Graph::new().diff(&old), old.diff(&new)] {
+        let bytes = diff.encode(&DefaultCodec).unwrap();
+        replica
+            .apply(&GraphDiff::decode(&bytes, &DefaultCodec).unwrap())
+            .unwrap();
+    }
+    assert_eq!(labels(&replica, a), ["Admin"]);
+    assert_eq!(labels(&replica, c), ["Person"]);
+    assert_eq!(sorted(replica.nodes_with_label("Admin")), [a, b]);
+}
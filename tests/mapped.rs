@@ -0,0 +1,241 @@
+use graphfruit::codec::DefaultCodec;
+use graphfruit::edge::Edge;
+use graphfruit::errors::StoreError;
+use graphfruit::graph::Graph;
+use graphfruit::hyperedge::{Hyperedge, HyperedgeKind};
+use graphfruit::mapped::MappedGraph;
+use graphfruit::node::NodeId;
+use graphfruit::read::GraphRead;
+use graphfruit::relation::{RelationId, RelationKind};
+use graphfruit::store::PersistentGraph;
+use std::collections::HashSet;
+use std::convert::TryInto;
+use std::fs;
+
+fn build_graph() -> (Graph, Vec<NodeId>, Vec<RelationId>) {
+    let mut graph = Graph::new();
+    let mut node_ids = Vec::new();
+    for i in 0..50 {
+        node_ids.push(graph.add_node(format!("Node{}", i)));
+    }
+    let mut rel_ids = Vec::new();
+    for i in 0..5 {
+        rel_ids.push(graph.add_relation(i));
+    }
+    for i in 0..50 {
+        for j in 0..50 {
+            if (i * 7 + j * 3) % 11 == 0 {
+                graph
+                    .connect(node_ids[i], node_ids[j], rel_ids[(i + j) % 5])
+                    .unwrap();
+            }
+        }
+    }
+    graph.remove_node(node_ids[10]);
+    graph.remove_relation(rel_ids[4]);
+    (graph, node_ids, rel_ids)
+}
+
+#[test]
+fn test_mapped_queries() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("graph.map");
+    let (graph, node_ids, rel_ids) = build_graph();
+    MappedGraph::write(&graph, &DefaultCodec, &path).unwrap();
+    let mapped = MappedGraph::open(&path).unwrap();
+
+    assert_eq!(mapped.nr_nodes(), graph.nr_nodes());
+    assert_eq!(mapped.nr_relations(), graph.nr_relations());
+    assert!(!mapped.contains_node(node_ids[10]));
+    assert!(!mapped.contains_relation(rel_ids[4]));
+    assert!(mapped.node_info(node_ids[10]).is_none());
+    assert!(mapped.iter_relation_edges(rel_ids[4]).is_none());
+
+    for node_id in mapped.iter_node_ids() {
+        let info = mapped.node_info(node_id).unwrap().unwrap();
+        assert_eq!(
+            info.downcast_ref::<String>(),
+            graph.node_info(node_id).unwrap().downcast_ref::<String>()
+        );
+        assert_eq!(mapped.in_degree_of(node_id), graph.in_degree_of(node_id));
+        assert_eq!(mapped.out_degree_of(node_id), graph.out_degree_of(node_id));
+
+        let next: HashSet<_> = mapped.iter_next_nodes(node_id).unwrap().collect();
+        let expected: HashSet<_> = graph.iter_next_nodes(node_id).unwrap().collect();
+        assert_eq!(next, expected);
+        let prev: HashSet<_> = mapped.iter_prev_nodes(node_id).unwrap().collect();
+        let expected: HashSet<_> = graph.iter_prev_nodes(node_id).unwrap().collect();
+        assert_eq!(prev, expected);
+    }
+
+    for relation_id in mapped.iter_relation_ids() {
+        let info = mapped.relation_info(relation_id).unwrap().unwrap();
+        assert_eq!(
+            info.downcast_ref::<i32>(),
+            graph
+                .relation(relation_id)
+                .unwrap()
+                .info()
+                .downcast_ref::<i32>()
+        );
+        let edges: HashSet<Edge> = mapped.iter_relation_edges(relation_id).unwrap().collect();
+        let expected: HashSet<Edge> = graph
+            .iter_relation_edges(relation_id)
+            .unwrap()
+            .copied()
+            .collect();
+        assert!(!edges.is_empty());
+        assert_eq!(edges, expected);
+    }
+}
+
+#[test]
+fn test_mapped_empty() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("empty.map");
+    MappedGraph::write(&Graph::new(), &DefaultCodec, &path).unwrap();
+    let mapped = MappedGraph::open(&path).unwrap();
+    assert_eq!(mapped.nr_nodes(), 0);
+    assert_eq!(mapped.nr_relations(), 0);
+    assert!(mapped.in_degree_of(NodeId::new(1)).is_none());
+}
+
+#[test]
+fn test_mapped_corrupt() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("graph.map");
+    let (graph, _, _) = build_graph();
+    MappedGraph::write(&graph, &DefaultCodec, &path).unwrap();
+
+    let bytes = fs::read(&path).unwrap();
+    let read = |pos: usize| u64::from_le_bytes(bytes[pos..pos + 8].try_into().unwrap()) as usize;
+    let node_info_offsets_pos = read(32);
+    let out_offsets_pos = read(read(48) + 32);
+    let nr_targets = read(read(48) + 24);
+    for (pos, value) in [
+        (node_info_offsets_pos + 8, u64::MAX),
+        (out_offsets_pos + 8, nr_targets as u64 + 1),
+        (out_offsets_pos + 16, 0),
+    ] {
+        let mut corrupt = bytes.clone();
+        corrupt[pos..pos + 8].copy_from_slice(&value.to_le_bytes());
+        fs::write(&path, &corrupt).unwrap();
+        assert!(matches!(
+            MappedGraph::open(&path),
+            Err(StoreError::CorruptSnapshot)
+        ));
+    }
+
+    fs::write(&path, &bytes[..bytes.len() - 1]).unwrap();
+    assert!(matches!(
+        MappedGraph::open(&path),
+        Err(StoreError::CorruptSnapshot)
+    ));
+
+    fs::write(&path, b"not a graph").unwrap();
+    assert!(matches!(
+        MappedGraph::open(&path),
+        Err(StoreError::CorruptSnapshot)
+    ));
+}
+
+#[test]
+fn test_mapped_parallel_edges() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("graph.map");
+    let mut graph = Graph::new();
+    let a = graph.add_node(1);
+    let b = graph.add_node(2);
+    let r1 = graph.add_multi_relation(1, RelationKind::Directed);
+    for _ in 0..3 {
+        graph.connect_multi(a, b, r1).unwrap();
+    }
+    MappedGraph::write(&graph, &DefaultCodec, &path).unwrap();
+    let mapped = MappedGraph::open(&path).unwrap();
+
+    assert_eq!(
+        mapped.iter_next_nodes(a).unwrap().collect::<Vec<_>>(),
+        [(b, r1)]
+    );
+    assert_eq!(
+        mapped.iter_prev_nodes(b).unwrap().collect::<Vec<_>>(),
+        [(a, r1)]
+    );
+    assert_eq!(
+        mapped.iter_relation_edges(r1).unwrap().collect::<Vec<_>>(),
+        [Edge::new(a, b)]
+    );
+    assert_eq!(mapped.multiplicity(Edge::new(a, b), r1), 3);
+    assert_eq!(mapped.out_degree_of(a), Some(3));
+
+    let nodes = mapped.filter_nodes(|_, _| true);
+    assert_eq!(nodes.out_degree_of(a), Some(3));
+    assert_eq!(nodes.in_degree_of(b), Some(3));
+    let relations = mapped.filter_relations([r1]);
+    assert_eq!(relations.out_degree_of(a), Some(3));
+    assert_eq!(relations.in_degree_of(b), Some(3));
+    assert_eq!(relations.iter_relation_edges(r1).unwrap().count(), 1);
+}
+
+#[test]
+fn test_mapped_from_store() {
+    let dir = tempfile::tempdir().unwrap();
+    let store_dir = dir.path().join("store");
+    let mut store = PersistentGraph::open(&store_dir).unwrap();
+    let node_ids: Vec<_> = (0..20)
+        .map(|i| store.add_node(format!("Node{}", i)).unwrap())
+        .collect();
+    let r1 = store.add_relation(1).unwrap();
+    let r2 = store
+        .add_relation_of_kind(2, RelationKind::Undirected)
+        .unwrap();
+    let r3 = store.add_multi_relation(3, RelationKind::Directed).unwrap();
+    let r4 = store.add_hyper_relation(4, HyperedgeKind::Ordered).unwrap();
+    store.add_label(node_ids[0], "a").unwrap();
+    for i in 0..20 {
+        store
+            .connect(node_ids[i], node_ids[(i * 7) % 20], r1)
+            .unwrap();
+        store
+            .connect_with(node_ids[i], node_ids[(i + 3) % 20], r2, i as i32)
+            .unwrap();
+        store
+            .connect_multi_with(node_ids[i % 5], node_ids[i % 3], r3, "x".to_string())
+            .unwrap();
+    }
+    store
+        .connect_hyperedge(r4, Hyperedge::new(vec![node_ids[0]], vec![node_ids[1]]))
+        .unwrap();
+    store.remove_node(node_ids[4]).unwrap();
+    store.checkpoint().unwrap();
+
+    let path = dir.path().join("store.map");
+    MappedGraph::write_store(&store_dir, &path).unwrap();
+    let expected = dir.path().join("graph.map");
+    MappedGraph::write(store.graph(), &DefaultCodec, &expected).unwrap();
+    assert_eq!(fs::read(&path).unwrap(), fs::read(&expected).unwrap());
+    let files: Vec<_> = fs::read_dir(dir.path()).unwrap().collect();
+    assert_eq!(files.len(), 3);
+
+    let mapped = MappedGraph::open(&path).unwrap();
+    assert_eq!(mapped.nr_nodes(), 19);
+    assert_eq!(
+        mapped.multiplicity(Edge::new(node_ids[0], node_ids[0]), r3),
+        2
+    );
+
+    store.add_node(String::new()).unwrap();
+    assert!(matches!(
+        MappedGraph::write_store(&store_dir, &path),
+        Err(StoreError::PendingLog)
+    ));
+    assert_eq!(fs::read(&path).unwrap(), fs::read(&expected).unwrap());
+    store.checkpoint().unwrap();
+    MappedGraph::write_store(&store_dir, &path).unwrap();
+    assert_eq!(MappedGraph::open(&path).unwrap().nr_nodes(), 20);
+
+    let empty_dir = dir.path().join("empty");
+    drop(PersistentGraph::open(&empty_dir).unwrap());
+    MappedGraph::write_store(&empty_dir, &path).unwrap();
+    assert_eq!(MappedGraph::open(&path).unwrap().nr_nodes(), 0);
+}
@@ -0,0 +1,210 @@
+use graphfruit::codec::DefaultCodec;
+use graphfruit::diff::GraphDiff;
+use graphfruit::edge::{Edge, EdgeId};
+use graphfruit::errors::ConnectError;
+use graphfruit::graph::Graph;
+use graphfruit::history::History;
+use graphfruit::mapped::MappedGraph;
+use graphfruit::relation::RelationKind;
+use graphfruit::schema::{Cardinality, RelationSchema};
+use graphfruit::store::PersistentGraph;
+
+fn weight(graph: &Graph, edge_id: EdgeId) -> Option<u32> {
+    graph
+        .parallel_edge_info(edge_id)
+        .map(|info| *info.downcast_ref::<u32>().unwrap())
+}
+
+#[test]
+fn test_parallel_edges() {
+    let mut graph = Graph::new();
+    let a = graph.add_node(1);
+    let b = graph.add_node(2);
+    let r1 = graph.add_multi_relation(1, RelationKind::Directed);
+    let r2 = graph.add_relation(2);
+
+    let e1 = graph.connect_multi_with(a, b, r1, 10u32).unwrap();
+    let e2 = graph.connect_multi_with(a, b, r1, 20u32).unwrap();
+    let e3 = graph.connect_multi(b, a, r1).unwrap();
+    assert_ne!(e1, e2);
+    assert_eq!(graph.parallel_edge(e2), Some((Edge::new(a, b), r1)));
+    assert_eq!(weight(&graph, e1), Some(10));
+    assert_eq!(weight(&graph, e2), Some(20));
+    assert_eq!(weight(&graph, e3), None);
+
+    let relation = graph.relation(r1).unwrap();
+    assert_eq!(relation.multiplicity(&Edge::new(a, b)), 2);
+    assert_eq!(relation.iter_edges().count(), 2);
+    assert_eq!(graph.out_degree_of(a), Some(2));
+    assert_eq!(graph.in_degree_of(b), Some(2));
+
+    assert!(matches!(
+        graph.connect(a, b, r1),
+        Err(ConnectError::MultiRelation)
+    ));
+    assert!(matches!(
+        graph.connect_multi(a, b, r2),
+        Err(ConnectError::NotMultiRelation)
+    ));
+    assert!(matches!(
+        graph.set_inverse(r1, r2),
+        Err(ConnectError::InverseConflict)
+    ));
+
+    assert!(graph.disconnect_edge(e1));
+    assert!(!graph.disconnect_edge(e1));
+    assert_eq!(graph.out_degree_of(a), Some(1));
+    assert!(graph.relation(r1).unwrap().contains_edge(&Edge::new(a, b)));
+    assert!(graph.disconnect_edge(e2));
+    assert!(!graph.relation(r1).unwrap().contains_edge(&Edge::new(a, b)));
+    assert_eq!(graph.iter_next_nodes(a).unwrap().count(), 0);
+    assert_eq!(graph.in_degree_of(a), Some(1));
+}
+
+#[test]
+fn test_undirected_multi_relation_and_schema() {
+    let mut graph = Graph::new();
+    let a = graph.add_node(1);
+    let b = graph.add_node(2);
+    let r1 = graph.add_multi_relation(1, RelationKind::Undirected);
+    graph.set_relation_schema(
+        r1,
+        RelationSchema::new().with_cardinality(Cardinality::MaxOutDegree(3)),
+    );
+
+    let e1 = graph.connect_multi(a, b, r1).unwrap();
+    graph.connect_multi(b, a, r1).unwrap();
+    assert_eq!(
+        graph.relation(r1).unwrap().multiplicity(&Edge::new(b, a)),
+        2
+    );
+    assert_eq!(graph.out_degree_of(a), Some(2));
+    assert_eq!(graph.out_degree_of(b), Some(2));
+    graph.connect_multi(a, b, r1).unwrap();
+    assert!(matches!(
+        graph.connect_multi(a, b, r1),
+        Err(ConnectError::SchemaViolation(_))
+    ));
+
+    graph.disconnect(b, a, r1).unwrap();
+    assert_eq!(graph.parallel_edge(e1), None);
+    assert_eq!(graph.out_degree_of(a), Some(0));
+}
+
+#[test]
+fn test_parallel_edges_of_removed_node_and_relation() {
+    let mut graph = Graph::new();
+    let a = graph.add_node(1);
+    let b = graph.add_node(2);
+    let c = graph.add_node(3);
+    let r1 = graph.add_multi_relation(1, RelationKind::Directed);
+    let r2 = graph.add_multi_relation(2, RelationKind::Directed);
+    let e1 = graph.connect_multi(a, b, r1).unwrap();
+    let e2 = graph.connect_multi(b, c, r1).unwrap();
+    let e3 = graph.connect_multi(a, c, r1).unwrap();
+    let e4 = graph.connect_multi(a, c, r2).unwrap();
+
+    graph.remove_node(b);
+    assert_eq!(graph.parallel_edge(e1), None);
+    assert_eq!(graph.parallel_edge(e2), None);
+    assert!(!graph.disconnect_edge(e2));
+    assert_eq!(graph.parallel_edge(e3), Some((Edge::new(a, c), r1)));
+
+    graph.remove_relation(r2);
+    assert_eq!(graph.parallel_edge(e4), None);
+    assert!(!graph.disconnect_edge(e4));
+    assert_eq!(graph.parallel_edge(e3), Some((Edge::new(a, c), r1)));
+}
+
+#[test]
+fn test_undo_parallel_edges() {
+    let mut history = History::new(Graph::new());
+    let a = history.add_node(1);
+    let b = history.add_node(2);
+    let r1 = history.add_multi_relation(1, RelationKind::Directed);
+    let e1 = history.connect_multi_with(a, b, r1, 1u32).unwrap();
+    let e2 = history.connect_multi_with(a, b, r1, 2u32).unwrap();
+    history.checkpoint("connect");
+
+    history.disconnect_edge(e1);
+    history.undo();
+    assert_eq!(weight(history.graph(), e1), Some(1));
+
+    history.remove_node(b);
+    history.checkpoint("remove node");
+    history.remove_relation(r1);
+    history.checkpoint("remove relation");
+    history.undo();
+    history.undo();
+    assert_eq!(weight(history.graph(), e1), Some(1));
+    assert_eq!(weight(history.graph(), e2), Some(2));
+    assert_eq!(history.graph().out_degree_of(a), Some(2));
+
+    history.disconnect(a, b, r1).unwrap();
+    assert_eq!(history.graph().out_degree_of(a), Some(0));
+    history.undo();
+    assert_eq!(history.graph().out_degree_of(a), Some(2));
+}
+
+#[test]
+fn test_persisted_parallel_edges() {
+    let dir = tempfile::tempdir().unwrap();
+    let (a, b, r1, e1, e2) = {
+        let mut store = PersistentGraph::open(dir.path()).unwrap();
+        let a = store.add_node(1).unwrap();
+        let b = store.add_node(2).unwrap();
+        let r1 = store.add_multi_relation(1, RelationKind::Directed).unwrap();
+        let e1 = store.connect_multi_with(a, b, r1, 5u32).unwrap();
+        let e2 = store.connect_multi(a, b, r1).unwrap();
+        (a, b, r1, e1, e2)
+    };
+
+    let mut store = PersistentGraph::open(dir.path()).unwrap();
+    assert_eq!(weight(store.graph(), e1), Some(5));
+    assert_eq!(store.graph().parallel_edge(e2), Some((Edge::new(a, b), r1)));
+    store.checkpoint().unwrap();
+    let mut store = PersistentGraph::open(dir.path()).unwrap();
+    assert_eq!(store.graph().out_degree_of(a), Some(2));
+    assert!(store.disconnect_edge(e2).unwrap());
+    let e3 = store.connect_multi(a, b, r1).unwrap();
+    assert!(e3 > e2);
+}
+
+#[test]
+fn test_diff_parallel_edges() {
+    let mut graph = Graph::new();
+    let a = graph.add_node(1);
+    let b = graph.add_node(2);
+    let r1 = graph.add_multi_relation(1, RelationKind::Directed);
+    let e1 = graph.connect_multi_with(a, b, r1, 5u32).unwrap();
+    let e2 = graph.connect_multi(a, b, r1).unwrap();
+    let mut replica = Graph::new();
+    replica.apply(&replica.diff(&graph)).unwrap();
+
+    assert!(graph.disconnect_edge(e2));
+    let e3 = graph.connect_multi(a, b, r1).unwrap();
+    let bytes = replica.diff(&graph).encode(&DefaultCodec).unwrap();
+    replica
+        .apply(&GraphDiff::decode(&bytes, &DefaultCodec).unwrap())
+        .unwrap();
+    assert_eq!(weight(&replica, e1), Some(5));
+    assert_eq!(replica.parallel_edge(e3), Some((Edge::new(a, b), r1)));
+    assert_eq!(replica.parallel_edge(e2), None);
+}
+
+#[test]
+fn test_mapped_parallel_edges() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut graph = Graph::new();
+    let a = graph.add_node(1);
+    let b = graph.add_node(2);
+    let r1 = graph.add_multi_relation(1, RelationKind::Directed);
+    graph.connect_multi(a, b, r1).unwrap();
+    graph.connect_multi(a, b, r1).unwrap();
+
+    let path = dir.path().join("mapped");
+    MappedGraph::write(&graph, &DefaultCodec, &path).unwrap();
+    let mapped = MappedGraph::open(&path).unwrap();
+    assert_eq!(mapped.out_degree_of(a), Some(2));
+    assert_eq!(mapped.in_degree_of(b), Some(2));
+}
@@ -0,0 +1,121 @@
+use graphfruit::codec::DefaultCodec;
+use graphfruit::diff::GraphDiff;
+use graphfruit::errors::{NameError, StoreError};
+use graphfruit::graph::Graph;
+use graphfruit::history::History;
+use graphfruit::relation::RelationId;
+use graphfruit::store::PersistentGraph;
+
+#[test]
+fn test_named_relations() {
+    let mut graph = Graph::new();
+    let knows = graph.add_named_relation("knows", 1).unwrap();
+    let likes = graph.add_relation(2);
+    assert_eq!(graph.relation_by_name("knows"), Some(knows));
+    assert_eq!(graph.relation(knows).unwrap().name(), Some("knows"));
+    assert_eq!(graph.relation(likes).unwrap().name(), None);
+
+    assert!(matches!(
+        graph.add_named_relation("knows", 3),
+        Err(NameError::NameTaken)
+    ));
+    assert_eq!(graph.nr_relations(), 2);
+    assert!(matches!(
+        graph.rename_relation(likes, "knows"),
+        Err(NameError::NameTaken)
+    ));
+    assert!(matches!(
+        graph.rename_relation(RelationId::new(9), "other"),
+        Err(NameError::InvalidRelationId)
+    ));
+
+    assert_eq!(graph.rename_relation(likes, "likes").unwrap(), None);
+    assert_eq!(
+        graph.rename_relation(knows, "knows").unwrap(),
+        Some("knows".to_string())
+    );
+    assert_eq!(
+        graph.rename_relation(knows, "met").unwrap(),
+        Some("knows".to_string())
+    );
+    assert_eq!(graph.relation_by_name("knows"), None);
+    assert_eq!(graph.relation_by_name("met"), Some(knows));
+
+    assert_eq!(graph.clear_relation_name(likes), Some("likes".to_string()));
+    assert_eq!(graph.relation_by_name("likes"), None);
+    graph.remove_relation(knows);
+    assert_eq!(graph.relation_by_name("met"), None);
+    graph.add_named_relation("met", 4).unwrap();
+}
+
+#[test]
+fn test_undo_names() {
+    let mut history = History::new(Graph::new());
+    let r1 = history.add_named_relation("a", 1).unwrap();
+    history.checkpoint("add");
+    history.rename_relation(r1, "b").unwrap();
+    history.checkpoint("rename");
+    history.remove_relation(r1);
+    history.checkpoint("remove");
+
+    history.undo();
+    assert_eq!(history.graph().relation_by_name("b"), Some(r1));
+    history.undo();
+    assert_eq!(history.graph().relation_by_name("a"), Some(r1));
+    assert_eq!(history.graph().relation_by_name("b"), None);
+    history.undo();
+    assert_eq!(history.graph().relation_by_name("a"), None);
+    history.redo();
+    history.redo();
+    assert_eq!(history.graph().relation_by_name("b"), Some(r1));
+}
+
+#[test]
+fn test_persisted_names() {
+    let dir = tempfile::tempdir().unwrap();
+    let (r1, r2) = {
+        let mut store = PersistentGraph::open(dir.path()).unwrap();
+        let r1 = store.add_named_relation("knows", 1).unwrap();
+        let r2 = store.add_relation(2).unwrap();
+        store.rename_relation(r2, "likes").unwrap();
+        assert!(matches!(
+            store.add_named_relation("likes", 3),
+            Err(StoreError::Name(NameError::NameTaken))
+        ));
+        (r1, r2)
+    };
+
+    let mut store = PersistentGraph::open(dir.path()).unwrap();
+    assert_eq!(store.graph().relation_by_name("knows"), Some(r1));
+    assert_eq!(store.graph().relation_by_name("likes"), Some(r2));
+    store.checkpoint().unwrap();
+    let mut store = PersistentGraph::open(dir.path()).unwrap();
+    assert_eq!(store.graph().relation_by_name("likes"), Some(r2));
+    assert_eq!(
+        store.clear_relation_name(r1).unwrap(),
+        Some("knows".to_string())
+    );
+    let store = PersistentGraph::open(dir.path()).unwrap();
+    assert_eq!(store.graph().relation_by_name("knows"), None);
+}
+
+#[test]
+fn test_diff_swaps_names() {
+    let mut old = Graph::new();
+    let r1 = old.add_named_relation("a", 1).unwrap();
+    let r2 = old.add_named_relation("b", 2).unwrap();
+    let mut new = old.clone();
+    new.clear_relation_name(r1);
+    new.rename_relation(r2, "a").unwrap();
+    new.rename_relation(r1, "b").unwrap();
+    let r3 = new.add_named_relation("c", 3).unwrap();
+
+    let diff = old.diff(&new);
+    assert_eq!(diff.iter_renamed_relations().count(), 2);
+    let bytes = diff.encode(&DefaultCodec).unwrap();
+    old.apply(&GraphDiff::decode(&bytes, &DefaultCodec).unwrap())
+        .unwrap();
+    assert_eq!(old.relation_by_name("a"), Some(r2));
+    assert_eq!(old.relation_by_name("b"), Some(r1));
+    assert_eq!(old.relation_by_name("c"), Some(r3));
+}
@@ -0,0 +1,217 @@
+use graphfruit::codec::DefaultCodec;
+use graphfruit::diff::GraphDiff;
+use graphfruit::edge::Edge;
+use graphfruit::errors::PropertyError;
+use graphfruit::graph::Graph;
+use graphfruit::history::History;
+use graphfruit::node::NodeId;
+use graphfruit::property::PropertyOwner;
+use graphfruit::relation::RelationKind;
+use graphfruit::store::PersistentGraph;
+use graphfruit::value::Value;
+use std::collections::BTreeMap;
+
+#[test]
+fn test_properties() {
+    let mut graph = Graph::new();
+    let a = graph.add_node(1);
+    let b = graph.add_node(2);
+    let r1 = graph.add_relation_of_kind(1, RelationKind::Undirected);
+    graph.connect(a, b, r1).unwrap();
+    let node = PropertyOwner::Node(a);
+    let relation = PropertyOwner::Relation(r1);
+    let edge = PropertyOwner::Edge(Edge::new(a, b), r1);
+
+    assert_eq!(graph.set_property(node, "name", "Ada").unwrap(), None);
+    assert_eq!(
+        graph.set_property(node, "name", "Grace").unwrap(),
+        Some(Value::from("Ada"))
+    );
+    graph.set_property(node, "age", 36).unwrap();
+    graph.set_property(relation, "weighted", true).unwrap();
+    graph.set_property(edge, "weight", 0.5).unwrap();
+
+    assert_eq!(
+        graph.property(node, "name").unwrap().as_str(),
+        Some("Grace")
+    );
+    assert_eq!(graph.property(node, "age").unwrap().as_int(), Some(36));
+    assert_eq!(graph.properties(node).unwrap().len(), 2);
+    assert_eq!(
+        graph.property(relation, "weighted").unwrap().as_bool(),
+        Some(true)
+    );
+    let reversed = PropertyOwner::Edge(Edge::new(b, a), r1);
+    assert_eq!(
+        graph.property(reversed, "weight").unwrap().as_float(),
+        Some(0.5)
+    );
+    assert_eq!(graph.iter_properties().count(), 3);
+
+    assert!(matches!(
+        graph.set_property(PropertyOwner::Node(NodeId::new(99)), "name", ()),
+        Err(PropertyError::InvalidNodeId)
+    ));
+    assert!(matches!(
+        graph.set_property(PropertyOwner::Edge(Edge::new(a, a), r1), "weight", 1),
+        Err(PropertyError::InvalidEdge)
+    ));
+
+    assert_eq!(graph.remove_property(node, "age"), Some(Value::Int(36)));
+    assert_eq!(graph.remove_property(node, "age"), None);
+    graph.remove_property(node, "name");
+    assert!(graph.properties(node).is_none());
+}
+
+#[test]
+fn test_properties_removed_with_owner() {
+    let mut graph = Graph::new();
+    let a = graph.add_node(1);
+    let b = graph.add_node(2);
+    let r1 = graph.add_relation(1);
+    let r2 = graph.add_multi_relation(2, RelationKind::Directed);
+    graph.connect(a, b, r1).unwrap();
+    let e1 = graph.connect_multi(a, b, r2).unwrap();
+    let e2 = graph.connect_multi(a, b, r2).unwrap();
+    let edge = PropertyOwner::Edge(Edge::new(a, b), r1);
+    let parallel = PropertyOwner::Edge(Edge::new(a, b), r2);
+    graph.set_property(edge, "weight", 1).unwrap();
+    graph.set_property(parallel, "weight", 2).unwrap();
+
+    graph.disconnect_edge(e1);
+    assert!(graph.property(parallel, "weight").is_some());
+    graph.disconnect_edge(e2);
+    assert!(graph.property(parallel, "weight").is_none());
+
+    graph.disconnect(a, b, r1).unwrap();
+    graph.connect(a, b, r1).unwrap();
+    assert!(graph.property(edge, "weight").is_none());
+
+    graph
+        .set_property(PropertyOwner::Node(b), "name", "b")
+        .unwrap();
+    graph.set_property(edge, "weight", 1).unwrap();
+    graph.remove_node(b);
+    assert_eq!(graph.iter_properties().count(), 0);
+}
+
+#[test]
+fn test_undo_properties() {
+    let mut history = History::new(Graph::new());
+    let a = history.add_node(1);
+    let b = history.add_node(2);
+    let r1 = history.add_relation(1);
+    history.connect(a, b, r1).unwrap();
+    let node = PropertyOwner::Node(a);
+    let edge = PropertyOwner::Edge(Edge::new(a, b), r1);
+    history.set_property(node, "name", "a").unwrap();
+    history.set_property(edge, "weight", 1).unwrap();
+    history.checkpoint("set");
+
+    history.set_property(node, "name", "A").unwrap();
+    history.remove_property(edge, "weight");
+    history.checkpoint("change");
+    history.undo();
+    assert_eq!(
+        history.graph().property(node, "name"),
+        Some(&Value::from("a"))
+    );
+    assert_eq!(
+        history.graph().property(edge, "weight"),
+        Some(&Value::Int(1))
+    );
+
+    history.remove_node(a);
+    history.checkpoint("remove node");
+    history.undo();
+    assert_eq!(
+        history.graph().property(node, "name"),
+        Some(&Value::from("a"))
+    );
+    assert_eq!(
+        history.graph().property(edge, "weight"),
+        Some(&Value::Int(1))
+    );
+
+    history.remove_relation(r1);
+    history.checkpoint("remove relation");
+    history.undo();
+    assert_eq!(
+        history.graph().property(edge, "weight"),
+        Some(&Value::Int(1))
+    );
+}
+
+#[test]
+fn test_persisted_properties() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut tags = BTreeMap::new();
+    tags.insert("kind".to_string(), Value::from(vec![Value::Null, 2.into()]));
+    let (a, b, r1) = {
+        let mut store = PersistentGraph::open(dir.path()).unwrap();
+        let a = store.add_node(1).unwrap();
+        let b = store.add_node(2).unwrap();
+        let r1 = store.add_relation(1).unwrap();
+        store.connect(a, b, r1).unwrap();
+        let node = PropertyOwner::Node(a);
+        store.set_property(node, "tags", tags.clone()).unwrap();
+        store.set_property(node, "raw", vec![1u8, 2]).unwrap();
+        store
+            .set_property(PropertyOwner::Edge(Edge::new(a, b), r1), "weight", -3)
+            .unwrap();
+        store.remove_property(node, "raw").unwrap();
+        (a, b, r1)
+    };
+    let node = PropertyOwner::Node(a);
+    let edge = PropertyOwner::Edge(Edge::new(a, b), r1);
+
+    let mut store = PersistentGraph::open(dir.path()).unwrap();
+    assert_eq!(
+        store.graph().property(node, "tags"),
+        Some(&Value::Map(tags.clone()))
+    );
+    assert!(store.graph().property(node, "raw").is_none());
+    store
+        .set_property(PropertyOwner::Relation(r1), "label", "knows")
+        .unwrap();
+    store.checkpoint().unwrap();
+    let store = PersistentGraph::open(dir.path()).unwrap();
+    assert_eq!(
+        store.graph().property(edge, "weight"),
+        Some(&Value::Int(-3))
+    );
+    assert_eq!(store.graph().iter_properties().count(), 3);
+}
+
+#[test]
+fn test_diff_properties() {
+    let mut old = Graph::new();
+    let a = old.add_node(1);
+    let b = old.add_node(2);
+    let r1 = old.add_relation(1);
+    old.connect(a, b, r1).unwrap();
+    let node = PropertyOwner::Node(a);
+    let relation = PropertyOwner::Relation(r1);
+    let edge = PropertyOwner::Edge(Edge::new(a, b), r1);
+    old.set_property(node, "tags", vec![Value::Null, 2.into()])
+        .unwrap();
+    old.set_property(relation, "label", "knows").unwrap();
+    old.set_property(edge, "weight", -3).unwrap();
+
+    let mut new = old.clone();
+    new.set_property(edge, "weight", 4).unwrap();
+    new.remove_property(node, "tags");
+    let mut replica = Graph::new();
+    for diff in [Graph::new().diff(&old), old.diff(&new)] {
+        let bytes = diff.encode(&DefaultCodec).unwrap();
+        replica
+            .apply(&GraphDiff::decode(&bytes, &DefaultCodec).unwrap())
+            .unwrap();
+    }
+    assert_eq!(replica.property(edge, "weight"), Some(&Value::Int(4)));
+    assert!(replica.properties(node).is_none());
+    assert_eq!(
+        replica.property(relation, "label"),
+        Some(&Value::from("knows"))
+    );
+}
@@ -0,0 +1,126 @@
+use graphfruit::codec::DefaultCodec;
+use graphfruit::edge::Edge;
+use graphfruit::errors::ConnectError;
+use graphfruit::graph::Graph;
+use graphfruit::history::History;
+use graphfruit::mapped::MappedGraph;
+use graphfruit::relation::RelationKind;
+use graphfruit::store::PersistentGraph;
+use std::collections::HashSet;
+
+#[test]
+fn test_undirected() {
+    let mut graph = Graph::new();
+    let a = graph.add_node(1);
+    let b = graph.add_node(2);
+    let c = graph.add_node(3);
+    let sibling = graph.add_relation_of_kind(1, RelationKind::Undirected);
+    assert_eq!(
+        graph.relation(sibling).unwrap().kind(),
+        RelationKind::Undirected
+    );
+
+    assert!(graph.connect(b, a, sibling).unwrap());
+    assert!(!graph.connect(a, b, sibling).unwrap());
+    assert!(graph.connect(c, b, sibling).unwrap());
+    assert!(graph.connect(c, c, sibling).unwrap());
+
+    let relation = graph.relation(sibling).unwrap();
+    assert!(relation.contains_edge(&Edge::new(a, b)));
+    assert!(relation.contains_edge(&Edge::new(b, a)));
+    let edges: HashSet<_> = relation.iter_edges().copied().collect();
+    let expected: HashSet<_> = vec![Edge::new(a, b), Edge::new(b, c), Edge::new(c, c)]
+        .into_iter()
+        .collect();
+    assert_eq!(edges, expected);
+
+    assert_eq!(graph.out_degree_of(b), Some(2));
+    assert_eq!(graph.in_degree_of(b), Some(2));
+    assert_eq!(graph.out_degree_of(c), Some(2));
+    let neighbors: HashSet<_> = graph.iter_prev_nodes(a).unwrap().map(|(n, _)| n).collect();
+    assert_eq!(neighbors, vec![b].into_iter().collect());
+
+    assert!(graph.disconnect(a, b, sibling).unwrap());
+    assert_eq!(graph.out_degree_of(a), Some(0));
+    assert_eq!(graph.in_degree_of(b), Some(1));
+
+    graph.remove_node(c);
+    assert_eq!(graph.out_degree_of(b), Some(0));
+    assert_eq!(graph.relation(sibling).unwrap().iter_edges().count(), 0);
+}
+
+#[test]
+fn test_antisymmetric() {
+    let mut graph = Graph::new();
+    let a = graph.add_node(1);
+    let b = graph.add_node(2);
+    let parent = graph.add_relation_of_kind(1, RelationKind::Antisymmetric);
+
+    assert!(graph.connect(a, b, parent).unwrap());
+    assert!(matches!(
+        graph.connect(b, a, parent),
+        Err(ConnectError::ReverseEdgeExists)
+    ));
+    assert!(graph.connect(a, a, parent).unwrap());
+    assert_eq!(graph.in_degree_of(a), Some(1));
+
+    graph.disconnect(a, b, parent).unwrap();
+    assert!(graph.connect(b, a, parent).unwrap());
+}
+
+#[test]
+fn test_undo_keeps_kind() {
+    let mut history = History::new(Graph::new());
+    let a = history.add_node(1);
+    let b = history.add_node(2);
+    let sibling = history.add_relation_of_kind(1, RelationKind::Undirected);
+    history.connect(b, a, sibling).unwrap();
+    history.checkpoint("setup");
+
+    history.remove_relation(sibling);
+    history.undo();
+    let relation = history.graph().relation(sibling).unwrap();
+    assert_eq!(relation.kind(), RelationKind::Undirected);
+    assert!(relation.contains_edge(&Edge::new(a, b)));
+    assert_eq!(history.graph().out_degree_of(a), Some(1));
+}
+
+#[test]
+fn test_persisted_kind() {
+    let dir = tempfile::tempdir().unwrap();
+    let (a, b, sibling) = {
+        let mut store = PersistentGraph::open(dir.path()).unwrap();
+        let a = store.add_node(1).unwrap();
+        let b = store.add_node(2).unwrap();
+        let sibling = store
+            .add_relation_of_kind(1, RelationKind::Undirected)
+            .unwrap();
+        store.connect(b, a, sibling).unwrap();
+        (a, b, sibling)
+    };
+
+    let mut store = PersistentGraph::open(dir.path()).unwrap();
+    assert_eq!(
+        store.graph().relation(sibling).unwrap().kind(),
+        RelationKind::Undirected
+    );
+    store.checkpoint().unwrap();
+    let store = PersistentGraph::open(dir.path()).unwrap();
+    let graph = store.graph();
+    assert!(graph
+        .relation(sibling)
+        .unwrap()
+        .contains_edge(&Edge::new(a, b)));
+
+    let path = dir.path().join("mapped");
+    MappedGraph::write(graph, &DefaultCodec, &path).unwrap();
+    let mapped = MappedGraph::open(&path).unwrap();
+    assert_eq!(
+        mapped.relation_kind(sibling),
+        Some(RelationKind::Undirected)
+    );
+    assert_eq!(mapped.out_degree_of(a), Some(1));
+    assert_eq!(mapped.out_degree_of(b), Some(1));
+    let edges: Vec<_> = mapped.iter_relation_edges(sibling).unwrap().collect();
+    assert_eq!(edges, vec![Edge::new(a, b).normalized()]);
+}
@@ -0,0 +1,132 @@
+use graphfruit::errors::{ConnectError, SchemaViolation, StoreError};
+use graphfruit::graph::Graph;
+use graphfruit::schema::{Cardinality, RelationSchema};
+use graphfruit::store::PersistentGraph;
+
+fn violation(result: Result<bool, ConnectError>) -> Option<SchemaViolation> {
+    match result {
+        Err(ConnectError::SchemaViolation(violation)) => Some(violation),
+        _ => None,
+    }
+}
+
+#[test]
+fn test_endpoint_types() {
+    let mut graph = Graph::new();
+    let person = graph.add_node("alice".to_string());
+    let age = graph.add_node(42u32);
+    let score = graph.add_node(7i32);
+    let has_age = graph.add_relation(1);
+    assert!(graph.set_relation_schema(
+        has_age,
+        RelationSchema::new()
+            .allow_src::<String>()
+            .allow_dst::<u32>()
+            .allow_dst::<i32>(),
+    ));
+
+    assert!(graph.connect(person, age, has_age).unwrap());
+    assert!(graph.connect(person, score, has_age).unwrap());
+    assert_eq!(
+        violation(graph.connect(age, person, has_age)),
+        Some(SchemaViolation::SrcType)
+    );
+    assert_eq!(
+        violation(graph.connect(person, person, has_age)),
+        Some(SchemaViolation::DstType)
+    );
+    assert_eq!(graph.relation(has_age).unwrap().iter_edges().count(), 2);
+
+    assert!(graph.clear_relation_schema(has_age));
+    assert!(graph.relation(has_age).unwrap().schema().is_none());
+    assert!(graph.connect(age, person, has_age).unwrap());
+}
+
+#[test]
+fn test_cardinality() {
+    let mut graph = Graph::new();
+    let nodes: Vec<_> = (0..4).map(|i| graph.add_node(i)).collect();
+    let one_to_one = graph.add_relation(1);
+    let one_to_many = graph.add_relation(2);
+    let limited = graph.add_relation(3);
+    graph.set_relation_schema(
+        one_to_one,
+        RelationSchema::new().with_cardinality(Cardinality::OneToOne),
+    );
+    graph.set_relation_schema(
+        one_to_many,
+        RelationSchema::new().with_cardinality(Cardinality::OneToMany),
+    );
+    graph.set_relation_schema(
+        limited,
+        RelationSchema::new().with_cardinality(Cardinality::MaxOutDegree(2)),
+    );
+
+    assert!(graph.connect(nodes[0], nodes[1], one_to_one).unwrap());
+    assert!(!graph.connect(nodes[0], nodes[1], one_to_one).unwrap());
+    assert_eq!(
+        violation(graph.connect(nodes[0], nodes[2], one_to_one)),
+        Some(SchemaViolation::MaxOutDegree)
+    );
+    assert_eq!(
+        violation(graph.connect(nodes[2], nodes[1], one_to_one)),
+        Some(SchemaViolation::MaxInDegree)
+    );
+
+    graph.connect(nodes[0], nodes[1], one_to_many).unwrap();
+    graph.connect(nodes[0], nodes[2], one_to_many).unwrap();
+    assert_eq!(
+        violation(graph.connect(nodes[3], nodes[2], one_to_many)),
+        Some(SchemaViolation::MaxInDegree)
+    );
+
+    graph.connect(nodes[0], nodes[0], limited).unwrap();
+    graph.connect(nodes[0], nodes[1], limited).unwrap();
+    graph.connect(nodes[1], nodes[0], limited).unwrap();
+    assert_eq!(
+        violation(graph.connect(nodes[0], nodes[2], limited)),
+        Some(SchemaViolation::MaxOutDegree)
+    );
+    graph.disconnect(nodes[0], nodes[1], limited).unwrap();
+    assert!(graph.connect(nodes[0], nodes[2], limited).unwrap());
+}
+
+#[test]
+fn test_invalid_ids_before_schema() {
+    let mut graph = Graph::new();
+    let n1 = graph.add_node(1);
+    let r1 = graph.add_relation(1);
+    graph.set_relation_schema(r1, RelationSchema::new().allow_src::<String>());
+    let removed = graph.add_node(2);
+    graph.remove_node(removed);
+    assert!(matches!(
+        graph.connect(n1, removed, r1),
+        Err(ConnectError::InvalidDstNodeId)
+    ));
+    let r2 = graph.add_relation(2);
+    graph.remove_relation(r2);
+    assert!(!graph.set_relation_schema(r2, RelationSchema::new()));
+}
+
+#[test]
+fn test_store_schema_is_not_persisted() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut store = PersistentGraph::open(dir.path()).unwrap();
+    let person = store.add_node("alice".to_string()).unwrap();
+    let age = store.add_node(42u32).unwrap();
+    let has_age = store.add_relation(1).unwrap();
+    assert!(store.set_relation_schema(has_age, RelationSchema::new().allow_src::<String>()));
+    assert!(matches!(
+        store.connect(age, person, has_age),
+        Err(StoreError::Connect(ConnectError::SchemaViolation(
+            SchemaViolation::SrcType
+        )))
+    ));
+    assert!(store.connect(person, age, has_age).unwrap());
+    drop(store);
+
+    let store = PersistentGraph::open(dir.path()).unwrap();
+    let relation = store.graph().relation(has_age).unwrap();
+    assert!(relation.schema().is_none());
+    assert_eq!(relation.iter_edges().count(), 1);
+}
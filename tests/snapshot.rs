@@ -0,0 +1,215 @@
+use graphfruit::edge::Edge;
+use graphfruit::graph::Graph;
+use graphfruit::node::{AnyNodeInfo, NodeInfo};
+use graphfruit::property::PropertyOwner;
+use graphfruit::relation::Relation;
+use graphfruit::value::Value;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+struct Tracked(Arc<AtomicUsize>);
+
+impl NodeInfo for Tracked {}
+
+impl Drop for Tracked {
+    fn drop(&mut self) {
+        self.0.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+#[test]
+fn test_snapshot_isolation() {
+    let mut graph = Graph::new();
+    let n1 = graph.add_node(1);
+    let n2 = graph.add_node(2);
+    let r1 = graph.add_relation(1);
+    graph.connect(n1, n2, r1).unwrap();
+
+    let snapshot = graph.snapshot();
+    let n3 = graph.add_node(3);
+    graph.connect(n2, n3, r1).unwrap();
+    graph.disconnect(n1, n2, r1).unwrap();
+    let r2 = graph.add_relation(2);
+    graph.remove_node(n1);
+
+    assert_eq!(snapshot.nr_nodes(), 2);
+    assert_eq!(snapshot.nr_relations(), 1);
+    assert!(snapshot.contains_node(n1));
+    assert!(!snapshot.contains_node(n3));
+    assert!(!snapshot.contains_relation(r2));
+    assert_eq!(snapshot.out_degree_of(n1), Some(1));
+    assert_eq!(snapshot.in_degree_of(n2), Some(1));
+    let edges: Vec<Edge> = snapshot.iter_relation_edges(r1).unwrap().copied().collect();
+    assert_eq!(edges, vec![Edge::new(n1, n2)]);
+
+    assert_eq!(graph.nr_nodes(), 2);
+    assert_eq!(graph.nr_relations(), 2);
+    assert_eq!(graph.in_degree_of(n2), Some(0));
+    let edges: Vec<Edge> = graph.iter_relation_edges(r1).unwrap().copied().collect();
+    assert_eq!(edges, vec![Edge::new(n2, n3)]);
+
+    let clone = snapshot.clone();
+    drop(snapshot);
+    assert_eq!(clone.nr_nodes(), 2);
+}
+
+#[test]
+fn test_snapshot_reader_thread() {
+    let mut graph = Graph::new();
+    let relations: Vec<_> = (0..10).map(|i| graph.add_relation(i)).collect();
+    let node_ids: Vec<_> = (0..100).map(|i| graph.add_node(i)).collect();
+    for (i, relation_id) in relations.iter().enumerate() {
+        for j in 0..99 {
+            graph
+                .connect(node_ids[j], node_ids[(j + i + 1) % 100], *relation_id)
+                .unwrap();
+        }
+    }
+
+    let snapshot = graph.snapshot();
+    let reader = thread::spawn(move || {
+        let mut total = 0;
+        for _ in 0..20 {
+            total = snapshot
+                .iter_relations()
+                .map(|(_, relation)| relation.iter_edges().count())
+                .sum::<usize>();
+            assert_eq!(total, 990);
+        }
+        total
+    });
+
+    for relation_id in &relations {
+        for j in 0..50 {
+            graph
+                .disconnect(node_ids[j], node_ids[(j + 1) % 100], *relation_id)
+                .unwrap();
+            graph
+                .connect(node_ids[j], node_ids[j], *relation_id)
+                .unwrap();
+        }
+    }
+    for node_id in &node_ids[..10] {
+        graph.remove_node(*node_id);
+    }
+
+    assert_eq!(reader.join().unwrap(), 990);
+}
+
+#[test]
+fn test_snapshot_garbage_collection() {
+    let drops = Arc::new(AtomicUsize::new(0));
+    let mut graph = Graph::new();
+    let n1 = graph.add_node(Tracked(drops.clone()));
+    let n2 = graph.add_node(Tracked(drops.clone()));
+
+    let snapshot = graph.snapshot();
+    let mut info = graph.remove_node(n1).unwrap();
+    assert!(info.get_mut().is_none());
+    assert!(info.make_mut::<i32>().is_none());
+    drop(info);
+    assert_eq!(drops.load(Ordering::SeqCst), 0);
+    assert!(snapshot.node_info(n1).unwrap().is::<Tracked>());
+
+    drop(snapshot);
+    assert_eq!(drops.load(Ordering::SeqCst), 1);
+    graph.remove_node(n2);
+    assert_eq!(drops.load(Ordering::SeqCst), 2);
+}
+
+#[test]
+fn test_shared_info_is_copied_on_write() {
+    let mut graph = Graph::new();
+    let n1 = graph.add_node(1);
+    let r1 = graph.add_relation(1);
+
+    let snapshot = graph.snapshot();
+    let doubled = graph.with_node_info_mut(n1, |n: &mut i32| {
+        *n *= 2;
+        *n
+    });
+    assert_eq!(doubled.unwrap(), Some(2));
+    assert_eq!(
+        snapshot.node_info(n1).unwrap().downcast_ref::<i32>(),
+        Some(&1)
+    );
+    assert_eq!(graph.node_info(n1).unwrap().downcast_ref::<i32>(), Some(&2));
+
+    let mut info = graph.remove_relation(r1).unwrap();
+    *info.make_mut::<i32>().unwrap() = 5;
+    assert_eq!(
+        snapshot.relation(r1).unwrap().info().downcast_ref::<i32>(),
+        Some(&1)
+    );
+}
+
+#[test]
+fn test_snapshot_isolates_nested_data() {
+    let mut graph = Graph::new();
+    let n1 = graph.add_node(1);
+    let n2 = graph.add_node(2);
+    let r1 = graph.add_relation(1);
+    let index = graph.add_hash_index(|info| info.downcast_ref::<i32>().copied());
+    graph.connect(n1, n2, r1).unwrap();
+    graph.add_label(n1, "a");
+    graph.set_property(PropertyOwner::Node(n1), "k", 1).unwrap();
+
+    let snapshot = graph.snapshot();
+    let r2 = graph.add_relation(2);
+    graph.connect(n1, n2, r2).unwrap();
+    graph.connect(n2, n1, r1).unwrap();
+    graph.add_label(n2, "a");
+    graph.set_property(PropertyOwner::Node(n1), "k", 2).unwrap();
+    graph.set_property(PropertyOwner::Node(n2), "k", 3).unwrap();
+    let n3 = graph.add_node(1);
+
+    assert_eq!(snapshot.out_degree_of(n1), Some(1));
+    assert_eq!(snapshot.in_degree_of(n1), Some(0));
+    assert_eq!(snapshot.nodes_with_label("a").collect::<Vec<_>>(), vec![n1]);
+    assert_eq!(
+        snapshot.property(PropertyOwner::Node(n1), "k"),
+        Some(&Value::from(1))
+    );
+    assert_eq!(snapshot.property(PropertyOwner::Node(n2), "k"), None);
+    assert_eq!(snapshot.lookup(index, &1).collect::<Vec<_>>(), vec![n1]);
+
+    assert_eq!(graph.out_degree_of(n1), Some(2));
+    assert_eq!(graph.in_degree_of(n1), Some(1));
+    assert_eq!(graph.nodes_with_label("a").count(), 2);
+    assert_eq!(
+        graph.property(PropertyOwner::Node(n1), "k"),
+        Some(&Value::from(2))
+    );
+    assert_eq!(graph.lookup(index, &1).count(), 2);
+    assert!(graph.lookup(index, &1).any(|node_id| node_id == n3));
+}
+
+#[test]
+fn test_unshared_info_deref_mut() {
+    let mut info = AnyNodeInfo::from(1);
+    *info.downcast_mut::<i32>().unwrap() = 2;
+    assert_eq!(info.downcast_ref::<i32>(), Some(&2));
+
+    let mut relation = Relation::new(1);
+    *relation
+        .try_info_mut()
+        .unwrap()
+        .downcast_mut::<i32>()
+        .unwrap() = 2;
+    let clone = relation.clone();
+    assert!(relation.try_info_mut().is_none());
+    *relation.make_info_mut::<i32>().unwrap() = 3;
+    assert_eq!(relation.info().downcast_ref::<i32>(), Some(&3));
+    assert_eq!(clone.info().downcast_ref::<i32>(), Some(&2));
+}
+
+#[test]
+#[should_panic(expected = "the node info is shared")]
+fn test_shared_info_deref_mut() {
+    let mut graph = Graph::new();
+    let n1 = graph.add_node(1);
+    let _snapshot = graph.snapshot();
+    let mut info = graph.remove_node(n1).unwrap();
+    info.downcast_mut::<i32>();
+}
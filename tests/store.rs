@@ -0,0 +1,294 @@
+use graphfruit::edge::Edge;
+use graphfruit::errors::{ConnectError, StoreError};
+use graphfruit::relation::RelationId;
+use graphfruit::store::PersistentGraph;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+
+/// Get the offsets of the records in an intact log.
+fn record_offsets(log: &[u8]) -> Vec<usize> {
+    let mut offsets = Vec::new();
+    let mut offset = 0;
+    while offset < log.len() {
+        offsets.push(offset);
+        let len = u32::from_le_bytes([
+            log[offset],
+            log[offset + 1],
+            log[offset + 2],
+            log[offset + 3],
+        ]);
+        offset += 12 + len as usize;
+    }
+    offsets
+}
+
+#[test]
+fn test_reopen() {
+    let dir = tempfile::tempdir().unwrap();
+    let (n1, n2, n3, r1, r2);
+    {
+        let mut store = PersistentGraph::open(dir.path()).unwrap();
+        n1 = store.add_node("Node1".to_string()).unwrap();
+        n2 = store.add_node(2).unwrap();
+        n3 = store.add_node(()).unwrap();
+        r1 = store.add_relation("Relation1".to_string()).unwrap();
+        r2 = store.add_relation(2u32).unwrap();
+        assert!(store.connect(n1, n2, r1).unwrap());
+        assert!(!store.connect(n1, n2, r1).unwrap());
+        assert!(store.connect(n2, n3, r1).unwrap());
+        assert!(store.connect(n3, n1, r2).unwrap());
+        assert!(store.disconnect(n2, n3, r1).unwrap());
+        assert!(matches!(
+            store.connect(n1, n2, RelationId::new(1000)),
+            Err(StoreError::Connect(ConnectError::InvalidRelationId))
+        ));
+    }
+
+    let mut store = PersistentGraph::open(dir.path()).unwrap();
+    let graph = store.graph();
+    assert_eq!(graph.nr_nodes(), 3);
+    assert_eq!(graph.nr_relations(), 2);
+    assert_eq!(
+        graph
+            .node_info(n1)
+            .unwrap()
+            .downcast_ref::<String>()
+            .unwrap(),
+        "Node1"
+    );
+    assert_eq!(
+        *graph.node_info(n2).unwrap().downcast_ref::<i32>().unwrap(),
+        2
+    );
+    assert!(graph.node_info(n3).unwrap().is::<()>());
+    assert_eq!(
+        *graph
+            .relation(r2)
+            .unwrap()
+            .info()
+            .downcast_ref::<u32>()
+            .unwrap(),
+        2
+    );
+    assert!(graph
+        .relation(r1)
+        .unwrap()
+        .contains_edge(&Edge::new(n1, n2)));
+    assert!(!graph
+        .relation(r1)
+        .unwrap()
+        .contains_edge(&Edge::new(n2, n3)));
+    assert!(graph
+        .relation(r2)
+        .unwrap()
+        .contains_edge(&Edge::new(n3, n1)));
+
+    // Ids are never handed out twice after a restart.
+    let n4 = store.add_node(4).unwrap();
+    assert!(n4 != n1 && n4 != n2 && n4 != n3);
+    assert!(store.remove_node(n1).unwrap().is_some());
+    assert!(store.remove_node(n1).unwrap().is_none());
+    assert!(store.remove_relation(r2).unwrap().is_some());
+    drop(store);
+
+    let store = PersistentGraph::open(dir.path()).unwrap();
+    assert_eq!(store.graph().nr_nodes(), 3);
+    assert_eq!(store.graph().nr_relations(), 1);
+    assert_eq!(store.graph().iter_relation_edges(r1).unwrap().count(), 0);
+}
+
+#[test]
+fn test_torn_write() {
+    let dir = tempfile::tempdir().unwrap();
+    let (n1, n2, r1);
+    {
+        let mut store = PersistentGraph::open(dir.path()).unwrap();
+        n1 = store.add_node(1).unwrap();
+        n2 = store.add_node(2).unwrap();
+        r1 = store.add_relation(1).unwrap();
+        store.connect(n1, n2, r1).unwrap();
+        store.add_node(3).unwrap();
+    }
+
+    // Simulate a crash in the middle of writing the last record.
+    let wal = dir.path().join("wal");
+    let len = fs::metadata(&wal).unwrap().len();
+    OpenOptions::new()
+        .write(true)
+        .open(&wal)
+        .unwrap()
+        .set_len(len - 3)
+        .unwrap();
+
+    {
+        let mut store = PersistentGraph::open(dir.path()).unwrap();
+        assert_eq!(store.graph().nr_nodes(), 2);
+        assert_eq!(store.graph().out_degree_of(n1).unwrap(), 1);
+        let n3 = store.add_node(3).unwrap();
+        store.connect(n2, n3, r1).unwrap();
+    }
+
+    let store = PersistentGraph::open(dir.path()).unwrap();
+    assert_eq!(store.graph().nr_nodes(), 3);
+    assert_eq!(store.graph().iter_relation_edges(r1).unwrap().count(), 2);
+}
+
+#[test]
+fn test_corrupt_tail() {
+    let dir = tempfile::tempdir().unwrap();
+    {
+        let mut store = PersistentGraph::open(dir.path()).unwrap();
+        for i in 0..10 {
+            store.add_node(i).unwrap();
+        }
+    }
+
+    // A last record whose checksum doesn't match its payload.
+    let wal = dir.path().join("wal");
+    let log = fs::read(&wal).unwrap();
+    let mut record = log[*record_offsets(&log).last().unwrap()..].to_vec();
+    *record.last_mut().unwrap() ^= 0xff;
+    let mut file = OpenOptions::new().append(true).open(&wal).unwrap();
+    file.write_all(&record).unwrap();
+    drop(file);
+
+    let mut store = PersistentGraph::open(dir.path()).unwrap();
+    assert_eq!(store.graph().nr_nodes(), 10);
+    store.add_node(10).unwrap();
+    drop(store);
+
+    let store = PersistentGraph::open(dir.path()).unwrap();
+    assert_eq!(store.graph().nr_nodes(), 11);
+    drop(store);
+
+    // A damaged record followed by intact ones is not a torn write.
+    let mut log = fs::read(&wal).unwrap();
+    let middle = log.len() / 2;
+    log[middle] ^= 0xff;
+    fs::write(&wal, &log).unwrap();
+    assert!(matches!(
+        PersistentGraph::open(dir.path()),
+        Err(StoreError::CorruptLog)
+    ));
+    assert_eq!(fs::read(&wal).unwrap(), log);
+}
+
+#[test]
+fn test_corrupt_length() {
+    let dir = tempfile::tempdir().unwrap();
+    {
+        let mut store = PersistentGraph::open(dir.path()).unwrap();
+        for i in 0..10 {
+            store.add_node(i).unwrap();
+        }
+    }
+
+    // A damaged length pointing past the end of the log must not be taken
+    // for a torn write, which would drop the records after it.
+    let wal = dir.path().join("wal");
+    let mut log = fs::read(&wal).unwrap();
+    let offsets = record_offsets(&log);
+    log[offsets[offsets.len() / 2] + 3] ^= 0xff;
+    fs::write(&wal, &log).unwrap();
+    assert!(matches!(
+        PersistentGraph::open(dir.path()),
+        Err(StoreError::CorruptLog)
+    ));
+    assert_eq!(fs::read(&wal).unwrap(), log);
+}
+
+#[test]
+fn test_checkpoint() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut node_ids = Vec::new();
+    let relation_id;
+    {
+        let mut store = PersistentGraph::open(dir.path()).unwrap();
+        store.set_checkpoint_interval(16);
+        relation_id = store.add_relation("next".to_string()).unwrap();
+        for i in 0..100 {
+            node_ids.push(store.add_node(i).unwrap());
+        }
+        for i in 0..99 {
+            store
+                .connect(node_ids[i], node_ids[i + 1], relation_id)
+                .unwrap();
+        }
+        assert!(dir.path().join("snapshot").exists());
+        assert!(fs::metadata(dir.path().join("wal")).unwrap().len() > 0);
+    }
+
+    let mut store = PersistentGraph::open(dir.path()).unwrap();
+    assert_eq!(store.graph().nr_nodes(), 100);
+    assert_eq!(
+        store
+            .graph()
+            .iter_relation_edges(relation_id)
+            .unwrap()
+            .count(),
+        99
+    );
+    store.checkpoint().unwrap();
+    assert_eq!(fs::metadata(dir.path().join("wal")).unwrap().len(), 0);
+    drop(store);
+
+    let mut store = PersistentGraph::open(dir.path()).unwrap();
+    assert_eq!(store.graph().nr_nodes(), 100);
+    for node_id in &node_ids[1..99] {
+        assert_eq!(store.graph().in_degree_of(*node_id).unwrap(), 1);
+        assert_eq!(store.graph().out_degree_of(*node_id).unwrap(), 1);
+    }
+
+    // A snapshot that can't be written doesn't fail the mutation
+    fs::create_dir(dir.path().join("snapshot.tmp")).unwrap();
+    store.set_checkpoint_interval(1);
+    let n1 = store.add_node(100).unwrap();
+    assert!(matches!(
+        store.take_checkpoint_error(),
+        Some(StoreError::Io(_))
+    ));
+    assert!(store.take_checkpoint_error().is_none());
+    fs::remove_dir(dir.path().join("snapshot.tmp")).unwrap();
+    let n2 = store.add_node(101).unwrap();
+    assert!(store.take_checkpoint_error().is_none());
+    assert_eq!(fs::metadata(dir.path().join("wal")).unwrap().len(), 0);
+    drop(store);
+
+    let store = PersistentGraph::open(dir.path()).unwrap();
+    assert!(store.graph().contains_node(n1));
+    assert!(store.graph().contains_node(n2));
+}
+
+#[test]
+fn test_crash_before_log_truncation() {
+    let dir = tempfile::tempdir().unwrap();
+    let wal = dir.path().join("wal");
+    {
+        let mut store = PersistentGraph::open(dir.path()).unwrap();
+        let n1 = store.add_node(1).unwrap();
+        let n2 = store.add_node(2).unwrap();
+        let r1 = store.add_relation(1).unwrap();
+        store.connect(n1, n2, r1).unwrap();
+        store.remove_node(n2).unwrap();
+
+        // Keep the log as it was before the checkpoint emptied it.
+        let log = fs::read(&wal).unwrap();
+        store.checkpoint().unwrap();
+        fs::write(&wal, log).unwrap();
+    }
+
+    let store = PersistentGraph::open(dir.path()).unwrap();
+    assert_eq!(store.graph().nr_nodes(), 1);
+    assert_eq!(store.graph().nr_relations(), 1);
+    assert_eq!(
+        store
+            .graph()
+            .iter_relations()
+            .next()
+            .unwrap()
+            .1
+            .iter_edges()
+            .count(),
+        0
+    );
+}
@@ -0,0 +1,265 @@
+use graphfruit::edge::Edge;
+use graphfruit::graph::Graph;
+use graphfruit::hyperedge::{Hyperedge, HyperedgeKind};
+use graphfruit::node::{AnyNodeInfo, NodeId};
+use graphfruit::property::PropertyOwner;
+use graphfruit::read::GraphRead;
+use graphfruit::relation::RelationKind;
+use graphfruit::schema::RelationSchema;
+use graphfruit::value::Value;
+
+fn share(_: NodeId, info: &AnyNodeInfo) -> AnyNodeInfo {
+    info.clone()
+}
+
+fn info_of(graph: &Graph, node_id: NodeId) -> i32 {
+    *graph
+        .node_info(node_id)
+        .unwrap()
+        .downcast_ref::<i32>()
+        .unwrap()
+}
+
+#[test]
+fn test_induced_subgraph() {
+    let mut graph = Graph::new();
+    let nodes: Vec<_> = (0..4).map(|i| graph.add_node(i)).collect();
+    let r1 = graph.add_named_relation("next", 1).unwrap();
+    let r2 = graph.add_multi_relation(2, RelationKind::Directed);
+    let r3 = graph.add_hyper_relation(3, HyperedgeKind::Ordered);
+    for pair in nodes.windows(2) {
+        graph.connect_with(pair[0], pair[1], r1, 5u32).unwrap();
+    }
+    graph.connect_multi(nodes[0], nodes[1], r2).unwrap();
+    graph.connect_multi(nodes[0], nodes[1], r2).unwrap();
+    graph
+        .connect_hyperedge(r3, Hyperedge::new(vec![nodes[0]], vec![nodes[1], nodes[2]]))
+        .unwrap();
+    graph
+        .connect_hyperedge(r3, Hyperedge::new(vec![nodes[0]], vec![nodes[3]]))
+        .unwrap();
+    graph.add_label(nodes[1], "middle");
+    graph
+        .set_property(PropertyOwner::Node(nodes[2]), "name", "two")
+        .unwrap();
+
+    let (sub, ids) = graph.induced_subgraph(nodes[..3].iter().copied(), share);
+    assert_eq!(sub.nr_nodes(), 3);
+    assert_eq!(ids.nr_nodes(), 3);
+    assert_eq!(ids.nr_relations(), 3);
+    assert!(ids.node_id(nodes[3]).is_none());
+    let (a, b, c) = (
+        ids.node_id(nodes[0]).unwrap(),
+        ids.node_id(nodes[1]).unwrap(),
+        ids.node_id(nodes[2]).unwrap(),
+    );
+    assert_eq!(info_of(&sub, c), 2);
+    assert!(sub.has_label(b, "middle"));
+    assert_eq!(
+        sub.property(PropertyOwner::Node(c), "name"),
+        Some(&Value::from("two"))
+    );
+
+    let new_r1 = ids.relation_id(r1).unwrap();
+    assert_eq!(sub.relation_by_name("next"), Some(new_r1));
+    assert_eq!(sub.iter_relation_edges(new_r1).unwrap().count(), 2);
+    assert_eq!(
+        sub.edge_info(a, b, new_r1).unwrap().downcast_ref::<u32>(),
+        Some(&5)
+    );
+    let new_r2 = ids.relation_id(r2).unwrap();
+    assert_eq!(
+        sub.relation(new_r2).unwrap().multiplicity(&Edge::new(a, b)),
+        2
+    );
+    let new_r3 = ids.relation_id(r3).unwrap();
+    assert_eq!(sub.relation(new_r3).unwrap().iter_hyperedges().count(), 1);
+    assert_eq!(graph.nr_nodes(), 4);
+}
+
+#[test]
+fn test_edge_subgraph() {
+    let mut graph = Graph::new();
+    let a = graph.add_node(1);
+    let b = graph.add_node(2);
+    let c = graph.add_node(3);
+    let r1 = graph.add_relation_of_kind(1, RelationKind::Undirected);
+    let r2 = graph.add_relation(2);
+    graph.connect(a, b, r1).unwrap();
+    graph.connect(b, c, r1).unwrap();
+    graph.connect(a, c, r2).unwrap();
+    graph
+        .set_property(PropertyOwner::Edge(Edge::new(a, b), r1), "weight", 3)
+        .unwrap();
+
+    let edges = vec![
+        (Edge::new(b, a), r1),
+        (Edge::new(a, b), r1),
+        (Edge::new(c, a), r2),
+    ];
+    let (sub, ids) = graph.edge_subgraph(edges, |_, info| {
+        let value = info.downcast_ref::<i32>().unwrap();
+        AnyNodeInfo::from(value * 10)
+    });
+    assert_eq!(sub.nr_nodes(), 2);
+    assert_eq!(sub.nr_relations(), 1);
+    assert!(ids.relation_id(r2).is_none());
+    let (new_a, new_b) = (ids.node_id(a).unwrap(), ids.node_id(b).unwrap());
+    assert_eq!(info_of(&sub, new_b), 20);
+    let new_r1 = ids.relation_id(r1).unwrap();
+    assert_eq!(sub.iter_relation_edges(new_r1).unwrap().count(), 1);
+    assert_eq!(
+        sub.property(
+            PropertyOwner::Edge(Edge::new(new_b, new_a), new_r1),
+            "weight"
+        ),
+        Some(&Value::Int(3))
+    );
+}
+
+#[test]
+fn test_ego_graph() {
+    let mut graph = Graph::new();
+    let nodes: Vec<_> = (0..6).map(|i| graph.add_node(i)).collect();
+    let r1 = graph.add_relation(1);
+    let r2 = graph.add_relation(2);
+    // 0 -> 1 -> 2 -> 3 in r1, 4 -> 1 in r1 and 1 -> 5 in r2
+    for pair in nodes[..4].windows(2) {
+        graph.connect(pair[0], pair[1], r1).unwrap();
+    }
+    graph.connect(nodes[4], nodes[1], r1).unwrap();
+    graph.connect(nodes[1], nodes[5], r2).unwrap();
+
+    let (ego, ids) = graph.ego_graph(nodes[1], 1, None, share).unwrap();
+    let mut kept: Vec<_> = ids.iter_node_ids().map(|(old, _)| old).collect();
+    kept.sort_by_key(|id| **id);
+    assert_eq!(kept, [nodes[0], nodes[1], nodes[2], nodes[4], nodes[5]]);
+    assert_eq!(ego.nr_relations(), 2);
+
+    let (ego, ids) = graph.ego_graph(nodes[1], 2, Some(&[r1]), share).unwrap();
+    assert_eq!(ego.nr_nodes(), 5);
+    assert!(ids.node_id(nodes[3]).is_some());
+    assert!(ids.node_id(nodes[5]).is_none());
+    assert!(ids.relation_id(r2).is_none());
+    let new_r1 = ids.relation_id(r1).unwrap();
+    assert_eq!(ego.iter_relation_edges(new_r1).unwrap().count(), 4);
+
+    let (ego, _) = graph.ego_graph(nodes[3], 0, None, share).unwrap();
+    assert_eq!(ego.nr_nodes(), 1);
+    assert!(graph.ego_graph(NodeId::new(99), 1, None, share).is_none());
+}
+
+#[test]
+fn test_subgraph_is_independent() {
+    let mut graph = Graph::new();
+    let a = graph.add_node(1);
+    let b = graph.add_node(2);
+    let r1 = graph.add_relation(1);
+    graph.connect_with(a, b, r1, 5u32).unwrap();
+
+    let (mut sub, ids) = graph.induced_subgraph(vec![a, b], share);
+    let new_a = ids.node_id(a).unwrap();
+    let new_b = ids.node_id(b).unwrap();
+    let new_r1 = ids.relation_id(r1).unwrap();
+    *graph.edge_info_mut::<u32>(a, b, r1).unwrap() = 6;
+    let info = sub.edge_info(new_a, new_b, new_r1).unwrap();
+    assert_eq!(info.downcast_ref::<u32>(), Some(&5));
+
+    let c = sub.add_node(3);
+    sub.connect(new_a, c, new_r1).unwrap();
+    sub.remove_node(new_b);
+
+    assert_eq!(graph.nr_nodes(), 2);
+    assert_eq!(graph.iter_relation_edges(r1).unwrap().count(), 1);
+    assert_eq!(graph.out_degree_of(a), Some(1));
+    assert_eq!(sub.out_degree_of(new_a), Some(1));
+}
+
+#[test]
+fn test_subgraph_of_view() {
+    let mut graph = Graph::new();
+    let nodes: Vec<_> = (0..4).map(|i| graph.add_node(i)).collect();
+    let r1 = graph.add_relation(1);
+    let r2 = graph.add_relation(2);
+    for pair in nodes.windows(2) {
+        graph.connect_with(pair[0], pair[1], r1, 7u32).unwrap();
+    }
+    graph.connect(nodes[0], nodes[2], r2).unwrap();
+    graph.add_label(nodes[2], "even");
+
+    // Node 1 is hidden, so only the edge 2 -> 3 of r1 survives, reversed.
+    let view = graph.filter_nodes(|_, info| info.downcast_ref::<i32>() != Some(&1));
+    let view = view.reversed();
+    let (sub, ids) = view.induced_subgraph(nodes.iter().copied(), share);
+    assert_eq!(sub.nr_nodes(), 3);
+    assert!(ids.node_id(nodes[1]).is_none());
+    let (a, c, d) = (
+        ids.node_id(nodes[0]).unwrap(),
+        ids.node_id(nodes[2]).unwrap(),
+        ids.node_id(nodes[3]).unwrap(),
+    );
+    let new_r1 = ids.relation_id(r1).unwrap();
+    assert_eq!(
+        sub.iter_relation_edges(new_r1).unwrap().collect::<Vec<_>>(),
+        [&Edge::new(d, c)]
+    );
+    let info = sub.edge_info(d, c, new_r1).unwrap();
+    assert_eq!(info.downcast_ref::<u32>(), Some(&7));
+    let new_r2 = ids.relation_id(r2).unwrap();
+    assert!(sub
+        .relation(new_r2)
+        .unwrap()
+        .contains_edge(&Edge::new(c, a)));
+    assert!(sub.has_label(c, "even"));
+
+    let only_r1 = graph.filter_relations(vec![r1]);
+    let (ego, ids) = only_r1.ego_graph(nodes[0], 2, None, share).unwrap();
+    assert_eq!(ego.nr_nodes(), 3);
+    assert_eq!(ego.nr_relations(), 1);
+    assert!(ids.relation_id(r2).is_none());
+    let (sub, _) = only_r1.edge_subgraph(vec![(Edge::new(nodes[0], nodes[2]), r2)], share);
+    assert_eq!(sub.nr_nodes(), 0);
+}
+
+#[test]
+fn test_subgraph_keeps_edges_the_schema_rejects() {
+    let mut graph = Graph::new();
+    let nodes: Vec<_> = (0..3).map(|i| graph.add_node(i)).collect();
+    let r1 = graph.add_relation(1);
+    let r2 = graph.add_multi_relation(2, RelationKind::Directed);
+    let schema = RelationSchema::new().allow_src::<i32>().allow_dst::<i32>();
+    graph.set_relation_schema(r1, schema.clone());
+    graph.set_relation_schema(r2, schema);
+    graph.connect(nodes[0], nodes[1], r1).unwrap();
+    graph.connect(nodes[1], nodes[2], r1).unwrap();
+    graph.connect_multi(nodes[0], nodes[1], r2).unwrap();
+    graph.connect_multi(nodes[0], nodes[1], r2).unwrap();
+    let to_string = |_: NodeId, info: &AnyNodeInfo| {
+        AnyNodeInfo::from(info.downcast_ref::<i32>().unwrap().to_string())
+    };
+
+    let (mut sub, ids) = graph.induced_subgraph(nodes.clone(), to_string);
+    assert_eq!(
+        sub.iter_relation_edges(ids.relation_id(r1).unwrap())
+            .unwrap()
+            .count(),
+        2
+    );
+    let new_r2 = ids.relation_id(r2).unwrap();
+    assert_eq!(
+        sub.relation(new_r2).unwrap().iter_parallel_edges().count(),
+        2
+    );
+    assert!(sub.relation(new_r2).unwrap().schema().is_some());
+    let (a, c) = (
+        ids.node_id(nodes[0]).unwrap(),
+        ids.node_id(nodes[2]).unwrap(),
+    );
+    assert!(sub.connect(a, c, ids.relation_id(r1).unwrap()).is_err());
+
+    let (sub, ids) = graph.edge_subgraph([(Edge::new(nodes[0], nodes[1]), r1)], to_string);
+    assert_eq!(sub.out_degree_of(ids.node_id(nodes[0]).unwrap()), Some(1));
+    let (sub, ids) = graph.ego_graph(nodes[1], 1, None, to_string).unwrap();
+    assert_eq!(sub.out_degree_of(ids.node_id(nodes[0]).unwrap()), Some(3));
+    assert_eq!(sub.out_degree_of(ids.node_id(nodes[1]).unwrap()), Some(1));
+}
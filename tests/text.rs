@@ -0,0 +1,130 @@
+use graphfruit::graph::Graph;
+use graphfruit::history::History;
+use graphfruit::index::NodeIndex;
+use graphfruit::node::NodeInfo;
+use graphfruit::store::PersistentGraph;
+use graphfruit::text::tokenize;
+
+#[derive(Clone)]
+struct Product {
+    name: &'static str,
+    description: &'static str,
+}
+
+impl NodeInfo for Product {}
+
+fn product_text(info: &dyn NodeInfo) -> Option<String> {
+    let product = info.downcast_ref::<Product>()?;
+    Some(format!("{} {}", product.name, product.description))
+}
+
+#[test]
+fn test_tokenize() {
+    let terms: Vec<_> = tokenize("Hello, World! It's 2024-01").collect();
+    assert_eq!(terms, ["hello", "world", "it", "s", "2024", "01"]);
+    assert_eq!(tokenize(" ,; ").count(), 0);
+}
+
+#[test]
+fn test_search_strings() {
+    let mut graph = Graph::new();
+    let a = graph.add_node("the quick brown fox".to_string());
+    let index = graph.add_string_text_index();
+    let b = graph.add_node("a lazy brown dog, a brown dog".to_string());
+    let c = graph.add_node("The Fox".to_string());
+    graph.add_node(7u32);
+
+    let hits = graph.search(index, "FOX");
+    assert_eq!(hits.iter().map(|(id, _)| *id).collect::<Vec<_>>(), [c, a]);
+    assert!(hits[0].1 > hits[1].1);
+
+    let hits = graph.search(index, "brown dog");
+    assert_eq!(hits[0].0, b);
+    assert_eq!(hits.len(), 2);
+    assert!(graph.search(index, "cat").is_empty());
+
+    graph.remove_node(c);
+    assert_eq!(graph.search(index, "fox")[0].0, a);
+    assert_eq!(graph.search(index, "fox").len(), 1);
+    assert!(graph.remove_text_index(index));
+    assert!(graph.search(index, "fox").is_empty());
+}
+
+#[test]
+fn test_search_prefix() {
+    let mut graph = Graph::new();
+    let index = graph.add_text_index(product_text);
+    let a = graph.add_node(Product {
+        name: "Graph database",
+        description: "Stores graphs",
+    });
+    let b = graph.add_node(Product {
+        name: "Grapefruit",
+        description: "A citrus fruit",
+    });
+    let c = graph.add_node(Product {
+        name: "Spreadsheet",
+        description: "Draws graphs and charts",
+    });
+
+    let mut found: Vec<_> = graph
+        .search_prefix(index, "Graph")
+        .into_iter()
+        .map(|(id, _)| id)
+        .collect();
+    found.sort_by_key(|id| **id);
+    assert_eq!(found, [a, c]);
+    assert_eq!(graph.search_prefix(index, "grape")[0].0, b);
+    assert_eq!(graph.search_prefix(index, "").len(), 3);
+
+    graph
+        .replace_node_info(
+            b,
+            Product {
+                name: "Orange",
+                description: "A citrus fruit",
+            },
+        )
+        .unwrap();
+    assert!(graph.search_prefix(index, "grape").is_empty());
+    assert_eq!(graph.search(index, "orange")[0].0, b);
+}
+
+#[test]
+fn test_text_index_follows_undo() {
+    let dir = tempfile::tempdir().unwrap();
+    let a = {
+        let mut store = PersistentGraph::open(dir.path()).unwrap();
+        store.add_node("hello world".to_string()).unwrap()
+    };
+    let mut store = PersistentGraph::open(dir.path()).unwrap();
+    let index = store.add_text_index(|info| info.downcast_ref::<String>().cloned());
+    assert_eq!(store.graph().search(index, "hello")[0].0, a);
+
+    let mut history = History::new(store.graph().clone());
+    let b = history.add_node("hello again".to_string());
+    history.checkpoint("add");
+    assert_eq!(history.graph().search(index, "hello").len(), 2);
+    history.undo();
+    assert_eq!(history.graph().search(index, "hello").len(), 1);
+    history.redo();
+    assert_eq!(history.graph().search(index, "again")[0].0, b);
+}
+
+#[test]
+fn test_text_indexes_are_separate() {
+    let mut other = Graph::new();
+    let hash = other.add_hash_index(|info| info.downcast_ref::<String>().cloned());
+
+    let mut graph = Graph::new();
+    let a = graph.add_node("hello world".to_string());
+    let text = graph.add_string_text_index();
+    assert_eq!(hash.id(), text.id());
+
+    // A handle to another kind of index can't remove the text index.
+    assert!(!graph.remove_index(hash));
+    assert_eq!(graph.search(text, "hello")[0].0, a);
+    assert!(graph.remove_text_index(text));
+    assert!(!graph.remove_text_index(text));
+    assert!(graph.search(text, "hello").is_empty());
+}
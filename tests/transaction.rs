@@ -0,0 +1,164 @@
+use graphfruit::edge::Edge;
+use graphfruit::errors::ConnectError;
+use graphfruit::event::{EventFilter, GraphEvent};
+use graphfruit::graph::Graph;
+use graphfruit::node::NodeId;
+use graphfruit::relation::RelationKind;
+use std::panic::{self, AssertUnwindSafe};
+
+#[test]
+fn test_rollback_on_error() {
+    let mut graph = Graph::new();
+    let n1 = graph.add_node(1);
+    let n2 = graph.add_node(2);
+    let r1 = graph.add_relation(1);
+    graph.connect(n1, n2, r1).unwrap();
+
+    let mut added = None;
+    let result = graph.transaction(|tx| {
+        let n3 = tx.add_node(3);
+        added = Some(n3);
+        tx.connect(n2, n3, r1)?;
+        tx.remove_relation(r1);
+        tx.remove_node(n1);
+        tx.connect(n3, NodeId::new(1000), r1)
+    });
+    assert!(matches!(result, Err(ConnectError::InvalidDstNodeId)));
+
+    assert_eq!(graph.nr_nodes(), 2);
+    assert_eq!(graph.nr_relations(), 1);
+    assert!(graph.contains_node(n1));
+    assert!(!graph.contains_node(added.unwrap()));
+    assert_eq!(graph.out_degree_of(n1), Some(1));
+    assert_eq!(graph.out_degree_of(n2), Some(0));
+
+    // The id handed out inside the transaction is handed out again.
+    assert_eq!(graph.add_node(3), added.unwrap());
+}
+
+#[test]
+fn test_commit_and_guard() {
+    let mut graph = Graph::new();
+    let n1 = graph.add_node(1);
+    let r1 = graph.add_relation(1);
+
+    let n2 = graph
+        .transaction(|tx| {
+            let n2 = tx.add_node(2);
+            tx.connect(n1, n2, r1)?;
+            tx.connect(n2, n1, r1)?;
+            Ok::<_, ConnectError>(n2)
+        })
+        .unwrap();
+    assert_eq!(graph.nr_nodes(), 2);
+    assert_eq!(graph.in_degree_of(n1), Some(1));
+    graph.remove_node(n2);
+
+    {
+        let mut tx = graph.begin();
+        tx.connect(n1, n1, r1).unwrap();
+        tx.add_relation(2);
+        assert_eq!(tx.out_degree_of(n1), Some(1));
+    }
+    assert_eq!(graph.out_degree_of(n1), Some(0));
+    assert_eq!(graph.nr_relations(), 1);
+
+    let mut tx = graph.begin();
+    tx.connect(n1, n1, r1).unwrap();
+    tx.rollback();
+    assert_eq!(graph.out_degree_of(n1), Some(0));
+
+    let mut tx = graph.begin();
+    tx.connect(n1, n1, r1).unwrap();
+    tx.commit();
+    assert_eq!(graph.out_degree_of(n1), Some(1));
+}
+
+#[test]
+fn test_rollback_on_panic() {
+    let mut graph = Graph::new();
+    let n1 = graph.add_node(1);
+
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        graph.transaction(|tx| {
+            tx.remove_node(n1);
+            panic!("failed halfway");
+            #[allow(unreachable_code)]
+            Ok::<_, ConnectError>(())
+        })
+    }));
+    assert!(result.is_err());
+    assert!(graph.contains_node(n1));
+}
+
+#[test]
+fn test_info_mutation_in_transaction() {
+    let mut graph = Graph::new();
+    let n1 = graph.add_node(1);
+    let r1 = graph.add_relation(1);
+    graph.connect_with(n1, n1, r1, 10u32).unwrap();
+
+    let mut tx = graph.begin();
+    tx.with_node_info_mut(n1, |n: &mut i32| *n = 2)
+        .unwrap()
+        .unwrap();
+    *tx.edge_info_mut::<u32>(n1, n1, r1).unwrap() = 20;
+    assert_eq!(tx.node_info(n1).unwrap().downcast_ref::<i32>(), Some(&2));
+    tx.rollback();
+    assert_eq!(graph.node_info(n1).unwrap().downcast_ref::<i32>(), Some(&1));
+    let info = graph.edge_info(n1, n1, r1).unwrap();
+    assert_eq!(info.downcast_ref::<u32>(), Some(&10));
+
+    graph
+        .transaction(|tx| {
+            tx.with_node_info_mut(n1, |n: &mut i32| *n = 3)
+                .unwrap()
+                .ok_or(ConnectError::InvalidSrcNodeId)
+        })
+        .unwrap();
+    assert_eq!(graph.node_info(n1).unwrap().downcast_ref::<i32>(), Some(&3));
+}
+
+#[test]
+fn test_events_on_rollback() {
+    let mut graph = Graph::new();
+    let (n1, n2) = (graph.add_node(1), graph.add_node(2));
+    let r1 = graph.add_relation(1);
+    let r2 = graph.add_relation(2);
+    graph.connect(n1, n2, r2).unwrap();
+    graph.connect(n2, n1, r2).unwrap();
+    let (_, receiver) = graph.subscribe_channel(EventFilter::all());
+
+    let mut tx = graph.begin();
+    tx.add_node(9);
+    assert_eq!(receiver.try_iter().count(), 0);
+    tx.rollback();
+    assert_eq!(receiver.try_iter().count(), 0);
+    assert_eq!(graph.nr_nodes(), 2);
+
+    // Mirroring n2 -> n1 into the antisymmetric r3 conflicts with n1 -> n2
+    let r3 = graph.add_relation_of_kind(3, RelationKind::Antisymmetric);
+    assert!(graph.set_inverse(r2, r3).is_err());
+    assert_eq!(
+        receiver.try_iter().collect::<Vec<_>>(),
+        vec![GraphEvent::RelationAdded(r3)]
+    );
+
+    let mut outer = graph.begin();
+    outer.connect(n1, n1, r1).unwrap();
+    let result = outer.transaction(|inner| {
+        inner.connect(n2, n2, r1)?;
+        inner.connect(n1, n2, r3)?;
+        inner.connect(n2, n1, r3)
+    });
+    assert!(matches!(result, Err(ConnectError::ReverseEdgeExists)));
+    assert_eq!(receiver.try_iter().count(), 0);
+    outer.commit();
+    assert_eq!(
+        receiver.try_iter().collect::<Vec<_>>(),
+        vec![GraphEvent::EdgeConnected {
+            edge: Edge::new(n1, n1),
+            relation_id: r1
+        }]
+    );
+}
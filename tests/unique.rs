@@ -0,0 +1,189 @@
+use graphfruit::errors::{StoreError, UniqueError, WriteError};
+use graphfruit::graph::Graph;
+use graphfruit::history::History;
+use graphfruit::node::NodeInfo;
+use graphfruit::store::PersistentGraph;
+use graphfruit::write::GraphWrite;
+
+#[derive(Clone, Debug, PartialEq)]
+struct Person {
+    external_id: u32,
+    name: &'static str,
+}
+
+impl NodeInfo for Person {}
+
+fn person(external_id: u32, name: &'static str) -> Person {
+    Person { external_id, name }
+}
+
+fn external_id(info: &dyn NodeInfo) -> Option<u32> {
+    info.downcast_ref::<Person>()
+        .map(|person| person.external_id)
+}
+
+#[test]
+fn test_unique_index() {
+    let mut graph = Graph::new();
+    let index = graph.add_unique_index(external_id).unwrap();
+    let a = graph.add_node(person(1, "Ada"));
+    let b = graph.add_node(person(2, "Grace"));
+    graph.add_node("not a person".to_string());
+
+    assert!(matches!(
+        graph.try_add_node(person(1, "Alan")),
+        Err(UniqueError::DuplicateKey(node_id)) if node_id == a
+    ));
+    assert!(matches!(
+        graph.replace_node_info(b, person(1, "Grace")),
+        Err(UniqueError::DuplicateKey(node_id)) if node_id == a
+    ));
+    assert_eq!(graph.iter_nodes().count(), 3);
+    assert!(graph.replace_node_info(a, person(1, "Ada L.")).is_ok());
+    assert_eq!(graph.lookup(index, &1).collect::<Vec<_>>(), [a]);
+    let rename = |person: &mut Person| person.name = "Ada";
+    assert!(matches!(graph.with_node_info_mut(a, rename), Ok(Some(()))));
+    let steal_key = |person: &mut Person| person.external_id = 2;
+    assert!(matches!(
+        graph.with_node_info_mut(a, steal_key),
+        Err(UniqueError::DuplicateKey(node_id)) if node_id == b
+    ));
+    assert_eq!(
+        graph.node_info(a).unwrap().downcast_ref(),
+        Some(&person(1, "Ada"))
+    );
+    assert_eq!(graph.lookup(index, &2).collect::<Vec<_>>(), [b]);
+
+    graph.remove_node(a);
+    assert!(graph.try_add_node(person(1, "Alan")).is_ok());
+    assert!(graph.remove_index(index));
+    assert!(graph.try_add_node(person(1, "Alan")).is_ok());
+    assert!(matches!(
+        graph.add_unique_index(external_id),
+        Err(UniqueError::DuplicateKey(_))
+    ));
+}
+
+#[test]
+fn test_upsert_node() {
+    let mut graph = Graph::new();
+    let a = graph.add_node(person(1, "Ada"));
+    let index = graph.add_unique_index(external_id).unwrap();
+
+    assert_eq!(graph.upsert_node(index, person(1, "Ada L.")).unwrap(), a);
+    assert_eq!(
+        graph
+            .node_info(a)
+            .unwrap()
+            .downcast_ref::<Person>()
+            .unwrap()
+            .name,
+        "Ada L."
+    );
+    let b = graph.upsert_node(index, person(2, "Grace")).unwrap();
+    assert_ne!(a, b);
+    assert_eq!(graph.iter_nodes().count(), 2);
+    assert_eq!(graph.lookup(index, &2).collect::<Vec<_>>(), [b]);
+
+    assert!(graph.remove_index(index));
+    assert!(matches!(
+        graph.upsert_node(index, person(3, "Alan")),
+        Err(UniqueError::UnknownIndex)
+    ));
+    assert_eq!(graph.iter_nodes().count(), 2);
+}
+
+#[test]
+fn test_undo_upsert_node() {
+    let mut graph = Graph::new();
+    let index = graph.add_unique_index(external_id).unwrap();
+    let mut history = History::new(graph);
+    let a = history.upsert_node(index, person(1, "Ada")).unwrap();
+    history.checkpoint("add");
+    history.upsert_node(index, person(1, "Ada L.")).unwrap();
+    history.checkpoint("update");
+    assert!(history.try_add_node(person(1, "Alan")).is_err());
+
+    history.undo();
+    assert_eq!(
+        history
+            .graph()
+            .node_info(a)
+            .unwrap()
+            .downcast_ref::<Person>()
+            .unwrap()
+            .name,
+        "Ada"
+    );
+    history.undo();
+    assert_eq!(history.graph().lookup(index, &1).count(), 0);
+    history.redo();
+    assert_eq!(history.graph().lookup(index, &1).collect::<Vec<_>>(), [a]);
+}
+
+#[test]
+fn test_persisted_unique_index() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut store = PersistentGraph::open(dir.path()).unwrap();
+    let index = store
+        .add_unique_index(|info: &dyn NodeInfo| info.downcast_ref::<u32>().copied())
+        .unwrap();
+    let a = store.upsert_node(index, 1u32).unwrap();
+    assert_eq!(store.upsert_node(index, 1u32).unwrap(), a);
+    let b = store.add_node(2u32).unwrap();
+    assert!(matches!(
+        store.add_node(1u32),
+        Err(StoreError::Unique(UniqueError::DuplicateKey(node_id))) if node_id == a
+    ));
+    assert!(matches!(
+        store.replace_node_info(b, 1u32),
+        Err(StoreError::Unique(_))
+    ));
+    drop(store);
+
+    let mut store = PersistentGraph::open(dir.path()).unwrap();
+    assert_eq!(store.graph().iter_nodes().count(), 2);
+    assert_eq!(
+        store.graph().node_info(b).unwrap().downcast_ref(),
+        Some(&2u32)
+    );
+    assert!(matches!(
+        store.upsert_node(index, 1u32),
+        Err(StoreError::Unique(UniqueError::UnknownIndex))
+    ));
+    assert_eq!(store.graph().iter_nodes().count(), 2);
+}
+
+#[test]
+fn test_unique_index_merge_and_write() {
+    let mut graph = Graph::new();
+    let index = graph.add_unique_index(external_id).unwrap();
+    let a = graph.add_node(person(1, "Ada"));
+    let r = graph.add_relation(1);
+    let mut other = Graph::new();
+    let b = other.add_node(person(2, "Grace"));
+    let c = other.add_node(person(1, "Alan"));
+    let other_r = other.add_relation(1);
+    other.connect(b, c, other_r).unwrap();
+
+    // Nodes are matched by name, so Alan is copied and clashes with Ada.
+    let name = |info: &dyn NodeInfo| info.downcast_ref::<Person>().map(|person| person.name);
+    let keep_first = |info: &_, _: &_| Clone::clone(info);
+    assert!(matches!(
+        graph.merge(&other, name, keep_first),
+        Err(UniqueError::DuplicateKey(node_id)) if node_id == a
+    ));
+    assert!(graph.union(&other, name, keep_first).is_err());
+    assert_eq!(graph.nr_nodes(), 1);
+    assert_eq!(graph.lookup(index, &2).count(), 0);
+    assert_eq!(graph.nr_relations(), 1);
+    assert_eq!(graph.relation(r).unwrap().iter_edges().count(), 0);
+
+    assert!(matches!(
+        GraphWrite::add_node(&mut graph, person(1, "Alan")),
+        Err(WriteError::Unique(UniqueError::DuplicateKey(node_id))) if node_id == a
+    ));
+    let mut history = History::new(graph);
+    assert!(GraphWrite::add_node(&mut history, person(1, "Alan")).is_err());
+    assert!(GraphWrite::add_node(&mut history, person(2, "Grace")).is_ok());
+}
@@ -0,0 +1,144 @@
+use graphfruit::edge::Edge;
+use graphfruit::graph::Graph;
+use graphfruit::node::NodeId;
+use graphfruit::read::GraphRead;
+use graphfruit::relation::{RelationId, RelationKind};
+
+fn sorted<I>(nodes: I) -> Vec<NodeId>
+where
+    I: Iterator<Item = (NodeId, RelationId)>,
+{
+    let mut nodes: Vec<_> = nodes.map(|(node_id, _)| node_id).collect();
+    nodes.sort_by_key(|id| **id);
+    nodes
+}
+
+/// Count the nodes reachable from `start`, written once for every view.
+fn reachable<G: GraphRead>(graph: &G, start: NodeId) -> usize {
+    let mut seen = vec![start];
+    let mut stack = vec![start];
+    while let Some(node_id) = stack.pop() {
+        for (next, _) in graph.iter_next_nodes(node_id).into_iter().flatten() {
+            if !seen.contains(&next) {
+                seen.push(next);
+                stack.push(next);
+            }
+        }
+    }
+    seen.len()
+}
+
+#[test]
+fn test_reversed() {
+    let mut graph = Graph::new();
+    let a = graph.add_node(1);
+    let b = graph.add_node(2);
+    let c = graph.add_node(3);
+    let r1 = graph.add_multi_relation(1, RelationKind::Directed);
+    let r2 = graph.add_relation_of_kind(2, RelationKind::Undirected);
+    graph.connect_multi(a, b, r1).unwrap();
+    graph.connect_multi(a, b, r1).unwrap();
+    graph.connect(b, c, r2).unwrap();
+
+    let reversed = graph.reversed();
+    assert_eq!(reversed.nr_nodes(), 3);
+    assert_eq!(sorted(reversed.iter_next_nodes(b).unwrap()), [a, c]);
+    assert_eq!(sorted(reversed.iter_prev_nodes(a).unwrap()), [b]);
+    assert_eq!(reversed.out_degree_of(b), Some(3));
+    assert_eq!(reversed.in_degree_of(a), Some(2));
+    assert_eq!(
+        reversed
+            .iter_relation_edges(r1)
+            .unwrap()
+            .collect::<Vec<_>>(),
+        [Edge::new(b, a)]
+    );
+    assert_eq!(
+        reversed
+            .iter_relation_edges(r2)
+            .unwrap()
+            .collect::<Vec<_>>(),
+        [Edge::new(b, c)]
+    );
+    assert_eq!(reachable(&graph, c), 2);
+    assert_eq!(reachable(&reversed, c), 3);
+}
+
+#[test]
+fn test_filter_nodes() {
+    let mut graph = Graph::new();
+    let nodes: Vec<_> = (0..4).map(|i| graph.add_node(i)).collect();
+    let r1 = graph.add_relation(1);
+    for pair in nodes.windows(2) {
+        graph.connect(pair[0], pair[1], r1).unwrap();
+    }
+    graph.connect(nodes[0], nodes[2], r1).unwrap();
+
+    let even = graph.filter_nodes(|_, info| info.downcast_ref::<i32>().unwrap() % 2 == 0);
+    assert_eq!(even.nr_nodes(), 2);
+    assert!(!even.contains_node(nodes[1]));
+    assert!(even.with_node_info(nodes[1], |_| ()).is_none());
+    assert!(even.iter_next_nodes(nodes[1]).is_none());
+    assert_eq!(sorted(even.iter_next_nodes(nodes[0]).unwrap()), [nodes[2]]);
+    assert_eq!(even.out_degree_of(nodes[0]), Some(1));
+    assert_eq!(even.in_degree_of(nodes[2]), Some(1));
+    assert_eq!(even.iter_relation_edges(r1).unwrap().count(), 1);
+    assert!(!even.contains_edge(Edge::new(nodes[0], nodes[1]), r1));
+    assert_eq!(even.relation_kind(r1), Some(RelationKind::Directed));
+    assert_eq!(reachable(&even, nodes[0]), 2);
+    assert_eq!(reachable(&graph, nodes[0]), 4);
+}
+
+#[test]
+fn test_filter_relations() {
+    let mut graph = Graph::new();
+    let a = graph.add_node(1);
+    let b = graph.add_node(2);
+    let c = graph.add_node(3);
+    let r1 = graph.add_relation(1);
+    let r2 = graph.add_relation(2);
+    graph.connect(a, b, r1).unwrap();
+    graph.connect(a, c, r2).unwrap();
+
+    let only_r1 = graph.filter_relations(vec![r1]);
+    assert_eq!(only_r1.nr_nodes(), 3);
+    assert!(only_r1.contains_relation(r1));
+    assert!(!only_r1.contains_relation(r2));
+    assert!(only_r1.relation_kind(r2).is_none());
+    assert!(!only_r1.contains_edge(Edge::new(a, c), r2));
+    assert!(only_r1.iter_relation_edges(r2).is_none());
+    assert_eq!(only_r1.relation_ids().collect::<Vec<_>>(), [r1]);
+    assert_eq!(sorted(only_r1.iter_next_nodes(a).unwrap()), [b]);
+    assert_eq!(only_r1.out_degree_of(a), Some(1));
+    assert_eq!(only_r1.in_degree_of(c), Some(0));
+}
+
+#[test]
+fn test_stacked_views() {
+    let mut graph = Graph::new();
+    let a = graph.add_node(1);
+    let b = graph.add_node(2);
+    let c = graph.add_node(3);
+    let r1 = graph.add_relation(1);
+    let r2 = graph.add_relation(2);
+    graph.connect(a, b, r1).unwrap();
+    graph.connect(b, c, r1).unwrap();
+    graph.connect(c, a, r2).unwrap();
+
+    let relations = graph.filter_relations(vec![r1]);
+    let without_a = relations.filter_nodes(|node_id, _| node_id != a);
+    let view = without_a.reversed();
+    assert_eq!(view.nr_nodes(), 2);
+    assert_eq!(sorted(view.iter_next_nodes(c).unwrap()), [b]);
+    assert_eq!(view.out_degree_of(b), Some(0));
+    assert_eq!(
+        view.iter_relation_edges(r1).unwrap().collect::<Vec<_>>(),
+        [Edge::new(c, b)]
+    );
+    assert!(view.iter_relation_edges(r2).is_none());
+
+    let snapshot = graph.snapshot();
+    assert_eq!(reachable(&*snapshot, a), 3);
+    let only_r1 = snapshot.filter_relations(vec![r1]);
+    assert_eq!(reachable(&only_r1.reversed(), b), 2);
+}